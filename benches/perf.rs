@@ -0,0 +1,75 @@
+//! Regression coverage for the hot paths: nmcli parsing, scan grouping and
+//! sorting, and drawing a full frame. Run with `cargo bench`.
+
+use std::hint::black_box;
+use std::sync::Arc;
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use ratatui::backend::TestBackend;
+use ratatui::Terminal;
+
+use wifi_tui::app::{sort_networks, App, SortMode};
+use wifi_tui::backend::{group_networks, Network};
+use wifi_tui::config::Config;
+use wifi_tui::{nmcli, ui};
+
+/// A scan from an apartment block: 40 SSIDs, 6 BSSIDs each.
+fn scan() -> Vec<Network> {
+    (0..240)
+        .map(|i| Network {
+            ssid: format!("Neighbour-{:02}", i % 40),
+            signal: (i * 37 % 100) as u8,
+            security: if i % 7 == 0 { String::new() } else { "WPA2 WPA3".to_string() },
+            in_use: i == 42,
+            bssid: format!("AA:BB:CC:DD:{:02X}:{:02X}", i / 256, i % 256),
+            channel: Some([1, 6, 11, 36, 44, 149][i % 6]),
+            frequency: Some([2412, 2437, 2462, 5180, 5220, 5745][i % 6]),
+            rate: Some(270),
+            mode: "Infra".to_string(),
+            ..Network::default()
+        })
+        .collect()
+}
+
+fn parsing(c: &mut Criterion) {
+    let line = r"*:AA\:BB\:CC\:DD\:EE\:FF:Cafe\: upstairs:6:2437 MHz:130 Mbit/s:72:WPA2 WPA3:Infra";
+    c.bench_function("parse_terse_line", |b| b.iter(|| nmcli::parse_terse_line(black_box(line))));
+}
+
+fn grouping(c: &mut Criterion) {
+    c.bench_function("group and sort 240 BSSIDs", |b| {
+        b.iter_batched(
+            scan,
+            |scanned| {
+                let mut networks = group_networks(scanned);
+                sort_networks(&mut networks, SortMode::Signal);
+                networks
+            },
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+fn drawing(c: &mut Criterion) {
+    let mut app = App::new("wlan0".to_string(), Arc::new(nmcli::NmcliBackend), Config::default());
+    app.networks = group_networks(scan());
+    sort_networks(&mut app.networks, SortMode::Signal);
+    app.expanded.extend(app.networks.iter().take(5).map(|net| net.ssid.clone()));
+    let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+
+    c.bench_function("draw frame", |b| {
+        b.iter(|| {
+            terminal.draw(|frame| ui::draw(frame, &app)).unwrap();
+        });
+    });
+    c.bench_function("draw frame, cold row cache", |b| {
+        b.iter(|| {
+            app.row_cache.borrow_mut().clear();
+            app.status_line.replace(None);
+            terminal.draw(|frame| ui::draw(frame, &app)).unwrap();
+        });
+    });
+}
+
+criterion_group!(benches, parsing, grouping, drawing);
+criterion_main!(benches);
@@ -0,0 +1,12 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use wifi_tui::nmcli::parse_scan;
+
+fuzz_target!(|stdout: &str| {
+    for net in parse_scan(stdout) {
+        // Hidden networks are dropped, and every SSID is listed once
+        assert!(!net.ssid.is_empty());
+        assert!(net.access_points.iter().all(|ap| !ap.bssid.is_empty()));
+    }
+});
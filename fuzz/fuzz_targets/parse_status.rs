@@ -0,0 +1,14 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use wifi_tui::nmcli::{parse_in_use, parse_ip4_address, parse_tx_bitrate};
+
+fuzz_target!(|stdout: &str| {
+    if let Some((ssid, _)) = parse_in_use(stdout) {
+        assert!(!ssid.is_empty());
+    }
+    if let Some(ip) = parse_ip4_address(stdout) {
+        assert!(!ip.contains('/'));
+    }
+    let _ = parse_tx_bitrate(stdout);
+});
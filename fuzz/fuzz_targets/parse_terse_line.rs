@@ -0,0 +1,12 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use wifi_tui::nmcli::parse_terse_line;
+
+fuzz_target!(|line: &str| {
+    let fields = parse_terse_line(line);
+    // Without escapes, splitting must lose nothing
+    if !line.contains('\\') {
+        assert_eq!(fields.join(":"), line);
+    }
+});
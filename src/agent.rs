@@ -0,0 +1,112 @@
+use std::collections::HashMap;
+use std::sync::{mpsc, Mutex};
+
+use zbus::blocking::{connection, Connection, Proxy};
+use zbus::zvariant::{OwnedObjectPath, Value};
+
+use crate::dbus::{setting_bytes, setting_str, OwnedSettings};
+use crate::event::Event;
+
+const AGENT_PATH: &str = "/org/freedesktop/NetworkManager/SecretAgent";
+const AGENT_MANAGER_PATH: &str = "/org/freedesktop/NetworkManager/AgentManager";
+const AGENT_MANAGER_IFACE: &str = "org.freedesktop.NetworkManager.AgentManager";
+const AGENT_ID: &str = "wifi-tui";
+
+const WIRELESS_SECURITY: &str = "802-11-wireless-security";
+const NM_SECRET_AGENT_GET_SECRETS_FLAG_ALLOW_INTERACTION: u32 = 0x1;
+const NM_SECRET_AGENT_GET_SECRETS_FLAG_REQUEST_NEW: u32 = 0x2;
+
+/// A password request from NetworkManager, answered from the password modal.
+pub struct SecretRequest {
+    pub ssid: String,
+    /// NetworkManager asks again because the last password didn't work.
+    pub retry: bool,
+    reply: async_channel::Sender<Option<String>>,
+}
+
+impl SecretRequest {
+    /// Answer the request; None means the user cancelled.
+    pub fn reply(self, secret: Option<String>) {
+        let _ = self.reply.send_blocking(secret);
+    }
+}
+
+#[derive(Debug, zbus::DBusError)]
+#[zbus(prefix = "org.freedesktop.NetworkManager.SecretAgent")]
+enum AgentError {
+    #[zbus(error)]
+    ZBus(zbus::Error),
+    UserCanceled(String),
+    NoSecrets(String),
+}
+
+struct SecretAgent {
+    events: Mutex<mpsc::Sender<Event>>,
+}
+
+#[zbus::interface(name = "org.freedesktop.NetworkManager.SecretAgent")]
+impl SecretAgent {
+    async fn get_secrets(
+        &self,
+        connection: OwnedSettings,
+        _connection_path: OwnedObjectPath,
+        setting_name: String,
+        _hints: Vec<String>,
+        flags: u32,
+    ) -> Result<HashMap<String, HashMap<String, Value<'static>>>, AgentError> {
+        if setting_name != WIRELESS_SECURITY
+            || flags & NM_SECRET_AGENT_GET_SECRETS_FLAG_ALLOW_INTERACTION == 0
+        {
+            return Err(AgentError::NoSecrets("wifi-tui only provides WiFi passwords".to_string()));
+        }
+
+        let ssid = setting_bytes(&connection, "802-11-wireless", "ssid")
+            .map(|ssid| String::from_utf8_lossy(&ssid).into_owned())
+            .or_else(|| setting_str(&connection, "connection", "id").map(str::to_string))
+            .unwrap_or_default();
+
+        let (tx, rx) = async_channel::bounded(1);
+        let retry = flags & NM_SECRET_AGENT_GET_SECRETS_FLAG_REQUEST_NEW != 0;
+        let request = SecretRequest { ssid, retry, reply: tx };
+        if self.events.lock().unwrap().send(Event::SecretRequest(request)).is_err() {
+            return Err(AgentError::NoSecrets("wifi-tui is shutting down".to_string()));
+        }
+
+        match rx.recv().await {
+            Ok(Some(psk)) => Ok(HashMap::from([(
+                WIRELESS_SECURITY.to_string(),
+                HashMap::from([("psk".to_string(), Value::from(psk))]),
+            )])),
+            _ => Err(AgentError::UserCanceled("Password entry cancelled".to_string())),
+        }
+    }
+
+    async fn cancel_get_secrets(&self, _connection_path: OwnedObjectPath, _setting_name: String) {
+        let _ = self.events.lock().unwrap().send(Event::SecretCancelled);
+    }
+
+    // Secrets are stored by NetworkManager itself; nothing to persist here.
+    async fn save_secrets(&self, _connection: OwnedSettings, _connection_path: OwnedObjectPath) {}
+
+    async fn delete_secrets(&self, _connection: OwnedSettings, _connection_path: OwnedObjectPath) {}
+}
+
+/// Export the secret agent on a new system bus connection and register it with
+/// NetworkManager. The agent stays registered for as long as the returned
+/// connection is alive.
+pub fn register(events: mpsc::Sender<Event>) -> zbus::Result<Connection> {
+    let agent = SecretAgent {
+        events: Mutex::new(events),
+    };
+    let conn = connection::Builder::system()?
+        .serve_at(AGENT_PATH, agent)?
+        .build()?;
+    Proxy::new(
+        &conn,
+        "org.freedesktop.NetworkManager",
+        AGENT_MANAGER_PATH,
+        AGENT_MANAGER_IFACE,
+    )?
+    .call::<_, _, ()>("Register", &(AGENT_ID,))?;
+    Ok(conn)
+}
@@ -0,0 +1,110 @@
+use std::collections::BTreeMap;
+
+use crate::backend::{Band, Network};
+
+/// Channels worth putting a router on: the three that don't overlap at
+/// 2.4 GHz, the ones without radar detection (DFS) at 5 GHz, and the
+/// preferred scanning channels at 6 GHz.
+fn candidates(band: Band) -> &'static [u32] {
+    match band {
+        Band::Ghz2 => &[1, 6, 11],
+        Band::Ghz5 => &[36, 40, 44, 48, 149, 153, 157, 161, 165],
+        Band::Ghz6 => &[5, 21, 37, 53, 69, 85, 101, 117, 133, 149, 165, 181, 197, 213, 229],
+    }
+}
+
+/// Access points heard on one channel.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ChannelUsage {
+    pub channel: u32,
+    pub access_points: usize,
+    /// Their signal percentages added up: how busy the channel sounds.
+    pub signal: u32,
+}
+
+/// Every access point in `networks` on `band`, counted per channel. The
+/// band's candidate channels are listed even when nobody uses them, and at
+/// 2.4 GHz so is every channel from 1 to 13.
+pub fn channel_usage(networks: &[Network], band: Band) -> Vec<ChannelUsage> {
+    let mut usage: BTreeMap<u32, ChannelUsage> = BTreeMap::new();
+    let listed: &[u32] = if band == Band::Ghz2 { &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13] } else { candidates(band) };
+    for &channel in listed {
+        usage.insert(channel, ChannelUsage { channel, ..ChannelUsage::default() });
+    }
+
+    for net in networks {
+        // Without BSSIDs, the network's best AP stands in for all of them
+        let aps: Vec<(Option<u32>, Option<u32>, u8)> = if net.access_points.is_empty() {
+            vec![(net.channel, net.frequency, net.signal)]
+        } else {
+            net.access_points.iter().map(|ap| (ap.channel, ap.frequency, ap.signal)).collect()
+        };
+        for (channel, frequency, signal) in aps {
+            let (Some(channel), Some(mhz)) = (channel, frequency) else {
+                continue;
+            };
+            if !band.contains(mhz) {
+                continue;
+            }
+            let entry = usage.entry(channel).or_insert(ChannelUsage { channel, ..ChannelUsage::default() });
+            entry.access_points += 1;
+            entry.signal += u32::from(signal);
+        }
+    }
+    usage.into_values().collect()
+}
+
+/// The candidate channel with the least signal from others on it. At 2.4 GHz
+/// channels within four of each other overlap, so those count too.
+pub fn clearest_channel(usage: &[ChannelUsage], band: Band) -> Option<u32> {
+    let reach = if band == Band::Ghz2 { 4 } else { 0 };
+    candidates(band).iter().copied().min_by_key(|&candidate| {
+        usage
+            .iter()
+            .filter(|used| used.channel.abs_diff(candidate) <= reach)
+            .map(|used| used.signal)
+            .sum::<u32>()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::AccessPoint;
+
+    #[test]
+    fn test_channel_usage() {
+        let ap = |channel, frequency, signal| AccessPoint {
+            channel: Some(channel),
+            frequency: Some(frequency),
+            signal,
+            ..AccessPoint::default()
+        };
+        let networks = [
+            Network {
+                access_points: vec![ap(1, 2412, 80), ap(36, 5180, 60)],
+                ..Network::default()
+            },
+            Network {
+                channel: Some(3),
+                frequency: Some(2422),
+                signal: 40,
+                ..Network::default()
+            },
+            Network {
+                access_points: vec![ap(11, 2462, 20)],
+                ..Network::default()
+            },
+        ];
+        let usage = channel_usage(&networks, Band::Ghz2);
+        assert_eq!(usage.len(), 13);
+        assert_eq!(usage[0], ChannelUsage { channel: 1, access_points: 1, signal: 80 });
+        assert_eq!(usage[2], ChannelUsage { channel: 3, access_points: 1, signal: 40 });
+        // 1 hears itself and 3, 6 hears 3, 11 only the weak one
+        assert_eq!(clearest_channel(&usage, Band::Ghz2), Some(11));
+
+        let usage = channel_usage(&networks, Band::Ghz5);
+        assert_eq!(usage.iter().map(|used| used.access_points).sum::<usize>(), 1);
+        assert_eq!(clearest_channel(&usage, Band::Ghz5), Some(40));
+    }
+}
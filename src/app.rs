@@ -1,34 +1,597 @@
-use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use crate::event::{EventLoop, Task};
-use crate::nmcli::{ConnectionStatus, Network, SavedNetwork};
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::{Position, Rect};
+use ratatui::text::Line;
+use ratatui::widgets::{ListItem, ListState};
+use serde::Deserialize;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+use crate::checks::Check;
+use crate::clipboard;
+use crate::config::Config;
+use crate::devicepref;
+use crate::diagnostics::DropReport;
+use crate::dispatcher::{validate_script_name, DispatcherEvent, DispatcherScript};
+use crate::event::{EventLoop, MonitorChange, Task};
+use crate::form::{Form, FormAction};
+use crate::fuzzy;
+use crate::history::{self, ConnectHistory};
+use crate::input::TextInput;
+use crate::internet::{self, Internet};
+use crate::keymap::{Action, Keymap};
+use crate::ping::PingReport;
+use crate::qr::WifiQr;
+use crate::ratelimit::{self, RateLimits};
+use crate::rfkill::Rfkill;
+use crate::roaming::{self, Bgscan, RoamSettings};
+use crate::snapshot::Snapshot;
+use crate::theme::Theme;
+use crate::units::{SignalUnit, Units};
+use crate::update;
+use crate::usage::{self, UsageTotals};
+use crate::viewstate::{self, ViewState};
+use crate::agent::SecretRequest;
+use crate::backend::{
+    device_mac, device_present, device_with_mac, format_dns, format_routes, is_mac_address, is_virtual_interface,
+    parse_dns, parse_routes, permanent_mac, traffic_counters, Backend, Band, Change, ClonedMac, ConnectStage, ConnectionStatus,
+    ConnectivityCheck, DnsConfig, EapConfig, EapMethod, HotspotBand, HotspotConfig, Ipv4Address, Ipv4Config, Metered, MetricChange,
+    Network, ProfileOptions, RouteConfig, SavedNetwork, TrafficCounters, Uplink, NO_WIFI_ADAPTER,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
 pub enum View {
+    #[serde(rename = "available")]
     AvailableNetworks,
+    #[serde(rename = "saved")]
     SavedNetworks,
+    #[serde(rename = "hotspot")]
+    Hotspot,
+    #[serde(rename = "monitor")]
+    Monitor,
+    #[serde(rename = "analyzer")]
+    Analyzer,
+}
+
+impl View {
+    pub const ALL: [View; 5] =
+        [View::AvailableNetworks, View::SavedNetworks, View::Hotspot, View::Monitor, View::Analyzer];
+
+    /// Lists that can be filtered with `/`.
+    pub fn has_filter(self) -> bool {
+        matches!(self, View::AvailableNetworks | View::SavedNetworks)
+    }
+}
+
+/// One reading of the connection, plotted by the Monitor view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MonitorSample {
+    pub at: Instant,
+    pub signal: u8,
+    /// Link rate in kbit/s.
+    pub bitrate: Option<u32>,
+    pub throughput: Option<Throughput>,
+    pub ping: Option<PingReport>,
+}
+
+/// How fast data is actually moving over the device, in bytes per second.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Throughput {
+    pub rx: u64,
+    pub tx: u64,
+}
+
+/// Time windows the Monitor view can show; samples older than the last are dropped.
+pub const MONITOR_WINDOWS: [Duration; 4] = [
+    Duration::from_secs(60),
+    Duration::from_secs(5 * 60),
+    Duration::from_secs(15 * 60),
+    Duration::from_secs(60 * 60),
+];
+
+/// A row of the Available list: a network, or one of its access points
+/// when the network is expanded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NetRow {
+    Network(usize),
+    AccessPoint(usize, usize), // network index, access point index
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Modal {
     PasswordInput,
     ConfirmDisconnect,
-    ConfirmForget(String), // network name
-    Message(String),       // message text
+    ConfirmForget(String),      // network name
+    Message(String),            // message text
+    Checks(String, Vec<Check>), // title, results
+    Form(FormKind),
+    ConfirmQuit,
+    Qr(WifiQr),
+    Password(String, String),   // profile name, password (empty when open)
+    SignalHistory,
+    Dispatcher,
+    NetworkDetails(Network),
+    Connectivity(ConnectivityCheck),
+    /// Apply the DNS or routing form, after showing what it changes
+    ConfirmApply(FormKind),
+    /// Change a profile's route metric, after showing what it runs
+    ConfirmMetric(String, MetricChange), // profile name
+    /// The probe hit a sign-in page; offer to open it
+    CaptivePortal(String),
+    /// What can be done with the selected network
+    Actions(ActionMenu),
+    /// Recent results and errors, newest last
+    Messages,
+}
+
+/// The actions offered for one network or saved profile, with their labels.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ActionMenu {
+    /// SSID or profile name the actions apply to
+    pub target: String,
+    pub items: Vec<(Action, &'static str)>,
+}
+
+/// A short-lived note in the bottom-right corner, for results that need no
+/// answer: nothing to dismiss, unlike a modal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Toast {
+    pub text: String,
+    /// Something didn't work, but not badly enough to interrupt.
+    pub warning: bool,
+    ticks_left: u32,
+}
+
+/// How a message in the history came about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageKind {
+    /// A toast saying something worked
+    Info,
+    /// A toast saying something didn't
+    Warning,
+    /// A message modal, mostly errors
+    Error,
+}
+
+/// A toast or modal message, kept after it's gone.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoggedMessage {
+    pub at: Instant,
+    pub kind: MessageKind,
+    pub text: String,
+}
+
+/// A status bar segment that responds to clicks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusTarget {
+    Ssid,
+    Signal,
+    /// Blank cells the signal sparkline is drawn over.
+    SignalTrend,
+    Spinner,
+}
+
+/// The status bar's left side as last built.
+#[derive(Debug)]
+pub struct StatusLine {
+    /// Hash of what it was built from.
+    pub key: u64,
+    pub line: Line<'static>,
+    /// Indexes of the spans that can be clicked.
+    pub targets: Vec<(usize, StatusTarget)>,
 }
 
+/// What a `Modal::Form` is for, so its values can be read back on submit.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormKind {
+    /// Saved hotspot profile
+    Hotspot,
+    /// Hotspot started immediately on this device
+    StartHotspot,
+    DispatcherScript,
+    /// Bandwidth limit of `App::form_target`
+    RateLimit,
+    /// Background scan of `App::form_target`
+    Roaming,
+    /// Autoconnect, priority and metered setting of `App::form_target`
+    Profile,
+    /// New name for `App::form_target`
+    Rename,
+    /// Routing options of `App::form_target`
+    Routes,
+    /// DNS servers of `App::form_target`
+    Dns,
+    /// Fixed or DHCP address of `App::form_target`
+    Ipv4,
+    /// Credentials for joining the enterprise network `App::form_target`
+    Enterprise,
+}
+
+fn hotspot_form(interface: &str) -> Form {
+    let bands: Vec<&str> = HotspotBand::ALL.iter().map(|b| b.label()).collect();
+    Form::new("New Hotspot")
+        .text("Name", TextInput::with_value("Hotspot").validator(validate_required))
+        .text("SSID", TextInput::new().max_len(32).validator(validate_ssid))
+        .secret(
+            "Password",
+            TextInput::new().max_len(63).validator(validate_hotspot_password),
+        )
+        .choice("Band", &bands, 0)
+        .text("Interface", TextInput::with_value(interface).validator(validate_required))
+}
+
+fn start_hotspot_form() -> Form {
+    Form::new("Start Hotspot")
+        .text("SSID", TextInput::new().max_len(32).validator(validate_ssid))
+        .secret(
+            "Password",
+            TextInput::new().max_len(63).validator(validate_start_hotspot_password),
+        )
+}
+
+fn dispatcher_form() -> Form {
+    let events: Vec<&str> = DispatcherEvent::ALL.iter().map(|e| e.label()).collect();
+    Form::new("New Dispatcher Script")
+        .text("Name", TextInput::with_value("90-wifi-tui").validator(validate_script_name))
+        .choice("Run when", &events, 0)
+        .text("Command", TextInput::new().validator(validate_required))
+}
+
+const RATE_LIMIT_LABEL: &str = "kbit/s (empty = none)";
+
+fn rate_limit_form(name: &str, current: Option<u32>) -> Form {
+    let value = current.map(|kbit| kbit.to_string()).unwrap_or_default();
+    Form::new(&format!("Bandwidth limit for {}", name)).text(
+        RATE_LIMIT_LABEL,
+        TextInput::with_value(&value).max_len(9).validator(validate_rate_limit),
+    )
+}
+
+fn rename_form(name: &str) -> Form {
+    Form::new(&format!("Rename {}", name))
+        .text("Name", TextInput::with_value(name).validator(validate_required))
+}
+
+const ROAM_MODE_LABEL: &str = "Background scan";
+const ROAM_SHORT_LABEL: &str = "Scan every (s) when weak";
+const ROAM_THRESHOLD_LABEL: &str = "Weak below (dBm)";
+const ROAM_LONG_LABEL: &str = "Scan every (s) otherwise";
+
+fn roaming_form(name: &str, current: Option<Bgscan>) -> Form {
+    let bgscan = current.unwrap_or(Bgscan::NM_MULTI_AP);
+    Form::new(&format!("Roaming for {}", name))
+        .choice(ROAM_MODE_LABEL, &["NetworkManager's", "Custom"], usize::from(current.is_some()))
+        .text(
+            ROAM_SHORT_LABEL,
+            TextInput::with_value(&bgscan.short_secs.to_string())
+                .max_len(5)
+                .validator(validate_scan_interval),
+        )
+        .text(
+            ROAM_THRESHOLD_LABEL,
+            TextInput::with_value(&bgscan.threshold.to_string())
+                .max_len(4)
+                .validator(validate_roam_threshold),
+        )
+        .text(
+            ROAM_LONG_LABEL,
+            TextInput::with_value(&bgscan.long_secs.to_string())
+                .max_len(6)
+                .validator(validate_scan_interval),
+        )
+}
+
+fn validate_scan_interval(value: &str) -> Result<(), String> {
+    match value.trim().parse::<u32>() {
+        Ok(secs) if secs > 0 => Ok(()),
+        _ => Err("enter a whole number of seconds".to_string()),
+    }
+}
+
+fn validate_roam_threshold(value: &str) -> Result<(), String> {
+    match value.trim().parse::<i32>() {
+        Ok(dbm) if (-100..=-20).contains(&dbm) => Ok(()),
+        _ => Err("enter a signal level from -100 to -20 dBm".to_string()),
+    }
+}
+
+const PROFILE_AUTOCONNECT_LABEL: &str = "Connect automatically";
+const PROFILE_PRIORITY_LABEL: &str = "Priority (higher wins)";
+const PROFILE_METERED_LABEL: &str = "Metered";
+const PROFILE_MAC_LABEL: &str = "MAC address";
+const PROFILE_CUSTOM_MAC_LABEL: &str = "Custom MAC (overrides)";
+
+fn profile_form(name: &str, options: &ProfileOptions) -> Form {
+    let metered: Vec<&str> = Metered::ALL.iter().map(|m| m.label()).collect();
+    let selected = Metered::ALL.iter().position(|m| *m == options.metered).unwrap_or(0);
+    let macs = ClonedMac::CHOICES;
+    let mac_labels: Vec<&str> = macs.iter().map(ClonedMac::label).collect();
+    Form::new(&format!("Edit {}", name))
+        .choice(PROFILE_AUTOCONNECT_LABEL, &["Yes", "No"], usize::from(!options.autoconnect))
+        .text(
+            PROFILE_PRIORITY_LABEL,
+            TextInput::with_value(&options.priority.to_string())
+                .max_len(4)
+                .validator(validate_priority),
+        )
+        .choice(PROFILE_METERED_LABEL, &metered, selected)
+        .choice(
+            PROFILE_MAC_LABEL,
+            &mac_labels,
+            macs.iter().position(|mac| *mac == options.mac).unwrap_or(0),
+        )
+        .text(
+            PROFILE_CUSTOM_MAC_LABEL,
+            TextInput::with_value(match &options.mac {
+                ClonedMac::Custom(mac) => mac,
+                _ => "",
+            })
+            .max_len(17)
+            .validator(validate_custom_mac),
+        )
+}
+
+fn validate_custom_mac(value: &str) -> Result<(), String> {
+    match value.trim() {
+        "" => Ok(()),
+        mac if is_mac_address(mac) => Ok(()),
+        _ => Err("enter a MAC address like 02:12:34:56:78:9A".to_string()),
+    }
+}
+
+/// NetworkManager accepts -999 to 999.
+fn validate_priority(value: &str) -> Result<(), String> {
+    match value.trim().parse::<i32>() {
+        Ok(p) if (-999..=999).contains(&p) => Ok(()),
+        _ => Err("enter a whole number from -999 to 999".to_string()),
+    }
+}
+
+const DNS_AUTO_LABEL: &str = "Network's DNS";
+const DNS_SERVERS_LABEL: &str = "DNS servers";
+
+fn dns_form(name: &str, config: &DnsConfig) -> Form {
+    Form::new(&format!("DNS for {}", name))
+        .text(
+            DNS_SERVERS_LABEL,
+            TextInput::with_value(&format_dns(&config.servers)).validator(validate_dns),
+        )
+        .choice(DNS_AUTO_LABEL, &["Also use", "Ignore"], usize::from(config.ignore_auto))
+}
+
+fn dns_config(form: &Form) -> DnsConfig {
+    DnsConfig {
+        ignore_auto: form.selected(DNS_AUTO_LABEL) == 1,
+        // Already validated
+        servers: parse_dns(form.value(DNS_SERVERS_LABEL)).unwrap_or_default(),
+    }
+}
+
+/// e.g. "1.1.1.1, 9.9.9.9"
+fn validate_dns(value: &str) -> Result<(), String> {
+    parse_dns(value).map(|_| ())
+}
+
+const IPV4_METHOD_LABEL: &str = "Address from";
+const IPV4_ADDRESS_LABEL: &str = "IP address/prefix";
+const IPV4_GATEWAY_LABEL: &str = "Gateway";
+const IPV4_DNS_LABEL: &str = "DNS servers";
+
+fn ipv4_form(name: &str, config: &Ipv4Config) -> Form {
+    let address = config.address.map(|address| address.to_string()).unwrap_or_default();
+    let gateway = config.gateway.map(|gateway| gateway.to_string()).unwrap_or_default();
+    Form::new(&format!("IPv4 for {}", name))
+        .choice(IPV4_METHOD_LABEL, &["DHCP", "Manual"], usize::from(config.address.is_some()))
+        .text(IPV4_ADDRESS_LABEL, TextInput::with_value(&address).validator(validate_ipv4_address))
+        .text(IPV4_GATEWAY_LABEL, TextInput::with_value(&gateway).validator(validate_gateway))
+        .text(IPV4_DNS_LABEL, TextInput::with_value(&format_dns(&config.dns)).validator(validate_dns))
+}
+
+/// The form's values; a manual address needs the address itself.
+fn ipv4_config(form: &Form) -> Result<Ipv4Config, String> {
+    // Already validated
+    let dns = parse_dns(form.value(IPV4_DNS_LABEL)).unwrap_or_default();
+    if form.selected(IPV4_METHOD_LABEL) == 0 {
+        // NetworkManager only takes a gateway next to a fixed address
+        return Ok(Ipv4Config { address: None, gateway: None, dns });
+    }
+    if form.value(IPV4_ADDRESS_LABEL).trim().is_empty() {
+        return Err(format!("{}: required for a manual address", IPV4_ADDRESS_LABEL));
+    }
+    Ok(Ipv4Config {
+        address: Ipv4Address::parse(form.value(IPV4_ADDRESS_LABEL)).ok(),
+        gateway: form.value(IPV4_GATEWAY_LABEL).trim().parse().ok(),
+        dns,
+    })
+}
+
+/// e.g. "192.168.1.50/24"
+fn validate_ipv4_address(value: &str) -> Result<(), String> {
+    match value.trim() {
+        "" => Ok(()),
+        v => Ipv4Address::parse(v).map(|_| ()),
+    }
+}
+
+fn validate_gateway(value: &str) -> Result<(), String> {
+    match value.trim() {
+        "" => Ok(()),
+        v if v.parse::<std::net::Ipv4Addr>().is_ok() => Ok(()),
+        v => Err(format!("'{}' is not an IPv4 address", v)),
+    }
+}
+
+const EAP_METHOD_LABEL: &str = "Method";
+const EAP_IDENTITY_LABEL: &str = "Identity";
+const EAP_PASSWORD_LABEL: &str = "Password";
+const EAP_CA_CERT_LABEL: &str = "CA certificate (empty = don't check)";
+
+fn enterprise_form(ssid: &str) -> Form {
+    let methods: Vec<&str> = EapMethod::ALL.iter().map(|m| m.label()).collect();
+    Form::new(&format!("Join {}", ssid))
+        .choice(EAP_METHOD_LABEL, &methods, 0)
+        .text(EAP_IDENTITY_LABEL, TextInput::new().validator(validate_required))
+        .secret(EAP_PASSWORD_LABEL, TextInput::new().validator(validate_required))
+        .text(EAP_CA_CERT_LABEL, TextInput::new().validator(validate_ca_cert))
+}
+
+fn eap_config(form: &Form) -> EapConfig {
+    let ca_cert = form.value(EAP_CA_CERT_LABEL).trim();
+    EapConfig {
+        method: EapMethod::ALL[form.selected(EAP_METHOD_LABEL)],
+        identity: form.value(EAP_IDENTITY_LABEL).trim().to_string(),
+        password: form.value(EAP_PASSWORD_LABEL).to_string(),
+        ca_cert: (!ca_cert.is_empty()).then(|| PathBuf::from(ca_cert)),
+    }
+}
+
+fn validate_ca_cert(value: &str) -> Result<(), String> {
+    match value.trim() {
+        "" => Ok(()),
+        path if Path::new(path).is_file() => Ok(()),
+        _ => Err("no such file".to_string()),
+    }
+}
+
+const ROUTES_DEFAULT_LABEL: &str = "Default route";
+const ROUTES_METRIC_LABEL: &str = "Route metric (empty = auto)";
+const ROUTES_LABEL: &str = "Static routes";
+
+/// How far `+`/`-` move a profile's route metric.
+const ROUTE_METRIC_STEP: i64 = 100;
+
+fn routes_form(name: &str, config: &RouteConfig) -> Form {
+    Form::new(&format!("Routes for {}", name))
+        .choice(ROUTES_DEFAULT_LABEL, &["Allowed", "Never"], usize::from(config.never_default))
+        .text(
+            ROUTES_METRIC_LABEL,
+            TextInput::with_value(&config.metric.map(|m| m.to_string()).unwrap_or_default())
+                .max_len(10)
+                .validator(validate_route_metric),
+        )
+        .text(
+            ROUTES_LABEL,
+            TextInput::with_value(&format_routes(&config.routes)).validator(validate_routes),
+        )
+}
+
+fn route_config(form: &Form) -> RouteConfig {
+    RouteConfig {
+        never_default: form.selected(ROUTES_DEFAULT_LABEL) == 1,
+        metric: form.value(ROUTES_METRIC_LABEL).trim().parse().ok(),
+        // Already validated
+        routes: parse_routes(form.value(ROUTES_LABEL)).unwrap_or_default(),
+    }
+}
+
+/// e.g. "10.0.0.0/8 192.168.1.1 100, 172.16.0.0/12"
+fn validate_routes(value: &str) -> Result<(), String> {
+    parse_routes(value).map(|_| ())
+}
+
+fn validate_route_metric(value: &str) -> Result<(), String> {
+    match value.trim() {
+        "" => Ok(()),
+        v if v.parse::<u32>().is_ok() => Ok(()),
+        _ => Err("enter a whole number (lower is preferred)".to_string()),
+    }
+}
+
+fn validate_rate_limit(value: &str) -> Result<(), String> {
+    match value.trim() {
+        "" => Ok(()),
+        v if v.parse::<u32>().is_ok_and(|kbit| kbit > 0) => Ok(()),
+        _ => Err("enter a whole number of kbit/s".to_string()),
+    }
+}
+
+fn hotspot_config(form: &Form) -> HotspotConfig {
+    HotspotConfig {
+        name: form.value("Name").trim().to_string(),
+        ssid: form.value("SSID").to_string(),
+        password: form.value("Password").to_string(),
+        band: HotspotBand::ALL[form.selected("Band")],
+        interface: form.value("Interface").trim().to_string(),
+    }
+}
+
+fn validate_required(value: &str) -> Result<(), String> {
+    if value.trim().is_empty() {
+        return Err("required".to_string());
+    }
+    Ok(())
+}
+
+fn validate_ssid(value: &str) -> Result<(), String> {
+    validate_required(value)?;
+    if value.len() > 32 {
+        return Err("must be at most 32 bytes".to_string());
+    }
+    Ok(())
+}
+
+fn validate_hotspot_password(value: &str) -> Result<(), String> {
+    if !value.is_empty() && value.chars().count() < 8 {
+        return Err("must be 8-63 characters, or empty for an open hotspot".to_string());
+    }
+    validate_passphrase_chars(value)
+}
+
+// nmcli makes up a password when none is given, which the user never sees
+fn validate_start_hotspot_password(value: &str) -> Result<(), String> {
+    if value.chars().count() < 8 {
+        return Err("must be 8-63 characters".to_string());
+    }
+    validate_passphrase_chars(value)
+}
+
+// The password reaches nmcli's editor as a line of its script, which would
+// drop the spaces at its ends and run whatever follows a line break
+fn validate_passphrase_chars(value: &str) -> Result<(), String> {
+    if value.chars().any(char::is_control) {
+        return Err("can't contain tabs or line breaks".to_string());
+    }
+    if value.trim() != value {
+        return Err("can't start or end with a space".to_string());
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum BgStatus {
     Idle,
     Scanning,
+    /// Re-reading status and saved profiles without a scan.
+    Refreshing,
     Connecting,
     Disconnecting,
     Forgetting,
+    Checking,
+    Saving,
+    StartingHotspot,
+    StoppingHotspot,
+    ReadingPassword,
+    Unblocking,
+}
+
+impl BgStatus {
+    /// Whether Esc (or a click on the spinner) can stop it.
+    pub fn cancellable(self) -> bool {
+        matches!(
+            self,
+            BgStatus::Scanning | BgStatus::Connecting | BgStatus::Disconnecting | BgStatus::Forgetting
+        )
+    }
 }
 
 pub struct App {
     pub running: bool,
     pub view: View,
+    /// The tabs shown, in order; never empty.
+    pub tabs: Vec<View>,
     pub modal: Option<Modal>,
     pub bg_status: BgStatus,
 
@@ -36,30 +599,371 @@ pub struct App {
     pub networks: Vec<Network>,
     pub saved: Vec<SavedNetwork>,
     pub status: ConnectionStatus,
+    /// Default routes, the one in use first.
+    pub uplinks: Vec<Uplink>,
+    /// Whether the device's radio is switched off, which otherwise just
+    /// looks like an empty scan.
+    pub rfkill: Rfkill,
+    /// The device's hardware address when the details modal was opened.
+    pub device_mac: Option<String>,
+    pub status_line: RefCell<Option<StatusLine>>,
+    /// Where the clickable status segments were last drawn.
+    pub status_targets: RefCell<Vec<(Rect, StatusTarget)>>,
+    /// The running operation was cancelled (Esc or a click on the spinner);
+    /// the failure that follows is the cancellation, not an error.
+    cancelling: bool,
+    /// How far the running connect got, polled while connecting.
+    pub connect_stage: Option<ConnectStage>,
+    polling_connect_stage: bool,
+    /// A quick signal-only read is on its way, see `SIGNAL_INTERVAL`.
+    polling_signal: bool,
     pub device: String,
+    /// Every WiFi device, for switching between adapters.
+    pub devices: Vec<String>,
+    /// Permanent hardware address of the adapter to use at startup.
+    preferred_mac: Option<String>,
+    /// Which of `devices` has `preferred_mac`.
+    pub preferred_device: Option<String>,
+    /// Devices with an `nmcli monitor` running; main starts the first one.
+    monitored: HashSet<String>,
+    /// The backend driving the device, for its name (e.g. "nmcli", "iwd")
+    /// and for describing changes before they run.
+    pub backend: Arc<dyn Backend>,
 
     // List selection
     pub net_index: usize,
+    /// SSIDs whose access points are listed under them.
+    pub expanded: HashSet<String>,
+    pub sort_mode: SortMode,
     pub saved_index: usize,
+    pub saved_sort: SavedSort,
+    /// Scroll position of the Available and Saved lists, kept between frames
+    /// so a list only scrolls once the selection reaches its edge.
+    pub net_list: Cell<ListState>,
+    pub saved_list: Cell<ListState>,
+    /// Rows the lists had room for last frame: how far the paging keys move.
+    pub list_height: Cell<usize>,
+    /// Rows drawn last frame, keyed by a hash of what they show, so rows
+    /// that haven't changed aren't rebuilt on every redraw.
+    pub row_cache: RefCell<HashMap<u64, ListItem<'static>>>,
+
+    /// Fuzzy SSID filters of the Available and Saved lists; `filtering`
+    /// while the user is typing into the current tab's.
+    pub available_filter: TextInput,
+    pub saved_filter: TextInput,
+    pub filtering: bool,
+    /// Only list networks heard on this band, cycled with `Action::Band`.
+    pub band_filter: Option<Band>,
 
     // Password input
-    pub password: String,
+    pub password: TextInput,
     pub password_visible: bool,
     pub password_target_ssid: String,
+    /// Configured password manager command; see `Config::password_command`.
+    pub password_command: Option<String>,
+    pending_password_fetch: bool,
+    /// Outcome of asking the password manager, shown in the prompt.
+    pub password_status: Option<String>,
+    /// Why the prompt is back, e.g. the last password was wrong. Shown
+    /// instead of `password_status`.
+    pub password_error: Option<String>,
+    /// Open request from NetworkManager's secret agent; the password modal
+    /// answers it instead of starting a new connect.
+    pending_secret: Option<SecretRequest>,
+    /// NetworkManager prompts for passwords itself via our secret agent.
+    pub secret_agent: bool,
+
+    // Multi-field form backing Modal::Form
+    pub form: Form,
+    /// Saved profile a form applies to (e.g. the rate limit form).
+    pub form_target: String,
+
+    /// Per-profile bandwidth limits, and the one currently applied with tc.
+    pub rate_limits: RateLimits,
+    applied_limit: Option<(String, u32)>,
+
+    /// Per-profile background scan settings, and the one last applied.
+    pub roam_settings: RoamSettings,
+    applied_bgscan: Option<(String, Bgscan)>,
+
+    /// Low-data mode: active connection marked metered, background scans paused.
+    pub low_data: bool,
+    /// Profile in call mode (power saving off, band pinned); scans are rare meanwhile.
+    pub call_mode: Option<String>,
+
+    // Quit protection: while a background task runs, `q` must be pressed twice
+    pub quit_protection: bool,
+    /// Show what confirmations will run, see `Config::confirm_details`.
+    pub confirm_details: bool,
+    pub quit_armed_ticks: u32,
 
     // Auto-refresh
+    tick_rate: Duration,
+    /// Passed with every connect; None waits as long as NetworkManager does.
+    connect_timeout: Option<Duration>,
+    auto_refresh_ticks: u32,
     pub ticks_since_scan: u32,
+    /// Scan on the next idle tick, e.g. after connecting.
+    scan_requested: bool,
+    /// Scan every `auto_refresh_ticks`, not only when requested.
+    auto_scan: bool,
+    /// Background scans are paused by the user until they resume them.
+    pub refresh_paused: bool,
+    /// Last move through a list; background scans wait `NAVIGATION_HOLD`
+    /// after it so the rows don't shift under the selection.
+    navigated_at: Option<Instant>,
     pub spinner_frame: usize,
     pending_scan_tasks: u8,
+    /// The networks and saved profiles are the last run's, until the first
+    /// refresh is back.
+    pub stale: bool,
+    /// External changes seen by `nmcli monitor`, refreshed on the next idle tick.
+    pending_status_refresh: bool,
+    pending_saved_refresh: bool,
+    /// Devices may have come or gone: list them again on the next tick.
+    pending_device_check: bool,
+    detecting_devices: bool,
+    /// The startup device listing is in.
+    devices_listed: bool,
+    /// WiFi devices just listed, acted on at the next tick.
+    detected_devices: Option<Vec<String>>,
+    /// Whether `device` was in /sys/class/net at the last look; not at all
+    /// when NetworkManager runs in another network namespace.
+    device_seen: bool,
+    /// The device went away and no other adapter is left; nothing runs
+    /// until one appears.
+    pub waiting_for_adapter: bool,
+    /// Toasts on screen, oldest first.
+    pub toasts: Vec<Toast>,
+    /// The last `MESSAGE_HISTORY` toasts and messages, oldest first.
+    pub messages: VecDeque<LoggedMessage>,
+    /// Selected row of the message history, and its scroll position.
+    pub message_index: usize,
+    pub message_list: Cell<ListState>,
+    /// Show signal quality as words instead of bar glyphs.
+    pub text_signal: bool,
+    pub units: Units,
+    pub theme: Theme,
+    pub keys: Keymap,
+    /// Modifiers armed with the sticky keys, applied to the next key.
+    pub sticky: KeyModifiers,
+    key_repeat_delay: Duration,
+    /// Last key handled and when, to drop repeats within `key_repeat_delay`.
+    last_key: Option<(KeyEvent, Instant)>,
+
+    /// Journal panel under the lists, toggled with `l`.
+    pub show_logs: bool,
+    pub logs: VecDeque<String>,
+    log_tail_started: bool,
+
+    // NetworkManager dispatcher scripts, shown in Modal::Dispatcher
+    pub dispatcher_scripts: Vec<DispatcherScript>,
+    pub dispatcher_index: usize,
+
+    /// Selected entry of `Modal::Actions`.
+    pub menu_index: usize,
+
+    /// Clients on the running hotspot; None if unknown.
+    pub hotspot_clients: Option<usize>,
+    /// Join code of the running hotspot, by profile name, shown in its panel.
+    pub hotspot_qr: Option<(String, Result<WifiQr, String>)>,
+    loading_hotspot_qr: bool,
+
+    /// Recent signal readings of the current connection, for drop reports
+    /// and the history view.
+    pub signal_history: VecDeque<u8>,
+    /// Timed readings of the current connection for the Monitor view, and
+    /// the index of its time window in `MONITOR_WINDOWS`.
+    pub monitor_samples: VecDeque<MonitorSample>,
+    pub monitor_window: usize,
+    /// Band whose channels the Analyzer view shows.
+    pub analyzer_band: Band,
+    /// Download and upload rates over the last `THROUGHPUT_INTERVAL`, from
+    /// the device's byte counters read then.
+    pub throughput: Option<Throughput>,
+    traffic_sample: Option<(Instant, TrafficCounters)>,
+    /// Latest round trips to the gateway and `ping_host`, pinged every
+    /// `ping_interval` while connected.
+    pub ping: Option<PingReport>,
+    pub ping_host: String,
+    /// What the probe of `internet_probe_url` found, once the connection
+    /// has an address; None until then.
+    pub internet: Option<Internet>,
+    internet_probe_url: String,
+    internet_checked_at: Option<Instant>,
+    checking_internet: bool,
+    /// A later release than this one, if the update check found one.
+    pub newer_version: Option<String>,
+    ping_interval: Option<Duration>,
+    pinged_at: Option<Instant>,
+    pinging: bool,
+    /// Data used per network in total and since wifi-tui started; the
+    /// totals are written out every `USAGE_SAVE_INTERVAL` and on exit.
+    pub usage: UsageTotals,
+    pub session_usage: UsageTotals,
+    usage_changed: bool,
+    usage_saved_at: Instant,
+    /// Connects that worked and failed per network, for spotting flaky ones.
+    pub connect_history: ConnectHistory,
+    /// The next status change was caused by the user (connect, disconnect,
+    /// forget, hotspot), so losing the connection is not a drop.
+    expect_status_change: bool,
+    /// A drop was noticed; diagnostics are captured on the next tick.
+    pending_drop_report: Option<DropReport>,
+
+    /// Network being joined with `ConnectTemporarily`.
+    temporary_ssid: Option<String>,
+    /// Profiles deleted on disconnect and exit, and whether each was seen
+    /// active yet (before that, it isn't in the saved list).
+    pub temporary_profiles: HashMap<String, bool>,
+    /// Just connected temporarily; autoconnect is turned off on the next tick.
+    pending_make_temporary: Option<String>,
+}
+
+const QUIT_ARM_TIME: Duration = Duration::from_secs(2); // window for the second `q`
+const TOAST_TIME: Duration = Duration::from_secs(4);
+const MAX_TOASTS: usize = 3;
+const MESSAGE_HISTORY: usize = 200;
+const SIGNAL_HISTORY_LEN: usize = 30;
+const HOTSPOT_CLIENT_INTERVAL: Duration = Duration::from_secs(2); // while the Hotspot tab is open
+const DEVICE_CHECK_INTERVAL: Duration = Duration::from_secs(2);
+const CONNECT_STAGE_INTERVAL: Duration = Duration::from_millis(500);
+const SIGNAL_INTERVAL: Duration = Duration::from_secs(1); // between full refreshes, while connected
+const THROUGHPUT_INTERVAL: Duration = Duration::from_secs(1);
+const USAGE_SAVE_INTERVAL: Duration = Duration::from_secs(60);
+const INTERNET_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+const LOG_LINES: usize = 200;
+
+/// Order of the Available list, cycled with `s`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortMode {
+    Signal,
+    #[serde(alias = "name")]
+    Ssid,
+    Security,
+    Channel,
+}
+
+impl SortMode {
+    pub const ALL: [SortMode; 4] = [SortMode::Signal, SortMode::Ssid, SortMode::Security, SortMode::Channel];
+
+    pub fn next(self) -> Self {
+        match self {
+            SortMode::Signal => SortMode::Ssid,
+            SortMode::Ssid => SortMode::Security,
+            SortMode::Security => SortMode::Channel,
+            SortMode::Channel => SortMode::Signal,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SortMode::Signal => "signal",
+            SortMode::Ssid => "name",
+            SortMode::Security => "security",
+            SortMode::Channel => "channel",
+        }
+    }
+}
+
+/// Order of the Saved list, cycled with `s` on that tab.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SavedSort {
+    /// As NetworkManager lists them.
+    #[default]
+    Default,
+    Name,
+    /// Active profiles first.
+    Active,
+}
+
+impl SavedSort {
+    pub const ALL: [SavedSort; 3] = [SavedSort::Default, SavedSort::Name, SavedSort::Active];
+
+    pub fn next(self) -> Self {
+        match self {
+            SavedSort::Default => SavedSort::Name,
+            SavedSort::Name => SavedSort::Active,
+            SavedSort::Active => SavedSort::Default,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SavedSort::Default => "default",
+            SavedSort::Name => "name",
+            SavedSort::Active => "active",
+        }
+    }
+}
+
+/// Number of ticks in `time`, at least one.
+fn ticks(time: Duration, tick_rate: Duration) -> u32 {
+    (time.as_millis() / tick_rate.as_millis().max(1)).max(1) as u32
+}
+
+/// Selection after a movement `action` in a `len`-row list showing `page`
+/// rows at a time. Stays within the list.
+pub fn move_selection(action: Action, index: usize, len: usize, page: usize) -> usize {
+    let page = page.max(1);
+    let last = len.saturating_sub(1);
+    let index = match action {
+        Action::Up => index.saturating_sub(1),
+        Action::Down => index + 1,
+        Action::PageUp => index.saturating_sub(page),
+        Action::PageDown => index + page,
+        Action::HalfPageUp => index.saturating_sub((page / 2).max(1)),
+        Action::HalfPageDown => index + (page / 2).max(1),
+        Action::Top => 0,
+        Action::Bottom => last,
+        _ => index,
+    };
+    index.min(last)
+}
+
+/// Sort networks for display. By signal the connected network comes first;
+/// the other modes fall back to signal for ties.
+pub fn sort_networks(networks: &mut [Network], mode: SortMode) {
+    networks.sort_by(|a, b| {
+        let by_signal = b.signal.cmp(&a.signal);
+        match mode {
+            SortMode::Signal => b.in_use.cmp(&a.in_use).then(by_signal),
+            SortMode::Ssid => a.ssid.to_lowercase().cmp(&b.ssid.to_lowercase()).then(by_signal),
+            SortMode::Security => a.security.cmp(&b.security).then(by_signal),
+            // Unknown channels last
+            SortMode::Channel => a
+                .frequency
+                .is_none()
+                .cmp(&b.frequency.is_none())
+                .then(a.frequency.cmp(&b.frequency))
+                .then(by_signal),
+        }
+    });
 }
 
-const AUTO_REFRESH_TICKS: u32 = 120; // 30s at 250ms tick rate
+const CALL_MODE_REFRESH: Duration = Duration::from_secs(300); // scans can cause latency spikes
+const NAVIGATION_HOLD: Duration = Duration::from_secs(5);
 
 impl App {
-    pub fn new(device: String) -> Self {
+    pub fn new(device: String, backend: Arc<dyn Backend>, config: Config) -> Self {
+        let tick_rate = config.tick_rate();
+        let auto_refresh_ticks = ticks(Duration::from_secs(config.auto_refresh_secs), tick_rate);
+        // Already checked when the config and arguments were read
+        let theme = config.theme().unwrap_or_default();
+        let mut tabs: Vec<View> = Vec::new();
+        for view in &config.tabs {
+            if !tabs.contains(view) {
+                tabs.push(*view);
+            }
+        }
+        if tabs.is_empty() {
+            tabs = View::ALL.to_vec();
+        }
         Self {
             running: true,
-            view: View::AvailableNetworks,
+            view: tabs[0],
+            tabs,
             modal: None,
             bg_status: BgStatus::Idle,
 
@@ -69,187 +973,1221 @@ impl App {
                 ssid: None,
                 signal: None,
                 ip: None,
-                speed: None,
+                bitrate: None,
+                dbm: None,
+                link: None,
             },
+            uplinks: Vec::new(),
+            rfkill: Rfkill::default(),
+            device_mac: None,
+            status_line: RefCell::new(None),
+            status_targets: RefCell::new(Vec::new()),
+            cancelling: false,
+            connect_stage: None,
+            polling_connect_stage: false,
+            polling_signal: false,
+            devices: vec![device.clone()],
+            preferred_mac: None,
+            preferred_device: None,
+            monitored: HashSet::from([device.clone()]),
             device,
+            backend,
 
             net_index: 0,
+            expanded: HashSet::new(),
+            sort_mode: config.default_sort,
+            saved_sort: SavedSort::Default,
             saved_index: 0,
+            net_list: Cell::new(ListState::default()),
+            saved_list: Cell::new(ListState::default()),
+            list_height: Cell::new(10),
+            row_cache: RefCell::new(HashMap::new()),
+
+            available_filter: TextInput::new(),
+            saved_filter: TextInput::new(),
+            filtering: false,
+            band_filter: None,
 
-            password: String::new(),
+            password: TextInput::new(),
             password_visible: false,
             password_target_ssid: String::new(),
+            password_command: config.password_command,
+            pending_password_fetch: false,
+            password_status: None,
+            password_error: None,
+            pending_secret: None,
+            secret_agent: false,
+
+            form: Form::default(),
+            form_target: String::new(),
+
+            rate_limits: RateLimits::new(),
+            applied_limit: None,
 
-            ticks_since_scan: AUTO_REFRESH_TICKS, // trigger immediate scan
+            roam_settings: RoamSettings::new(),
+            applied_bgscan: None,
+
+            low_data: false,
+            call_mode: None,
+
+            quit_protection: config.quit_protection,
+            confirm_details: config.confirm_details,
+            quit_armed_ticks: 0,
+
+            tick_rate,
+            connect_timeout: (config.connect_timeout_secs > 0).then(|| Duration::from_secs(config.connect_timeout_secs)),
+            auto_refresh_ticks,
+            ticks_since_scan: 0,
+            scan_requested: true, // scan right away
+            auto_scan: config.auto_scan,
+            refresh_paused: false,
+            navigated_at: None,
             spinner_frame: 0,
             pending_scan_tasks: 0,
+            stale: false,
+            pending_status_refresh: false,
+            pending_saved_refresh: false,
+            pending_device_check: false,
+            detecting_devices: false,
+            devices_listed: false,
+            detected_devices: None,
+            device_seen: false,
+            waiting_for_adapter: false,
+            toasts: Vec::new(),
+            messages: VecDeque::with_capacity(MESSAGE_HISTORY),
+            message_index: 0,
+            message_list: Cell::new(ListState::default()),
+            text_signal: config.text_signal,
+            units: Units::new(config.units, config.signal_unit),
+            theme,
+            keys: config.keys,
+            sticky: KeyModifiers::NONE,
+            key_repeat_delay: Duration::from_millis(config.key_repeat_delay_ms),
+            last_key: None,
+
+            show_logs: false,
+            logs: VecDeque::with_capacity(LOG_LINES),
+            log_tail_started: false,
+
+            dispatcher_scripts: Vec::new(),
+            dispatcher_index: 0,
+            menu_index: 0,
+
+            hotspot_clients: None,
+            hotspot_qr: None,
+            loading_hotspot_qr: false,
+
+            signal_history: VecDeque::with_capacity(SIGNAL_HISTORY_LEN),
+            monitor_samples: VecDeque::new(),
+            throughput: None,
+            traffic_sample: None,
+            ping: None,
+            ping_host: config.ping_host.clone(),
+            internet: None,
+            internet_probe_url: config.internet_probe_url.clone(),
+            internet_checked_at: None,
+            checking_internet: false,
+            newer_version: None,
+            ping_interval: (config.ping_interval_secs > 0).then(|| Duration::from_secs(config.ping_interval_secs)),
+            pinged_at: None,
+            pinging: false,
+            usage: UsageTotals::new(),
+            session_usage: UsageTotals::new(),
+            usage_changed: false,
+            usage_saved_at: Instant::now(),
+            connect_history: ConnectHistory::new(),
+            monitor_window: 1,
+            analyzer_band: Band::Ghz2,
+            expect_status_change: false,
+            pending_drop_report: None,
+
+            temporary_ssid: None,
+            temporary_profiles: HashMap::new(),
+            pending_make_temporary: None,
         }
     }
 
     /// Handle a keyboard event. Returns true if the event was consumed.
-    pub fn handle_key(&mut self, key: KeyEvent, events: &EventLoop) {
+    pub fn handle_key(&mut self, mut key: KeyEvent, events: &EventLoop) {
+        if !self.key_repeat_delay.is_zero() {
+            let now = Instant::now();
+            let repeated = self
+                .last_key
+                .is_some_and(|(last, at)| last == key && now.duration_since(at) < self.key_repeat_delay);
+            self.last_key = Some((key, now));
+            if repeated {
+                return;
+            }
+        }
+
+        // Sticky modifiers: pressing one again disarms it
+        if self.keys.is(&key, Action::StickyCtrl) {
+            self.sticky.toggle(KeyModifiers::CONTROL);
+            return;
+        }
+        if self.keys.is(&key, Action::StickyShift) {
+            self.sticky.toggle(KeyModifiers::SHIFT);
+            return;
+        }
+        let sticky = std::mem::replace(&mut self.sticky, KeyModifiers::NONE);
+        key.modifiers |= sticky;
+        if let KeyCode::Char(c) = key.code {
+            if sticky.contains(KeyModifiers::SHIFT) {
+                key.code = KeyCode::Char(c.to_ascii_uppercase());
+            }
+        }
+
         // Ctrl+C always quits
         if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
             self.running = false;
             return;
         }
 
+        // For terminals that don't paste on Ctrl+V themselves
+        if self.keys.is(&key, Action::Paste)
+            && matches!(self.modal, Some(Modal::PasswordInput) | Some(Modal::Form(_)))
+        {
+            match clipboard::paste() {
+                Ok(text) => self.handle_paste(text.trim_end_matches(['\r', '\n'])),
+                Err(e) => self.show_warning(e),
+            }
+            return;
+        }
+
         // Modal gets priority
         if let Some(ref modal) = self.modal.clone() {
             self.handle_modal_key(key, modal, events);
             return;
         }
 
+        if self.filtering {
+            self.handle_filter_key(key, events);
+            return;
+        }
+
         // Global keys
         match key.code {
-            KeyCode::Char('q') | KeyCode::Char('Q') => {
-                self.running = false;
+            _ if self.keys.is(&key, Action::Quit) => self.request_quit(false),
+            _ if self.keys.is(&key, Action::ForceQuit) => self.request_quit(true),
+            _ if self.view == View::SavedNetworks
+                && self.keys.is(&key, Action::ShowPassword)
+                && self.bg_status == BgStatus::Idle =>
+            {
+                if let Some(name) = self.selected_saved().map(|saved| saved.name.clone()) {
+                    self.bg_status = BgStatus::ReadingPassword;
+                    events.send_task(Task::RevealPassword(name));
+                }
             }
-            KeyCode::Tab | KeyCode::BackTab => {
-                self.view = match self.view {
-                    View::AvailableNetworks => View::SavedNetworks,
-                    View::SavedNetworks => View::AvailableNetworks,
-                };
+            _ if self.keys.is(&key, Action::NextView) => self.step_view(1),
+            _ if self.keys.is(&key, Action::PrevView) => self.step_view(self.tabs.len() - 1),
+            _ if self.keys.is(&key, Action::Filter) && self.view.has_filter() => self.filtering = true,
+            KeyCode::Esc if !self.filter().value().is_empty() => {
+                self.set_filter("");
+                self.save_views();
             }
-            KeyCode::Char('r') | KeyCode::Char('R') => {
+            KeyCode::Esc if self.bg_status.cancellable() => self.cancel_operation(events),
+            _ if self.keys.is(&key, Action::Refresh) => {
                 if self.bg_status == BgStatus::Idle {
                     self.start_scan(events);
                 }
             }
-            _ => match self.view {
-                View::AvailableNetworks => self.handle_available_key(key, events),
-                View::SavedNetworks => self.handle_saved_key(key, events),
-            },
-        }
-    }
-
-    fn handle_available_key(&mut self, key: KeyEvent, events: &EventLoop) {
-        match key.code {
-            KeyCode::Up | KeyCode::Char('k') => {
-                if self.net_index > 0 {
-                    self.net_index -= 1;
+            _ if self.keys.is(&key, Action::Rescan) => {
+                if self.bg_status == BgStatus::Idle {
+                    self.start_refresh(events, true, false);
                 }
             }
-            KeyCode::Down | KeyCode::Char('j') => {
-                if !self.networks.is_empty() && self.net_index < self.networks.len() - 1 {
-                    self.net_index += 1;
+            _ if self.keys.is(&key, Action::RefreshStatus) => {
+                if self.bg_status == BgStatus::Idle {
+                    self.start_refresh(events, false, true);
+                } else {
+                    self.pending_status_refresh = true;
                 }
             }
-            KeyCode::Enter => {
-                if self.bg_status != BgStatus::Idle {
-                    return;
-                }
-                if let Some(net) = self.networks.get(self.net_index) {
-                    if net.in_use {
-                        self.modal = Some(Modal::Message("Already connected to this network.".to_string()));
-                        return;
-                    }
-                    let ssid = net.ssid.clone();
-                    // Always try connecting first - nmcli will use saved
-                    // credentials if available. If it needs a password,
-                    // the result handler will show the password modal.
-                    self.bg_status = BgStatus::Connecting;
-                    events.send_task(Task::Connect(ssid, Some(String::new())));
+            _ if self.keys.is(&key, Action::Logs) => {
+                self.show_logs = !self.show_logs;
+                // Only start following the journal once someone looks at it
+                if self.show_logs && !self.log_tail_started {
+                    self.log_tail_started = true;
+                    events.start_log_tail(&self.device);
                 }
             }
-            KeyCode::Char('d') | KeyCode::Char('D') => {
-                if self.bg_status == BgStatus::Idle && self.status.ssid.is_some() {
-                    self.modal = Some(Modal::ConfirmDisconnect);
-                }
+            _ if self.keys.is(&key, Action::Messages) => {
+                self.message_index = self.messages.len().saturating_sub(1);
+                self.modal = Some(Modal::Messages);
             }
-            _ => {}
-        }
-    }
-
-    fn handle_saved_key(&mut self, key: KeyEvent, events: &EventLoop) {
-        match key.code {
-            KeyCode::Up | KeyCode::Char('k') => {
-                if self.saved_index > 0 {
-                    self.saved_index -= 1;
-                }
+            _ if self.keys.is(&key, Action::PauseRefresh) => {
+                self.refresh_paused = !self.refresh_paused;
+                let state = if self.refresh_paused { "paused" } else { "resumed" };
+                self.show_toast(format!("Background scans {}.", state));
+            }
+            _ if self.keys.is(&key, Action::LowData) => {
+                self.low_data = !self.low_data;
+                let profile = self
+                    .saved
+                    .iter()
+                    .find(|saved| saved.active && !saved.hotspot)
+                    .map(|saved| saved.name.clone());
+                events.send_task(Task::SetLowData(self.device.clone(), profile, self.low_data));
+            }
+            _ if self.keys.is(&key, Action::CallMode) && self.bg_status == BgStatus::Idle => {
+                self.toggle_call_mode(events);
+            }
+            _ if self.keys.is(&key, Action::CopyIp) => match self.status.ip.clone() {
+                Some(ip) => match clipboard::copy(&ip) {
+                    Ok(()) => self.show_toast(format!("Copied {}.", ip)),
+                    Err(e) => self.show_warning(e),
+                },
+                None => self.show_toast("No IP address to copy.".to_string()),
+            },
+            _ if self.keys.is(&key, Action::SignalUnit) => {
+                self.units.toggle_signal_unit();
+                let unit = match self.units.signal_unit() {
+                    SignalUnit::Percent => "percent",
+                    SignalUnit::Dbm => "dBm",
+                };
+                self.show_toast(format!("Signal shown in {}.", unit));
+            }
+            _ if self.keys.is(&key, Action::Unblock) && self.bg_status == BgStatus::Idle => {
+                self.unblock_radio(events);
+            }
+            _ if self.keys.is(&key, Action::SwitchDevice) && self.bg_status == BgStatus::Idle => {
+                self.switch_device(events);
+            }
+            _ if self.keys.is(&key, Action::PreferDevice) => {
+                self.toggle_preferred_device();
             }
-            KeyCode::Down | KeyCode::Char('j') => {
-                if !self.saved.is_empty() && self.saved_index < self.saved.len() - 1 {
-                    self.saved_index += 1;
+            _ if self.keys.is(&key, Action::PreferUplink) && self.bg_status == BgStatus::Idle => {
+                self.toggle_preferred_uplink(events);
+            }
+            _ if self.keys.is(&key, Action::Scripts) => {
+                if self.bg_status == BgStatus::Idle {
+                    self.bg_status = BgStatus::Checking;
+                    events.send_task(Task::ListDispatcherScripts);
                 }
             }
-            KeyCode::Enter => {
-                if self.bg_status != BgStatus::Idle {
-                    return;
+            _ if self.keys.is(&key, Action::CheckSharing) => {
+                if self.bg_status == BgStatus::Idle {
+                    self.bg_status = BgStatus::Checking;
+                    events.send_task(Task::CheckSharing(self.device.clone()));
                 }
-                if let Some(saved) = self.saved.get(self.saved_index) {
-                    if saved.active {
-                        self.modal = Some(Modal::Message("Already connected to this network.".to_string()));
-                        return;
-                    }
-                    let name = saved.name.clone();
-                    self.bg_status = BgStatus::Connecting;
-                    events.send_task(Task::Connect(name, None));
+            }
+            _ if self.keys.is(&key, Action::SixGhz) => {
+                if self.bg_status == BgStatus::Idle {
+                    self.bg_status = BgStatus::Checking;
+                    let seen = self.networks.iter().filter(|net| net.has_6ghz()).count();
+                    events.send_task(Task::CheckSixGhz(self.device.clone(), seen));
                 }
             }
-            KeyCode::Char('f') | KeyCode::Char('F') => {
-                if self.bg_status != BgStatus::Idle {
-                    return;
+            _ if self.keys.is(&key, Action::Connectivity) => {
+                if self.bg_status == BgStatus::Idle {
+                    self.bg_status = BgStatus::Checking;
+                    events.send_task(Task::CheckConnectivity);
                 }
-                if let Some(saved) = self.saved.get(self.saved_index) {
+            }
+            _ => match self.view {
+                View::AvailableNetworks => self.handle_available_key(key, events),
+                View::SavedNetworks => self.handle_saved_key(key, events),
+                View::Hotspot => self.handle_hotspot_key(key, events),
+                View::Monitor => self.handle_monitor_key(key),
+                View::Analyzer => self.handle_analyzer_key(key),
+            },
+        }
+    }
+
+    /// Move `by` tabs to the right, wrapping around.
+    fn step_view(&mut self, by: usize) {
+        let i = self.tabs.iter().position(|view| *view == self.view).unwrap_or(0);
+        self.view = self.tabs[(i + by) % self.tabs.len()];
+    }
+
+    /// Quit, unless a background operation is in flight and quit protection is
+    /// on: then `q` must be pressed twice, and `Q` asks for confirmation.
+    fn request_quit(&mut self, confirm: bool) {
+        if !self.quit_protection || self.bg_status == BgStatus::Idle || self.quit_armed_ticks > 0 {
+            self.running = false;
+        } else if confirm {
+            self.modal = Some(Modal::ConfirmQuit);
+        } else {
+            self.quit_armed_ticks = ticks(QUIT_ARM_TIME, self.tick_rate);
+        }
+    }
+
+    /// Turn call mode on for the active profile, or revert the profile it
+    /// was turned on for.
+    fn toggle_call_mode(&mut self, events: &EventLoop) {
+        let (name, on, band) = match self.call_mode.clone() {
+            Some(name) => (name, false, None),
+            None => {
+                let Some(saved) = self.saved.iter().find(|saved| saved.active && !saved.hotspot) else {
+                    self.show_message("Connect to a network first.".to_string());
+                    return;
+                };
+                let band = match self.networks.iter().find(|net| net.in_use).and_then(Network::band) {
+                    Some(Band::Ghz2) => Some("bg".to_string()),
+                    Some(Band::Ghz5) => Some("a".to_string()),
+                    _ => None,
+                };
+                (saved.name.clone(), true, band)
+            }
+        };
+        self.bg_status = BgStatus::Connecting;
+        events.send_task(Task::SetCallMode(self.device.clone(), name, on, band));
+    }
+
+    fn unblock_radio(&mut self, events: &EventLoop) {
+        if self.rfkill.hard {
+            self.show_message(
+                "The WiFi radio is off at a hardware switch. Turn it on with the WiFi key or switch on the laptop.".to_string(),
+            );
+        } else if self.rfkill.soft {
+            self.bg_status = BgStatus::Unblocking;
+            events.send_task(Task::UnblockRadio);
+        } else {
+            self.show_toast("The WiFi radio is already on.".to_string());
+        }
+    }
+
+    /// Manage the next WiFi adapter: everything shown is re-read for it.
+    fn switch_device(&mut self, events: &EventLoop) {
+        let Some(i) = self.devices.iter().position(|device| *device == self.device) else {
+            return;
+        };
+        if self.devices.len() < 2 {
+            self.show_toast(format!("{} is the only WiFi adapter.", self.device));
+            return;
+        }
+        self.use_device(self.devices[(i + 1) % self.devices.len()].clone(), events);
+        self.show_toast(format!("Switched to {}.", self.device));
+    }
+
+    pub fn set_preferred_mac(&mut self, mac: Option<String>) {
+        self.preferred_mac = mac;
+        self.preferred_device = self.preferred_mac.as_deref().and_then(|mac| device_with_mac(&self.devices, mac));
+    }
+
+    /// Make the current adapter the one picked at startup (by hardware
+    /// address, so renames don't matter), or drop the preference if it
+    /// already is.
+    fn toggle_preferred_device(&mut self) {
+        if self.preferred_device.as_ref() == Some(&self.device) {
+            match devicepref::save(None) {
+                Ok(()) => {
+                    self.set_preferred_mac(None);
+                    self.show_toast("No preferred adapter; the first one found will be used.".to_string());
+                }
+                Err(e) => self.show_message(e),
+            }
+            return;
+        }
+        let Some(mac) = permanent_mac(&self.device) else {
+            self.show_warning(format!("Couldn't read the hardware address of {}.", self.device));
+            return;
+        };
+        match devicepref::save(Some(&mac)) {
+            Ok(()) => {
+                self.show_toast(format!("{} ({}) will be used from now on.", self.device, mac));
+                self.set_preferred_mac(Some(mac));
+            }
+            Err(e) => self.show_message(e),
+        }
+    }
+
+    /// Forget what was shown for the old device and start on `device`.
+    fn use_device(&mut self, device: String, events: &EventLoop) {
+        self.device = device;
+        self.clear_device_state();
+        if self.monitored.insert(self.device.clone()) {
+            events.start_monitor(&self.device);
+        }
+        self.scan_requested = true;
+    }
+
+    fn clear_device_state(&mut self) {
+        self.networks.clear();
+        self.expanded.clear();
+        self.net_index = 0;
+        self.status = ConnectionStatus::default();
+        self.hotspot_clients = None;
+        self.hotspot_qr = None;
+        self.rfkill = Rfkill::default();
+        self.throughput = None;
+        self.traffic_sample = None;
+        self.ping = None;
+        self.internet = None;
+    }
+
+    /// A fresh list of WiFi devices: move off the current one if it's gone,
+    /// or wait for one to be plugged in.
+    fn update_devices(&mut self, devices: Vec<String>, events: &EventLoop) {
+        self.devices = devices;
+        // Listings skip virtual interfaces, and anything asked for with
+        // --device is kept from the start; either stays while it's there
+        let first = !std::mem::replace(&mut self.devices_listed, true);
+        if (first || is_virtual_interface(&self.device))
+            && !self.devices.contains(&self.device)
+            && device_present(&self.device)
+        {
+            self.devices.insert(0, self.device.clone());
+        }
+        let preferred_mac = self.preferred_mac.take();
+        self.set_preferred_mac(preferred_mac);
+        if self.devices.contains(&self.device) {
+            if std::mem::take(&mut self.waiting_for_adapter) {
+                self.scan_requested = true;
+                self.show_toast(format!("{} is back.", self.device));
+            }
+            return;
+        }
+        // The preferred adapter may be back under a new name
+        match self.preferred_device.clone().or_else(|| self.devices.first().cloned()) {
+            Some(device) => {
+                self.waiting_for_adapter = false;
+                let old = std::mem::take(&mut self.device);
+                self.use_device(device, events);
+                self.show_toast(format!("{} went away; switched to {}.", old, self.device));
+            }
+            None if !self.waiting_for_adapter => {
+                self.waiting_for_adapter = true;
+                self.clear_device_state();
+            }
+            None => {}
+        }
+    }
+
+    /// When both WiFi and a wired connection have a default route: (wired,
+    /// WiFi). The first of `uplinks` carries the traffic.
+    pub fn dual_uplink(&self) -> Option<(&Uplink, &Uplink)> {
+        let wired = self.uplinks.iter().find(|uplink| uplink.wired)?;
+        let wifi = self.uplinks.iter().find(|uplink| uplink.device == self.device)?;
+        Some((wired, wifi))
+    }
+
+    /// Move internet traffic to whichever of WiFi and wired doesn't carry it
+    /// now, by putting the WiFi profile's route metric just above or below
+    /// the wired one.
+    fn toggle_preferred_uplink(&mut self, events: &EventLoop) {
+        let Some((wired, wifi)) = self.dual_uplink() else {
+            self.show_message(
+                "Preferring WiFi or wired only works while both are connected.".to_string(),
+            );
+            return;
+        };
+        let metric = if wifi.metric <= wired.metric {
+            wired.metric + 100
+        } else if wired.metric > 0 {
+            wired.metric - 1
+        } else {
+            self.show_message(
+                "The wired connection already has the lowest possible metric.".to_string(),
+            );
+            return;
+        };
+        let active = self.saved.iter().find(|saved| saved.active && !saved.hotspot);
+        let Some(name) = active.map(|saved| saved.name.clone()) else {
+            return;
+        };
+        self.change_route_metric(name, MetricChange::Set(metric), events);
+    }
+
+    /// Change a profile's route metric, or ask first if `confirm_details` is on.
+    fn change_route_metric(&mut self, name: String, metric: MetricChange, events: &EventLoop) {
+        if self.confirm_details {
+            self.modal = Some(Modal::ConfirmMetric(name, metric));
+        } else {
+            self.send_route_metric(name, metric, events);
+        }
+    }
+
+    fn send_route_metric(&mut self, name: String, metric: MetricChange, events: &EventLoop) {
+        self.bg_status = BgStatus::Saving;
+        let task = match metric {
+            MetricChange::Set(metric) => Task::SetRouteMetric(self.device.clone(), name, Some(metric)),
+            MetricChange::Adjust(change) => Task::AdjustRouteMetric(self.device.clone(), name, change),
+        };
+        events.send_task(task);
+    }
+
+    /// Typing into the filter: Enter keeps it, Esc clears it, arrows still
+    /// move through the (filtered) list.
+    fn handle_filter_key(&mut self, key: KeyEvent, events: &EventLoop) {
+        match key.code {
+            KeyCode::Enter => {
+                self.filtering = false;
+                self.save_views();
+            }
+            KeyCode::Esc => {
+                self.filtering = false;
+                self.set_filter("");
+                self.save_views();
+            }
+            KeyCode::Up | KeyCode::Down => match self.view {
+                View::AvailableNetworks => self.handle_available_key(key, events),
+                View::SavedNetworks => self.handle_saved_key(key, events),
+                View::Hotspot | View::Monitor | View::Analyzer => {}
+            },
+            _ => {
+                let before = self.filter().value().to_string();
+                let filter = match self.view {
+                    View::SavedNetworks => &mut self.saved_filter,
+                    _ => &mut self.available_filter,
+                };
+                if filter.handle_key(key) && filter.value() != before {
+                    self.net_index = 0;
+                    self.saved_index = 0;
+                }
+            }
+        }
+    }
+
+    /// The current tab's filter (the Available one on the Hotspot tab,
+    /// which has none).
+    pub fn filter(&self) -> &TextInput {
+        match self.view {
+            View::SavedNetworks => &self.saved_filter,
+            _ => &self.available_filter,
+        }
+    }
+
+    fn set_filter(&mut self, value: &str) {
+        match self.view {
+            View::SavedNetworks => self.saved_filter.set_value(value),
+            _ => self.available_filter.set_value(value),
+        }
+        self.net_index = 0;
+        self.saved_index = 0;
+    }
+
+    /// Filters and sorts from the last run.
+    pub fn restore_views(&mut self, state: ViewState) {
+        self.available_filter.set_value(&state.available_filter);
+        if let Some(sort) = state.available_sort {
+            self.sort_mode = sort;
+            sort_networks(&mut self.networks, sort);
+        }
+        self.band_filter = state.available_band;
+        self.saved_filter.set_value(&state.saved_filter);
+        self.saved_sort = state.saved_sort;
+    }
+
+    /// Show what the last run saw until the first scan is back.
+    pub fn restore_snapshot(&mut self, snapshot: Snapshot) {
+        self.networks = snapshot.networks;
+        sort_networks(&mut self.networks, self.sort_mode);
+        self.saved = snapshot.saved;
+        self.status = snapshot.status;
+        // The real status may well differ; that's no drop
+        self.expect_status_change = true;
+        self.stale = true;
+    }
+
+    /// What to show on the next start, without profiles about to be deleted.
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            device: self.device.clone(),
+            networks: self.networks.clone(),
+            saved: self
+                .saved
+                .iter()
+                .filter(|saved| !self.temporary_profiles.contains_key(&saved.name))
+                .cloned()
+                .collect(),
+            status: self.status.clone(),
+        }
+    }
+
+    /// Remember the filters and sorts for next time.
+    fn save_views(&mut self) {
+        let state = ViewState {
+            available_filter: self.available_filter.value().to_string(),
+            available_sort: Some(self.sort_mode),
+            available_band: self.band_filter,
+            saved_filter: self.saved_filter.value().to_string(),
+            saved_sort: self.saved_sort,
+        };
+        if let Err(e) = viewstate::save(&state) {
+            self.show_warning(e);
+        }
+    }
+
+    /// Where a movement key takes the selection `index` of a `len`-row
+    /// list; None for other keys.
+    fn moved_selection(&self, key: &KeyEvent, index: usize, len: usize) -> Option<usize> {
+        const MOVES: [Action; 8] = [
+            Action::Up,
+            Action::Down,
+            Action::PageUp,
+            Action::PageDown,
+            Action::HalfPageUp,
+            Action::HalfPageDown,
+            Action::Top,
+            Action::Bottom,
+        ];
+        let action = MOVES.into_iter().find(|&action| self.keys.is(key, action))?;
+        Some(move_selection(action, index, len, self.list_height.get()))
+    }
+
+    fn handle_available_key(&mut self, key: KeyEvent, events: &EventLoop) {
+        if let Some(index) = self.moved_selection(&key, self.net_index, self.available_rows().len()) {
+            self.net_index = index;
+            self.navigated_at = Some(Instant::now());
+            return;
+        }
+        match key.code {
+            _ if self.keys.is(&key, Action::Expand) => {
+                if let Some(NetRow::Network(i)) = self.selected_row() {
+                    let net = &self.networks[i];
+                    if !net.access_points.is_empty() {
+                        self.expanded.insert(net.ssid.clone());
+                    }
+                }
+            }
+            _ if self.keys.is(&key, Action::Collapse) => match self.selected_row() {
+                Some(NetRow::Network(i)) => {
+                    self.expanded.remove(&self.networks[i].ssid);
+                }
+                Some(NetRow::AccessPoint(i, _)) => {
+                    self.expanded.remove(&self.networks[i].ssid);
+                    self.net_index = self
+                        .available_rows()
+                        .iter()
+                        .position(|row| *row == NetRow::Network(i))
+                        .unwrap_or(0);
+                }
+                None => {}
+            },
+            _ if self.keys.is(&key, Action::Connect) || self.keys.is(&key, Action::ConnectTemporarily) => {
+                if self.bg_status != BgStatus::Idle {
+                    return;
+                }
+                let temporary = self.keys.is(&key, Action::ConnectTemporarily);
+                if let Some(NetRow::AccessPoint(i, ap)) = self.selected_row() {
+                    if !temporary {
+                        self.connect_access_point(i, ap, events);
+                    }
+                    return;
+                }
+                if let Some(net) = self.selected_network() {
+                    if net.in_use {
+                        self.show_message("Already connected to this network.".to_string());
+                        return;
+                    }
+                    let ssid = net.ssid.clone();
+                    if temporary && self.saved.iter().any(|s| s.name == ssid) {
+                        self.show_message(format!(
+                            "{} is already saved. Forget it first to join it temporarily.",
+                            ssid
+                        ));
+                        return;
+                    }
+                    let needs_password = !net.is_open() && !self.saved.iter().any(|s| s.name == ssid);
+                    let enterprise = net.is_enterprise();
+                    self.temporary_ssid = temporary.then(|| ssid.clone());
+                    if needs_password && enterprise {
+                        // An identity and password instead of one shared key
+                        self.form = enterprise_form(&ssid);
+                        self.form_target = ssid;
+                        self.modal = Some(Modal::Form(FormKind::Enterprise));
+                        return;
+                    }
+                    if needs_password {
+                        // Secured and never joined: ask for the password up front
+                        self.prompt_password(ssid);
+                        return;
+                    }
+                    // Saved or open network - the backend will use saved
+                    // credentials if available. If it still needs a password,
+                    // the secret agent (or, without one, the result handler)
+                    // will show the password modal.
+                    self.bg_status = BgStatus::Connecting;
+                    events.send_task(Task::Connect(self.device.clone(), ssid, Some(String::new()), self.connect_timeout));
+                }
+            }
+            _ if self.keys.is(&key, Action::Disconnect)
+                && self.bg_status == BgStatus::Idle
+                && self.status.ssid.is_some() =>
+            {
+                self.modal = Some(Modal::ConfirmDisconnect);
+            }
+            _ if self.keys.is(&key, Action::Sort) => {
+                // Keep the selected network selected across the re-sort
+                let selected = self.selected_network().map(|net| net.ssid.clone());
+                self.sort_mode = self.sort_mode.next();
+                sort_networks(&mut self.networks, self.sort_mode);
+                self.select_network(selected);
+                self.save_views();
+            }
+            _ if self.keys.is(&key, Action::Menu) => self.open_menu(),
+            _ if self.keys.is(&key, Action::Band) => {
+                let selected = self.selected_network().map(|net| net.ssid.clone());
+                self.band_filter = match self.band_filter {
+                    None => Some(Band::ALL[0]),
+                    Some(band) if band == Band::ALL[Band::ALL.len() - 1] => None,
+                    Some(band) => Some(band.next()),
+                };
+                self.select_network(selected);
+                self.save_views();
+            }
+            _ if self.keys.is(&key, Action::Details) => {
+                let net = match self.selected_row() {
+                    Some(NetRow::Network(i)) => self.networks[i].clone(),
+                    Some(NetRow::AccessPoint(i, ap)) => {
+                        // Details of that one AP rather than the network's best
+                        let mut net = self.networks[i].clone();
+                        let ap = &net.access_points[ap];
+                        net.bssid = ap.bssid.clone();
+                        net.signal = ap.signal;
+                        net.channel = ap.channel;
+                        net.frequency = ap.frequency;
+                        net.in_use = ap.in_use;
+                        net
+                    }
+                    None => return,
+                };
+                // Read now: a randomized address changes on every connect
+                self.device_mac = device_mac(&self.device);
+                self.modal = Some(Modal::NetworkDetails(net));
+            }
+            _ => {}
+        }
+    }
+
+    /// Roam to one access point of a network by pinning its saved profile
+    /// to that BSSID.
+    fn connect_access_point(&mut self, net: usize, ap: usize, events: &EventLoop) {
+        let net = &self.networks[net];
+        let ap = &net.access_points[ap];
+        if ap.in_use {
+            self.show_message("Already connected to this access point.".to_string());
+            return;
+        }
+        if !self.saved.iter().any(|s| s.name == net.ssid) {
+            self.show_message(format!(
+                "Connect to {} once first, then pick an access point to roam to.",
+                net.ssid
+            ));
+            return;
+        }
+        self.bg_status = BgStatus::Connecting;
+        events.send_task(Task::ConnectBssid(self.device.clone(), net.ssid.clone(), ap.bssid.clone()));
+    }
+
+    /// Rows of the Available list, with expanded networks' access points.
+    /// With a band filter, only what's heard on that band.
+    pub fn available_rows(&self) -> Vec<NetRow> {
+        let on_band = |mhz: Option<u32>| match self.band_filter {
+            Some(band) => mhz.is_some_and(|mhz| band.contains(mhz)),
+            None => true,
+        };
+        let mut rows = Vec::with_capacity(self.networks.len());
+        for (i, net) in self.networks.iter().enumerate() {
+            if !fuzzy::matches(self.available_filter.value(), &net.ssid) {
+                continue;
+            }
+            if self.band_filter.is_some_and(|band| !net.bands().contains(&band)) {
+                continue;
+            }
+            rows.push(NetRow::Network(i));
+            if self.expanded.contains(&net.ssid) {
+                let aps = net.access_points.iter().enumerate().filter(|(_, ap)| on_band(ap.frequency));
+                rows.extend(aps.map(|(ap, _)| NetRow::AccessPoint(i, ap)));
+            }
+        }
+        rows
+    }
+
+    /// Put the selection back on the network called `ssid` after the list
+    /// changed order or filter, or on the top row if it's gone.
+    fn select_network(&mut self, ssid: Option<String>) {
+        self.net_index = ssid
+            .and_then(|ssid| {
+                self.available_rows()
+                    .iter()
+                    .position(|row| matches!(row, NetRow::Network(i) if self.networks[*i].ssid == ssid))
+            })
+            .unwrap_or(0);
+    }
+
+    fn selected_row(&self) -> Option<NetRow> {
+        self.available_rows().get(self.net_index).copied()
+    }
+
+    /// The selected network, or the network of the selected access point.
+    fn selected_network(&self) -> Option<&Network> {
+        match self.selected_row()? {
+            NetRow::Network(i) | NetRow::AccessPoint(i, _) => self.networks.get(i),
+        }
+    }
+
+    /// Indices into `saved` of the profiles passing the filter, in
+    /// `saved_sort` order.
+    pub fn visible_saved(&self) -> Vec<usize> {
+        let mut visible: Vec<usize> = (0..self.saved.len())
+            .filter(|&i| fuzzy::matches(self.saved_filter.value(), &self.saved[i].name))
+            .collect();
+        let saved = &self.saved;
+        match self.saved_sort {
+            SavedSort::Default => {}
+            SavedSort::Name => visible.sort_by_key(|&i| saved[i].name.to_lowercase()),
+            SavedSort::Active => visible.sort_by_key(|&i| !saved[i].active),
+        }
+        visible
+    }
+
+    fn selected_saved(&self) -> Option<&SavedNetwork> {
+        let i = *self.visible_saved().get(self.saved_index)?;
+        self.saved.get(i)
+    }
+
+    /// Open the actions menu for the selected network or saved profile,
+    /// listing only what applies to it.
+    fn open_menu(&mut self) {
+        let mut items = Vec::new();
+        let target = match self.view {
+            View::AvailableNetworks => {
+                let Some(row) = self.selected_row() else {
+                    return;
+                };
+                let (NetRow::Network(i) | NetRow::AccessPoint(i, _)) = row;
+                let net = &self.networks[i];
+                let saved = self.saved.iter().find(|saved| saved.name == net.ssid);
+                match row {
+                    NetRow::AccessPoint(_, ap) if !net.access_points[ap].in_use => {
+                        items.push((Action::Connect, "Roam to this access point"));
+                    }
+                    NetRow::Network(_) if !net.in_use => {
+                        items.push((Action::Connect, "Connect"));
+                        if saved.is_none() {
+                            items.push((Action::ConnectTemporarily, "Join temporarily"));
+                        }
+                    }
+                    _ => {}
+                }
+                if net.in_use {
+                    items.push((Action::Disconnect, "Disconnect"));
+                }
+                items.push((Action::Details, "Details"));
+                if net.access_points.len() > 1 {
+                    items.push(if self.expanded.contains(&net.ssid) {
+                        (Action::Collapse, "Hide access points")
+                    } else {
+                        (Action::Expand, "Show access points")
+                    });
+                }
+                if let Some(saved) = saved {
+                    items.push((Action::ShareQr, "Share with a QR code"));
+                    if !saved.hotspot {
+                        items.push((Action::Edit, "Edit profile"));
+                    }
+                    items.push((Action::Forget, "Forget"));
+                }
+                net.ssid.clone()
+            }
+            View::SavedNetworks => {
+                let Some(saved) = self.selected_saved() else {
+                    return;
+                };
+                if !saved.active {
+                    items.push((Action::Connect, if saved.hotspot { "Start hotspot" } else { "Connect" }));
+                } else if !saved.hotspot {
+                    items.push((Action::Disconnect, "Disconnect"));
+                }
+                items.push((Action::ShareQr, "Share with a QR code"));
+                items.push((Action::ShowPassword, "Show password"));
+                if !saved.hotspot {
+                    items.push((Action::Edit, "Edit profile"));
+                    items.push((Action::Rename, "Rename"));
+                    items.push((Action::Dns, "DNS servers"));
+                    items.push((Action::Ipv4, "IPv4 address"));
+                    items.push((Action::Routes, "Routes"));
+                    items.push((Action::Roaming, "Roaming"));
+                }
+                items.push((Action::Limit, "Bandwidth limit"));
+                items.push((Action::Forget, "Forget"));
+                saved.name.clone()
+            }
+            _ => return,
+        };
+        // The rest run through their key, so drop any left without one
+        items.retain(|(action, _)| Self::menu_runs_directly(*action) || self.keys.key_event(*action).is_some());
+        self.menu_index = 0;
+        self.modal = Some(Modal::Actions(ActionMenu { target, items }));
+    }
+
+    /// Profile actions the menu runs itself, as the Available list has no
+    /// keys for them.
+    fn menu_runs_directly(action: Action) -> bool {
+        matches!(action, Action::ShareQr | Action::ShowPassword | Action::Edit | Action::Forget)
+    }
+
+    /// Do what picking `action` for `target` in the actions menu means: the
+    /// same as pressing its key in the current view.
+    fn run_menu_action(&mut self, action: Action, target: &str, events: &EventLoop) {
+        if Self::menu_runs_directly(action) && self.bg_status != BgStatus::Idle {
+            return;
+        }
+        match action {
+            Action::ShareQr => {
+                self.bg_status = BgStatus::ReadingPassword;
+                events.send_task(Task::ShareNetwork(target.to_string()));
+            }
+            Action::ShowPassword => {
+                self.bg_status = BgStatus::ReadingPassword;
+                events.send_task(Task::RevealPassword(target.to_string()));
+            }
+            Action::Edit => {
+                self.bg_status = BgStatus::Checking;
+                events.send_task(Task::LoadProfileOptions(target.to_string()));
+            }
+            Action::Forget => self.modal = Some(Modal::ConfirmForget(target.to_string())),
+            _ => {
+                let Some(key) = self.keys.key_event(action) else {
+                    return;
+                };
+                match self.view {
+                    View::AvailableNetworks => self.handle_available_key(key, events),
+                    View::SavedNetworks => self.handle_saved_key(key, events),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    fn handle_saved_key(&mut self, key: KeyEvent, events: &EventLoop) {
+        if let Some(index) = self.moved_selection(&key, self.saved_index, self.visible_saved().len()) {
+            self.saved_index = index;
+            self.navigated_at = Some(Instant::now());
+            return;
+        }
+        match key.code {
+            _ if self.keys.is(&key, Action::Sort) => {
+                let selected = self.selected_saved().map(|saved| saved.name.clone());
+                self.saved_sort = self.saved_sort.next();
+                self.saved_index = selected
+                    .and_then(|name| self.visible_saved().iter().position(|&i| self.saved[i].name == name))
+                    .unwrap_or(0);
+                self.save_views();
+            }
+            _ if self.keys.is(&key, Action::Connect) => {
+                if self.bg_status != BgStatus::Idle {
+                    return;
+                }
+                if let Some(saved) = self.selected_saved() {
+                    if saved.active {
+                        self.show_message("Already connected to this network.".to_string());
+                        return;
+                    }
                     let name = saved.name.clone();
-                    self.modal = Some(Modal::ConfirmForget(name));
+                    if saved.hotspot {
+                        self.bg_status = BgStatus::StartingHotspot;
+                        events.send_task(Task::StartHotspot(name));
+                    } else {
+                        self.bg_status = BgStatus::Connecting;
+                        events.send_task(Task::Connect(self.device.clone(), name, None, self.connect_timeout));
+                    }
+                }
+            }
+            _ if self.keys.is(&key, Action::Menu) => self.open_menu(),
+            _ if self.keys.is(&key, Action::ShareQr) && self.bg_status == BgStatus::Idle => {
+                if let Some(name) = self.selected_saved().map(|saved| saved.name.clone()) {
+                    self.bg_status = BgStatus::ReadingPassword;
+                    events.send_task(Task::ShareNetwork(name));
+                }
+            }
+            _ if self.keys.is(&key, Action::Limit) => {
+                if let Some(name) = self.selected_saved().map(|saved| saved.name.clone()) {
+                    self.form = rate_limit_form(&name, self.rate_limits.get(&name).copied());
+                    self.form_target = name;
+                    self.modal = Some(Modal::Form(FormKind::RateLimit));
                 }
             }
-            KeyCode::Char('d') | KeyCode::Char('D') => {
-                if self.bg_status == BgStatus::Idle && self.status.ssid.is_some() {
-                    self.modal = Some(Modal::ConfirmDisconnect);
+            _ if self.keys.is(&key, Action::Roaming) => {
+                let selected = self.selected_saved().filter(|saved| !saved.hotspot);
+                if let Some(name) = selected.map(|saved| saved.name.clone()) {
+                    if !self.backend.supports_bgscan() {
+                        self.show_message(
+                            "iwd decides when to roam itself. Set RoamThreshold in /etc/iwd/main.conf to tune it."
+                                .to_string(),
+                        );
+                    } else {
+                        self.form = roaming_form(&name, self.roam_settings.get(&name).copied());
+                        self.form_target = name;
+                        self.modal = Some(Modal::Form(FormKind::Roaming));
+                    }
+                }
+            }
+            _ if self.keys.is(&key, Action::Edit) && self.bg_status == BgStatus::Idle => {
+                let selected = self.selected_saved().filter(|saved| !saved.hotspot);
+                if let Some(name) = selected.map(|saved| saved.name.clone()) {
+                    self.bg_status = BgStatus::Checking;
+                    events.send_task(Task::LoadProfileOptions(name));
+                }
+            }
+            _ if self.keys.is(&key, Action::Rename) => {
+                let selected = self.selected_saved().filter(|saved| !saved.hotspot);
+                if let Some(name) = selected.map(|saved| saved.name.clone()) {
+                    self.form = rename_form(&name);
+                    self.form_target = name;
+                    self.modal = Some(Modal::Form(FormKind::Rename));
+                }
+            }
+            _ if self.keys.is(&key, Action::Dns) && self.bg_status == BgStatus::Idle => {
+                let selected = self.selected_saved().filter(|saved| !saved.hotspot);
+                if let Some(name) = selected.map(|saved| saved.name.clone()) {
+                    self.bg_status = BgStatus::Checking;
+                    events.send_task(Task::LoadDns(name));
+                }
+            }
+            _ if self.keys.is(&key, Action::Ipv4) && self.bg_status == BgStatus::Idle => {
+                let selected = self.selected_saved().filter(|saved| !saved.hotspot);
+                if let Some(name) = selected.map(|saved| saved.name.clone()) {
+                    self.bg_status = BgStatus::Checking;
+                    events.send_task(Task::LoadIpv4(name));
+                }
+            }
+            _ if self.keys.is(&key, Action::Routes) && self.bg_status == BgStatus::Idle => {
+                if let Some(name) = self.selected_saved().map(|saved| saved.name.clone()) {
+                    self.bg_status = BgStatus::Checking;
+                    events.send_task(Task::LoadRoutes(name));
+                }
+            }
+            _ if (self.keys.is(&key, Action::RaiseMetric) || self.keys.is(&key, Action::LowerMetric))
+                && self.bg_status == BgStatus::Idle =>
+            {
+                let change = if self.keys.is(&key, Action::RaiseMetric) {
+                    ROUTE_METRIC_STEP
+                } else {
+                    -ROUTE_METRIC_STEP
+                };
+                let selected = self.selected_saved().filter(|saved| !saved.hotspot);
+                if let Some(name) = selected.map(|saved| saved.name.clone()) {
+                    self.change_route_metric(name, MetricChange::Adjust(change), events);
+                }
+            }
+            _ if self.keys.is(&key, Action::NewHotspot) => {
+                self.form = hotspot_form(&self.device);
+                self.modal = Some(Modal::Form(FormKind::Hotspot));
+            }
+            _ if self.keys.is(&key, Action::Forget) => {
+                if self.bg_status != BgStatus::Idle {
+                    return;
+                }
+                if let Some(saved) = self.selected_saved() {
+                    let name = saved.name.clone();
+                    self.modal = Some(Modal::ConfirmForget(name));
                 }
             }
+            _ if self.keys.is(&key, Action::Disconnect)
+                && self.bg_status == BgStatus::Idle
+                && self.status.ssid.is_some() =>
+            {
+                self.modal = Some(Modal::ConfirmDisconnect);
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_hotspot_key(&mut self, key: KeyEvent, events: &EventLoop) {
+        if self.bg_status != BgStatus::Idle {
+            return;
+        }
+        let active = self.active_hotspot().map(|hotspot| hotspot.name.clone());
+        match active {
+            None if self.keys.is(&key, Action::Connect) => {
+                self.form = start_hotspot_form();
+                self.modal = Some(Modal::Form(FormKind::StartHotspot));
+            }
+            Some(name) if self.keys.is(&key, Action::StopHotspot) => {
+                self.bg_status = BgStatus::StoppingHotspot;
+                events.send_task(Task::StopHotspot(name));
+            }
             _ => {}
         }
     }
 
+    fn handle_monitor_key(&mut self, key: KeyEvent) {
+        if self.keys.is(&key, Action::ZoomIn) {
+            self.monitor_window = self.monitor_window.saturating_sub(1);
+        } else if self.keys.is(&key, Action::ZoomOut) {
+            self.monitor_window = (self.monitor_window + 1).min(MONITOR_WINDOWS.len() - 1);
+        }
+    }
+
+    fn handle_analyzer_key(&mut self, key: KeyEvent) {
+        if self.keys.is(&key, Action::Band) {
+            self.analyzer_band = self.analyzer_band.next();
+        }
+    }
+
+    /// What confirming the open dialog will run or change, if
+    /// `confirm_details` is on.
+    pub fn confirm_summary(&self) -> Option<String> {
+        if !self.confirm_details {
+            return None;
+        }
+        let (dns, routes, ipv4);
+        let change = match self.modal.as_ref()? {
+            Modal::ConfirmDisconnect => Change::Disconnect,
+            Modal::ConfirmForget(name) => Change::Forget(name),
+            Modal::ConfirmApply(FormKind::Dns) => {
+                dns = dns_config(&self.form);
+                Change::Dns(&self.form_target, &dns)
+            }
+            Modal::ConfirmApply(FormKind::Ipv4) => {
+                ipv4 = ipv4_config(&self.form).ok()?;
+                Change::Ipv4(&self.form_target, &ipv4)
+            }
+            Modal::ConfirmApply(_) => {
+                routes = route_config(&self.form);
+                Change::Routes(&self.form_target, &routes)
+            }
+            Modal::ConfirmMetric(name, metric) => Change::RouteMetric(name, *metric),
+            _ => return None,
+        };
+        Some(self.backend.change_summary(&self.device, &change))
+    }
+
+    /// The hotspot profile currently running, if any.
+    pub fn active_hotspot(&self) -> Option<&SavedNetwork> {
+        self.saved.iter().find(|saved| saved.hotspot && saved.active)
+    }
+
     fn handle_modal_key(&mut self, key: KeyEvent, modal: &Modal, events: &EventLoop) {
         match modal {
-            Modal::PasswordInput => {
-                // Ctrl+U clears password, Ctrl+W deletes last word
-                if key.modifiers.contains(KeyModifiers::CONTROL) {
-                    match key.code {
-                        KeyCode::Char('u') => self.password.clear(),
-                        KeyCode::Char('w') => {
-                            let trimmed = self.password.trim_end().to_string();
-                            if let Some(pos) = trimmed.rfind(' ') {
-                                self.password = trimmed[..=pos].to_string();
-                            } else {
-                                self.password.clear();
-                            }
-                        }
-                        _ => {}
+            Modal::PasswordInput => match key.code {
+                KeyCode::Esc => {
+                    self.modal = None;
+                    self.password.clear();
+                    if let Some(request) = self.pending_secret.take() {
+                        request.reply(None);
                     }
-                } else {
-                    match key.code {
-                        KeyCode::Esc => {
-                            self.modal = None;
-                            self.password.clear();
-                        }
-                        KeyCode::Enter => {
-                            let ssid = self.password_target_ssid.clone();
-                            let pw = self.password.clone();
-                            self.modal = None;
-                            self.bg_status = BgStatus::Connecting;
-                            events.send_task(Task::Connect(ssid, Some(pw)));
-                        }
-                        KeyCode::Backspace => {
-                            self.password.pop();
-                        }
-                        KeyCode::Tab => {
-                            self.password_visible = !self.password_visible;
-                        }
-                        KeyCode::Char(c) => {
-                            self.password.push(c);
-                        }
-                        _ => {}
+                }
+                KeyCode::Enter if self.pending_secret.is_some() => {
+                    let pw = self.password.value().to_string();
+                    self.modal = None;
+                    self.password.clear();
+                    if let Some(request) = self.pending_secret.take() {
+                        request.reply(Some(pw));
                     }
                 }
-            }
+                KeyCode::Enter => {
+                    let ssid = self.password_target_ssid.clone();
+                    let pw = self.password.value().to_string();
+                    self.modal = None;
+                    self.bg_status = BgStatus::Connecting;
+                    events.send_task(Task::Connect(self.device.clone(), ssid, Some(pw), self.connect_timeout));
+                }
+                KeyCode::Tab => {
+                    self.password_visible = !self.password_visible;
+                }
+                _ if self.keys.is(&key, Action::FetchPassword) => {
+                    self.pending_password_fetch = self.password_command.is_some();
+                }
+                _ => {
+                    self.password.handle_key(key);
+                }
+            },
             Modal::ConfirmDisconnect => match key.code {
-                KeyCode::Char('y') | KeyCode::Char('Y') => {
+                _ if self.keys.is(&key, Action::Confirm) => {
                     self.modal = None;
                     self.bg_status = BgStatus::Disconnecting;
                     events.send_task(Task::Disconnect(self.device.clone()));
@@ -261,7 +2199,7 @@ impl App {
             Modal::ConfirmForget(name) => {
                 let name = name.clone();
                 match key.code {
-                    KeyCode::Char('y') | KeyCode::Char('Y') => {
+                    _ if self.keys.is(&key, Action::Confirm) => {
                         self.modal = None;
                         self.bg_status = BgStatus::Forgetting;
                         events.send_task(Task::Forget(name));
@@ -271,38 +2209,695 @@ impl App {
                     }
                 }
             }
-            Modal::Message(_) => {
-                // Any key dismisses
-                self.modal = None;
+            Modal::Dispatcher => match key.code {
+                KeyCode::Esc => self.modal = None,
+                _ if self.keys.is(&key, Action::Quit) => self.modal = None,
+                _ if self.keys.is(&key, Action::Up) => {
+                    self.dispatcher_index = self.dispatcher_index.saturating_sub(1);
+                }
+                _ if self.keys.is(&key, Action::Down) => {
+                    self.dispatcher_index = (self.dispatcher_index + 1).min(self.dispatcher_scripts.len().saturating_sub(1));
+                }
+                _ if (key.code == KeyCode::Char(' ') || self.keys.is(&key, Action::Connect))
+                    && self.bg_status == BgStatus::Idle =>
+                {
+                    if let Some(script) = self.dispatcher_scripts.get(self.dispatcher_index) {
+                        self.bg_status = BgStatus::Saving;
+                        events.send_task(Task::SetDispatcherEnabled(
+                            script.name.clone(),
+                            !script.enabled,
+                        ));
+                    }
+                }
+                KeyCode::Char('a') | KeyCode::Char('A') if self.bg_status == BgStatus::Idle => {
+                    self.form = dispatcher_form();
+                    self.modal = Some(Modal::Form(FormKind::DispatcherScript));
+                }
+                _ => {}
+            },
+            Modal::Actions(menu) => match key.code {
+                KeyCode::Esc => self.modal = None,
+                _ if self.keys.is(&key, Action::Quit) || self.keys.is(&key, Action::Menu) => self.modal = None,
+                _ if self.keys.is(&key, Action::Up) => {
+                    self.menu_index = self.menu_index.saturating_sub(1);
+                }
+                _ if self.keys.is(&key, Action::Down) => {
+                    self.menu_index = (self.menu_index + 1).min(menu.items.len().saturating_sub(1));
+                }
+                _ if self.keys.is(&key, Action::Connect) => {
+                    if let Some(&(action, _)) = menu.items.get(self.menu_index) {
+                        self.modal = None;
+                        self.run_menu_action(action, &menu.target, events);
+                    }
+                }
+                _ => {}
+            },
+            Modal::Form(FormKind::DispatcherScript) if key.code == KeyCode::Esc => {
+                // Back to the script list rather than all the way out
+                self.modal = Some(Modal::Dispatcher);
+            }
+            Modal::Form(kind) => match self.form.handle_key(key) {
+                FormAction::None => {}
+                FormAction::Cancel => self.modal = None,
+                FormAction::Submit if *kind == FormKind::Ipv4 && ipv4_config(&self.form).is_err() => {
+                    self.form.error = ipv4_config(&self.form).err();
+                }
+                FormAction::Submit
+                    if self.confirm_details && matches!(kind, FormKind::Dns | FormKind::Routes | FormKind::Ipv4) =>
+                {
+                    self.modal = Some(Modal::ConfirmApply(*kind));
+                }
+                FormAction::Submit => {
+                    self.modal = None;
+                    self.submit_form(*kind, events);
+                }
+            },
+            Modal::ConfirmApply(kind) => match key.code {
+                _ if self.keys.is(&key, Action::Confirm) => {
+                    self.modal = None;
+                    self.submit_form(*kind, events);
+                }
+                // Back to the form, to fix what the summary showed
+                _ => self.modal = Some(Modal::Form(*kind)),
+            },
+            Modal::ConfirmMetric(name, metric) => {
+                let (name, metric) = (name.clone(), *metric);
+                self.modal = None;
+                if self.keys.is(&key, Action::Confirm) {
+                    self.send_route_metric(name, metric, events);
+                }
+            }
+            Modal::ConfirmQuit => match key.code {
+                _ if self.keys.is(&key, Action::Confirm) => {
+                    self.running = false;
+                }
+                _ => {
+                    self.modal = None;
+                }
+            },
+            Modal::Password(_, password) if self.keys.is(&key, Action::Copy) && !password.is_empty() => {
+                match clipboard::copy(password) {
+                    Ok(()) => self.show_toast("Password copied to the clipboard.".to_string()),
+                    Err(e) => self.show_warning(e),
+                }
+                self.modal = None;
+            }
+            Modal::CaptivePortal(url) if self.keys.is(&key, Action::Confirm) => {
+                match internet::open_in_browser(url) {
+                    Ok(()) => {
+                        self.modal = None;
+                        self.show_toast("Opened the sign-in page.".to_string());
+                    }
+                    Err(e) => self.show_message(e),
+                }
+            }
+            Modal::CaptivePortal(url) if self.keys.is(&key, Action::Copy) => {
+                match clipboard::copy(url) {
+                    Ok(()) => self.show_toast("Sign-in address copied to the clipboard.".to_string()),
+                    Err(e) => self.show_warning(e),
+                }
+                self.modal = None;
+            }
+            Modal::Connectivity(check) if self.keys.is(&key, Action::Connectivity) && check.available => {
+                if self.bg_status == BgStatus::Idle {
+                    self.bg_status = BgStatus::Saving;
+                    events.send_task(Task::SetConnectivityCheck(!check.enabled));
+                }
+            }
+            Modal::Messages => {
+                if let Some(index) = self.moved_selection(&key, self.message_index, self.messages.len()) {
+                    self.message_index = index;
+                } else if key.code == KeyCode::Esc || self.keys.is(&key, Action::Messages) {
+                    self.modal = None;
+                }
+            }
+            Modal::Message(_)
+            | Modal::Checks(..)
+            | Modal::Qr(_)
+            | Modal::Password(..)
+            | Modal::SignalHistory
+            | Modal::NetworkDetails(_)
+            | Modal::Connectivity(_)
+            | Modal::CaptivePortal(_) => {
+                // Any key dismisses
+                self.modal = None;
+            }
+        }
+    }
+
+    /// Whether the last scan showed `ssid` without security.
+    fn is_open_network(&self, ssid: &str) -> bool {
+        self.networks.iter().any(|net| net.ssid == ssid && net.is_open())
+    }
+
+    fn is_enterprise_network(&self, ssid: &str) -> bool {
+        self.networks.iter().any(|net| net.ssid == ssid && net.is_enterprise())
+    }
+
+    /// A click, with `mouse` on. Only the status bar's segments respond.
+    pub fn handle_mouse(&mut self, mouse: MouseEvent, events: &EventLoop) {
+        if mouse.kind != MouseEventKind::Down(MouseButton::Left) || self.modal.is_some() {
+            return;
+        }
+        let at = Position::new(mouse.column, mouse.row);
+        let target = self
+            .status_targets
+            .borrow()
+            .iter()
+            .find(|(area, _)| area.contains(at))
+            .map(|(_, target)| *target);
+        match target {
+            Some(StatusTarget::Ssid) => {
+                if let Some(net) = self.networks.iter().find(|net| net.in_use).cloned() {
+                    self.device_mac = device_mac(&self.device);
+                    self.modal = Some(Modal::NetworkDetails(net));
+                }
+            }
+            Some(StatusTarget::Signal | StatusTarget::SignalTrend) => self.modal = Some(Modal::SignalHistory),
+            Some(StatusTarget::Spinner) => self.cancel_operation(events),
+            None => {}
+        }
+    }
+
+    /// Stop the running operation. A connect is also stopped in
+    /// NetworkManager, which would otherwise carry on activating.
+    fn cancel_operation(&mut self, events: &EventLoop) {
+        if self.cancelling || self.bg_status == BgStatus::Idle {
+            return;
+        }
+        if !self.bg_status.cancellable() {
+            self.show_toast("This can't be cancelled halfway.".to_string());
+            return;
+        }
+        self.cancelling = true;
+        if self.bg_status == BgStatus::Connecting {
+            events.cancel_activation(&self.device);
+        }
+        events.cancel_task();
+        self.show_toast("Cancelling...".to_string());
+    }
+
+    fn show_toast(&mut self, msg: String) {
+        self.push_toast(msg, false);
+    }
+
+    fn show_warning(&mut self, msg: String) {
+        self.push_toast(msg, true);
+    }
+
+    /// Show `msg` in a modal until a key is pressed.
+    fn show_message(&mut self, msg: String) {
+        self.log_message(MessageKind::Error, &msg);
+        self.modal = Some(Modal::Message(msg));
+    }
+
+    /// Keep `text` in the message history, and the log file.
+    fn log_message(&mut self, kind: MessageKind, text: &str) {
+        match kind {
+            MessageKind::Info => tracing::info!("{}", text),
+            MessageKind::Warning | MessageKind::Error => tracing::warn!("{}", text),
+        }
+        if self.messages.len() == MESSAGE_HISTORY {
+            self.messages.pop_front();
+        }
+        self.messages.push_back(LoggedMessage { at: Instant::now(), kind, text: text.to_string() });
+    }
+
+    fn push_toast(&mut self, text: String, warning: bool) {
+        self.log_message(if warning { MessageKind::Warning } else { MessageKind::Info }, &text);
+        // The same message again just stays up longer
+        self.toasts.retain(|toast| toast.text != text);
+        if self.toasts.len() == MAX_TOASTS {
+            self.toasts.remove(0);
+        }
+        let ticks_left = ticks(TOAST_TIME, self.tick_rate);
+        self.toasts.push(Toast { text, warning, ticks_left });
+    }
+
+    /// Open the password modal for connecting to `ssid` as a new network.
+    fn prompt_password(&mut self, ssid: String) {
+        self.password.clear();
+        self.password_visible = false;
+        self.password_target_ssid = ssid;
+        self.password_status = None;
+        self.password_error = None;
+        // Ask the password manager right away, so nothing needs typing
+        self.pending_password_fetch = self.password_command.is_some();
+        self.modal = Some(Modal::PasswordInput);
+    }
+
+    /// Ask for `ssid`'s password again after `error`, keeping what was typed
+    /// (selected, so typing replaces it) to fix a typo. The password manager
+    /// isn't asked again since its answer was likely the one that failed.
+    fn reprompt_password(&mut self, ssid: String, error: String) {
+        self.log_message(MessageKind::Error, &error);
+        let typed = self.password.value().to_string();
+        self.prompt_password(ssid);
+        self.pending_password_fetch = false;
+        self.password_error = Some(error);
+        self.password.set_value(&typed);
+        self.password.select_all();
+    }
+
+    /// NetworkManager needs a password mid-activation: prompt for it.
+    pub fn handle_secret_request(&mut self, request: SecretRequest) {
+        let ssid = request.ssid.clone();
+        // Only one prompt at a time; a superseded request is cancelled
+        let retry = request.retry;
+        if let Some(previous) = self.pending_secret.replace(request) {
+            previous.reply(None);
+        }
+        if retry {
+            self.reprompt_password(ssid, "Incorrect password. Please try again.".to_string());
+        } else {
+            self.prompt_password(ssid);
+        }
+    }
+
+    /// NetworkManager gave up waiting for the password.
+    pub fn handle_secret_cancelled(&mut self) {
+        self.pending_secret = None;
+        if self.modal == Some(Modal::PasswordInput) {
+            self.modal = None;
+            self.password.clear();
+        }
+    }
+
+    /// Handle pasted text: goes to whichever text input has focus.
+    pub fn handle_paste(&mut self, text: &str) {
+        match self.modal {
+            Some(Modal::PasswordInput) => self.password.insert_str(text),
+            Some(Modal::Form(_)) => self.form.handle_paste(text),
+            _ => {}
+        }
+    }
+
+    fn submit_form(&mut self, kind: FormKind, events: &EventLoop) {
+        match kind {
+            FormKind::Hotspot => {
+                self.bg_status = BgStatus::Saving;
+                events.send_task(Task::SaveHotspot(hotspot_config(&self.form)));
+            }
+            FormKind::DispatcherScript => {
+                self.bg_status = BgStatus::Saving;
+                events.send_task(Task::CreateDispatcherScript(
+                    self.form.value("Name").to_string(),
+                    self.device.clone(),
+                    DispatcherEvent::ALL[self.form.selected("Run when")],
+                    self.form.value("Command").to_string(),
+                ));
+            }
+            FormKind::RateLimit => {
+                let name = self.form_target.clone();
+                match self.form.value(RATE_LIMIT_LABEL).trim().parse::<u32>() {
+                    Ok(kbit) => self.rate_limits.insert(name, kbit),
+                    Err(_) => self.rate_limits.remove(&name),
+                };
+                // Applied (or cleared) on the next tick if the profile is active
+                match ratelimit::save(&self.rate_limits) {
+                    Ok(()) => self.show_toast("Saved bandwidth limit.".to_string()),
+                    Err(e) => self.show_message(e),
+                }
+            }
+            FormKind::Roaming => {
+                let name = self.form_target.clone();
+                if self.form.selected(ROAM_MODE_LABEL) == 1 {
+                    // Already validated
+                    let bgscan = Bgscan {
+                        short_secs: self.form.value(ROAM_SHORT_LABEL).trim().parse().unwrap_or(30),
+                        threshold: self.form.value(ROAM_THRESHOLD_LABEL).trim().parse().unwrap_or(-65),
+                        long_secs: self.form.value(ROAM_LONG_LABEL).trim().parse().unwrap_or(300),
+                    };
+                    self.roam_settings.insert(name, bgscan);
+                } else {
+                    self.roam_settings.remove(&name);
+                }
+                // Applied on the next tick if the profile is active
+                match roaming::save(&self.roam_settings) {
+                    Ok(()) => self.show_toast("Saved roaming settings.".to_string()),
+                    Err(e) => self.show_message(e),
+                }
+            }
+            FormKind::Profile => {
+                let options = ProfileOptions {
+                    autoconnect: self.form.selected(PROFILE_AUTOCONNECT_LABEL) == 0,
+                    // Already validated
+                    priority: self.form.value(PROFILE_PRIORITY_LABEL).trim().parse().unwrap_or(0),
+                    metered: Metered::ALL[self.form.selected(PROFILE_METERED_LABEL)],
+                    mac: match self.form.value(PROFILE_CUSTOM_MAC_LABEL).trim() {
+                        "" => ClonedMac::CHOICES[self.form.selected(PROFILE_MAC_LABEL)].clone(),
+                        mac => ClonedMac::Custom(mac.to_uppercase()),
+                    },
+                };
+                self.bg_status = BgStatus::Saving;
+                events.send_task(Task::SetProfileOptions(self.form_target.clone(), options));
+            }
+            FormKind::Rename => {
+                let new_name = self.form.value("Name").trim().to_string();
+                if new_name != self.form_target {
+                    self.bg_status = BgStatus::Saving;
+                    events.send_task(Task::RenameProfile(self.form_target.clone(), new_name));
+                }
+            }
+            FormKind::Dns => {
+                let config = dns_config(&self.form);
+                self.bg_status = BgStatus::Saving;
+                events.send_task(Task::SetDns(self.device.clone(), self.form_target.clone(), config));
+            }
+            FormKind::Ipv4 => {
+                let Ok(config) = ipv4_config(&self.form) else {
+                    return;
+                };
+                self.bg_status = BgStatus::Saving;
+                events.send_task(Task::SetIpv4(self.device.clone(), self.form_target.clone(), config));
+            }
+            FormKind::Enterprise => {
+                self.bg_status = BgStatus::Connecting;
+                events.send_task(Task::ConnectEnterprise(
+                    self.device.clone(),
+                    self.form_target.clone(),
+                    eap_config(&self.form),
+                ));
+            }
+            FormKind::Routes => {
+                let config = route_config(&self.form);
+                self.bg_status = BgStatus::Saving;
+                events.send_task(Task::SetRoutes(self.device.clone(), self.form_target.clone(), config));
+            }
+            FormKind::StartHotspot => {
+                self.bg_status = BgStatus::StartingHotspot;
+                events.send_task(Task::CreateHotspot(
+                    self.device.clone(),
+                    self.form.value("SSID").to_string(),
+                    self.form.value("Password").to_string(),
+                ));
+            }
+        }
+    }
+
+    /// Carry wifi-tui's own per-profile settings over to a renamed profile.
+    fn rename_settings(&mut self, name: &str, new_name: &str) {
+        if let Some(kbit) = self.rate_limits.remove(name) {
+            self.rate_limits.insert(new_name.to_string(), kbit);
+            if let Err(e) = ratelimit::save(&self.rate_limits) {
+                self.show_message(e);
+            }
+        }
+        if let Some(bgscan) = self.roam_settings.remove(name) {
+            self.roam_settings.insert(new_name.to_string(), bgscan);
+            if let Err(e) = roaming::save(&self.roam_settings) {
+                self.show_message(e);
             }
         }
+        if self.call_mode.as_deref() == Some(name) {
+            self.call_mode = Some(new_name.to_string());
+        }
     }
 
     /// Handle a tick event - auto-refresh, spinner.
     pub fn handle_tick(&mut self, events: &EventLoop) {
         self.spinner_frame = (self.spinner_frame + 1) % 4;
+        self.quit_armed_ticks = self.quit_armed_ticks.saturating_sub(1);
+        for toast in &mut self.toasts {
+            toast.ticks_left = toast.ticks_left.saturating_sub(1);
+        }
+        self.toasts.retain(|toast| toast.ticks_left > 0);
+
+        if let Some(devices) = self.detected_devices.take() {
+            self.update_devices(devices, events);
+        }
 
         self.ticks_since_scan += 1;
-        if self.ticks_since_scan >= AUTO_REFRESH_TICKS && self.bg_status == BgStatus::Idle {
+        let refresh_ticks = if self.call_mode.is_some() {
+            ticks(CALL_MODE_REFRESH, self.tick_rate)
+        } else {
+            self.auto_refresh_ticks
+        };
+        let navigating = self.navigated_at.is_some_and(|at| at.elapsed() < NAVIGATION_HOLD);
+        let scan_due =
+            self.auto_scan && !self.refresh_paused && !navigating && self.ticks_since_scan >= refresh_ticks;
+        if (scan_due || self.scan_requested)
+            && self.bg_status == BgStatus::Idle
+            && !self.low_data
+            && !self.waiting_for_adapter
+        {
             self.start_scan(events);
         }
+
+        if self.status.ssid.is_some()
+            && !self.polling_signal
+            && self.ticks_since_scan.is_multiple_of(ticks(SIGNAL_INTERVAL, self.tick_rate))
+        {
+            self.polling_signal = true;
+            // The Monitor view also plots the link rate, which takes the full read
+            if self.view == View::Monitor {
+                events.send_task(Task::RefreshStatus(self.device.clone()));
+            } else {
+                events.send_task(Task::RefreshSignal(self.device.clone()));
+            }
+        }
+
+        if self.traffic_sample.is_none_or(|(at, _)| at.elapsed() >= THROUGHPUT_INTERVAL) {
+            self.sample_traffic();
+        }
+        if let (Some(interval), Some(_), false) = (self.ping_interval, &self.status.ssid, self.pinging) {
+            if self.pinged_at.is_none_or(|at| at.elapsed() >= interval) {
+                self.pinging = true;
+                self.pinged_at = Some(Instant::now());
+                events.ping(&self.device, &self.ping_host);
+            }
+        }
+        // Without an address yet there's no point asking
+        if self.status.ip.is_some()
+            && !self.internet_probe_url.is_empty()
+            && !self.checking_internet
+            && self.internet_checked_at.is_none_or(|at| at.elapsed() >= INTERNET_CHECK_INTERVAL)
+        {
+            self.checking_internet = true;
+            self.internet_checked_at = Some(Instant::now());
+            events.check_internet(&self.internet_probe_url);
+        }
+        if self.usage_changed && self.usage_saved_at.elapsed() >= USAGE_SAVE_INTERVAL {
+            self.save_usage();
+        }
+
+        // Unplugging can beat `nmcli monitor`, and iwd has no monitor at all
+        if self.ticks_since_scan.is_multiple_of(ticks(DEVICE_CHECK_INTERVAL, self.tick_rate)) {
+            let present = device_present(&self.device);
+            if (self.device_seen && !present) || self.waiting_for_adapter {
+                self.pending_device_check = true;
+            }
+            self.device_seen = present;
+        }
+        if self.pending_device_check && !self.detecting_devices {
+            self.pending_device_check = false;
+            self.detecting_devices = true;
+            events.send_task(Task::DetectDevices);
+        }
+
+        if self.bg_status == BgStatus::Idle {
+            // A cancelled task that finished anyway
+            self.cancelling = false;
+        }
+        if self.bg_status != BgStatus::Connecting {
+            self.connect_stage = None;
+        } else if !self.polling_connect_stage
+            && self.ticks_since_scan.is_multiple_of(ticks(CONNECT_STAGE_INTERVAL, self.tick_rate))
+        {
+            self.polling_connect_stage = true;
+            events.poll_connect_stage(&self.device);
+        }
+
+        if self.view == View::Hotspot
+            && self.bg_status == BgStatus::Idle
+            && self.ticks_since_scan.is_multiple_of(ticks(HOTSPOT_CLIENT_INTERVAL, self.tick_rate))
+            && self.active_hotspot().is_some()
+        {
+            events.send_task(Task::RefreshHotspotClients(self.device.clone()));
+        }
+
+        if self.view == View::Hotspot && self.bg_status == BgStatus::Idle && !self.loading_hotspot_qr {
+            let stale = self
+                .active_hotspot()
+                .filter(|hotspot| self.hotspot_qr.as_ref().is_none_or(|(name, _)| *name != hotspot.name))
+                .map(|hotspot| hotspot.name.clone());
+            if let Some(name) = stale {
+                self.loading_hotspot_qr = true;
+                events.send_task(Task::LoadHotspotQr(name));
+            }
+        }
+
+        if self.bg_status == BgStatus::Idle {
+            self.sync_rate_limit(events);
+            self.sync_bgscan(events);
+        }
+
+        if std::mem::take(&mut self.pending_password_fetch) && self.modal == Some(Modal::PasswordInput) {
+            if let Some(command) = &self.password_command {
+                self.password_status = Some("Asking the password manager...".to_string());
+                events.send_task(Task::FetchPassword(command.clone(), self.password_target_ssid.clone()));
+            }
+        }
+
+        if let Some(name) = self.pending_make_temporary.take() {
+            events.send_task(Task::MakeTemporary(name));
+        }
+        if self.bg_status == BgStatus::Idle {
+            self.sync_temporary(events);
+        }
+
+        if let Some(report) = self.pending_drop_report.take() {
+            events.send_task(Task::CaptureDiagnostics(report));
+        }
+
+        // Lightweight refresh for external changes. Status reads have their
+        // own worker, so they don't wait for a running scan or connect
+        if !self.waiting_for_adapter && std::mem::take(&mut self.pending_status_refresh) {
+            events.send_task(Task::RefreshStatus(self.device.clone()));
+            events.send_task(Task::RefreshUplinks);
+            events.send_task(Task::RefreshRfkill(self.device.clone()));
+        }
+        // A running scan re-reads the saved profiles itself
+        if self.bg_status == BgStatus::Idle
+            && !self.waiting_for_adapter
+            && std::mem::take(&mut self.pending_saved_refresh)
+        {
+            events.send_task(Task::RefreshSaved);
+        }
+    }
+
+    pub fn handle_log_line(&mut self, line: String) {
+        if self.logs.len() == LOG_LINES {
+            self.logs.pop_front();
+        }
+        self.logs.push_back(line);
+    }
+
+    /// Apply the active profile's bandwidth limit, or remove ours once it's
+    /// no longer wanted (disconnected, switched network, limit removed).
+    fn sync_rate_limit(&mut self, events: &EventLoop) {
+        let wanted = self
+            .saved
+            .iter()
+            .find(|saved| saved.active && !saved.hotspot)
+            .and_then(|saved| {
+                let kbit = self.rate_limits.get(&saved.name)?;
+                Some((saved.name.clone(), *kbit))
+            });
+        if wanted == self.applied_limit {
+            return;
+        }
+        match &wanted {
+            Some((_, kbit)) => events.send_task(Task::ApplyRateLimit(self.device.clone(), *kbit)),
+            None => events.send_task(Task::ClearRateLimit(self.device.clone())),
+        }
+        // Recorded up front so a failure doesn't re-prompt for a password every tick
+        self.applied_limit = wanted;
+    }
+
+    /// Delete temporary profiles once they're no longer connected.
+    fn sync_temporary(&mut self, events: &EventLoop) {
+        let mut done = Vec::new();
+        for (name, seen_active) in &mut self.temporary_profiles {
+            match self.saved.iter().find(|saved| saved.name == *name) {
+                Some(saved) if saved.active => *seen_active = true,
+                // Forgotten by hand already
+                None if *seen_active => done.push((name.clone(), false)),
+                _ if *seen_active => done.push((name.clone(), true)),
+                _ => {}
+            }
+        }
+        for (name, saved) in done {
+            self.temporary_profiles.remove(&name);
+            if saved {
+                events.send_task(Task::RemoveTemporary(name));
+            }
+        }
+    }
+
+    /// Apply the active profile's background scan after each connect. Going
+    /// back to NetworkManager's needs nothing: it sets its own on reconnect.
+    fn sync_bgscan(&mut self, events: &EventLoop) {
+        let wanted = self
+            .saved
+            .iter()
+            .find(|saved| saved.active && !saved.hotspot)
+            .and_then(|saved| {
+                let bgscan = self.roam_settings.get(&saved.name)?;
+                Some((saved.name.clone(), *bgscan))
+            });
+        if wanted == self.applied_bgscan || !self.backend.supports_bgscan() {
+            return;
+        }
+        if let Some((_, bgscan)) = &wanted {
+            events.send_task(Task::ApplyBgscan(self.device.clone(), *bgscan));
+        }
+        // Recorded up front so a failure doesn't re-prompt for a password every tick
+        self.applied_bgscan = wanted;
+    }
+
+    /// Something changed outside the TUI; batch it into the next tick.
+    pub fn handle_network_changed(&mut self, change: MonitorChange) {
+        match change {
+            MonitorChange::Status => self.pending_status_refresh = true,
+            MonitorChange::Saved => {
+                self.pending_status_refresh = true;
+                self.pending_saved_refresh = true;
+            }
+            MonitorChange::Devices => self.pending_device_check = true,
+        }
     }
 
-    /// Start a scan + status refresh. Queues 3 serialized tasks.
+    /// Load everything at once instead of waiting for the first tick: the
+    /// scan runs on the main worker while the status, saved profiles and
+    /// devices come from the status worker, each shown as it arrives.
+    pub fn start(&mut self, events: &EventLoop) {
+        self.start_scan(events);
+        self.detecting_devices = true;
+        events.send_task(Task::DetectDevices);
+    }
+
+    /// Start a scan + status refresh.
     fn start_scan(&mut self, events: &EventLoop) {
-        self.bg_status = BgStatus::Scanning;
-        self.ticks_since_scan = 0;
-        self.pending_scan_tasks = 3;
-        events.send_task(Task::Scan(self.device.clone()));
-        events.send_task(Task::RefreshStatus(self.device.clone()));
-        events.send_task(Task::RefreshSaved);
+        self.start_refresh(events, true, true);
+    }
+
+    /// Rescan for networks (`scan`), re-read the status and saved profiles
+    /// (`state`), or both; on slow systems each nmcli call counts.
+    fn start_refresh(&mut self, events: &EventLoop, scan: bool, state: bool) {
+        if self.waiting_for_adapter {
+            self.show_toast("Waiting for a WiFi adapter.".to_string());
+            return;
+        }
+        self.bg_status = if scan { BgStatus::Scanning } else { BgStatus::Refreshing };
+        self.pending_scan_tasks = 0;
+        if scan {
+            self.ticks_since_scan = 0;
+            self.scan_requested = false;
+            self.pending_scan_tasks += 1;
+            events.send_task(Task::Scan(self.device.clone()));
+        }
+        if state {
+            self.pending_scan_tasks += 1;
+            events.send_task(Task::RefreshSaved);
+            // Not counted: status reads run on their own worker and may come
+            // back before or after the scan
+            events.send_task(Task::RefreshStatus(self.device.clone()));
+            events.send_task(Task::RefreshUplinks);
+        }
+        events.send_task(Task::RefreshRfkill(self.device.clone()));
     }
 
-    /// Decrement pending scan task counter; set Idle when all done.
+    /// Decrement pending scan task counter; set Idle when all done. Saved
+    /// refreshes outside a scan (from `nmcli monitor`) don't count.
     fn scan_task_done(&mut self) {
+        if !matches!(self.bg_status, BgStatus::Scanning | BgStatus::Refreshing) {
+            return;
+        }
         self.pending_scan_tasks = self.pending_scan_tasks.saturating_sub(1);
-        if self.pending_scan_tasks == 0 && self.bg_status == BgStatus::Scanning {
+        if self.pending_scan_tasks == 0 {
             self.bg_status = BgStatus::Idle;
+            self.stale = false;
         }
     }
 
@@ -310,71 +2905,604 @@ impl App {
     pub fn handle_task_result(&mut self, result: crate::event::TaskResult) {
         use crate::event::TaskResult;
 
+        if matches!(
+            result,
+            TaskResult::ConnectComplete(..)
+                | TaskResult::DisconnectComplete(_)
+                | TaskResult::ForgetComplete(_)
+                | TaskResult::HotspotStarted(_)
+                | TaskResult::HotspotStopped(_)
+                | TaskResult::CallModeChanged(..)
+        ) {
+            self.expect_status_change = true;
+        }
+
         match result {
-            TaskResult::ScanComplete(Ok(networks)) => {
+            TaskResult::ScanComplete(Ok(mut networks)) => {
+                sort_networks(&mut networks, self.sort_mode);
                 self.networks = networks;
-                if self.networks.is_empty() {
-                    self.net_index = 0;
-                } else if self.net_index >= self.networks.len() {
-                    self.net_index = self.networks.len() - 1;
-                }
+                self.net_index = self.net_index.min(self.available_rows().len().saturating_sub(1));
                 self.scan_task_done();
             }
             TaskResult::ScanComplete(Err(e)) => {
+                // Better no list than one that looks current
+                if self.stale {
+                    self.networks.clear();
+                    self.net_index = 0;
+                }
                 self.scan_task_done();
-                self.modal = Some(Modal::Message(e));
+                if std::mem::take(&mut self.cancelling) {
+                    self.show_toast("Scan cancelled.".to_string());
+                } else {
+                    self.show_message(e);
+                }
             }
-            TaskResult::ConnectComplete(Ok(msg), _ssid) => {
+            TaskResult::ConnectComplete(Ok(msg), ssid) => {
                 self.bg_status = BgStatus::Idle;
-                self.modal = Some(Modal::Message(msg));
-                self.ticks_since_scan = AUTO_REFRESH_TICKS; // trigger refresh
+                self.cancelling = false;
+                self.record_connect(&ssid, true);
+                if self.temporary_ssid.as_ref() == Some(&ssid) {
+                    self.temporary_ssid = None;
+                    self.temporary_profiles.insert(ssid.clone(), false);
+                    self.pending_make_temporary = Some(ssid.clone());
+                }
+                // Probe again once the address is in, even on the same network
+                self.internet = None;
+                self.internet_checked_at = None;
+                self.show_toast(msg);
+                self.scan_requested = true;
             }
             TaskResult::ConnectComplete(Err(e), ssid) => {
                 self.bg_status = BgStatus::Idle;
-                if crate::nmcli::error_needs_password(&e) {
-                    // Password needed - show password prompt instead of error
-                    self.password.clear();
-                    self.password_visible = false;
-                    self.password_target_ssid = ssid;
-                    self.modal = Some(Modal::PasswordInput);
+                if std::mem::take(&mut self.cancelling) {
+                    self.show_toast(format!("Stopped connecting to {}.", ssid));
+                } else if crate::backend::error_needs_password(&e)
+                    && !self.secret_agent
+                    && !self.is_open_network(&ssid)
+                    && !self.is_enterprise_network(&ssid)
+                {
+                    // Password needed - show password prompt instead of error.
+                    // With a secret agent the user was already prompted.
+                    if crate::backend::error_wrong_password(&e) && self.password_target_ssid == ssid {
+                        self.reprompt_password(ssid, e);
+                    } else {
+                        self.prompt_password(ssid);
+                    }
                 } else {
-                    self.modal = Some(Modal::Message(e));
+                    // Cancelling or a missing password says nothing about the network
+                    if !crate::backend::error_needs_password(&e) {
+                        self.record_connect(&ssid, false);
+                    }
+                    self.show_message(e);
                 }
             }
             TaskResult::DisconnectComplete(Ok(msg)) => {
                 self.bg_status = BgStatus::Idle;
-                self.modal = Some(Modal::Message(msg));
-                self.ticks_since_scan = AUTO_REFRESH_TICKS;
+                self.show_toast(msg);
+                self.scan_requested = true;
             }
             TaskResult::DisconnectComplete(Err(e)) => {
                 self.bg_status = BgStatus::Idle;
-                self.modal = Some(Modal::Message(e));
+                if std::mem::take(&mut self.cancelling) {
+                    self.show_toast("Disconnect cancelled.".to_string());
+                } else {
+                    self.show_message(e);
+                }
             }
             TaskResult::ForgetComplete(Ok(msg)) => {
                 self.bg_status = BgStatus::Idle;
-                self.modal = Some(Modal::Message(msg));
-                self.ticks_since_scan = AUTO_REFRESH_TICKS;
+                self.show_toast(msg);
+                self.scan_requested = true;
             }
             TaskResult::ForgetComplete(Err(e)) => {
                 self.bg_status = BgStatus::Idle;
-                self.modal = Some(Modal::Message(e));
+                if std::mem::take(&mut self.cancelling) {
+                    self.show_toast("Forget cancelled.".to_string());
+                } else {
+                    self.show_message(e);
+                }
+            }
+            TaskResult::MadeTemporary(name, Ok(_)) => {
+                self.show_toast(format!("{} is forgotten again on disconnect or when you quit.", name));
+            }
+            TaskResult::MadeTemporary(name, Err(e)) => {
+                self.show_message(format!("Couldn't make {} temporary: {}", name, e));
+            }
+            TaskResult::TemporaryRemoved(name, Ok(_)) => {
+                self.show_toast(format!("Forgot temporary network {}.", name));
+                self.pending_saved_refresh = true;
+            }
+            TaskResult::TemporaryRemoved(name, Err(e)) => {
+                self.show_message(format!("Couldn't forget temporary network {}: {}", name, e));
             }
             TaskResult::StatusUpdate(status) => {
-                self.status = status;
-                self.scan_task_done();
+                // May be the Monitor view's poll
+                self.polling_signal = false;
+                self.record_status(status);
+                self.record_monitor_sample();
+            }
+            TaskResult::SignalUpdate(signal, dbm) => {
+                self.polling_signal = false;
+                // Dropping off is left to the full status refresh
+                if let (Some(signal), Some(_)) = (signal, &self.status.ssid) {
+                    self.status.signal = Some(signal);
+                    // Without iw, keep what the last status refresh measured
+                    if dbm.is_some() {
+                        self.status.dbm = dbm;
+                    }
+                    self.record_monitor_sample();
+                }
             }
             TaskResult::SavedUpdate(Ok(saved)) => {
                 self.saved = saved;
-                if self.saved.is_empty() {
-                    self.saved_index = 0;
-                } else if self.saved_index >= self.saved.len() {
-                    self.saved_index = self.saved.len() - 1;
-                }
+                self.saved_index = self.saved_index.min(self.visible_saved().len().saturating_sub(1));
                 self.scan_task_done();
             }
             TaskResult::SavedUpdate(Err(_)) => {
                 self.scan_task_done();
             }
+            TaskResult::HotspotSaved(Ok(msg))
+            | TaskResult::HotspotStarted(Ok(msg))
+            | TaskResult::HotspotStopped(Ok(msg)) => {
+                self.bg_status = BgStatus::Idle;
+                // The profile may have a new password now
+                self.hotspot_qr = None;
+                self.show_toast(msg);
+                self.scan_requested = true;
+            }
+            TaskResult::HotspotSaved(Err(e))
+            | TaskResult::HotspotStarted(Err(e))
+            | TaskResult::HotspotStopped(Err(e)) => {
+                self.bg_status = BgStatus::Idle;
+                self.show_message(e);
+            }
+            TaskResult::HotspotClients(count) => {
+                self.hotspot_clients = count;
+            }
+            TaskResult::HotspotSecret(name, result) => {
+                self.loading_hotspot_qr = false;
+                self.hotspot_qr = Some((name, result.and_then(|secret| WifiQr::new(&secret))));
+            }
+            TaskResult::DispatcherListed(Ok(scripts)) => {
+                self.bg_status = BgStatus::Idle;
+                self.set_dispatcher_scripts(scripts);
+                self.modal = Some(Modal::Dispatcher);
+            }
+            TaskResult::DispatcherListed(Err(e)) => {
+                self.bg_status = BgStatus::Idle;
+                self.show_message(e);
+            }
+            TaskResult::DispatcherChanged(result, scripts) => {
+                self.bg_status = BgStatus::Idle;
+                self.set_dispatcher_scripts(scripts);
+                match result {
+                    Ok(msg) => {
+                        self.show_toast(msg);
+                        self.modal = Some(Modal::Dispatcher);
+                    }
+                    Err(e) => self.show_message(e),
+                }
+            }
+            TaskResult::CallModeChanged(name, on, result) => {
+                self.bg_status = BgStatus::Idle;
+                match result {
+                    Ok(msg) => {
+                        // Remembered so turning it off reverts the right profile
+                        self.call_mode = on.then_some(name);
+                        self.show_toast(msg);
+                    }
+                    Err(e) => self.show_message(format!("Call mode: {}", e)),
+                }
+            }
+            // The prompt may have been closed or moved on meanwhile
+            TaskResult::PasswordFetched(ssid, result)
+                if self.modal == Some(Modal::PasswordInput) && self.password_target_ssid == ssid =>
+            {
+                self.password_error = None;
+                self.password_status = Some(match result {
+                    Ok(password) => {
+                        self.password.set_value(&password);
+                        "Filled from the password manager.".to_string()
+                    }
+                    Err(e) => e,
+                });
+            }
+            TaskResult::PasswordFetched(..) => {}
+            TaskResult::LowDataChanged(_, Ok(msg)) => self.show_toast(msg),
+            TaskResult::LowDataChanged(on, Err(e)) => {
+                let mode = if on { "on" } else { "off" };
+                self.show_message(format!("Low-data mode {}, but: {}", mode, e));
+            }
+            TaskResult::RateLimitChanged(Ok(msg)) => self.show_toast(msg),
+            TaskResult::RateLimitChanged(Err(e)) => {
+                self.show_message(format!("Bandwidth limit: {}", e));
+            }
+            TaskResult::BgscanApplied(Ok(msg)) => self.show_toast(msg),
+            TaskResult::BgscanApplied(Err(e)) => {
+                self.show_message(format!("Roaming: {}", e));
+            }
+            TaskResult::SecretFetched(result) => {
+                self.bg_status = BgStatus::Idle;
+                match result.and_then(|secret| WifiQr::new(&secret)) {
+                    Ok(qr) => self.modal = Some(Modal::Qr(qr)),
+                    Err(e) => self.show_message(e),
+                }
+            }
+            TaskResult::PasswordRevealed(name, result) => {
+                self.bg_status = BgStatus::Idle;
+                match result {
+                    Ok(secret) => self.modal = Some(Modal::Password(name, secret.psk)),
+                    Err(e) => self.show_message(e),
+                }
+            }
+            TaskResult::ProfileOptionsLoaded(name, Ok(options)) => {
+                self.bg_status = BgStatus::Idle;
+                self.form = profile_form(&name, &options);
+                self.form_target = name;
+                self.modal = Some(Modal::Form(FormKind::Profile));
+            }
+            TaskResult::ProfileOptionsLoaded(_, Err(e)) | TaskResult::ProfileOptionsChanged(Err(e)) => {
+                self.bg_status = BgStatus::Idle;
+                self.show_message(e);
+            }
+            TaskResult::ProfileOptionsChanged(Ok(msg)) => {
+                self.bg_status = BgStatus::Idle;
+                self.show_toast(msg);
+            }
+            TaskResult::ProfileRenamed(name, new_name, Ok(msg)) => {
+                self.bg_status = BgStatus::Idle;
+                self.show_toast(msg);
+                self.rename_settings(&name, &new_name);
+                self.pending_saved_refresh = true;
+            }
+            TaskResult::ProfileRenamed(_, _, Err(e)) => {
+                self.bg_status = BgStatus::Idle;
+                self.show_message(e);
+            }
+            TaskResult::DnsLoaded(name, Ok(config)) => {
+                self.bg_status = BgStatus::Idle;
+                self.form = dns_form(&name, &config);
+                self.form_target = name;
+                self.modal = Some(Modal::Form(FormKind::Dns));
+            }
+            TaskResult::DnsLoaded(_, Err(e)) | TaskResult::DnsChanged(Err(e)) => {
+                self.bg_status = BgStatus::Idle;
+                self.show_message(e);
+            }
+            TaskResult::DnsChanged(Ok(msg)) => {
+                self.bg_status = BgStatus::Idle;
+                self.show_toast(msg);
+            }
+            TaskResult::Ipv4Loaded(name, Ok(config)) => {
+                self.bg_status = BgStatus::Idle;
+                self.form = ipv4_form(&name, &config);
+                self.form_target = name;
+                self.modal = Some(Modal::Form(FormKind::Ipv4));
+            }
+            TaskResult::Ipv4Loaded(_, Err(e)) | TaskResult::Ipv4Changed(Err(e)) => {
+                self.bg_status = BgStatus::Idle;
+                self.show_message(e);
+            }
+            TaskResult::Ipv4Changed(Ok(msg)) => {
+                self.bg_status = BgStatus::Idle;
+                self.show_toast(msg);
+            }
+            TaskResult::RoutesLoaded(name, Ok(config)) => {
+                self.bg_status = BgStatus::Idle;
+                self.form = routes_form(&name, &config);
+                self.form_target = name;
+                self.modal = Some(Modal::Form(FormKind::Routes));
+            }
+            TaskResult::RoutesLoaded(_, Err(e)) | TaskResult::RoutesChanged(Err(e)) => {
+                self.bg_status = BgStatus::Idle;
+                self.show_message(e);
+            }
+            TaskResult::RouteMetricChanged(Ok(msg)) => {
+                self.bg_status = BgStatus::Idle;
+                self.show_toast(msg);
+                self.scan_requested = true;
+            }
+            TaskResult::RouteMetricChanged(Err(e)) => {
+                self.bg_status = BgStatus::Idle;
+                self.show_message(e);
+            }
+            TaskResult::UplinksUpdate(uplinks) => {
+                self.uplinks = uplinks;
+            }
+            TaskResult::RfkillUpdate(rfkill) => {
+                self.rfkill = rfkill.unwrap_or_default();
+            }
+            TaskResult::DevicesDetected(result) => {
+                self.detecting_devices = false;
+                match result {
+                    Ok(devices) => self.detected_devices = Some(devices),
+                    Err(e) if e == NO_WIFI_ADAPTER => self.detected_devices = Some(Vec::new()),
+                    // e.g. NetworkManager restarting; look again next time
+                    Err(_) => {}
+                }
+            }
+            TaskResult::ConnectStage(stage) => {
+                self.polling_connect_stage = false;
+                // A late answer after the connect finished
+                if self.bg_status == BgStatus::Connecting {
+                    self.connect_stage = stage;
+                }
+            }
+            TaskResult::UpdateChecked(Ok(latest)) => {
+                if update::is_newer(&latest, update::VERSION) {
+                    self.show_toast(format!("wifi-tui {} is out (this is {}).", latest, update::VERSION));
+                    self.newer_version = Some(latest);
+                }
+            }
+            // Opted into, but not worth interrupting anyone over
+            TaskResult::UpdateChecked(Err(_)) => {}
+            // Disconnected while probing
+            TaskResult::InternetChecked(_) if self.status.ip.is_none() => {
+                self.checking_internet = false;
+            }
+            TaskResult::InternetChecked(internet) => {
+                self.checking_internet = false;
+                // Offer the sign-in page once, not on every recheck
+                let was_portal = matches!(self.internet, Some(Internet::CaptivePortal(_)));
+                if let (Internet::CaptivePortal(url), false) = (&internet, was_portal) {
+                    // Don't clobber whatever the user is in the middle of
+                    if matches!(self.modal, None | Some(Modal::Message(_))) {
+                        self.modal = Some(Modal::CaptivePortal(url.clone()));
+                    } else {
+                        self.show_toast(format!("This network wants you to sign in at {}", url));
+                    }
+                }
+                self.internet = Some(internet);
+            }
+            TaskResult::PingUpdate(report) => {
+                self.pinging = false;
+                // Disconnected while pinging
+                self.ping = self.status.ssid.as_ref().map(|_| report);
+            }
+            TaskResult::RadioUnblocked(Ok(msg)) => {
+                self.bg_status = BgStatus::Idle;
+                self.rfkill.soft = false;
+                self.show_toast(msg);
+                self.scan_requested = true;
+            }
+            TaskResult::RadioUnblocked(Err(e)) => {
+                self.bg_status = BgStatus::Idle;
+                self.show_message(e);
+            }
+            TaskResult::RoutesChanged(Ok(msg)) => {
+                self.bg_status = BgStatus::Idle;
+                self.show_toast(msg);
+            }
+            TaskResult::SharingChecked(checks) => {
+                self.bg_status = BgStatus::Idle;
+                self.modal = Some(Modal::Checks("Hotspot sharing".to_string(), checks));
+            }
+            TaskResult::SixGhzChecked(checks) => {
+                self.bg_status = BgStatus::Idle;
+                self.modal = Some(Modal::Checks("6 GHz (WiFi 6E) readiness".to_string(), checks));
+            }
+            TaskResult::ConnectivityChecked(result) => {
+                self.bg_status = BgStatus::Idle;
+                match result {
+                    Ok(check) => self.modal = Some(Modal::Connectivity(check)),
+                    Err(e) => self.show_message(format!("Couldn't read the connectivity check settings: {}", e)),
+                }
+            }
+            TaskResult::ConnectivityCheckChanged(on, result) => {
+                self.bg_status = BgStatus::Idle;
+                let state = if on { "on" } else { "off" };
+                match result {
+                    Ok(check) => {
+                        if matches!(self.modal, Some(Modal::Connectivity(_))) {
+                            self.modal = Some(Modal::Connectivity(check));
+                        }
+                        self.show_toast(format!("Connectivity checking turned {}.", state));
+                    }
+                    Err(e) => {
+                        self.show_message(format!("Couldn't turn connectivity checking {}: {}", state, e));
+                    }
+                }
+            }
+            TaskResult::DiagnosticsSaved(result, ssid) => {
+                let msg = match result {
+                    Ok(path) => format!(
+                        "Lost connection to '{}' unexpectedly. Diagnostics saved to {}",
+                        ssid,
+                        path.display()
+                    ),
+                    Err(e) => format!(
+                        "Lost connection to '{}' unexpectedly. Couldn't save diagnostics: {}",
+                        ssid, e
+                    ),
+                };
+                // Don't clobber whatever the user is in the middle of
+                if self.modal.is_none() {
+                    self.show_message(msg);
+                } else {
+                    self.show_warning(msg);
+                }
+            }
+        }
+    }
+
+    fn set_dispatcher_scripts(&mut self, scripts: Vec<DispatcherScript>) {
+        self.dispatcher_scripts = scripts;
+        self.dispatcher_index = self
+            .dispatcher_index
+            .min(self.dispatcher_scripts.len().saturating_sub(1));
+    }
+
+    /// Store a status refresh, watching for connections that drop on their own.
+    fn record_status(&mut self, status: ConnectionStatus) {
+        let expected = std::mem::take(&mut self.expect_status_change);
+        if status.ssid != self.status.ssid {
+            if let (Some(ssid), None, false) = (&self.status.ssid, &status.ssid, expected) {
+                self.pending_drop_report = Some(DropReport {
+                    device: self.device.clone(),
+                    ssid: ssid.clone(),
+                    signal_history: self.signal_history.iter().copied().collect(),
+                });
+            }
+            self.signal_history.clear();
+            self.monitor_samples.clear();
+            // Traffic since the last read belongs to the old network
+            self.traffic_sample = None;
+            self.ping = None;
+            self.pinged_at = None;
+            self.internet = None;
+            self.internet_checked_at = None;
+        }
+        if let Some(signal) = status.signal {
+            if self.signal_history.len() == SIGNAL_HISTORY_LEN {
+                self.signal_history.pop_front();
+            }
+            self.signal_history.push_back(signal);
+        }
+        self.status = status;
+    }
+
+    /// Add the current signal and link rate to the Monitor view's plot.
+    fn record_monitor_sample(&mut self) {
+        let (Some(_), Some(signal)) = (&self.status.ssid, self.status.signal) else {
+            return;
+        };
+        let now = Instant::now();
+        let oldest = MONITOR_WINDOWS[MONITOR_WINDOWS.len() - 1];
+        while self.monitor_samples.front().is_some_and(|sample| now - sample.at > oldest) {
+            self.monitor_samples.pop_front();
+        }
+        self.monitor_samples.push_back(MonitorSample {
+            at: now,
+            signal,
+            bitrate: self.status.bitrate,
+            throughput: self.throughput,
+            ping: self.ping,
+        });
+    }
+
+    /// Read the device's byte counters and work out the rates since the last read.
+    fn sample_traffic(&mut self) {
+        let counters = self.status.ssid.as_ref().and_then(|_| traffic_counters(&self.device));
+        let Some(counters) = counters else {
+            self.throughput = None;
+            self.traffic_sample = None;
+            return;
+        };
+        let now = Instant::now();
+        if let (Some((at, last)), Some(ssid)) = (self.traffic_sample, &self.status.ssid) {
+            // Counters start over when the device is re-created
+            let rx = counters.rx.saturating_sub(last.rx);
+            let tx = counters.tx.saturating_sub(last.tx);
+            let secs = now.duration_since(at).as_secs_f64();
+            self.throughput = Some(Throughput {
+                rx: (rx as f64 / secs) as u64,
+                tx: (tx as f64 / secs) as u64,
+            });
+            if rx > 0 || tx > 0 {
+                for totals in [&mut self.usage, &mut self.session_usage] {
+                    let usage = totals.entry(ssid.clone()).or_default();
+                    usage.rx += rx;
+                    usage.tx += tx;
+                }
+                self.usage_changed = true;
+            }
+        }
+        self.traffic_sample = Some((now, counters));
+    }
+
+    /// Count a connect to `ssid` that worked or failed. Connects are rare,
+    /// so the history is written out straight away.
+    fn record_connect(&mut self, ssid: &str, connected: bool) {
+        let stats = self.connect_history.entry(ssid.to_string()).or_default();
+        if connected {
+            stats.successes += 1;
+        } else {
+            stats.failures += 1;
         }
+        if let Err(e) = history::save(&self.connect_history) {
+            self.show_warning(e);
+        }
+    }
+
+    /// Write out the data used per network; also done on exit.
+    pub fn save_usage(&mut self) {
+        self.usage_saved_at = Instant::now();
+        self.usage_changed = false;
+        if let Err(e) = usage::save(&self.usage) {
+            self.show_warning(e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_hotspot_password() {
+        assert!(validate_hotspot_password("").is_ok());
+        assert!(validate_hotspot_password("correct horse").is_ok());
+        assert!(validate_hotspot_password("short").is_err());
+        assert!(validate_hotspot_password("password\nquit").is_err());
+        assert!(validate_start_hotspot_password(" password").is_err());
+        assert!(validate_start_hotspot_password("password ").is_err());
+    }
+
+    #[test]
+    fn test_move_selection() {
+        assert_eq!(move_selection(Action::Up, 0, 50, 10), 0);
+        assert_eq!(move_selection(Action::Down, 49, 50, 10), 49);
+        assert_eq!(move_selection(Action::PageDown, 3, 50, 10), 13);
+        assert_eq!(move_selection(Action::PageDown, 45, 50, 10), 49);
+        assert_eq!(move_selection(Action::PageUp, 4, 50, 10), 0);
+        assert_eq!(move_selection(Action::HalfPageDown, 3, 50, 10), 8);
+        assert_eq!(move_selection(Action::HalfPageUp, 3, 50, 1), 2);
+        assert_eq!(move_selection(Action::Top, 30, 50, 10), 0);
+        assert_eq!(move_selection(Action::Bottom, 3, 50, 10), 49);
+        assert_eq!(move_selection(Action::Bottom, 0, 0, 10), 0);
+    }
+
+    #[test]
+    fn test_sort_networks() {
+        let net = |ssid: &str, signal, frequency, in_use| Network {
+            ssid: ssid.to_string(),
+            signal,
+            frequency,
+            in_use,
+            ..Network::default()
+        };
+        let mut networks = vec![
+            net("cafe", 90, Some(5180), false),
+            net("Home", 40, Some(2412), true),
+            net("Attic", 60, None, false),
+        ];
+        let order = |networks: &[Network]| networks.iter().map(|n| n.ssid.clone()).collect::<Vec<_>>();
+
+        sort_networks(&mut networks, SortMode::Signal);
+        assert_eq!(order(&networks), ["Home", "cafe", "Attic"]);
+        sort_networks(&mut networks, SortMode::Ssid);
+        assert_eq!(order(&networks), ["Attic", "cafe", "Home"]);
+        sort_networks(&mut networks, SortMode::Channel);
+        assert_eq!(order(&networks), ["Home", "cafe", "Attic"]);
+    }
+
+    #[test]
+    fn test_ipv4_config() {
+        let config = Ipv4Config {
+            address: Some(Ipv4Address::parse("192.168.1.50/24").unwrap()),
+            gateway: Some("192.168.1.1".parse().unwrap()),
+            dns: vec!["1.1.1.1".parse().unwrap()],
+        };
+        let form = ipv4_form("Home", &config);
+        assert_eq!(form.selected(IPV4_METHOD_LABEL), 1);
+        assert_eq!(ipv4_config(&form), Ok(config));
+
+        // Back to DHCP drops the fixed address and its gateway
+        let dhcp = ipv4_form("Home", &Ipv4Config::default());
+        assert_eq!(ipv4_config(&dhcp), Ok(Ipv4Config::default()));
+        let empty = Ipv4Config { gateway: Some("192.168.1.1".parse().unwrap()), ..Ipv4Config::default() };
+        let mut form = ipv4_form("Home", &empty);
+        form.handle_key(KeyEvent::new(KeyCode::Right, KeyModifiers::NONE));
+        assert!(ipv4_config(&form).is_err());
     }
 }
@@ -0,0 +1,1248 @@
+use std::collections::HashMap;
+use std::net::Ipv4Addr;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::mpsc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::dbus::DbusBackend;
+use crate::event::Event;
+use crate::iwd::IwdBackend;
+use crate::logging;
+use crate::nl80211;
+use crate::nmcli::NmcliBackend;
+use crate::phy::LinkRate;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Network {
+    /// The SSID as text, from [`decode_ssid`]: what's shown and matched on.
+    pub ssid: String,
+    /// The SSID exactly as broadcast, which needn't be UTF-8.
+    #[serde(skip)]
+    pub ssid_bytes: Vec<u8>,
+    pub signal: u8,
+    pub security: String,
+    pub in_use: bool,
+    /// Access point MAC address; empty if the backend doesn't report it.
+    pub bssid: String,
+    pub channel: Option<u32>,
+    /// Centre frequency in MHz.
+    pub frequency: Option<u32>,
+    /// Maximum bitrate in Mbit/s.
+    pub rate: Option<u32>,
+    /// "Infra", "Ad-Hoc" or "Mesh".
+    pub mode: String,
+    /// Every access point seen broadcasting this SSID, best first. Empty if
+    /// the backend doesn't report BSSIDs.
+    pub access_points: Vec<AccessPoint>,
+}
+
+/// One BSSID of a network, for mesh/roaming setups with several APs.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccessPoint {
+    pub bssid: String,
+    pub signal: u8,
+    pub channel: Option<u32>,
+    pub frequency: Option<u32>,
+    pub in_use: bool,
+}
+
+impl Network {
+    /// No security advertised (nmcli shows "--" for these).
+    pub fn is_open(&self) -> bool {
+        self.security.is_empty() || self.security == "--"
+    }
+
+    /// Joined with an identity and password (802.1X) rather than a shared key.
+    pub fn is_enterprise(&self) -> bool {
+        self.security.contains("802.1X")
+    }
+
+    pub fn band(&self) -> Option<Band> {
+        self.frequency.and_then(Band::of)
+    }
+
+    /// Every band it's heard on, lowest first. Band steering shows up as
+    /// one network on several.
+    pub fn bands(&self) -> Vec<Band> {
+        let frequencies: Vec<Option<u32>> = if self.access_points.is_empty() {
+            vec![self.frequency]
+        } else {
+            self.access_points.iter().map(|ap| ap.frequency).collect()
+        };
+        Band::ALL
+            .into_iter()
+            .filter(|band| frequencies.iter().flatten().any(|&mhz| band.contains(mhz)))
+            .collect()
+    }
+
+    /// What makes traffic on this network easy to snoop on: no encryption,
+    /// or only WEP, which is cracked in minutes.
+    pub fn weakness(&self) -> Option<&'static str> {
+        if self.is_open() {
+            Some("unencrypted")
+        } else if self.security.contains("WEP") && !self.security.contains("WPA") {
+            Some("WEP")
+        } else {
+            None
+        }
+    }
+
+    /// Whether any of its access points broadcasts on 6 GHz (WiFi 6E).
+    pub fn has_6ghz(&self) -> bool {
+        self.frequency.is_some_and(is_6ghz) || self.access_points.iter().any(|ap| ap.frequency.is_some_and(is_6ghz))
+    }
+}
+
+/// A WiFi band, as filtered on in the Available list and picked in the
+/// Analyzer view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Band {
+    Ghz2,
+    Ghz5,
+    Ghz6,
+}
+
+impl Band {
+    pub const ALL: [Band; 3] = [Band::Ghz2, Band::Ghz5, Band::Ghz6];
+
+    pub fn of(mhz: u32) -> Option<Band> {
+        Band::ALL.into_iter().find(|band| band.contains(mhz))
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Band::Ghz2 => "2.4 GHz",
+            Band::Ghz5 => "5 GHz",
+            Band::Ghz6 => "6 GHz",
+        }
+    }
+
+    /// For the band column: "2.4", "5" or "6".
+    pub fn short_label(self) -> &'static str {
+        self.label().trim_end_matches(" GHz")
+    }
+
+    pub fn contains(self, mhz: u32) -> bool {
+        match self {
+            Band::Ghz2 => (2400..=2500).contains(&mhz),
+            Band::Ghz5 => (5150..=5900).contains(&mhz),
+            Band::Ghz6 => is_6ghz(mhz),
+        }
+    }
+
+    pub fn next(self) -> Band {
+        let i = Band::ALL.iter().position(|band| *band == self).unwrap_or(0);
+        Band::ALL[(i + 1) % Band::ALL.len()]
+    }
+}
+
+/// An SSID's bytes as text. UTF-8 stays as it is; otherwise the invalid
+/// bytes become `\xNN` and backslashes are doubled, so [`encode_ssid`] can
+/// get the bytes back.
+pub fn decode_ssid(bytes: &[u8]) -> String {
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        return text.to_string();
+    }
+    let mut text = String::new();
+    for chunk in bytes.utf8_chunks() {
+        text.push_str(&chunk.valid().replace('\\', "\\\\"));
+        for byte in chunk.invalid() {
+            text.push_str(&format!("\\x{:02x}", byte));
+        }
+    }
+    text
+}
+
+/// The exact bytes of an SSID from [`decode_ssid`], to connect with.
+pub fn encode_ssid(ssid: &str) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(ssid.len());
+    let mut rest = ssid.as_bytes();
+    while let Some((&first, tail)) = rest.split_first() {
+        let escaped = match tail {
+            [b'\\', ..] if first == b'\\' => Some((b'\\', 1)),
+            [b'x', hi, lo, ..] if first == b'\\' => std::str::from_utf8(&[*hi, *lo])
+                .ok()
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                .map(|byte| (byte, 3)),
+            _ => None,
+        };
+        let (byte, skip) = escaped.unwrap_or((first, 0));
+        bytes.push(byte);
+        rest = &tail[skip..];
+    }
+    // A UTF-8 SSID that merely looks escaped, e.g. a literal "\x41"
+    if decode_ssid(&bytes) == ssid {
+        bytes
+    } else {
+        ssid.as_bytes().to_vec()
+    }
+}
+
+/// Whether a frequency in MHz is in the 6 GHz band.
+pub fn is_6ghz(mhz: u32) -> bool {
+    (5925..=7125).contains(&mhz)
+}
+
+/// Signal in dBm for a percentage on the usual 2 × (dBm + 100) scale.
+pub fn percent_to_dbm(percent: u8) -> i32 {
+    i32::from(percent) / 2 - 100
+}
+
+/// Signal of `device`'s link in dBm, as the kernel measures it. None if it
+/// isn't connected or nl80211 isn't available.
+pub fn link_dbm(device: &str) -> Option<i32> {
+    nl80211::link_station(device)?.signal_dbm
+}
+
+/// Roughly how far away an access point is, from its signal. Higher bands
+/// fade faster, so the same signal there means closer.
+pub fn distance_hint(signal: u8, frequency: Option<u32>) -> &'static str {
+    let band_loss = match frequency.and_then(Band::of) {
+        Some(Band::Ghz6) => 8,
+        Some(Band::Ghz5) => 6,
+        _ => 0,
+    };
+    match percent_to_dbm(signal) + band_loss {
+        -52.. => "very close",
+        -65.. => "nearby",
+        -75.. => "a few rooms away",
+        _ => "far",
+    }
+}
+
+/// WiFi channel number for a centre frequency in MHz.
+pub fn frequency_to_channel(mhz: u32) -> Option<u32> {
+    match mhz {
+        2484 => Some(14),
+        2412..=2472 => Some((mhz - 2407) / 5),
+        // 6 GHz starts at channel 1 = 5955 MHz
+        5955..=7115 => Some((mhz - 5950) / 5),
+        5160..=5885 => Some((mhz - 5000) / 5),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedNetwork {
+    pub name: String,
+    pub active: bool,
+    /// Access-point profile (hotspot) rather than a client connection.
+    pub hotspot: bool,
+}
+
+/// Settings for a saved hotspot (access point) profile.
+#[derive(Debug, Clone)]
+pub struct HotspotConfig {
+    pub name: String,
+    pub ssid: String,
+    pub password: String,
+    pub band: HotspotBand,
+    pub interface: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HotspotBand {
+    Auto,
+    Ghz2,
+    Ghz5,
+}
+
+impl HotspotBand {
+    pub const ALL: [HotspotBand; 3] = [HotspotBand::Auto, HotspotBand::Ghz2, HotspotBand::Ghz5];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            HotspotBand::Auto => "Auto",
+            HotspotBand::Ghz2 => "2.4 GHz",
+            HotspotBand::Ghz5 => "5 GHz",
+        }
+    }
+}
+
+/// Outer method of an enterprise (802.1X) network. Either carries the
+/// password inside a TLS tunnel, checked with MSCHAPv2.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EapMethod {
+    Peap,
+    Ttls,
+}
+
+impl EapMethod {
+    pub const ALL: [EapMethod; 2] = [EapMethod::Peap, EapMethod::Ttls];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            EapMethod::Peap => "PEAP",
+            EapMethod::Ttls => "TTLS",
+        }
+    }
+
+    /// `802-1x.eap` value.
+    pub fn nm_value(self) -> &'static str {
+        match self {
+            EapMethod::Peap => "peap",
+            EapMethod::Ttls => "ttls",
+        }
+    }
+}
+
+/// What joining an enterprise (802.1X) network takes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EapConfig {
+    pub method: EapMethod,
+    pub identity: String,
+    pub password: String,
+    /// Certificate the server's must be signed with; None doesn't check it.
+    pub ca_cert: Option<PathBuf>,
+}
+
+/// General options of a saved profile (`connection.autoconnect`,
+/// `connection.autoconnect-priority`, `connection.metered`,
+/// `802-11-wireless.cloned-mac-address`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfileOptions {
+    pub autoconnect: bool,
+    /// Higher wins when several saved networks are in range.
+    pub priority: i32,
+    pub metered: Metered,
+    pub mac: ClonedMac,
+}
+
+/// `802-11-wireless.cloned-mac-address`: the MAC address a profile shows
+/// the network.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum ClonedMac {
+    /// Whatever NetworkManager's global configuration says.
+    #[default]
+    Default,
+    /// A new random address on every connect.
+    Random,
+    /// A random address that stays the same for this network.
+    Stable,
+    /// The adapter's own address.
+    Permanent,
+    /// Keep the address the adapter currently has.
+    Preserve,
+    Custom(String),
+}
+
+impl ClonedMac {
+    /// Every choice but `Custom`, in the order the edit form offers them.
+    pub const CHOICES: [ClonedMac; 5] =
+        [ClonedMac::Default, ClonedMac::Random, ClonedMac::Stable, ClonedMac::Permanent, ClonedMac::Preserve];
+
+    pub fn label(&self) -> &str {
+        match self {
+            ClonedMac::Default => "Default",
+            ClonedMac::Random => "Random",
+            ClonedMac::Stable => "Stable",
+            ClonedMac::Permanent => "Permanent",
+            ClonedMac::Preserve => "Preserve",
+            ClonedMac::Custom(mac) => mac,
+        }
+    }
+
+    /// The value nmcli shows and takes; empty resets it.
+    pub fn nmcli_value(&self) -> &str {
+        match self {
+            ClonedMac::Default => "",
+            ClonedMac::Random => "random",
+            ClonedMac::Stable => "stable",
+            ClonedMac::Permanent => "permanent",
+            ClonedMac::Preserve => "preserve",
+            ClonedMac::Custom(mac) => mac,
+        }
+    }
+
+    pub fn from_nmcli(value: &str) -> Self {
+        match value {
+            "" | "--" => ClonedMac::Default,
+            "random" => ClonedMac::Random,
+            "stable" => ClonedMac::Stable,
+            "permanent" => ClonedMac::Permanent,
+            "preserve" => ClonedMac::Preserve,
+            mac => ClonedMac::Custom(mac.to_string()),
+        }
+    }
+}
+
+/// Whether `text` is a MAC address like "AA:BB:CC:DD:EE:FF".
+pub fn is_mac_address(text: &str) -> bool {
+    let parts: Vec<&str> = text.split(':').collect();
+    parts.len() == 6 && parts.iter().all(|part| part.len() == 2 && u8::from_str_radix(part, 16).is_ok())
+}
+
+/// Whether `device` looks like a P2P or AP interface some drivers add next
+/// to the real one (`p2p-dev-wlan0`, `p2p-wlan0-0`, `ap0`, `uap0`). These
+/// show up as WiFi devices but can't join a network, so they're only used
+/// when asked for with `--device`.
+pub fn is_virtual_interface(device: &str) -> bool {
+    let numbered = |prefix: &str| {
+        device
+            .strip_prefix(prefix)
+            .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+    };
+    device.starts_with("p2p-") || numbered("ap") || numbered("uap")
+}
+
+/// Whether the kernel still has `device`, e.g. a USB adapter not yet unplugged.
+pub fn device_present(device: &str) -> bool {
+    Path::new("/sys/class/net").join(device).exists()
+}
+
+/// The current hardware address of `device`, e.g. to check randomization.
+pub fn device_mac(device: &str) -> Option<String> {
+    let address = std::fs::read_to_string(Path::new("/sys/class/net").join(device).join("address")).ok()?;
+    Some(address.trim().to_uppercase())
+}
+
+/// The adapter's burned-in address, which MAC randomization and cloning
+/// leave alone; the current address when the kernel doesn't say.
+pub fn permanent_mac(device: &str) -> Option<String> {
+    let phy = Path::new("/sys/class/net").join(device).join("phy80211").join("macaddress");
+    match std::fs::read_to_string(phy) {
+        Ok(address) => Some(address.trim().to_uppercase()),
+        Err(_) => device_mac(device),
+    }
+}
+
+/// Which of `devices` has the permanent address `mac`, whatever it's called now.
+pub fn device_with_mac(devices: &[String], mac: &str) -> Option<String> {
+    devices
+        .iter()
+        .find(|device| permanent_mac(device).is_some_and(|address| address.eq_ignore_ascii_case(mac)))
+        .cloned()
+}
+
+/// `connection.metered` as it can be set; NetworkManager's guesses count
+/// as `Auto`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Metered {
+    #[default]
+    Auto,
+    Yes,
+    No,
+}
+
+impl Metered {
+    pub const ALL: [Metered; 3] = [Metered::Auto, Metered::Yes, Metered::No];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Metered::Auto => "Automatic",
+            Metered::Yes => "Yes",
+            Metered::No => "No",
+        }
+    }
+
+    /// The value nmcli shows and takes.
+    pub fn nmcli_value(self) -> &'static str {
+        match self {
+            Metered::Auto => "unknown",
+            Metered::Yes => "yes",
+            Metered::No => "no",
+        }
+    }
+
+    pub fn from_nmcli(value: &str) -> Self {
+        match value {
+            "yes" => Metered::Yes,
+            "no" => Metered::No,
+            _ => Metered::Auto,
+        }
+    }
+}
+
+/// Result text for `Backend::set_profile_options`.
+pub fn profile_options_message(name: &str, options: &ProfileOptions) -> String {
+    let connect = if options.autoconnect { "connects automatically" } else { "connects only when asked" };
+    let mac = match &options.mac {
+        ClonedMac::Default => String::new(),
+        mac => format!(", {} MAC", mac.label().to_lowercase()),
+    };
+    format!("Saved {}: {}, priority {}{}.", name, connect, options.priority, mac)
+}
+
+/// IPv4 routing of a saved profile (`ipv4.never-default`, `ipv4.routes`).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RouteConfig {
+    /// Never use this connection for the default route, e.g. a lab network
+    /// next to the one that reaches the internet.
+    pub never_default: bool,
+    /// `ipv4.route-metric`; None leaves it to NetworkManager.
+    pub metric: Option<u32>,
+    pub routes: Vec<StaticRoute>,
+}
+
+/// Route metric NetworkManager gives WiFi profiles without their own.
+pub const WIFI_DEFAULT_ROUTE_METRIC: u32 = 600;
+
+/// One static route, written the way nmcli does: `10.0.0.0/8 192.168.1.1 100`
+/// (destination, then optional next hop and metric).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StaticRoute {
+    pub dest: Ipv4Addr,
+    pub prefix: u32,
+    pub next_hop: Option<Ipv4Addr>,
+    pub metric: Option<u32>,
+}
+
+impl StaticRoute {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut parts = text.split_whitespace();
+        let dest = parts.next().ok_or("empty route")?;
+        let (addr, prefix) = dest.split_once('/').unwrap_or((dest, "32"));
+        let dest = addr
+            .parse()
+            .map_err(|_| format!("'{}' is not an IPv4 address", addr))?;
+        let prefix = prefix
+            .parse()
+            .ok()
+            .filter(|p| *p <= 32)
+            .ok_or_else(|| format!("'/{}' is not a prefix length (0-32)", prefix))?;
+        let mut route = StaticRoute { dest, prefix, next_hop: None, metric: None };
+        for part in parts {
+            if route.next_hop.is_none() && route.metric.is_none() && part.contains('.') {
+                route.next_hop = Some(part.parse().map_err(|_| format!("'{}' is not a gateway address", part))?);
+            } else if route.metric.is_none() {
+                route.metric = Some(part.parse().map_err(|_| format!("'{}' is not a metric", part))?);
+            } else {
+                return Err(format!("unexpected '{}' in route", part));
+            }
+        }
+        Ok(route)
+    }
+}
+
+impl std::fmt::Display for StaticRoute {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}/{}", self.dest, self.prefix)?;
+        if let Some(next_hop) = self.next_hop {
+            write!(f, " {}", next_hop)?;
+        }
+        if let Some(metric) = self.metric {
+            write!(f, " {}", metric)?;
+        }
+        Ok(())
+    }
+}
+
+/// DNS of a saved profile (`ipv4.dns`, `ipv4.ignore-auto-dns`).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DnsConfig {
+    /// Use only `servers`, not the ones the network hands out.
+    pub ignore_auto: bool,
+    pub servers: Vec<Ipv4Addr>,
+}
+
+/// Parse a DNS server list separated by commas or spaces, e.g.
+/// "1.1.1.1, 9.9.9.9".
+pub fn parse_dns(text: &str) -> Result<Vec<Ipv4Addr>, String> {
+    text.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|server| !server.is_empty())
+        .map(|server| server.parse().map_err(|_| format!("'{}' is not an IPv4 address", server)))
+        .collect()
+}
+
+pub fn format_dns(servers: &[Ipv4Addr]) -> String {
+    servers.iter().map(|server| server.to_string()).collect::<Vec<_>>().join(", ")
+}
+
+/// Result text for `Backend::set_dns`.
+pub fn dns_message(name: &str, config: &DnsConfig) -> String {
+    match (config.servers.is_empty(), config.ignore_auto) {
+        (true, false) => format!("{} uses the network's DNS servers.", name),
+        (true, true) => format!("{} has no DNS servers.", name),
+        (false, false) => format!("{} uses {} before the network's DNS servers.", name, format_dns(&config.servers)),
+        (false, true) => format!("{} uses only {} for DNS.", name, format_dns(&config.servers)),
+    }
+}
+
+/// How a saved profile gets its address (`ipv4.method`, `ipv4.addresses`,
+/// `ipv4.gateway`, `ipv4.dns`).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Ipv4Config {
+    /// None asks the network with DHCP.
+    pub address: Option<Ipv4Address>,
+    pub gateway: Option<Ipv4Addr>,
+    pub dns: Vec<Ipv4Addr>,
+}
+
+/// An address with its prefix length, e.g. `192.168.1.50/24`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ipv4Address {
+    pub addr: Ipv4Addr,
+    pub prefix: u32,
+}
+
+impl Ipv4Address {
+    /// Parse `192.168.1.50/24`; without a prefix, /24 like most home networks.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let text = text.trim();
+        let (addr, prefix) = text.split_once('/').unwrap_or((text, "24"));
+        let addr = addr
+            .parse()
+            .map_err(|_| format!("'{}' is not an IPv4 address", addr))?;
+        let prefix = prefix
+            .parse()
+            .ok()
+            .filter(|p| (1..=32).contains(p))
+            .ok_or_else(|| format!("'/{}' is not a prefix length (1-32)", prefix))?;
+        Ok(Ipv4Address { addr, prefix })
+    }
+}
+
+impl std::fmt::Display for Ipv4Address {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}/{}", self.addr, self.prefix)
+    }
+}
+
+/// Result text for `Backend::set_ipv4`.
+pub fn ipv4_message(name: &str, config: &Ipv4Config) -> String {
+    match (config.address, config.gateway) {
+        (None, _) => format!("{} gets its address from the network.", name),
+        (Some(address), None) => format!("{} uses {}, without a gateway.", name, address),
+        (Some(address), Some(gateway)) => format!("{} uses {} via {}.", name, address, gateway),
+    }
+}
+
+/// Parse a comma-separated route list as shown by `nmcli -g ipv4.routes`.
+pub fn parse_routes(text: &str) -> Result<Vec<StaticRoute>, String> {
+    text.split(',')
+        .map(str::trim)
+        .filter(|route| !route.is_empty())
+        .map(StaticRoute::parse)
+        .collect()
+}
+
+pub fn format_routes(routes: &[StaticRoute]) -> String {
+    routes.iter().map(|route| route.to_string()).collect::<Vec<_>>().join(", ")
+}
+
+/// Result text for `Backend::set_routes`.
+pub fn routes_message(name: &str, config: &RouteConfig) -> String {
+    let default = if config.never_default { "not used for the default route" } else { "may carry the default route" };
+    match config.routes.len() {
+        0 => format!("Saved routing for {}: {}, no static routes.", name, default),
+        1 => format!("Saved routing for {}: {}, 1 static route.", name, default),
+        n => format!("Saved routing for {}: {}, {} static routes.", name, default, n),
+    }
+}
+
+pub const NO_WIFI_ADAPTER: &str = "No WiFi adapter found. Make sure your WiFi hardware is enabled.";
+
+/// A change to a profile's route metric.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricChange {
+    /// Set it to this value
+    Set(u32),
+    /// Raise it by this much (lower when negative), starting from
+    /// `WIFI_DEFAULT_ROUTE_METRIC` when it's automatic
+    Adjust(i64),
+}
+
+impl MetricChange {
+    /// The new `ipv4.route-metric`, as far as it's known before the profile
+    /// is read.
+    pub fn value(self) -> String {
+        match self {
+            MetricChange::Set(metric) => metric.to_string(),
+            MetricChange::Adjust(change) if change < 0 => format!("<current - {}>", change.unsigned_abs()),
+            MetricChange::Adjust(change) => format!("<current + {}>", change),
+        }
+    }
+}
+
+/// A change a confirmation dialog asks about, described by
+/// `Backend::change_summary`.
+pub enum Change<'a> {
+    Disconnect,
+    Forget(&'a str),                    // profile name
+    Dns(&'a str, &'a DnsConfig),        // profile name
+    Routes(&'a str, &'a RouteConfig),   // profile name
+    Ipv4(&'a str, &'a Ipv4Config),      // profile name
+    RouteMetric(&'a str, MetricChange), // profile name
+}
+
+/// `yes` or `no`, as NetworkManager spells booleans.
+pub fn yes_no(on: bool) -> &'static str {
+    if on { "yes" } else { "no" }
+}
+
+/// `ipv4.route-metric` for `config`, -1 being NetworkManager's default.
+pub fn route_metric(config: &RouteConfig) -> String {
+    config.metric.map_or("-1".to_string(), |m| m.to_string())
+}
+
+/// `ipv4.method` for `config`: `manual` with an address, DHCP's `auto` without.
+pub fn ipv4_method(config: &Ipv4Config) -> &'static str {
+    if config.address.is_some() { "manual" } else { "auto" }
+}
+
+/// `ipv4.addresses` for `config`, empty for DHCP.
+pub fn ipv4_addresses(config: &Ipv4Config) -> String {
+    config.address.map(|address| address.to_string()).unwrap_or_default()
+}
+
+/// `ipv4.gateway` for `config`, empty for none.
+pub fn ipv4_gateway(config: &Ipv4Config) -> String {
+    config.gateway.map(|gateway| gateway.to_string()).unwrap_or_default()
+}
+
+/// `text` as one shell word, quoted only when it needs to be.
+pub fn shell_quote(text: &str) -> String {
+    let plain = |c: char| c.is_ascii_alphanumeric() || "-_.,:/@%+=".contains(c);
+    if !text.is_empty() && text.chars().all(plain) {
+        text.to_string()
+    } else {
+        format!("'{}'", text.replace('\'', "'\\''"))
+    }
+}
+
+/// Result text for `Backend::set_route_metric`.
+pub fn route_metric_message(name: &str, metric: Option<u32>) -> String {
+    match metric {
+        Some(metric) => format!("Route metric of {} set to {}.", name, metric),
+        None => format!("Route metric of {} is automatic again.", name),
+    }
+}
+
+/// A connection with a default route, i.e. one that can carry internet traffic.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Uplink {
+    pub device: String,
+    pub metric: u32,
+    /// Ethernet (or USB tethering) rather than WiFi.
+    pub wired: bool,
+}
+
+/// Why `wifi_secret` fails when polkit won't let this user read secrets.
+pub const SECRETS_DENIED: &str = "Not allowed to read this password. NetworkManager only reveals \
+     secrets of profiles you own; for system-wide profiles run wifi-tui as root.";
+
+/// Stored credentials of a saved profile, for sharing.
+#[derive(Debug, Clone)]
+pub struct WifiSecret {
+    pub ssid: String,
+    /// `802-11-wireless-security.key-mgmt`; empty for open networks.
+    pub key_mgmt: String,
+    pub psk: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ConnectionStatus {
+    pub ssid: Option<String>,
+    pub signal: Option<u8>,
+    pub ip: Option<String>,
+    /// Link rate in kbit/s.
+    pub bitrate: Option<u32>,
+    /// Signal in dBm as measured, when the backend or iw reports it.
+    pub dbm: Option<i32>,
+    /// 802.11 generation, channel width and MCS of the link, from iw.
+    pub link: Option<LinkRate>,
+}
+
+/// A WiFi management backend. All methods but `name` and `change_summary`
+/// block and are only called from the background worker threads. Errors are
+/// already beginner-friendly text.
+pub trait Backend: Send + Sync {
+    /// Short name shown to the user (e.g. "nmcli").
+    fn name(&self) -> &'static str;
+
+    /// Whether wpa_supplicant does the scanning, so `roaming::apply` can
+    /// tune its background scans.
+    fn supports_bgscan(&self) -> bool {
+        false
+    }
+
+    /// Register to be asked for passwords during activation, delivered as
+    /// `Event::SecretRequest`. Returns false if the backend can't do this.
+    fn start_secret_agent(&mut self, _events: mpsc::Sender<Event>) -> bool {
+        false
+    }
+
+    /// Names of all WiFi devices (e.g. wlp3s0, wlan0); an error if there
+    /// are none.
+    fn detect_wifi_devices(&self) -> Result<Vec<String>, String>;
+
+    /// The first WiFi device.
+    fn detect_wifi_device(&self) -> Result<String, String> {
+        Ok(self.detect_wifi_devices()?.remove(0))
+    }
+
+    /// Scan for available networks, one entry per SSID, in no particular order.
+    fn scan_networks(&self, device: &str) -> Result<Vec<Network>, String>;
+
+    /// Get the current connection status.
+    fn get_status(&self, device: &str) -> ConnectionStatus;
+
+    /// How far a connect on `device` got, or None when it isn't activating
+    /// or the backend can't tell. Polled while the worker runs the connect.
+    fn connect_stage(&self, _device: &str) -> Option<ConnectStage> {
+        None
+    }
+
+    /// Signal of the current connection, read as cheaply as the backend
+    /// allows (no rescan); None if not connected.
+    fn current_signal(&self, device: &str) -> Option<u8> {
+        self.get_status(device).signal
+    }
+
+    /// List saved (known) WiFi connections.
+    fn saved_networks(&self) -> Result<Vec<SavedNetwork>, String>;
+
+    /// Connect to a network. If password is Some, connect as a new network
+    /// (empty for open networks); if None, reconnect a saved network.
+    fn connect(&self, device: &str, ssid: &str, password: Option<&str>) -> Result<String, String>;
+
+    /// Join an enterprise (802.1X) network for the first time, saving it.
+    fn connect_enterprise(&self, device: &str, ssid: &str, config: &EapConfig) -> Result<String, String>;
+
+    /// Disconnect from the current network.
+    fn disconnect(&self, device: &str) -> Result<String, String>;
+
+    /// Abort a connection attempt in progress on `device`. Runs outside the
+    /// worker, which is blocked waiting for that attempt to finish.
+    fn cancel_activation(&self, device: &str) -> Result<(), String>;
+
+    /// Forget (delete) a saved network.
+    fn forget(&self, name: &str) -> Result<String, String>;
+
+    /// Rename a saved profile.
+    fn rename_profile(&self, name: &str, new_name: &str) -> Result<String, String>;
+
+    /// Create a saved hotspot profile.
+    fn save_hotspot(&self, config: &HotspotConfig) -> Result<String, String>;
+
+    /// Start a saved hotspot profile.
+    fn start_hotspot(&self, name: &str) -> Result<String, String>;
+
+    /// Turn the device into an access point right away, replacing the
+    /// previous quick hotspot (the `nmcli device wifi hotspot` behaviour).
+    fn create_hotspot(&self, device: &str, ssid: &str, password: &str) -> Result<String, String>;
+
+    /// Stop a running hotspot profile.
+    fn stop_hotspot(&self, name: &str) -> Result<String, String>;
+
+    /// Read the SSID and stored password of a saved profile.
+    fn wifi_secret(&self, name: &str) -> Result<WifiSecret, String>;
+
+    /// Pin a saved profile to one access point (BSSID) and re-activate it,
+    /// to roam away from a weaker AP of the same network.
+    fn connect_bssid(&self, device: &str, name: &str, bssid: &str) -> Result<String, String>;
+
+    /// Latency-sensitive ("call") mode for a profile: with `on`, disable WiFi
+    /// power saving and pin the band (`"a"` or `"bg"`, if given); otherwise
+    /// restore both defaults. Re-activates the profile so it takes effect.
+    fn set_call_mode(&self, device: &str, name: &str, on: bool, band: Option<&str>) -> Result<String, String>;
+
+    /// Mark a profile as metered (or back to automatic detection), applying
+    /// it to the device right away if the profile is active.
+    fn set_metered(&self, device: &str, name: &str, metered: bool) -> Result<String, String>;
+
+    /// Autoconnect, priority, metered and MAC setting of a saved profile.
+    fn profile_options(&self, name: &str) -> Result<ProfileOptions, String>;
+
+    /// Change a saved profile's autoconnect, priority, metered and MAC setting.
+    fn set_profile_options(&self, name: &str, options: &ProfileOptions) -> Result<String, String>;
+
+    /// DNS servers of a saved profile.
+    fn dns(&self, name: &str) -> Result<DnsConfig, String>;
+
+    /// Replace a profile's DNS servers, applying them to the device right
+    /// away if the profile is active.
+    fn set_dns(&self, device: &str, name: &str, config: &DnsConfig) -> Result<String, String>;
+
+    /// Current IPv4 routing options of a saved profile.
+    fn routes(&self, name: &str) -> Result<RouteConfig, String>;
+
+    /// Replace a profile's IPv4 routing options, applying them to the device
+    /// right away if the profile is active.
+    fn set_routes(&self, device: &str, name: &str, config: &RouteConfig) -> Result<String, String>;
+
+    /// How a saved profile gets its address.
+    fn ipv4(&self, name: &str) -> Result<Ipv4Config, String>;
+
+    /// Switch a profile between DHCP and a fixed address, applying it to the
+    /// device right away if the profile is active.
+    fn set_ipv4(&self, device: &str, name: &str, config: &Ipv4Config) -> Result<String, String>;
+
+    /// Set a profile's `ipv4.route-metric` (None for NetworkManager's
+    /// default), applying it right away if the profile is active. Lower
+    /// metrics win the default route.
+    fn set_route_metric(&self, device: &str, name: &str, metric: Option<u32>) -> Result<String, String>;
+
+    /// What `change` will run or change on `device`, shown before it
+    /// happens when `confirm_details` is on.
+    fn change_summary(&self, device: &str, change: &Change) -> String;
+}
+
+/// `802-11-wireless.powersave` values.
+pub const POWERSAVE_DEFAULT: u32 = 0;
+pub const POWERSAVE_DISABLE: u32 = 2;
+
+/// Profile name used for hotspots created with `create_hotspot`; matches nmcli.
+pub const QUICK_HOTSPOT_NAME: &str = "Hotspot";
+
+/// Result text for `Backend::set_call_mode`.
+pub fn call_mode_message(name: &str, on: bool, band: Option<&str>) -> String {
+    match (on, band) {
+        (false, _) => format!("Call mode off for {}.", name),
+        (true, Some("a")) => format!("Call mode on for {}: power saving off, pinned to 5 GHz.", name),
+        (true, Some("bg")) => format!("Call mode on for {}: power saving off, pinned to 2.4 GHz.", name),
+        (true, _) => format!("Call mode on for {}: power saving off.", name),
+    }
+}
+
+/// Pick a backend: NetworkManager over D-Bus if it's reachable, then nmcli,
+/// then iwd. Falls back to nmcli so its "not installed" errors are shown.
+pub fn detect() -> Box<dyn Backend> {
+    if let Ok(backend) = DbusBackend::new() {
+        Box::new(backend)
+    } else if command_succeeds("nmcli", &["-t", "-f", "RUNNING", "general"]) {
+        Box::new(NmcliBackend)
+    } else if command_succeeds("iwctl", &["device", "list"]) {
+        Box::new(IwdBackend)
+    } else {
+        Box::new(NmcliBackend)
+    }
+}
+
+/// The device named with `--device`, else the one with the preferred
+/// hardware address, else the first WiFi device found.
+pub fn choose_device(backend: &dyn Backend, requested: Option<&str>, preferred: Option<&str>) -> Result<String, String> {
+    match requested {
+        Some(name) if std::path::Path::new("/sys/class/net").join(name).exists() => Ok(name.to_string()),
+        Some(name) => Err(format!("There is no network interface called '{}'.", name)),
+        None => {
+            let found = preferred.and_then(|mac| device_with_mac(&backend.detect_wifi_devices().ok()?, mac));
+            match found {
+                Some(device) => Ok(device),
+                None => backend.detect_wifi_device(),
+            }
+        }
+    }
+}
+
+fn command_succeeds(program: &str, args: &[&str]) -> bool {
+    logging::output(Command::new(program).args(args)).is_ok_and(|output| output.status.success())
+}
+
+/// Group per-BSSID scan results into one entry per SSID, dropping hidden
+/// networks. The entry shows the strongest AP, but always prefers the in-use
+/// one; every AP is kept in `access_points`. The order of networks is up to
+/// the caller.
+pub fn group_networks(scanned: Vec<Network>) -> Vec<Network> {
+    let mut best: HashMap<String, Network> = HashMap::new();
+    let mut aps: HashMap<String, Vec<AccessPoint>> = HashMap::new();
+
+    for net in scanned {
+        if net.ssid.is_empty() {
+            continue;
+        }
+        if !net.bssid.is_empty() {
+            aps.entry(net.ssid.clone()).or_default().push(AccessPoint {
+                bssid: net.bssid.clone(),
+                signal: net.signal,
+                channel: net.channel,
+                frequency: net.frequency,
+                in_use: net.in_use,
+            });
+        }
+        match best.get(&net.ssid) {
+            Some(existing) if !net.in_use && (existing.in_use || net.signal <= existing.signal) => {}
+            _ => {
+                best.insert(net.ssid.clone(), net);
+            }
+        }
+    }
+
+    best.into_values()
+        .map(|mut net| {
+            let mut access_points = aps.remove(&net.ssid).unwrap_or_default();
+            access_points.sort_by(|a, b| b.in_use.cmp(&a.in_use).then(b.signal.cmp(&a.signal)));
+            net.access_points = access_points;
+            net
+        })
+        .collect()
+}
+
+/// Number of clients associated with an access point on `device`. None if
+/// nl80211 isn't available.
+pub fn hotspot_clients(device: &str) -> Option<usize> {
+    nl80211::stations(device).ok().map(|stations| stations.len())
+}
+
+/// NetworkManager's connectivity checking, which captive portal detection
+/// relies on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConnectivityCheck {
+    /// A check URI is configured, so checking can be turned on at all.
+    pub available: bool,
+    pub enabled: bool,
+    /// `NMConnectivityState`: 0 unknown, 1 none, 2 portal, 3 limited, 4 full.
+    pub connectivity: u32,
+}
+
+/// How far NetworkManager got activating a device, from `GENERAL.STATE`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConnectStage {
+    Preparing,
+    Configuring,
+    NeedAuth,
+    GettingIp,
+    CheckingIp,
+    Secondaries,
+}
+
+impl ConnectStage {
+    pub fn label(self) -> &'static str {
+        match self {
+            ConnectStage::Preparing => "preparing",
+            ConnectStage::Configuring => "associating",
+            ConnectStage::NeedAuth => "waiting for the password",
+            ConnectStage::GettingIp => "getting an IP address",
+            ConnectStage::CheckingIp => "checking the connection",
+            ConnectStage::Secondaries => "starting dependent connections",
+        }
+    }
+}
+
+/// The activation stage of NetworkManager's device state `code`, or None
+/// outside activation. Still being connected to the previous network counts
+/// as not activating.
+pub fn device_state_stage(code: u32) -> Option<ConnectStage> {
+    match code {
+        40 => Some(ConnectStage::Preparing),
+        50 => Some(ConnectStage::Configuring),
+        60 => Some(ConnectStage::NeedAuth),
+        70 => Some(ConnectStage::GettingIp),
+        80 => Some(ConnectStage::CheckingIp),
+        90 => Some(ConnectStage::Secondaries),
+        _ => None,
+    }
+}
+
+/// IPv4 default routes from `ip route`, the one in use (lowest metric)
+/// first. Empty if `ip` isn't available.
+pub fn uplinks() -> Vec<Uplink> {
+    let Ok(output) = logging::output(Command::new("ip").args(["-4", "route", "show", "default"])) else {
+        return Vec::new();
+    };
+    parse_default_routes(&String::from_utf8_lossy(&output.stdout))
+        .into_iter()
+        .map(|(device, metric)| {
+            let sys = Path::new("/sys/class/net").join(&device);
+            // Virtual devices (VPNs, bridges) have no `device` link
+            let wired = sys.join("device").exists() && !sys.join("wireless").exists();
+            Uplink { device, metric, wired }
+        })
+        .collect()
+}
+
+/// Bytes a device has received and sent since it came up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TrafficCounters {
+    pub rx: u64,
+    pub tx: u64,
+}
+
+/// `device`'s byte counters from /proc/net/dev, or None if it isn't listed.
+pub fn traffic_counters(device: &str) -> Option<TrafficCounters> {
+    let text = std::fs::read_to_string("/proc/net/dev").ok()?;
+    parse_net_dev(&text, device)
+}
+
+/// The `wlan0: bytes packets ...` line of /proc/net/dev: eight receive
+/// columns, then the transmit ones.
+fn parse_net_dev(text: &str, device: &str) -> Option<TrafficCounters> {
+    text.lines().find_map(|line| {
+        let (name, columns) = line.split_once(':')?;
+        if name.trim() != device {
+            return None;
+        }
+        let columns: Vec<u64> = columns.split_whitespace().map(|c| c.parse().ok()).collect::<Option<_>>()?;
+        Some(TrafficCounters {
+            rx: *columns.first()?,
+            tx: *columns.get(8)?,
+        })
+    })
+}
+
+/// (device, metric) of each `default via ... dev wlan0 ... metric 600` line,
+/// sorted by metric. A missing metric is 0.
+fn parse_default_routes(text: &str) -> Vec<(String, u32)> {
+    let mut routes: Vec<(String, u32)> = text
+        .lines()
+        .filter_map(|line| {
+            let words: Vec<&str> = line.split_whitespace().collect();
+            let value = |key: &str| words.iter().position(|w| *w == key).and_then(|i| words.get(i + 1));
+            let device = value("dev")?.to_string();
+            let metric = value("metric").and_then(|m| m.parse().ok()).unwrap_or(0);
+            Some((device, metric))
+        })
+        .collect();
+    routes.sort_by_key(|(_, metric)| *metric);
+    routes
+}
+
+/// Check if an error message says the password given was wrong.
+pub fn error_wrong_password(msg: &str) -> bool {
+    msg.contains("Incorrect password")
+}
+
+/// Check if an error message indicates that a password is needed to connect.
+pub fn error_needs_password(msg: &str) -> bool {
+    msg.contains("Password required") || msg.contains("Incorrect password")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_virtual_interface() {
+        assert!(is_virtual_interface("p2p-dev-wlan0"));
+        assert!(is_virtual_interface("ap0"));
+        assert!(is_virtual_interface("uap1"));
+        assert!(!is_virtual_interface("wlan0"));
+        assert!(!is_virtual_interface("wlp3s0"));
+        assert!(!is_virtual_interface("ap"));
+        assert!(!is_virtual_interface("apple0"));
+    }
+
+    #[test]
+    fn test_parse_routes() {
+        let routes = parse_routes("10.0.0.0/8 192.168.1.1 100, 172.16.5.0/24, 192.168.9.9 50").unwrap();
+        assert_eq!(
+            format_routes(&routes),
+            "10.0.0.0/8 192.168.1.1 100, 172.16.5.0/24, 192.168.9.9/32 50"
+        );
+        assert_eq!(routes[2].metric, Some(50));
+        assert_eq!(routes[2].next_hop, None);
+        assert!(parse_routes("").unwrap().is_empty());
+        assert!(parse_routes("10.0.0.0/33").is_err());
+        assert!(parse_routes("lab").is_err());
+        assert!(parse_routes("10.0.0.0/8 gw 1").is_err());
+        assert!(parse_routes("10.0.0.0/8 10.0.0.1 1 2").is_err());
+    }
+
+    #[test]
+    fn test_parse_dns() {
+        let servers = parse_dns("1.1.1.1, 9.9.9.9 8.8.8.8").unwrap();
+        assert_eq!(format_dns(&servers), "1.1.1.1, 9.9.9.9, 8.8.8.8");
+        assert!(parse_dns(" ").unwrap().is_empty());
+        assert!(parse_dns("1.1.1.1, dns.google").is_err());
+    }
+
+    #[test]
+    fn test_parse_ipv4_address() {
+        assert_eq!(Ipv4Address::parse("10.0.0.7/8").unwrap().to_string(), "10.0.0.7/8");
+        assert_eq!(Ipv4Address::parse(" 192.168.1.50 ").unwrap().to_string(), "192.168.1.50/24");
+        assert!(Ipv4Address::parse("192.168.1.50/0").is_err());
+        assert!(Ipv4Address::parse("192.168.1/24").is_err());
+    }
+
+    #[test]
+    fn test_parse_default_routes() {
+        let routes = parse_default_routes(
+            "default via 192.168.1.1 dev wlp3s0 proto dhcp src 192.168.1.20 metric 600\n\
+             default via 10.0.0.1 dev enp0s31f6 proto dhcp src 10.0.0.5 metric 100\n\
+             default dev tun0 scope link\n",
+        );
+        assert_eq!(
+            routes,
+            [("tun0".to_string(), 0), ("enp0s31f6".to_string(), 100), ("wlp3s0".to_string(), 600)]
+        );
+        assert!(parse_default_routes("").is_empty());
+    }
+
+    #[test]
+    fn test_parse_net_dev() {
+        let text = "Inter-|   Receive                                                |  Transmit\n \
+                    face |bytes    packets errs drop fifo frame compressed multicast|bytes    packets errs drop fifo colls carrier compressed\n    \
+                    lo:    9000      90    0    0    0     0          0         0     9000      90    0    0    0     0       0          0\n\
+                    wlp3s0: 123456789  4000    0    0    0     0          0         0  2345678    3000    0    0    0     0       0          0\n";
+        assert_eq!(parse_net_dev(text, "wlp3s0"), Some(TrafficCounters { rx: 123_456_789, tx: 2_345_678 }));
+        assert_eq!(parse_net_dev(text, "wlan0"), None);
+    }
+
+    #[test]
+    fn test_ssid_bytes_roundtrip() {
+        assert_eq!(decode_ssid(b"Home"), "Home");
+        assert_eq!(decode_ssid(b"Caf\xe9 \\ 2"), "Caf\\xe9 \\\\ 2");
+        for ssid in [&b"Caf\xe9 \\ 2"[..], b"Home", b"a\\x41", b"\xff\xfe", "Café".as_bytes()] {
+            assert_eq!(encode_ssid(&decode_ssid(ssid)), ssid);
+        }
+    }
+
+    #[test]
+    fn test_distance_hint() {
+        assert_eq!(percent_to_dbm(100), -50);
+        assert_eq!(distance_hint(100, Some(2412)), "very close");
+        assert_eq!(distance_hint(60, Some(5180)), "nearby");
+        assert_eq!(distance_hint(60, None), "a few rooms away");
+        assert_eq!(distance_hint(30, Some(5955)), "far");
+    }
+
+    #[test]
+    fn test_weakness() {
+        let net = |security: &str| Network { security: security.to_string(), ..Network::default() };
+        assert_eq!(net("--").weakness(), Some("unencrypted"));
+        assert_eq!(net("WEP").weakness(), Some("WEP"));
+        assert_eq!(net("WPA2").weakness(), None);
+        assert_eq!(net("WPA2 WPA3").weakness(), None);
+    }
+
+    #[test]
+    fn test_group_networks() {
+        let ap = |ssid: &str, bssid: &str, signal, in_use| Network {
+            ssid: ssid.to_string(),
+            bssid: bssid.to_string(),
+            signal,
+            in_use,
+            ..Network::default()
+        };
+        let mut networks = group_networks(vec![
+            ap("Mesh", "aa", 40, true),
+            ap("Mesh", "bb", 80, false),
+            ap("Cafe", "cc", 60, false),
+            ap("", "dd", 90, false),
+        ]);
+        networks.sort_by(|a, b| b.ssid.cmp(&a.ssid));
+        assert_eq!(networks.len(), 2);
+        assert_eq!(networks[0].ssid, "Mesh");
+        assert_eq!(networks[0].bssid, "aa"); // in-use AP wins over stronger one
+        let bssids: Vec<&str> = networks[0].access_points.iter().map(|a| a.bssid.as_str()).collect();
+        assert_eq!(bssids, ["aa", "bb"]);
+        assert_eq!(networks[1].access_points.len(), 1);
+    }
+
+    #[test]
+    fn test_bands() {
+        let ap = |frequency| AccessPoint { frequency: Some(frequency), ..AccessPoint::default() };
+        let net = Network {
+            frequency: Some(5180),
+            access_points: vec![ap(5180), ap(2437), ap(5500)],
+            ..Network::default()
+        };
+        assert_eq!(net.band(), Some(Band::Ghz5));
+        assert_eq!(net.bands(), [Band::Ghz2, Band::Ghz5]);
+        let net = Network { frequency: Some(6115), ..Network::default() };
+        assert_eq!(net.bands(), [Band::Ghz6]);
+        assert_eq!(Band::of(900), None);
+        assert_eq!(Band::Ghz2.short_label(), "2.4");
+    }
+
+    #[test]
+    fn test_frequency_to_channel() {
+        assert_eq!(frequency_to_channel(2412), Some(1));
+        assert_eq!(frequency_to_channel(2484), Some(14));
+        assert_eq!(frequency_to_channel(5180), Some(36));
+        assert_eq!(frequency_to_channel(5975), Some(5));
+        assert_eq!(frequency_to_channel(900), None);
+    }
+}
@@ -0,0 +1,139 @@
+use std::cell::RefCell;
+use std::io::{self, Read, Write};
+use std::process::{Command, Output, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::logging;
+
+/// How often a running command checks whether it should be killed.
+const POLL: Duration = Duration::from_millis(20);
+
+/// Stops one background task: commands it runs through `output` are
+/// killed, and polling loops give up at their next `cancelled` check.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+thread_local! {
+    /// The token of the task running on this thread (the worker's).
+    static CURRENT: RefCell<Option<CancelToken>> = const { RefCell::new(None) };
+}
+
+/// Make `token` the one checked by `output` and `cancelled` on this thread.
+pub fn set_current(token: CancelToken) {
+    CURRENT.with(|current| *current.borrow_mut() = Some(token));
+}
+
+/// The token of the task running on this thread, to cancel it from another.
+pub fn current() -> Option<CancelToken> {
+    CURRENT.with(|current| current.borrow().clone())
+}
+
+/// Whether the task running on this thread was cancelled.
+pub fn cancelled() -> bool {
+    CURRENT.with(|current| current.borrow().as_ref().is_some_and(CancelToken::is_cancelled))
+}
+
+/// `Command::output`, but killed when the current task is cancelled, which
+/// then fails with "Cancelled.". Logged like `logging::output`.
+pub fn output(command: &mut Command) -> io::Result<Output> {
+    let started = Instant::now();
+    let result = run(command, None);
+    logging::log_command(command, started.elapsed(), &result);
+    result
+}
+
+/// `output` with `input` on the command's stdin, e.g. a password it
+/// prompts for. Stdin is closed afterwards, so a second prompt fails
+/// instead of waiting.
+pub fn output_with_input(command: &mut Command, input: &str) -> io::Result<Output> {
+    let started = Instant::now();
+    let result = run(command, Some(input));
+    logging::log_command(command, started.elapsed(), &result);
+    result
+}
+
+fn run(command: &mut Command, input: Option<&str>) -> io::Result<Output> {
+    let token = CURRENT.with(|current| current.borrow().clone());
+    if token.is_none() && input.is_none() {
+        return command.output();
+    }
+    let mut child = command
+        .stdin(if input.is_some() { Stdio::piped() } else { Stdio::null() })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+        // A command that exits without reading says why on stderr
+        let _ = stdin.write_all(input.as_bytes());
+    }
+    // Read both pipes meanwhile, so a chatty command can't fill one and stall
+    let stdout = read_in_background(child.stdout.take());
+    let stderr = read_in_background(child.stderr.take());
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if token.as_ref().is_some_and(CancelToken::is_cancelled) {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(io::Error::new(io::ErrorKind::Interrupted, "Cancelled."));
+        }
+        thread::sleep(POLL);
+    };
+    Ok(Output {
+        status,
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+    })
+}
+
+fn read_in_background(pipe: Option<impl Read + Send + 'static>) -> thread::JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buf = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut buf);
+        }
+        buf
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_output_killed_when_cancelled() {
+        let token = CancelToken::default();
+        set_current(token.clone());
+        let echoed = output(Command::new("echo").arg("hi")).unwrap();
+        assert_eq!(echoed.stdout, b"hi\n");
+
+        let canceller = token.clone();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(50));
+            canceller.cancel();
+        });
+        let err = output(Command::new("sleep").arg("10")).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::Interrupted);
+        assert!(cancelled());
+    }
+
+    #[test]
+    fn test_output_with_input() {
+        let output = output_with_input(&mut Command::new("cat"), "secret\n").unwrap();
+        assert_eq!(output.stdout, b"secret\n");
+    }
+}
@@ -0,0 +1,576 @@
+use std::net::Ipv4Addr;
+use std::process::Command;
+
+use crate::backend::{is_virtual_interface, ConnectivityCheck};
+use crate::logging;
+
+/// Outcome of a single system check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckState {
+    Ok,
+    Missing,
+    /// The check couldn't be performed (tool missing, permission denied).
+    Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+    pub name: &'static str,
+    pub state: CheckState,
+    pub detail: String,
+}
+
+impl Check {
+    fn new(name: &'static str, state: CheckState, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            state,
+            detail: detail.into(),
+        }
+    }
+}
+
+/// Check the pieces a NetworkManager shared (hotspot) connection needs to give
+/// its clients internet access: IP forwarding, a NAT masquerade rule, and the
+/// dnsmasq instance NM spawns for DHCP/DNS.
+/// Whether NetworkManager checks connectivity (and so spots captive
+/// portals), and what it found. `toggle_key` turns checking on or off.
+pub fn connectivity_checks(check: &ConnectivityCheck, toggle_key: &str) -> Vec<Check> {
+    const NAME: &str = "Connectivity checking";
+    let setting = if !check.available {
+        Check::new(
+            NAME,
+            CheckState::Missing,
+            "not configured: NetworkManager has no check URI. Set `uri=` under [connectivity] \
+             in NetworkManager.conf, or install your distribution's connectivity-check package",
+        )
+    } else if check.enabled {
+        Check::new(NAME, CheckState::Ok, format!("on. Press {} to turn it off", toggle_key))
+    } else {
+        Check::new(
+            NAME,
+            CheckState::Missing,
+            format!("off, so captive portals go unnoticed. Press {} to turn it on", toggle_key),
+        )
+    };
+
+    const STATE: &str = "Connectivity";
+    let state = match check.connectivity {
+        4 => Check::new(STATE, CheckState::Ok, "full internet access"),
+        3 => Check::new(STATE, CheckState::Missing, "limited: connected, but the internet isn't reachable"),
+        2 => Check::new(STATE, CheckState::Missing, "behind a captive portal: log in through a browser"),
+        1 => Check::new(STATE, CheckState::Missing, "not connected to any network"),
+        _ => Check::new(STATE, CheckState::Unknown, "unknown until checking is on"),
+    };
+    vec![setting, state]
+}
+
+/// Whether `device` can use 6 GHz (WiFi 6E) networks: the adapter's bands,
+/// the regulatory domain that unlocks them, the driver, and the 6 GHz
+/// networks seen in the last scan (`networks_seen`).
+pub fn six_ghz_checks(device: &str, networks_seen: usize) -> Vec<Check> {
+    let net = std::path::Path::new("/sys/class/net").join(device);
+    let phy = std::fs::read_to_string(net.join("phy80211").join("name")).ok();
+    let phy_info = phy.as_deref().and_then(|phy| run_stdout("iw", &["phy", phy.trim(), "info"]));
+
+    const ADAPTER: &str = "Adapter";
+    let adapter = match (phy, phy_info.as_deref().map(six_ghz_channels)) {
+        (None, _) => Check::new(ADAPTER, CheckState::Unknown, format!("{} has no wireless PHY in sysfs", device)),
+        (Some(_), None) => Check::new(ADAPTER, CheckState::Unknown, "couldn't run `iw phy info`; install iw"),
+        (Some(_), Some((0, _))) => Check::new(
+            ADAPTER,
+            CheckState::Missing,
+            "no 6 GHz band. WiFi 6E needs a 6E adapter (e.g. Intel AX210/BE200, MediaTek MT7921)",
+        ),
+        (Some(_), Some((_, 0))) => Check::new(
+            ADAPTER,
+            CheckState::Missing,
+            "has a 6 GHz band, but every channel is disabled, usually by the regulatory domain",
+        ),
+        (Some(_), Some((total, usable))) => Check::new(
+            ADAPTER,
+            CheckState::Ok,
+            format!("{} of {} 6 GHz channels usable", usable, total),
+        ),
+    };
+
+    const REGDOM: &str = "Regulatory domain";
+    let regdom = match run_stdout("iw", &["reg", "get"]).as_deref().and_then(reg_country) {
+        None => Check::new(REGDOM, CheckState::Unknown, "couldn't run `iw reg get`; install iw"),
+        Some("00") => Check::new(
+            REGDOM,
+            CheckState::Missing,
+            "world (00), which keeps 6 GHz off. Set your country, e.g. sudo iw reg set DE, \
+             or permanently in /etc/conf.d/wireless-regdom or your distribution's equivalent",
+        ),
+        Some(country) => Check::new(REGDOM, CheckState::Ok, format!("country {}", country)),
+    };
+
+    const DRIVER: &str = "Driver";
+    let driver = match std::fs::read_link(net.join("device").join("driver")) {
+        Ok(path) => {
+            let name = path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+            let kernel = std::fs::read_to_string("/proc/sys/kernel/osrelease").unwrap_or_default();
+            Check::new(DRIVER, CheckState::Ok, format!("{} on kernel {}", name, kernel.trim()))
+        }
+        Err(_) => Check::new(DRIVER, CheckState::Unknown, "not found in sysfs"),
+    };
+
+    const NETWORKS: &str = "6 GHz networks";
+    let networks = if networks_seen > 0 {
+        Check::new(NETWORKS, CheckState::Ok, format!("{} in the last scan, marked 6E", networks_seen))
+    } else {
+        Check::new(
+            NETWORKS,
+            CheckState::Missing,
+            "none in the last scan. 6 GHz reaches less far than 5 GHz, and needs WPA3 on the router",
+        )
+    };
+    vec![adapter, regdom, driver, networks]
+}
+
+/// (6 GHz channels, those not disabled) from `iw phy <phy> info`.
+fn six_ghz_channels(info: &str) -> (usize, usize) {
+    let channels: Vec<&str> = info
+        .lines()
+        .map(str::trim)
+        .filter_map(|line| line.strip_prefix("* "))
+        .filter(|line| {
+            line.split_once(" MHz")
+                .and_then(|(mhz, _)| mhz.trim().parse::<f64>().ok())
+                .is_some_and(|mhz| crate::backend::is_6ghz(mhz as u32))
+        })
+        .collect();
+    let usable = channels.iter().filter(|line| !line.contains("(disabled)")).count();
+    (channels.len(), usable)
+}
+
+/// The global country code from `iw reg get` ("country DE: DFS-ETSI").
+fn reg_country(stdout: &str) -> Option<&str> {
+    stdout.lines().find_map(|line| line.strip_prefix("country ")?.split(':').next())
+}
+
+pub fn sharing_checks(device: &str) -> Vec<Check> {
+    let subnet = run_stdout("ip", &["-4", "-o", "addr", "show", "dev", device])
+        .and_then(|stdout| ipv4_subnet(&stdout))
+        .unwrap_or_else(|| SHARED_SUBNET.to_string());
+    vec![check_ip_forward(), check_masquerade(&subnet), check_dnsmasq(device)]
+}
+
+/// What NetworkManager's shared mode hands out unless told otherwise.
+const SHARED_SUBNET: &str = "10.42.0.0/24";
+
+fn check_ip_forward() -> Check {
+    const NAME: &str = "IP forwarding";
+    match std::fs::read_to_string("/proc/sys/net/ipv4/ip_forward") {
+        Ok(value) if value.trim() == "1" => Check::new(NAME, CheckState::Ok, "enabled"),
+        Ok(_) => Check::new(
+            NAME,
+            CheckState::Missing,
+            "disabled. Enable with: sudo sysctl -w net.ipv4.ip_forward=1",
+        ),
+        Err(e) => Check::new(NAME, CheckState::Unknown, e.to_string()),
+    }
+}
+
+fn check_masquerade(subnet: &str) -> Check {
+    const NAME: &str = "NAT masquerade";
+
+    // firewalld manages its own rules; ask it directly if it's running.
+    if let Some(stdout) = run_stdout("firewall-cmd", &["--state"]) {
+        if stdout.trim() == "running" {
+            return match run_stdout("firewall-cmd", &["--list-all-zones"]) {
+                Some(zones) if has_firewalld_masquerade(&zones) => {
+                    Check::new(NAME, CheckState::Ok, "enabled in firewalld")
+                }
+                Some(_) => Check::new(
+                    NAME,
+                    CheckState::Missing,
+                    "no firewalld zone masquerades. Try: sudo firewall-cmd --zone=nm-shared --add-masquerade",
+                ),
+                None => Check::new(NAME, CheckState::Unknown, "could not query firewalld zones"),
+            };
+        }
+    }
+
+    // NetworkManager uses nftables on newer releases and iptables on older ones.
+    if let Some(ruleset) = run_stdout("nft", &["list", "ruleset"]) {
+        if has_masquerade_rule(&ruleset, subnet) {
+            return Check::new(NAME, CheckState::Ok, format!("nftables masquerades {}", subnet));
+        }
+    }
+    match run_stdout("iptables", &["-t", "nat", "-S", "POSTROUTING"]) {
+        Some(rules) if has_masquerade_rule(&rules, subnet) => {
+            Check::new(NAME, CheckState::Ok, format!("iptables masquerades {}", subnet))
+        }
+        Some(_) => Check::new(
+            NAME,
+            CheckState::Missing,
+            format!("no MASQUERADE rule for {} in nftables or iptables", subnet),
+        ),
+        None => Check::new(
+            NAME,
+            CheckState::Unknown,
+            "could not read firewall rules (try running as root)",
+        ),
+    }
+}
+
+fn check_dnsmasq(device: &str) -> Check {
+    const NAME: &str = "dnsmasq (DHCP/DNS)";
+    // Other dnsmasq instances (libvirt's, a local DNS cache) don't serve the hotspot
+    match logging::output(Command::new("pgrep").args(["-a", "-x", "dnsmasq"])) {
+        Ok(output) if String::from_utf8_lossy(&output.stdout).lines().any(|line| is_shared_dnsmasq(line, device)) => {
+            Check::new(NAME, CheckState::Ok, format!("NetworkManager's instance for {} is running", device))
+        }
+        Ok(_) => Check::new(
+            NAME,
+            CheckState::Missing,
+            format!(
+                "NetworkManager's instance for {} isn't running. Start the hotspot, and install dnsmasq \
+                 so NetworkManager can hand out addresses",
+                device
+            ),
+        ),
+        Err(e) => Check::new(NAME, CheckState::Unknown, e.to_string()),
+    }
+}
+
+/// Check what wifi-tui needs from NetworkManager before the UI starts, so
+/// problems show up with a fix instead of as scattered errors later.
+pub fn startup_checks() -> Vec<Check> {
+    let nmcli = check_nmcli();
+    if nmcli.state != CheckState::Ok {
+        // Every other check goes through nmcli
+        return vec![nmcli];
+    }
+    vec![nmcli, check_nm_running(), check_wifi_device(), check_radio(), check_permissions()]
+}
+
+/// Everything `wifi-tui doctor` looks at: the startup checks, and tools
+/// that only some features need.
+pub fn doctor_checks() -> Vec<Check> {
+    let mut checks = startup_checks();
+    checks.push(check_iw());
+    checks
+}
+
+fn check_nmcli() -> Check {
+    const NAME: &str = "nmcli";
+    match run_stdout("nmcli", &["--version"]) {
+        Some(stdout) => Check::new(
+            NAME,
+            CheckState::Ok,
+            nmcli_version(&stdout).map_or("installed".to_string(), |v| format!("version {}", v)),
+        ),
+        None => Check::new(
+            NAME,
+            CheckState::Missing,
+            "not installed. Install NetworkManager (e.g. sudo apt install network-manager)",
+        ),
+    }
+}
+
+fn check_nm_running() -> Check {
+    const NAME: &str = "NetworkManager";
+    match run_stdout("nmcli", &["-t", "-f", "RUNNING,STATE", "general"]) {
+        Some(stdout) if stdout.starts_with("running") => {
+            let state = stdout.trim().split(':').nth(1).unwrap_or("running");
+            Check::new(NAME, CheckState::Ok, state.to_string())
+        }
+        _ => Check::new(
+            NAME,
+            CheckState::Missing,
+            "not running. Start it with: sudo systemctl enable --now NetworkManager",
+        ),
+    }
+}
+
+fn check_wifi_device() -> Check {
+    const NAME: &str = "WiFi device";
+    let Some(stdout) = run_stdout("nmcli", &["-t", "-f", "DEVICE,TYPE,STATE", "device"]) else {
+        return Check::new(NAME, CheckState::Unknown, "could not list network devices");
+    };
+    let devices = wifi_devices(&stdout);
+    let managed: Vec<&str> = devices.iter().filter(|(_, state)| *state != "unmanaged").map(|(name, _)| *name).collect();
+    match devices.first() {
+        _ if !managed.is_empty() => Check::new(NAME, CheckState::Ok, managed.join(", ")),
+        Some((name, _)) => Check::new(
+            NAME,
+            CheckState::Missing,
+            format!(
+                "{} isn't managed by NetworkManager. Run: nmcli device set {} managed yes, \
+                 and remove it from unmanaged-devices in NetworkManager.conf",
+                name, name
+            ),
+        ),
+        None => Check::new(
+            NAME,
+            CheckState::Missing,
+            "none found. Check that the adapter's driver is loaded (lspci -k or lsusb -t) and that it \
+             isn't blocked (rfkill list)",
+        ),
+    }
+}
+
+fn check_radio() -> Check {
+    const NAME: &str = "WiFi radio";
+    match run_stdout("nmcli", &["-t", "-f", "WIFI-HW,WIFI", "general"]).as_deref().map(str::trim) {
+        Some("enabled:enabled") => Check::new(NAME, CheckState::Ok, "on"),
+        Some(state) if state.starts_with("missing") || state.starts_with("disabled") => Check::new(
+            NAME,
+            CheckState::Missing,
+            "blocked by a hardware switch or rfkill. Check the WiFi key, or run: rfkill unblock wifi",
+        ),
+        Some(_) => Check::new(NAME, CheckState::Missing, "off. Turn it on with: nmcli radio wifi on"),
+        None => Check::new(NAME, CheckState::Unknown, "could not read the radio state"),
+    }
+}
+
+fn check_permissions() -> Check {
+    const NAME: &str = "Permissions";
+    let Some(stdout) = run_stdout("nmcli", &["-t", "general", "permissions"]) else {
+        return Check::new(NAME, CheckState::Unknown, "could not query polkit permissions");
+    };
+    let denied: Vec<&str> = ["wifi.scan", "network-control", "settings.modify.system", "wifi.share.protected"]
+        .into_iter()
+        .filter(|permission| permission_value(&stdout, permission) == Some("no"))
+        .collect();
+    if denied.is_empty() {
+        Check::new(NAME, CheckState::Ok, "allowed to scan, connect and save networks")
+    } else {
+        Check::new(
+            NAME,
+            CheckState::Missing,
+            format!(
+                "polkit denies {}. Add your user to the netdev (or network) group, or run from an active local session",
+                denied.join(", ")
+            ),
+        )
+    }
+}
+
+fn check_iw() -> Check {
+    const NAME: &str = "iw";
+    match run_stdout("iw", &["--version"]) {
+        Some(stdout) => Check::new(NAME, CheckState::Ok, stdout.trim().replace("iw version", "version")),
+        None => Check::new(
+            NAME,
+            CheckState::Missing,
+            "not installed, so 6 GHz checks and disconnect reports are missing details. \
+             Install it (e.g. sudo apt install iw)",
+        ),
+    }
+}
+
+/// Why `--log-level` has no effect this time: the log file couldn't be
+/// opened.
+pub fn log_file_check(error: &str) -> Check {
+    Check::new("Log file", CheckState::Unknown, error)
+}
+
+/// Whether wifi-tui runs in a different network namespace than the host
+/// (VPN sandboxes, containers), where the WiFi adapter isn't visible. None
+/// when there's no answer (no access to PID 1, no sign of a named namespace),
+/// so an unprivileged user isn't warned for nothing. `helper` prints the host
+/// namespace's devices, e.g. "sudo nsenter -t 1 -n ip -br link".
+pub fn namespace_check(helper: Option<&str>) -> Option<Check> {
+    const NAME: &str = "Network namespace";
+    let foreign = match (std::fs::read_link("/proc/self/ns/net"), std::fs::read_link("/proc/1/ns/net")) {
+        (Ok(ours), Ok(host)) => ours != host,
+        _ => {
+            let pid = std::process::id().to_string();
+            !run_stdout("ip", &["netns", "identify", &pid])?.trim().is_empty()
+        }
+    };
+    if !foreign {
+        return Some(Check::new(NAME, CheckState::Ok, "host namespace"));
+    }
+    let mut detail = "running in a separate network namespace (VPN sandbox or container), so the host's WiFi adapters aren't visible. Run wifi-tui outside it".to_string();
+    if let Some(devices) = helper.and_then(|helper| run_stdout("sh", &["-c", helper])) {
+        detail.push_str(&format!(". Host devices: {}", device_names(&devices)));
+    }
+    Some(Check::new(NAME, CheckState::Missing, detail))
+}
+
+/// Interface names from `ip -br link` (or plain one-per-line) output,
+/// without loopback.
+fn device_names(stdout: &str) -> String {
+    let names: Vec<&str> = stdout
+        .lines()
+        .filter_map(|line| line.split_whitespace().next())
+        .map(|name| name.split('@').next().unwrap_or(name))
+        .filter(|name| *name != "lo")
+        .collect();
+    if names.is_empty() {
+        "none".to_string()
+    } else {
+        names.join(", ")
+    }
+}
+
+/// (name, state) of each WiFi adapter in `nmcli -t -f DEVICE,TYPE,STATE
+/// device`, leaving out the virtual ones NetworkManager adds for P2P and APs.
+fn wifi_devices(stdout: &str) -> Vec<(&str, &str)> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split(':');
+            let (name, kind, state) = (fields.next()?, fields.next()?, fields.next()?);
+            (kind == "wifi" && !is_virtual_interface(name)).then_some((name, state))
+        })
+        .collect()
+}
+
+/// Version number from `nmcli --version` ("nmcli tool, version 1.46.0").
+fn nmcli_version(stdout: &str) -> Option<&str> {
+    stdout.trim().rsplit(' ').next().filter(|v| v.starts_with(|c: char| c.is_ascii_digit()))
+}
+
+/// "yes", "no" or "auth" for a `nmcli -t general permissions` entry.
+fn permission_value<'a>(stdout: &'a str, permission: &str) -> Option<&'a str> {
+    let key = format!("org.freedesktop.NetworkManager.{}:", permission);
+    stdout.lines().find_map(|line| line.strip_prefix(key.as_str()))
+}
+
+/// Run a command and return its stdout, or None if it failed to run or exited non-zero.
+fn run_stdout(program: &str, args: &[&str]) -> Option<String> {
+    let output = logging::output(Command::new(program).args(args)).ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Whether an iptables or nftables listing masquerades traffic from
+/// `subnet`. Docker and libvirt add masquerade rules of their own.
+fn has_masquerade_rule(rules: &str, subnet: &str) -> bool {
+    rules
+        .lines()
+        .any(|line| line.to_ascii_lowercase().contains("masquerade") && line.contains(subnet))
+}
+
+/// Network of the first address in `ip -4 -o addr show`, e.g. "10.42.0.0/24"
+/// for 10.42.0.1/24.
+fn ipv4_subnet(stdout: &str) -> Option<String> {
+    let cidr = stdout.split_whitespace().skip_while(|word| *word != "inet").nth(1)?;
+    let (address, len) = cidr.split_once('/')?;
+    let address: Ipv4Addr = address.parse().ok()?;
+    let len: u32 = len.parse().ok().filter(|len| *len <= 32)?;
+    let mask = u32::MAX.checked_shl(32 - len).unwrap_or(0);
+    Some(format!("{}/{}", Ipv4Addr::from(u32::from(address) & mask), len))
+}
+
+/// Whether a `pgrep -a` line is the dnsmasq NetworkManager starts for
+/// sharing on `device`.
+fn is_shared_dnsmasq(line: &str, device: &str) -> bool {
+    line.contains("--conf-dir=/etc/NetworkManager/dnsmasq-shared.d")
+        && line.contains(&format!("/nm-dnsmasq-{}.pid", device))
+}
+
+fn has_firewalld_masquerade(zones: &str) -> bool {
+    zones
+        .lines()
+        .any(|line| line.trim() == "masquerade: yes")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_six_ghz_channels() {
+        let info = "\tBand 2:\n\t\tFrequencies:\n\t\t\t* 5180.0 MHz [36] (22.0 dBm)\n\
+                    \tBand 4:\n\t\tFrequencies:\n\t\t\t* 5955.0 MHz [1] (disabled)\n\
+                    \t\t\t* 5975 MHz [5] (12.0 dBm) (no IR)\n";
+        assert_eq!(six_ghz_channels(info), (2, 1));
+        assert_eq!(six_ghz_channels(""), (0, 0));
+    }
+
+    #[test]
+    fn test_reg_country() {
+        assert_eq!(reg_country("global\ncountry DE: DFS-ETSI\n\t(2400 - 2483 @ 40)\n"), Some("DE"));
+        assert_eq!(reg_country("global\ncountry 00: DFS-UNSET\n"), Some("00"));
+        assert_eq!(reg_country(""), None);
+    }
+
+    #[test]
+    fn test_connectivity_checks() {
+        let check = ConnectivityCheck { available: true, enabled: false, connectivity: 0 };
+        let checks = connectivity_checks(&check, "T");
+        assert_eq!(checks[0].state, CheckState::Missing);
+        assert!(checks[0].detail.contains("Press T to turn it on"));
+        assert_eq!(checks[1].state, CheckState::Unknown);
+
+        let check = ConnectivityCheck { available: true, enabled: true, connectivity: 2 };
+        let checks = connectivity_checks(&check, "T");
+        assert_eq!(checks[0].state, CheckState::Ok);
+        assert!(checks[1].detail.contains("captive portal"));
+    }
+
+    #[test]
+    fn test_device_names() {
+        let stdout = "lo               UNKNOWN        00:00:00:00:00:00 <LOOPBACK,UP,LOWER_UP>\n\
+                      wlp3s0           UP             3c:22:fb:00:00:01 <BROADCAST,MULTICAST,UP,LOWER_UP>\n\
+                      veth1@if2        UP             ee:11:22:33:44:55 <BROADCAST,MULTICAST,UP,LOWER_UP>\n";
+        assert_eq!(device_names(stdout), "wlp3s0, veth1");
+        assert_eq!(device_names("lo\n"), "none");
+    }
+
+    #[test]
+    fn test_has_masquerade_rule() {
+        let subnet = "10.42.0.0/24";
+        assert!(has_masquerade_rule("-A POSTROUTING -s 10.42.0.0/24 ! -d 10.42.0.0/24 -j MASQUERADE", subnet));
+        assert!(has_masquerade_rule("\t\tip saddr 10.42.0.0/24 ip daddr != 10.42.0.0/24 masquerade", subnet));
+        assert!(!has_masquerade_rule("-P POSTROUTING ACCEPT", subnet));
+        // Docker's
+        assert!(!has_masquerade_rule("-A POSTROUTING -s 172.17.0.0/16 ! -o docker0 -j MASQUERADE", subnet));
+    }
+
+    #[test]
+    fn test_ipv4_subnet() {
+        let stdout = "3: wlan0    inet 10.42.0.1/24 brd 10.42.0.255 scope global noprefixroute wlan0\n";
+        assert_eq!(ipv4_subnet(stdout).as_deref(), Some("10.42.0.0/24"));
+        assert_eq!(ipv4_subnet("3: wlan0    inet 192.168.5.77/20 brd").as_deref(), Some("192.168.0.0/20"));
+        assert_eq!(ipv4_subnet(""), None);
+    }
+
+    #[test]
+    fn test_is_shared_dnsmasq() {
+        let nm = "1234 /usr/sbin/dnsmasq --conf-file=/dev/null --listen-address=10.42.0.1 \
+                  --pid-file=/run/nm-dnsmasq-wlan0.pid --conf-dir=/etc/NetworkManager/dnsmasq-shared.d";
+        assert!(is_shared_dnsmasq(nm, "wlan0"));
+        assert!(!is_shared_dnsmasq(nm, "wlan1"));
+        let libvirt = "987 /usr/sbin/dnsmasq --conf-file=/var/lib/libvirt/dnsmasq/default.conf";
+        assert!(!is_shared_dnsmasq(libvirt, "wlan0"));
+    }
+
+    #[test]
+    fn test_has_firewalld_masquerade() {
+        assert!(has_firewalld_masquerade("nm-shared (active)\n  masquerade: yes\n"));
+        assert!(!has_firewalld_masquerade("public\n  masquerade: no\n"));
+    }
+
+    #[test]
+    fn test_wifi_devices() {
+        let stdout = "wlp3s0:wifi:connected\np2p-dev-wlp3s0:wifi-p2p:disconnected\n\
+                      enp0s31f6:ethernet:unavailable\nwlan1:wifi:unmanaged\nlo:loopback:unmanaged\n";
+        assert_eq!(wifi_devices(stdout), [("wlp3s0", "connected"), ("wlan1", "unmanaged")]);
+        assert!(wifi_devices("").is_empty());
+    }
+
+    #[test]
+    fn test_nmcli_version() {
+        assert_eq!(nmcli_version("nmcli tool, version 1.46.0\n"), Some("1.46.0"));
+        assert_eq!(nmcli_version("nmcli tool\n"), None);
+    }
+
+    #[test]
+    fn test_permission_value() {
+        let stdout = "org.freedesktop.NetworkManager.network-control:yes\n\
+                      org.freedesktop.NetworkManager.settings.modify.system:auth\n";
+        assert_eq!(permission_value(stdout, "network-control"), Some("yes"));
+        assert_eq!(permission_value(stdout, "settings.modify.system"), Some("auth"));
+        assert_eq!(permission_value(stdout, "wifi.share.open"), None);
+    }
+}
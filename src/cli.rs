@@ -0,0 +1,123 @@
+use clap::builder::PossibleValuesParser;
+use clap::{Parser, Subcommand};
+
+use crate::config::Config;
+use crate::logging::LogLevel;
+use crate::theme::THEME_NAMES;
+
+/// Manage WiFi connections from the terminal.
+#[derive(Debug, Parser)]
+#[command(version, about)]
+pub struct Cli {
+    /// WiFi interface to manage, e.g. wlan0 (default: the first one found).
+    #[arg(long, value_name = "IFACE", global = true)]
+    pub device: Option<String>,
+
+    /// Built-in color theme.
+    #[arg(long, value_parser = PossibleValuesParser::new(THEME_NAMES))]
+    pub theme: Option<String>,
+
+    /// Seconds between background scans.
+    #[arg(long, value_name = "SECS", value_parser = clap::value_parser!(u64).range(1..))]
+    pub refresh_interval: Option<u64>,
+
+    /// Only scan at startup, after changes, and when asked to.
+    #[arg(long)]
+    pub no_auto_scan: bool,
+
+    /// Show signal strength as words instead of bars.
+    #[arg(long)]
+    pub text_signal: bool,
+
+    /// What goes into the log file under ~/.local/state/wifi-tui/logs
+    /// (default: warn).
+    #[arg(long, value_enum, value_name = "LEVEL", global = true)]
+    pub log_level: Option<LogLevel>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Print connection changes and scans instead of starting the UI.
+    Events {
+        /// One JSON object per line.
+        #[arg(long)]
+        json: bool,
+    },
+    /// Scan and list the networks in range.
+    Scan {
+        /// Print JSON instead of text.
+        #[arg(long)]
+        json: bool,
+    },
+    /// Show the current connection.
+    Status {
+        /// Print JSON instead of text.
+        #[arg(long)]
+        json: bool,
+    },
+    /// List saved profiles.
+    Saved {
+        /// Print JSON instead of text.
+        #[arg(long)]
+        json: bool,
+    },
+    /// Check NetworkManager, the WiFi device and permissions, and say how
+    /// to fix what's wrong.
+    Doctor,
+}
+
+impl Cli {
+    /// Command-line options win over config.toml.
+    pub fn apply(&self, config: &mut Config) {
+        if let Some(theme) = &self.theme {
+            config.theme = theme.clone();
+        }
+        if let Some(secs) = self.refresh_interval {
+            config.auto_refresh_secs = secs;
+        }
+        if self.no_auto_scan {
+            config.auto_scan = false;
+        }
+        if self.text_signal {
+            config.text_signal = true;
+        }
+        if let Some(level) = self.log_level {
+            config.log_level = level;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn test_cli() {
+        Cli::command().debug_assert();
+
+        let cli = Cli::try_parse_from(["wifi-tui", "--device", "wlan1", "--theme", "gruvbox", "--no-auto-scan"]).unwrap();
+        let mut config = Config::default();
+        cli.apply(&mut config);
+        assert_eq!(cli.device.as_deref(), Some("wlan1"));
+        assert_eq!(config.theme, "gruvbox");
+        assert!(!config.auto_scan);
+
+        let cli = Cli::try_parse_from(["wifi-tui", "events", "--json"]).unwrap();
+        assert!(matches!(cli.command, Some(Command::Events { json: true })));
+        let cli = Cli::try_parse_from(["wifi-tui", "status", "--json", "--device", "wlan0"]).unwrap();
+        assert!(matches!(cli.command, Some(Command::Status { json: true })));
+        let cli = Cli::try_parse_from(["wifi-tui", "doctor"]).unwrap();
+        assert!(matches!(cli.command, Some(Command::Doctor)));
+        let cli = Cli::try_parse_from(["wifi-tui", "scan", "--log-level", "debug"]).unwrap();
+        cli.apply(&mut config);
+        assert_eq!(config.log_level, LogLevel::Debug);
+
+        assert!(Cli::try_parse_from(["wifi-tui", "--theme", "neon"]).is_err());
+        assert!(Cli::try_parse_from(["wifi-tui", "--refresh-interval", "0"]).is_err());
+        assert!(Cli::try_parse_from(["wifi-tui", "--log-level", "verbose"]).is_err());
+    }
+}
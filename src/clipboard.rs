@@ -0,0 +1,106 @@
+use std::io::{self, Write};
+use std::process::{Command, Output, Stdio};
+use std::time::Instant;
+
+use crate::logging;
+
+/// Put `text` on the system clipboard: through wl-copy, xclip or xsel when
+/// a desktop session is around, otherwise with the OSC 52 escape sequence,
+/// which most terminals (and SSH sessions) pass on to the local clipboard.
+pub fn copy(text: &str) -> Result<(), String> {
+    let mut tools: Vec<&[&str]> = Vec::new();
+    if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        tools.push(&["wl-copy"]);
+    }
+    if std::env::var_os("DISPLAY").is_some() {
+        tools.push(&["xclip", "-selection", "clipboard"]);
+        tools.push(&["xsel", "--clipboard", "--input"]);
+    }
+    if tools.iter().any(|tool| pipe_to(tool, text).is_ok()) {
+        return Ok(());
+    }
+
+    let mut out = std::io::stdout();
+    write!(out, "\x1b]52;c;{}\x07", base64(text.as_bytes()))
+        .and_then(|_| out.flush())
+        .map_err(|e| format!("Couldn't copy: {}", e))
+}
+
+/// Text on the system clipboard, for terminals that don't paste on Ctrl+V
+/// themselves. Needs wl-paste, xclip or xsel; terminals don't let programs
+/// read the clipboard.
+pub fn paste() -> Result<String, String> {
+    let mut tools: Vec<&[&str]> = Vec::new();
+    if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        tools.push(&["wl-paste", "--no-newline"]);
+    }
+    if std::env::var_os("DISPLAY").is_some() {
+        tools.push(&["xclip", "-selection", "clipboard", "-o"]);
+        tools.push(&["xsel", "--clipboard", "--output"]);
+    }
+    for tool in tools {
+        let Ok(output) = logging::output(Command::new(tool[0]).args(&tool[1..]).stderr(Stdio::null())) else {
+            continue;
+        };
+        if output.status.success() {
+            return Ok(String::from_utf8_lossy(&output.stdout).into_owned());
+        }
+    }
+    Err("Couldn't read the clipboard. Install wl-clipboard or xclip, or paste with your terminal.".to_string())
+}
+
+fn pipe_to(tool: &[&str], text: &str) -> Result<(), String> {
+    let mut command = Command::new(tool[0]);
+    command.args(&tool[1..]);
+    let started = Instant::now();
+    let result = feed(&mut command, text);
+    logging::log_command(&command, started.elapsed(), &result);
+    match result {
+        Ok(output) if output.status.success() => Ok(()),
+        Ok(_) => Err(format!("{} failed", tool[0])),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Run `command` with `text` on its stdin. wl-copy and xclip stay around to
+/// serve the clipboard with their output still open, so it isn't read.
+fn feed(command: &mut Command, text: &str) -> io::Result<Output> {
+    let mut child = command.stdin(Stdio::piped()).stdout(Stdio::null()).stderr(Stdio::null()).spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes())?;
+    }
+    let status = child.wait()?;
+    Ok(Output { status, stdout: Vec::new(), stderr: Vec::new() })
+}
+
+/// Standard base64 with padding, as OSC 52 expects.
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bytes = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base64() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(base64(b"hunter2!"), "aHVudGVyMiE=");
+    }
+}
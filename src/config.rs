@@ -0,0 +1,203 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::app::{SortMode, View};
+use crate::internet::DEFAULT_PROBE_URL;
+use crate::keymap::Keymap;
+use crate::logging::LogLevel;
+use crate::theme::{ColorRole, Theme, ThemeColor, THEME_NAMES};
+use crate::units::{SignalUnit, UnitSystem};
+
+/// `$XDG_CONFIG_HOME/wifi-tui`, falling back to `~/.config/wifi-tui`.
+pub fn config_dir() -> Option<PathBuf> {
+    let base = std::env::var("XDG_CONFIG_HOME")
+        .ok()
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var("HOME").ok().map(|home| PathBuf::from(home).join(".config")))?;
+    Some(base.join("wifi-tui"))
+}
+
+/// Settings from `config.toml`. Every key is optional.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Redraw/timer interval in milliseconds.
+    pub tick_rate_ms: u64,
+    /// Seconds between background scans.
+    pub auto_refresh_secs: u64,
+    /// Scan every `auto_refresh_secs` (off with `--no-auto-scan`).
+    pub auto_scan: bool,
+    /// Give up on a connection attempt after this many seconds. 0 waits as
+    /// long as NetworkManager does.
+    pub connect_timeout_secs: u64,
+    pub default_sort: SortMode,
+    /// Tabs in the order shown, from "available", "saved", "hotspot",
+    /// "monitor" and "analyzer"; leave one out to hide it. The first opens
+    /// at startup.
+    pub tabs: Vec<View>,
+    /// Show signal as words instead of bars (also `--text-signal`).
+    pub text_signal: bool,
+    /// Ask before quitting while a background operation runs.
+    pub quit_protection: bool,
+    /// Say in confirmations what exactly will run or change, and confirm
+    /// DNS, address, routing and route metric changes too.
+    pub confirm_details: bool,
+    /// Clickable status bar: the network name opens its details, the signal
+    /// its recent history, and the spinner cancels the running operation.
+    pub mouse: bool,
+    /// Prefixes for rates and sizes: "si" (Mbit/s) or "binary" (Mibit/s).
+    /// Separators follow the locale.
+    pub units: UnitSystem,
+    /// Show signal strength as "percent" or "dbm"; `%` switches while running.
+    pub signal_unit: SignalUnit,
+    /// Ignore a key pressed again within this many milliseconds, so a
+    /// tremor or a held key doesn't act twice. 0 is off.
+    pub key_repeat_delay_ms: u64,
+    /// Command printing a network's password, with `{ssid}` standing for
+    /// the SSID (e.g. "rbw get wifi/{ssid}"). Fills the password prompt.
+    pub password_command: Option<String>,
+    /// Seconds between pings of the gateway and `ping_host` while
+    /// connected. 0 is off.
+    pub ping_interval_secs: u64,
+    /// Host pinged besides the gateway, to tell a weak link from a dead
+    /// internet connection. Empty pings only the gateway.
+    pub ping_host: String,
+    /// Fetched after connecting and every minute after, expecting an empty
+    /// 204 answer, to tell "connected" from "connected, but no internet".
+    /// Empty turns the probe off.
+    pub internet_probe_url: String,
+    /// Look for a newer release on crates.io at startup (at most once a
+    /// day) and say so in a toast.
+    pub check_updates: bool,
+    /// Command listing the host's network devices, shown when wifi-tui runs
+    /// in another network namespace (e.g. "sudo nsenter -t 1 -n ip -br link").
+    pub host_devices_command: Option<String>,
+    /// What goes into the log file under `~/.local/state/wifi-tui/logs`
+    /// (also `--log-level`).
+    pub log_level: LogLevel,
+    /// Built-in theme (also `--theme`).
+    pub theme: String,
+    /// Colors replacing the theme's, e.g. `bad = "#ff5f5f"`.
+    pub colors: HashMap<ColorRole, ThemeColor>,
+    /// Key bindings by action, e.g. `up = ["up", "t"]`.
+    pub keys: Keymap,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            tick_rate_ms: 250,
+            auto_refresh_secs: 30,
+            auto_scan: true,
+            connect_timeout_secs: 60,
+            default_sort: SortMode::Signal,
+            tabs: View::ALL.to_vec(),
+            text_signal: false,
+            quit_protection: true,
+            confirm_details: true,
+            mouse: false,
+            units: UnitSystem::Si,
+            signal_unit: SignalUnit::Percent,
+            key_repeat_delay_ms: 0,
+            password_command: None,
+            ping_interval_secs: 5,
+            ping_host: "1.1.1.1".to_string(),
+            internet_probe_url: DEFAULT_PROBE_URL.to_string(),
+            check_updates: false,
+            host_devices_command: None,
+            log_level: LogLevel::default(),
+            theme: "default".to_string(),
+            colors: HashMap::new(),
+            keys: Keymap::default(),
+        }
+    }
+}
+
+impl Config {
+    pub fn tick_rate(&self) -> Duration {
+        Duration::from_millis(self.tick_rate_ms.max(MIN_TICK_RATE_MS))
+    }
+
+    /// The chosen theme with `[colors]` applied.
+    pub fn theme(&self) -> Result<Theme, String> {
+        let theme = Theme::named(&self.theme).ok_or_else(|| {
+            format!("unknown theme '{}' (choose one of: {})", self.theme, THEME_NAMES.join(", "))
+        })?;
+        Ok(theme.with_colors(&self.colors))
+    }
+}
+
+const MIN_TICK_RATE_MS: u64 = 20;
+
+/// Load `config.toml`, or the defaults if there is none. A broken file is
+/// an error so the user learns why their settings are ignored.
+pub fn load() -> Result<Config, String> {
+    let Some(path) = config_dir().map(|dir| dir.join("config.toml")) else {
+        return Ok(Config::default());
+    };
+    match std::fs::read_to_string(&path) {
+        Ok(text) => parse(&text).map_err(|e| format!("{}: {}", path.display(), e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Config::default()),
+        Err(e) => Err(format!("Couldn't read {}: {}", path.display(), e)),
+    }
+}
+
+fn parse(text: &str) -> Result<Config, String> {
+    let config: Config = toml::from_str(text).map_err(|e| e.message().to_string())?;
+    config.theme()?;
+    if config.tabs.is_empty() {
+        return Err("tabs: list at least one tab".to_string());
+    }
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keymap::Action;
+    use ratatui::style::Color;
+
+    #[test]
+    fn test_parse_partial() {
+        let config = parse(
+            "auto_refresh_secs = 60\n\
+             default_sort = \"channel\"\n\
+             theme = \"solarized\"\n\
+             [colors]\n\
+             good = \"#00ff00\"\n\
+             highlight = \"238\"\n\
+             [keys]\n\
+             refresh = \"u\"\n\
+             down = [\"down\", \"n\"]\n",
+        )
+        .unwrap();
+        assert_eq!(config.auto_refresh_secs, 60);
+        assert_eq!(config.tick_rate_ms, 250);
+        assert_eq!(config.default_sort, SortMode::Channel);
+        let theme = config.theme().unwrap();
+        assert_eq!(theme.good, Color::Rgb(0, 255, 0));
+        assert_eq!(theme.highlight, Color::Indexed(238));
+        assert_eq!(theme.bad, Color::Rgb(0xdc, 0x32, 0x2f));
+        assert_eq!(config.keys.label(Action::Refresh), "u");
+        assert_eq!(config.keys.label(Action::Forget), "F");
+        assert_eq!(config.keys.label(Action::Down), "↓");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse("").is_ok());
+        assert!(parse("auto_refresh = 5").is_err());
+        assert!(parse("theme = \"neon\"").is_err());
+        assert!(parse("[colors]\nbackground = \"red\"").is_err());
+        assert!(parse("[colors]\nbad = \"reddish\"").is_err());
+        assert!(parse("[keys]\nrefresh = \"hyper-r\"").is_err());
+        assert!(parse("[keys]\nteleport = \"t\"").is_err());
+        assert!(parse("tabs = []").is_err());
+        assert!(parse("tabs = [\"vpn\"]").is_err());
+        assert_eq!(parse("tabs = [\"saved\", \"available\"]").unwrap().tabs, [View::SavedNetworks, View::AvailableNetworks]);
+    }
+}
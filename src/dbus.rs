@@ -0,0 +1,1149 @@
+use std::collections::HashMap;
+use std::net::Ipv4Addr;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use zbus::blocking::{proxy, Connection, Proxy};
+use zbus::proxy::CacheProperties;
+use zbus::zvariant::{ObjectPath, OwnedObjectPath, OwnedValue, Str, Value};
+
+use crate::agent;
+use crate::cancel;
+use crate::backend::{
+    call_mode_message, decode_ssid, device_state_stage, dns_message, encode_ssid, format_dns, format_routes, frequency_to_channel,
+    group_networks, ipv4_addresses, ipv4_gateway, ipv4_message, ipv4_method, route_metric, yes_no, Change,
+    is_virtual_interface, profile_options_message, route_metric_message, routes_message, Backend, ConnectStage,
+    ConnectionStatus, ConnectivityCheck, DnsConfig, ClonedMac, EapConfig, HotspotBand, HotspotConfig, Ipv4Address, Ipv4Config, Metered,
+    Network, ProfileOptions, RouteConfig, SavedNetwork, StaticRoute,
+    WifiSecret, SECRETS_DENIED,
+    NO_WIFI_ADAPTER, POWERSAVE_DEFAULT, POWERSAVE_DISABLE, QUICK_HOTSPOT_NAME,
+};
+use crate::event::Event;
+use crate::nl80211;
+
+const NM_SERVICE: &str = "org.freedesktop.NetworkManager";
+const NM_PATH: &str = "/org/freedesktop/NetworkManager";
+const SETTINGS_PATH: &str = "/org/freedesktop/NetworkManager/Settings";
+
+const NM_IFACE: &str = "org.freedesktop.NetworkManager";
+const DEVICE_IFACE: &str = "org.freedesktop.NetworkManager.Device";
+const WIRELESS_IFACE: &str = "org.freedesktop.NetworkManager.Device.Wireless";
+const AP_IFACE: &str = "org.freedesktop.NetworkManager.AccessPoint";
+const IP4_IFACE: &str = "org.freedesktop.NetworkManager.IP4Config";
+const SETTINGS_IFACE: &str = "org.freedesktop.NetworkManager.Settings";
+const CONNECTION_IFACE: &str = "org.freedesktop.NetworkManager.Settings.Connection";
+const ACTIVE_IFACE: &str = "org.freedesktop.NetworkManager.Connection.Active";
+
+const NM_DEVICE_TYPE_WIFI: u32 = 2;
+const NM_ACTIVE_CONNECTION_STATE_ACTIVATED: u32 = 2;
+const NM_ACTIVE_CONNECTION_STATE_DEACTIVATED: u32 = 4;
+const NM_DEVICE_STATE_REASON_NO_SECRETS: u32 = 7;
+const NM_DEVICE_STATE_REASON_SUPPLICANT_DISCONNECT: u32 = 8;
+const NM_DEVICE_STATE_REASON_SUPPLICANT_TIMEOUT: u32 = 11;
+
+const NM_METERED_UNKNOWN: i32 = 0;
+const NM_METERED_YES: i32 = 1;
+const NM_METERED_NO: i32 = 2;
+const NM_SETTINGS_UPDATE2_FLAG_TO_DISK: u32 = 0x1;
+const NM_802_11_MODE_ADHOC: u32 = 1;
+const NM_802_11_MODE_INFRA: u32 = 2;
+const NM_802_11_MODE_MESH: u32 = 4;
+const NM_802_11_AP_FLAGS_PRIVACY: u32 = 0x1;
+const NM_802_11_AP_SEC_KEY_MGMT_PSK: u32 = 0x100;
+const NM_802_11_AP_SEC_KEY_MGMT_802_1X: u32 = 0x200;
+const NM_802_11_AP_SEC_KEY_MGMT_SAE: u32 = 0x400;
+
+/// How long to wait for an activation to either succeed or fail.
+const ACTIVATION_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// Settings dict in the `a{sa{sv}}` shape NetworkManager expects.
+type Settings<'a> = HashMap<&'a str, HashMap<&'a str, Value<'a>>>;
+pub type OwnedSettings = HashMap<String, HashMap<String, OwnedValue>>;
+
+/// Backend that talks to NetworkManager over the system D-Bus, without
+/// spawning `nmcli` or `iw`.
+pub struct DbusBackend {
+    conn: Connection,
+    /// Connection serving our secret agent, once registered.
+    agent: Option<Connection>,
+}
+
+impl DbusBackend {
+    /// Connect to the system bus. Fails if NetworkManager isn't reachable.
+    pub fn new() -> Result<Self, String> {
+        let conn = Connection::system().map_err(|e| dbus_error(&e))?;
+        let backend = Self { conn, agent: None };
+        backend
+            .proxy(NM_PATH, NM_IFACE)?
+            .get_property::<String>("Version")
+            .map_err(|e| dbus_error(&e))?;
+        Ok(backend)
+    }
+
+    fn proxy<'a>(&'a self, path: &'a str, iface: &'a str) -> Result<Proxy<'a>, String> {
+        proxy::Builder::new(&self.conn)
+            .destination(NM_SERVICE)
+            .and_then(|b| b.path(path))
+            .and_then(|b| b.interface(iface))
+            .map(|b| b.cache_properties(CacheProperties::No))
+            .and_then(|b| b.build())
+            .map_err(|e| dbus_error(&e))
+    }
+
+    fn get<T>(&self, path: &str, iface: &str, property: &str) -> Result<T, String>
+    where
+        T: TryFrom<OwnedValue>,
+        T::Error: Into<zbus::Error>,
+    {
+        self.proxy(path, iface)?
+            .get_property(property)
+            .map_err(|e| dbus_error(&e))
+    }
+
+    /// Object path of the WiFi device with the given interface name.
+    fn device_path(&self, device: &str) -> Result<OwnedObjectPath, String> {
+        for path in self.wifi_devices()? {
+            if self.get::<String>(path.as_str(), DEVICE_IFACE, "Interface")? == device {
+                return Ok(path);
+            }
+        }
+        Err("No WiFi adapter found. Make sure your WiFi hardware is enabled.".to_string())
+    }
+
+    fn wifi_devices(&self) -> Result<Vec<OwnedObjectPath>, String> {
+        let devices: Vec<OwnedObjectPath> = self
+            .proxy(NM_PATH, NM_IFACE)?
+            .call("GetDevices", &())
+            .map_err(|e| dbus_error(&e))?;
+        Ok(devices
+            .into_iter()
+            .filter(|path| {
+                self.get::<u32>(path.as_str(), DEVICE_IFACE, "DeviceType")
+                    .is_ok_and(|t| t == NM_DEVICE_TYPE_WIFI)
+            })
+            .collect())
+    }
+
+    /// All saved connection profiles with their settings.
+    fn connections(&self) -> Result<Vec<(OwnedObjectPath, OwnedSettings)>, String> {
+        let paths: Vec<OwnedObjectPath> = self
+            .proxy(SETTINGS_PATH, SETTINGS_IFACE)?
+            .call("ListConnections", &())
+            .map_err(|e| dbus_error(&e))?;
+        Ok(paths
+            .into_iter()
+            .filter_map(|path| {
+                let settings: OwnedSettings = self
+                    .proxy(path.as_str(), CONNECTION_IFACE)
+                    .ok()?
+                    .call("GetSettings", &())
+                    .ok()?;
+                Some((path, settings))
+            })
+            .collect())
+    }
+
+    /// Saved connection profile with the given name (`connection.id`).
+    fn connection_by_id(&self, name: &str) -> Result<OwnedObjectPath, String> {
+        self.connections()?
+            .into_iter()
+            .find(|(_, settings)| setting_str(settings, "connection", "id") == Some(name))
+            .map(|(path, _)| path)
+            .ok_or_else(|| "Saved connection not found. It may have already been removed.".to_string())
+    }
+
+    /// Saved client profile whose SSID matches, if any.
+    fn connection_for_ssid(&self, ssid: &str) -> Result<Option<OwnedObjectPath>, String> {
+        Ok(self
+            .connections()?
+            .into_iter()
+            .find(|(_, settings)| {
+                setting_bytes(settings, "802-11-wireless", "ssid").as_deref() == Some(&encode_ssid(ssid)[..])
+                    && setting_str(settings, "802-11-wireless", "mode") != Some("ap")
+            })
+            .map(|(path, _)| path))
+    }
+
+    fn access_point(&self, path: &str, active: Option<&str>) -> Result<Network, String> {
+        let ssid: Vec<u8> = self.get(path, AP_IFACE, "Ssid")?;
+        let flags: u32 = self.get(path, AP_IFACE, "Flags")?;
+        let wpa_flags: u32 = self.get(path, AP_IFACE, "WpaFlags")?;
+        let rsn_flags: u32 = self.get(path, AP_IFACE, "RsnFlags")?;
+        let frequency: Option<u32> = self.get(path, AP_IFACE, "Frequency").ok();
+        let max_bitrate: Option<u32> = self.get(path, AP_IFACE, "MaxBitrate").ok();
+        Ok(Network {
+            ssid: decode_ssid(&ssid),
+            ssid_bytes: ssid,
+            signal: self.get(path, AP_IFACE, "Strength")?,
+            security: security_string(flags, wpa_flags, rsn_flags),
+            in_use: active == Some(path),
+            bssid: self.get(path, AP_IFACE, "HwAddress").unwrap_or_default(),
+            channel: frequency.and_then(frequency_to_channel),
+            frequency,
+            rate: max_bitrate.map(|kbit| kbit / 1000),
+            mode: match self.get::<u32>(path, AP_IFACE, "Mode") {
+                Ok(NM_802_11_MODE_ADHOC) => "Ad-Hoc".to_string(),
+                Ok(NM_802_11_MODE_INFRA) => "Infra".to_string(),
+                Ok(NM_802_11_MODE_MESH) => "Mesh".to_string(),
+                _ => String::new(),
+            },
+            access_points: Vec::new(),
+        })
+    }
+
+    /// `key-mgmt` value for a secured access point with the given SSID: `sae`
+    /// for WPA3-only networks, `wpa-psk` for the rest.
+    fn key_mgmt_for(&self, device: &ObjectPath, ssid: &str) -> Option<&'static str> {
+        let aps: Vec<OwnedObjectPath> = self
+            .proxy(device.as_str(), WIRELESS_IFACE)
+            .ok()?
+            .call("GetAllAccessPoints", &())
+            .ok()?;
+        let ap = aps.iter().find(|ap| {
+            self.get::<Vec<u8>>(ap.as_str(), AP_IFACE, "Ssid")
+                .is_ok_and(|bytes| bytes == encode_ssid(ssid))
+        })?;
+        let wpa_flags: u32 = self.get(ap.as_str(), AP_IFACE, "WpaFlags").ok()?;
+        let rsn_flags: u32 = self.get(ap.as_str(), AP_IFACE, "RsnFlags").ok()?;
+        let key_mgmt = wpa_flags | rsn_flags;
+        if key_mgmt & NM_802_11_AP_SEC_KEY_MGMT_PSK != 0 {
+            Some("wpa-psk")
+        } else if key_mgmt & NM_802_11_AP_SEC_KEY_MGMT_SAE != 0 {
+            Some("sae")
+        } else {
+            None
+        }
+    }
+
+    /// Change a saved profile in place: `edit` gets its current settings, the
+    /// result is written back to disk. Returns the profile's path.
+    fn update_connection(
+        &self,
+        name: &str,
+        edit: impl FnOnce(&mut OwnedSettings),
+    ) -> Result<OwnedObjectPath, String> {
+        let connection = self.connection_by_id(name)?;
+        let proxy = self.proxy(connection.as_str(), CONNECTION_IFACE)?;
+        let mut settings: OwnedSettings = proxy.call("GetSettings", &()).map_err(|e| dbus_error(&e))?;
+        edit(&mut settings);
+
+        // Secrets left out of the update are kept by NetworkManager
+        let args: HashMap<&str, Value> = HashMap::new();
+        proxy
+            .call::<_, _, HashMap<String, OwnedValue>>(
+                "Update2",
+                &(settings, NM_SETTINGS_UPDATE2_FLAG_TO_DISK, args),
+            )
+            .map_err(|e| dbus_error(&e))?;
+        drop(proxy);
+        Ok(connection)
+    }
+
+    /// Best-effort: push changed settings to the running connection without
+    /// reconnecting.
+    fn reapply(&self, device: &str) {
+        if let Ok(device) = self.device_path(device) {
+            let applied: HashMap<&str, HashMap<&str, Value>> = HashMap::new();
+            if let Ok(proxy) = self.proxy(device.as_str(), DEVICE_IFACE) {
+                let _: Result<(), _> = proxy.call("Reapply", &(applied, 0u64, 0u32));
+            }
+        }
+    }
+
+    /// Activate a saved profile on the device and wait for the outcome.
+    fn activate(&self, connection: &ObjectPath, device: &ObjectPath) -> Result<(), String> {
+        let root = ObjectPath::from_static_str_unchecked("/");
+        let active: OwnedObjectPath = self
+            .proxy(NM_PATH, NM_IFACE)?
+            .call("ActivateConnection", &(connection, device, &root))
+            .map_err(|e| dbus_error(&e))?;
+        self.wait_for_activation(&active, device)
+    }
+
+    /// Poll an active connection until it is activated or torn down. On
+    /// failure, the device's state reason explains why.
+    fn wait_for_activation(&self, active: &ObjectPath, device: &ObjectPath) -> Result<(), String> {
+        let started = Instant::now();
+        while started.elapsed() < ACTIVATION_TIMEOUT {
+            match self.get::<u32>(active.as_str(), ACTIVE_IFACE, "State") {
+                Ok(NM_ACTIVE_CONNECTION_STATE_ACTIVATED) => return Ok(()),
+                Ok(NM_ACTIVE_CONNECTION_STATE_DEACTIVATED) | Err(_) => {
+                    let (_, reason): (u32, u32) = self
+                        .get(device.as_str(), DEVICE_IFACE, "StateReason")
+                        .unwrap_or((0, 0));
+                    return Err(reason_error(reason));
+                }
+                Ok(_) if cancel::cancelled() => return Err("Cancelled.".to_string()),
+                Ok(_) => thread::sleep(Duration::from_millis(250)),
+            }
+        }
+        Err("Connection timed out.".to_string())
+    }
+}
+
+impl Backend for DbusBackend {
+    fn name(&self) -> &'static str {
+        "dbus"
+    }
+
+    fn supports_bgscan(&self) -> bool {
+        true
+    }
+
+    fn start_secret_agent(&mut self, events: mpsc::Sender<Event>) -> bool {
+        match agent::register(events) {
+            Ok(conn) => {
+                self.agent = Some(conn);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    fn detect_wifi_devices(&self) -> Result<Vec<String>, String> {
+        let devices = self
+            .wifi_devices()?
+            .iter()
+            .map(|path| self.get(path.as_str(), DEVICE_IFACE, "Interface"))
+            .collect::<Result<Vec<String>, String>>()?;
+        let devices: Vec<String> = devices.into_iter().filter(|device| !is_virtual_interface(device)).collect();
+        if devices.is_empty() {
+            return Err(NO_WIFI_ADAPTER.to_string());
+        }
+        Ok(devices)
+    }
+
+    fn scan_networks(&self, device: &str) -> Result<Vec<Network>, String> {
+        let device = self.device_path(device)?;
+        let wireless = self.proxy(device.as_str(), WIRELESS_IFACE)?;
+
+        // Trigger a rescan first (best-effort, ignore errors)
+        let options: HashMap<&str, Value> = HashMap::new();
+        let _: Result<(), _> = wireless.call("RequestScan", &(options,));
+
+        let active: OwnedObjectPath = wireless
+            .get_property("ActiveAccessPoint")
+            .map_err(|e| dbus_error(&e))?;
+        let aps: Vec<OwnedObjectPath> = wireless
+            .call("GetAllAccessPoints", &())
+            .map_err(|e| dbus_error(&e))?;
+
+        let networks = aps
+            .iter()
+            .filter_map(|ap| self.access_point(ap.as_str(), Some(active.as_str())).ok())
+            .collect();
+        Ok(group_networks(networks))
+    }
+
+    fn connect_stage(&self, device: &str) -> Option<ConnectStage> {
+        let device = self.device_path(device).ok()?;
+        device_state_stage(self.get::<u32>(device.as_str(), DEVICE_IFACE, "State").ok()?)
+    }
+
+    fn get_status(&self, device: &str) -> ConnectionStatus {
+        let mut status = ConnectionStatus::default();
+        let name = device;
+        let Ok(device) = self.device_path(device) else {
+            return status;
+        };
+
+        let Ok(ap) = self.get::<OwnedObjectPath>(device.as_str(), WIRELESS_IFACE, "ActiveAccessPoint")
+        else {
+            return status;
+        };
+        if ap.as_str() == "/" {
+            return status;
+        }
+        if let Ok(net) = self.access_point(ap.as_str(), None) {
+            status.ssid = Some(net.ssid);
+            status.signal = Some(net.signal);
+        }
+
+        if let Ok(ip4) = self.get::<OwnedObjectPath>(device.as_str(), DEVICE_IFACE, "Ip4Config") {
+            let addresses: Vec<HashMap<String, OwnedValue>> = self
+                .get(ip4.as_str(), IP4_IFACE, "AddressData")
+                .unwrap_or_default();
+            status.ip = addresses
+                .first()
+                .and_then(|data| data.get("address"))
+                .and_then(|v| <&str>::try_from(v).ok())
+                .map(str::to_string);
+        }
+
+        if let Ok(kbits) = self.get::<u32>(device.as_str(), WIRELESS_IFACE, "Bitrate") {
+            if kbits > 0 {
+                status.bitrate = Some(kbits);
+            }
+        }
+        // NetworkManager only has the percentage, and no PHY details
+        if let Some(station) = nl80211::link_station(name) {
+            status.dbm = station.signal_dbm;
+            status.link = station.tx_rate;
+        }
+
+        status
+    }
+
+    fn current_signal(&self, device: &str) -> Option<u8> {
+        let device = self.device_path(device).ok()?;
+        let ap: OwnedObjectPath = self.get(device.as_str(), WIRELESS_IFACE, "ActiveAccessPoint").ok()?;
+        if ap.as_str() == "/" {
+            return None;
+        }
+        self.get(ap.as_str(), AP_IFACE, "Strength").ok()
+    }
+
+    fn saved_networks(&self) -> Result<Vec<SavedNetwork>, String> {
+        let active: Vec<OwnedObjectPath> = self.get(NM_PATH, NM_IFACE, "ActiveConnections")?;
+        let active_profiles: Vec<OwnedObjectPath> = active
+            .iter()
+            .filter_map(|path| self.get(path.as_str(), ACTIVE_IFACE, "Connection").ok())
+            .collect();
+
+        Ok(self
+            .connections()?
+            .into_iter()
+            .filter(|(_, settings)| setting_str(settings, "connection", "type") == Some("802-11-wireless"))
+            .map(|(path, settings)| SavedNetwork {
+                name: setting_str(&settings, "connection", "id").unwrap_or_default().to_string(),
+                active: active_profiles.contains(&path),
+                hotspot: setting_str(&settings, "802-11-wireless", "mode") == Some("ap"),
+            })
+            .collect())
+    }
+
+    fn connect(&self, device: &str, ssid: &str, password: Option<&str>) -> Result<String, String> {
+        let device = self.device_path(device)?;
+
+        let password = match password {
+            // Saved network - reconnect
+            None => {
+                let connection = self.connection_by_id(ssid)?;
+                self.activate(&connection, &device)?;
+                return Ok(format!("Connected to {}", ssid));
+            }
+            Some(pw) => pw,
+        };
+
+        // Reuse a saved profile when no new password was given
+        if password.is_empty() {
+            if let Some(connection) = self.connection_for_ssid(ssid)? {
+                self.activate(&connection, &device)?;
+                return Ok(format!("Connected to {}", ssid));
+            }
+        }
+
+        let mut settings: Settings = HashMap::new();
+        settings.insert(
+            "802-11-wireless",
+            HashMap::from([("ssid", Value::from(encode_ssid(ssid)))]),
+        );
+        if !password.is_empty() {
+            // WPA3-only networks take the password with SAE
+            let key_mgmt = self.key_mgmt_for(&device, ssid).unwrap_or("wpa-psk");
+            settings.insert(
+                "802-11-wireless-security",
+                HashMap::from([
+                    ("key-mgmt", Value::from(key_mgmt)),
+                    ("psk", Value::from(password)),
+                ]),
+            );
+        } else if self.agent.is_some() {
+            // Leave the secret out: NetworkManager asks our agent for it mid-activation
+            if let Some(key_mgmt) = self.key_mgmt_for(&device, ssid) {
+                settings.insert(
+                    "802-11-wireless-security",
+                    HashMap::from([("key-mgmt", Value::from(key_mgmt))]),
+                );
+            }
+        }
+
+        let root = ObjectPath::from_static_str_unchecked("/");
+        let (_, active): (OwnedObjectPath, OwnedObjectPath) = self
+            .proxy(NM_PATH, NM_IFACE)?
+            .call("AddAndActivateConnection", &(settings, &device, &root))
+            .map_err(|e| dbus_error(&e))?;
+        self.wait_for_activation(&active, &device)?;
+        Ok(format!("Connected to {}", ssid))
+    }
+
+    fn connect_enterprise(&self, device: &str, ssid: &str, config: &EapConfig) -> Result<String, String> {
+        let device = self.device_path(device)?;
+        let mut eap = HashMap::from([
+            ("eap", Value::from(vec![config.method.nm_value()])),
+            ("phase2-auth", Value::from("mschapv2")),
+            ("identity", Value::from(config.identity.as_str())),
+            ("password", Value::from(config.password.as_str())),
+        ]);
+        if let Some(ca_cert) = &config.ca_cert {
+            // A path is passed as a NUL-terminated file:// URI
+            let mut uri = format!("file://{}", ca_cert.display()).into_bytes();
+            uri.push(0);
+            eap.insert("ca-cert", Value::from(uri));
+        }
+        let settings: Settings = HashMap::from([
+            ("connection", HashMap::from([("id", Value::from(ssid))])),
+            ("802-11-wireless", HashMap::from([("ssid", Value::from(encode_ssid(ssid)))])),
+            ("802-11-wireless-security", HashMap::from([("key-mgmt", Value::from("wpa-eap"))])),
+            ("802-1x", eap),
+        ]);
+
+        let root = ObjectPath::from_static_str_unchecked("/");
+        let (connection, active): (OwnedObjectPath, OwnedObjectPath) = self
+            .proxy(NM_PATH, NM_IFACE)?
+            .call("AddAndActivateConnection", &(settings, &device, &root))
+            .map_err(|e| dbus_error(&e))?;
+        if let Err(e) = self.wait_for_activation(&active, &device) {
+            // So the next try starts clean instead of reusing a bad password
+            if let Ok(proxy) = self.proxy(connection.as_str(), CONNECTION_IFACE) {
+                let _: Result<(), _> = proxy.call("Delete", &());
+            }
+            return Err(e);
+        }
+        Ok(format!("Connected to {}", ssid))
+    }
+
+    fn cancel_activation(&self, device: &str) -> Result<(), String> {
+        let device = self.device_path(device)?;
+        let active: OwnedObjectPath = self.get(device.as_str(), DEVICE_IFACE, "ActiveConnection")?;
+        if active.as_str() == "/" {
+            return Ok(());
+        }
+        self.proxy(NM_PATH, NM_IFACE)?
+            .call::<_, _, ()>("DeactivateConnection", &(&active,))
+            .map_err(|e| dbus_error(&e))
+    }
+
+    fn disconnect(&self, device: &str) -> Result<String, String> {
+        let device = self.device_path(device)?;
+        self.proxy(device.as_str(), DEVICE_IFACE)?
+            .call::<_, _, ()>("Disconnect", &())
+            .map_err(|e| dbus_error(&e))?;
+        Ok("Disconnected.".to_string())
+    }
+
+    fn forget(&self, name: &str) -> Result<String, String> {
+        let connection = self.connection_by_id(name)?;
+        self.proxy(connection.as_str(), CONNECTION_IFACE)?
+            .call::<_, _, ()>("Delete", &())
+            .map_err(|e| dbus_error(&e))?;
+        Ok(format!("Forgot network '{}'.", name))
+    }
+
+    fn rename_profile(&self, name: &str, new_name: &str) -> Result<String, String> {
+        self.update_connection(name, |settings| {
+            settings
+                .entry("connection".to_string())
+                .or_default()
+                .insert("id".to_string(), OwnedValue::from(Str::from(new_name.to_string())));
+        })?;
+        Ok(format!("Renamed '{}' to '{}'.", name, new_name))
+    }
+
+    fn save_hotspot(&self, config: &HotspotConfig) -> Result<String, String> {
+        self.proxy(SETTINGS_PATH, SETTINGS_IFACE)?
+            .call::<_, _, OwnedObjectPath>("AddConnection", &(hotspot_settings(config),))
+            .map_err(|e| dbus_error(&e))?;
+        Ok(format!("Saved hotspot '{}'.", config.name))
+    }
+
+    fn start_hotspot(&self, name: &str) -> Result<String, String> {
+        let connection = self.connection_by_id(name)?;
+        let root = ObjectPath::from_static_str_unchecked("/");
+        self.activate(&connection, &root)?;
+        Ok(format!("Started hotspot '{}'.", name))
+    }
+
+    fn create_hotspot(&self, device: &str, ssid: &str, password: &str) -> Result<String, String> {
+        // Replace the previous quick hotspot, like nmcli does
+        if let Ok(previous) = self.connection_by_id(QUICK_HOTSPOT_NAME) {
+            self.proxy(previous.as_str(), CONNECTION_IFACE)?
+                .call::<_, _, ()>("Delete", &())
+                .map_err(|e| dbus_error(&e))?;
+        }
+
+        let config = HotspotConfig {
+            name: QUICK_HOTSPOT_NAME.to_string(),
+            ssid: ssid.to_string(),
+            password: password.to_string(),
+            band: HotspotBand::Auto,
+            interface: device.to_string(),
+        };
+        let device = self.device_path(device)?;
+        let root = ObjectPath::from_static_str_unchecked("/");
+        let (_, active): (OwnedObjectPath, OwnedObjectPath) = self
+            .proxy(NM_PATH, NM_IFACE)?
+            .call("AddAndActivateConnection", &(hotspot_settings(&config), &device, &root))
+            .map_err(|e| dbus_error(&e))?;
+        self.wait_for_activation(&active, &device)?;
+        Ok(format!("Started hotspot '{}'.", ssid))
+    }
+
+    fn stop_hotspot(&self, name: &str) -> Result<String, String> {
+        let active: Vec<OwnedObjectPath> = self.get(NM_PATH, NM_IFACE, "ActiveConnections")?;
+        let hotspot = active
+            .into_iter()
+            .find(|path| {
+                self.get::<String>(path.as_str(), ACTIVE_IFACE, "Id")
+                    .is_ok_and(|id| id == name)
+            })
+            .ok_or_else(|| format!("Hotspot '{}' is not running.", name))?;
+        self.proxy(NM_PATH, NM_IFACE)?
+            .call::<_, _, ()>("DeactivateConnection", &(&hotspot,))
+            .map_err(|e| dbus_error(&e))?;
+        Ok(format!("Stopped hotspot '{}'.", name))
+    }
+
+    fn connect_bssid(&self, device: &str, name: &str, bssid: &str) -> Result<String, String> {
+        let mac = parse_mac(bssid).ok_or_else(|| format!("'{}' is not a valid BSSID.", bssid))?;
+        let mac = OwnedValue::try_from(Value::from(mac.to_vec())).map_err(|e| e.to_string())?;
+        let connection = self.update_connection(name, |settings| {
+            settings
+                .entry("802-11-wireless".to_string())
+                .or_default()
+                .insert("bssid".to_string(), mac);
+        })?;
+        let device = self.device_path(device)?;
+        self.activate(&connection, &device)?;
+        Ok(format!("Connected to {} via {}", name, bssid))
+    }
+
+    fn set_call_mode(&self, device: &str, name: &str, on: bool, band: Option<&str>) -> Result<String, String> {
+        let powersave = if on { POWERSAVE_DISABLE } else { POWERSAVE_DEFAULT };
+        let connection = self.update_connection(name, |settings| {
+            let wireless = settings.entry("802-11-wireless".to_string()).or_default();
+            wireless.insert("powersave".to_string(), OwnedValue::from(powersave));
+            match band.filter(|_| on) {
+                Some(band) => {
+                    wireless.insert("band".to_string(), OwnedValue::from(Str::from(band.to_string())));
+                }
+                None => {
+                    wireless.remove("band");
+                }
+            }
+        })?;
+        let device = self.device_path(device)?;
+        self.activate(&connection, &device)?;
+        Ok(call_mode_message(name, on, band))
+    }
+
+    fn set_metered(&self, device: &str, name: &str, metered: bool) -> Result<String, String> {
+        let value = if metered { NM_METERED_YES } else { NM_METERED_UNKNOWN };
+        self.update_connection(name, |settings| {
+            settings
+                .entry("connection".to_string())
+                .or_default()
+                .insert("metered".to_string(), OwnedValue::from(value));
+        })?;
+        self.reapply(device);
+
+        Ok(if metered {
+            format!("Marked '{}' as metered.", name)
+        } else {
+            format!("'{}' is no longer marked as metered.", name)
+        })
+    }
+
+    fn profile_options(&self, name: &str) -> Result<ProfileOptions, String> {
+        let (_, settings) = self
+            .connections()?
+            .into_iter()
+            .find(|(_, settings)| setting_str(settings, "connection", "id") == Some(name))
+            .ok_or_else(|| "Saved connection not found. It may have already been removed.".to_string())?;
+
+        // Unset keys are NetworkManager's defaults
+        let connection = settings.get("connection");
+        let autoconnect = connection
+            .and_then(|c| c.get("autoconnect"))
+            .and_then(|v| bool::try_from(v).ok())
+            .unwrap_or(true);
+        let priority = connection
+            .and_then(|c| c.get("autoconnect-priority"))
+            .and_then(|v| i32::try_from(v).ok())
+            .unwrap_or(0);
+        let metered = match connection.and_then(|c| c.get("metered")).and_then(|v| i32::try_from(v).ok()) {
+            Some(NM_METERED_YES) => Metered::Yes,
+            Some(NM_METERED_NO) => Metered::No,
+            _ => Metered::Auto,
+        };
+        let mac = setting_str(&settings, "802-11-wireless", "assigned-mac-address")
+            .map_or(ClonedMac::Default, ClonedMac::from_nmcli);
+        Ok(ProfileOptions { autoconnect, priority, metered, mac })
+    }
+
+    fn set_profile_options(&self, name: &str, options: &ProfileOptions) -> Result<String, String> {
+        let metered = match options.metered {
+            Metered::Auto => NM_METERED_UNKNOWN,
+            Metered::Yes => NM_METERED_YES,
+            Metered::No => NM_METERED_NO,
+        };
+        self.update_connection(name, |settings| {
+            let connection = settings.entry("connection".to_string()).or_default();
+            connection.insert("autoconnect".to_string(), OwnedValue::from(options.autoconnect));
+            connection.insert("autoconnect-priority".to_string(), OwnedValue::from(options.priority));
+            connection.insert("metered".to_string(), OwnedValue::from(metered));
+            let wireless = settings.entry("802-11-wireless".to_string()).or_default();
+            // The legacy byte form only holds explicit addresses
+            wireless.remove("cloned-mac-address");
+            match &options.mac {
+                ClonedMac::Default => wireless.remove("assigned-mac-address"),
+                mac => {
+                    let value = OwnedValue::from(Str::from(mac.nmcli_value().to_string()));
+                    wireless.insert("assigned-mac-address".to_string(), value)
+                }
+            };
+        })?;
+        Ok(profile_options_message(name, options))
+    }
+
+    fn dns(&self, name: &str) -> Result<DnsConfig, String> {
+        let (_, settings) = self
+            .connections()?
+            .into_iter()
+            .find(|(_, settings)| setting_str(settings, "connection", "id") == Some(name))
+            .ok_or_else(|| "Saved connection not found. It may have already been removed.".to_string())?;
+
+        let ipv4 = settings.get("ipv4");
+        let ignore_auto = ipv4
+            .and_then(|ipv4| ipv4.get("ignore-auto-dns"))
+            .and_then(|v| bool::try_from(v).ok())
+            .unwrap_or(false);
+        // Addresses as u32 in network byte order
+        let servers = ipv4
+            .and_then(|ipv4| ipv4.get("dns"))
+            .and_then(|v| v.try_clone().ok())
+            .and_then(|v| Vec::<u32>::try_from(v).ok())
+            .unwrap_or_default();
+        Ok(DnsConfig {
+            ignore_auto,
+            servers: servers.into_iter().map(|addr| Ipv4Addr::from(addr.to_ne_bytes())).collect(),
+        })
+    }
+
+    fn set_dns(&self, device: &str, name: &str, config: &DnsConfig) -> Result<String, String> {
+        let servers: Vec<u32> = config.servers.iter().map(|server| u32::from_ne_bytes(server.octets())).collect();
+        let servers = OwnedValue::try_from(Value::from(servers)).map_err(|e| e.to_string())?;
+        self.update_connection(name, |settings| {
+            let ipv4 = settings.entry("ipv4".to_string()).or_default();
+            ipv4.insert("ignore-auto-dns".to_string(), OwnedValue::from(config.ignore_auto));
+            // Newer NetworkManager keeps a string copy that would win over `dns`
+            ipv4.remove("dns-data");
+            ipv4.insert("dns".to_string(), servers);
+        })?;
+        self.reapply(device);
+        Ok(dns_message(name, config))
+    }
+
+    fn routes(&self, name: &str) -> Result<RouteConfig, String> {
+        let (_, settings) = self
+            .connections()?
+            .into_iter()
+            .find(|(_, settings)| setting_str(settings, "connection", "id") == Some(name))
+            .ok_or_else(|| "Saved connection not found. It may have already been removed.".to_string())?;
+
+        let ipv4 = settings.get("ipv4");
+        let never_default = ipv4
+            .and_then(|ipv4| ipv4.get("never-default"))
+            .and_then(|v| bool::try_from(v).ok())
+            .unwrap_or(false);
+        let metric = ipv4
+            .and_then(|ipv4| ipv4.get("route-metric"))
+            .and_then(|v| i64::try_from(v).ok())
+            .and_then(|m| u32::try_from(m).ok());
+        let route_data = ipv4
+            .and_then(|ipv4| ipv4.get("route-data"))
+            .and_then(|v| v.try_clone().ok())
+            .and_then(|v| Vec::<HashMap<String, OwnedValue>>::try_from(v).ok())
+            .unwrap_or_default();
+        Ok(RouteConfig {
+            never_default,
+            metric,
+            routes: route_data.iter().filter_map(route_from_data).collect(),
+        })
+    }
+
+    fn set_routes(&self, device: &str, name: &str, config: &RouteConfig) -> Result<String, String> {
+        let route_data: Vec<HashMap<&str, Value>> = config.routes.iter().map(route_data).collect();
+        let route_data = OwnedValue::try_from(Value::from(route_data)).map_err(|e| e.to_string())?;
+        self.update_connection(name, |settings| {
+            let ipv4 = settings.entry("ipv4".to_string()).or_default();
+            ipv4.insert("never-default".to_string(), OwnedValue::from(config.never_default));
+            ipv4.insert("route-metric".to_string(), OwnedValue::from(config.metric.map_or(-1, i64::from)));
+            // The legacy `routes` key would be merged with `route-data`
+            ipv4.remove("routes");
+            ipv4.insert("route-data".to_string(), route_data);
+        })?;
+        self.reapply(device);
+        Ok(routes_message(name, config))
+    }
+
+    fn ipv4(&self, name: &str) -> Result<Ipv4Config, String> {
+        let (_, settings) = self
+            .connections()?
+            .into_iter()
+            .find(|(_, settings)| setting_str(settings, "connection", "id") == Some(name))
+            .ok_or_else(|| "Saved connection not found. It may have already been removed.".to_string())?;
+
+        let ipv4 = settings.get("ipv4");
+        // Only the first of several addresses is edited
+        let address = ipv4
+            .and_then(|ipv4| ipv4.get("address-data"))
+            .and_then(|v| v.try_clone().ok())
+            .and_then(|v| Vec::<HashMap<String, OwnedValue>>::try_from(v).ok())
+            .and_then(|data| data.first().and_then(address_from_data))
+            .filter(|_| setting_str(&settings, "ipv4", "method") == Some("manual"));
+        let gateway = setting_str(&settings, "ipv4", "gateway").and_then(|gateway| gateway.parse().ok());
+        // Addresses as u32 in network byte order
+        let dns = ipv4
+            .and_then(|ipv4| ipv4.get("dns"))
+            .and_then(|v| v.try_clone().ok())
+            .and_then(|v| Vec::<u32>::try_from(v).ok())
+            .unwrap_or_default();
+        Ok(Ipv4Config {
+            address,
+            gateway,
+            dns: dns.into_iter().map(|addr| Ipv4Addr::from(addr.to_ne_bytes())).collect(),
+        })
+    }
+
+    fn set_ipv4(&self, device: &str, name: &str, config: &Ipv4Config) -> Result<String, String> {
+        let address_data: Vec<HashMap<&str, Value>> = config
+            .address
+            .iter()
+            .map(|address| {
+                HashMap::from([
+                    ("address", Value::from(address.addr.to_string())),
+                    ("prefix", Value::from(address.prefix)),
+                ])
+            })
+            .collect();
+        let address_data = OwnedValue::try_from(Value::from(address_data)).map_err(|e| e.to_string())?;
+        let dns: Vec<u32> = config.dns.iter().map(|server| u32::from_ne_bytes(server.octets())).collect();
+        let dns = OwnedValue::try_from(Value::from(dns)).map_err(|e| e.to_string())?;
+        let method = if config.address.is_some() { "manual" } else { "auto" };
+        self.update_connection(name, |settings| {
+            let ipv4 = settings.entry("ipv4".to_string()).or_default();
+            ipv4.insert("method".to_string(), OwnedValue::from(Str::from(method)));
+            // The legacy `addresses` key would be merged with `address-data`
+            ipv4.remove("addresses");
+            ipv4.insert("address-data".to_string(), address_data);
+            match config.gateway {
+                Some(gateway) => {
+                    ipv4.insert("gateway".to_string(), OwnedValue::from(Str::from(gateway.to_string())));
+                }
+                None => {
+                    ipv4.remove("gateway");
+                }
+            }
+            ipv4.remove("dns-data");
+            ipv4.insert("dns".to_string(), dns);
+        })?;
+        self.reapply(device);
+        Ok(ipv4_message(name, config))
+    }
+
+    fn set_route_metric(&self, device: &str, name: &str, metric: Option<u32>) -> Result<String, String> {
+        // -1 is NetworkManager's "use the default"
+        let value = metric.map_or(-1, i64::from);
+        self.update_connection(name, |settings| {
+            settings
+                .entry("ipv4".to_string())
+                .or_default()
+                .insert("route-metric".to_string(), OwnedValue::from(value));
+        })?;
+        self.reapply(device);
+        Ok(route_metric_message(name, metric))
+    }
+
+    fn change_summary(&self, device: &str, change: &Change) -> String {
+        change_summary(device, change)
+    }
+
+    fn wifi_secret(&self, name: &str) -> Result<WifiSecret, String> {
+        let (path, settings) = self
+            .connections()?
+            .into_iter()
+            .find(|(_, settings)| setting_str(settings, "connection", "id") == Some(name))
+            .ok_or_else(|| "Saved connection not found. It may have already been removed.".to_string())?;
+
+        let ssid = setting_bytes(&settings, "802-11-wireless", "ssid")
+            .map(|ssid| decode_ssid(&ssid))
+            .unwrap_or_default();
+        let key_mgmt = setting_str(&settings, "802-11-wireless-security", "key-mgmt")
+            .unwrap_or_default()
+            .to_string();
+        if key_mgmt.is_empty() {
+            return Ok(WifiSecret {
+                ssid,
+                key_mgmt,
+                psk: String::new(),
+            });
+        }
+
+        let secrets: OwnedSettings = self
+            .proxy(path.as_str(), CONNECTION_IFACE)?
+            .call("GetSecrets", &("802-11-wireless-security",))
+            .map_err(|e| {
+                let msg = e.to_string();
+                if msg.contains("PermissionDenied") || msg.contains("not authorized") {
+                    SECRETS_DENIED.to_string()
+                } else {
+                    dbus_error(&e)
+                }
+            })?;
+        let psk = setting_str(&secrets, "802-11-wireless-security", "psk")
+            .ok_or("The password for this network isn't stored or can't be read.")?
+            .to_string();
+        Ok(WifiSecret { ssid, key_mgmt, psk })
+    }
+}
+
+/// What NetworkManager is asked to do for `change`.
+fn change_summary(device: &str, change: &Change) -> String {
+    match change {
+        Change::Disconnect => format!("Calls Disconnect on NetworkManager's {} device.", device),
+        Change::Forget(name) => format!("Deletes NetworkManager's '{}' profile.", name),
+        Change::Dns(name, config) => format!(
+            "Sets ipv4.ignore-auto-dns={} and ipv4.dns=[{}] on '{}', then reapplies it to {}.",
+            yes_no(config.ignore_auto),
+            format_dns(&config.servers),
+            name,
+            device
+        ),
+        Change::Routes(name, config) => format!(
+            "Sets ipv4.never-default={}, ipv4.route-metric={} and ipv4.route-data=[{}] on '{}', \
+             then reapplies it to {}.",
+            yes_no(config.never_default),
+            route_metric(config),
+            format_routes(&config.routes),
+            name,
+            device
+        ),
+        Change::Ipv4(name, config) => format!(
+            "Sets ipv4.method={}, ipv4.address-data=[{}], ipv4.gateway={} and ipv4.dns=[{}] on '{}', \
+             then reapplies it to {}.",
+            ipv4_method(config),
+            ipv4_addresses(config),
+            ipv4_gateway(config),
+            format_dns(&config.dns),
+            name,
+            device
+        ),
+        Change::RouteMetric(name, metric) => format!(
+            "Sets ipv4.route-metric={} on '{}', then reapplies it to {}.",
+            metric.value(),
+            name,
+            device
+        ),
+    }
+}
+
+/// Settings for an access-point profile sharing the device's uplink.
+fn hotspot_settings(config: &HotspotConfig) -> Settings<'_> {
+    let mut wireless = HashMap::from([
+        ("ssid", Value::from(config.ssid.as_bytes())),
+        ("mode", Value::from("ap")),
+    ]);
+    match config.band {
+        HotspotBand::Auto => {}
+        HotspotBand::Ghz2 => {
+            wireless.insert("band", Value::from("bg"));
+        }
+        HotspotBand::Ghz5 => {
+            wireless.insert("band", Value::from("a"));
+        }
+    }
+
+    let mut settings: Settings = HashMap::from([
+        (
+            "connection",
+            HashMap::from([
+                ("id", Value::from(config.name.as_str())),
+                ("type", Value::from("802-11-wireless")),
+                ("interface-name", Value::from(config.interface.as_str())),
+                ("autoconnect", Value::from(false)),
+            ]),
+        ),
+        ("802-11-wireless", wireless),
+        ("ipv4", HashMap::from([("method", Value::from("shared"))])),
+    ]);
+    if !config.password.is_empty() {
+        // No access point to look at here: WPA2, like `nmcli device wifi
+        // hotspot`, which every client can join and every driver can serve
+        settings.insert(
+            "802-11-wireless-security",
+            HashMap::from([
+                ("key-mgmt", Value::from("wpa-psk")),
+                ("psk", Value::from(config.password.as_str())),
+            ]),
+        );
+    }
+
+    settings
+}
+
+pub fn setting_str<'a>(settings: &'a OwnedSettings, group: &str, key: &str) -> Option<&'a str> {
+    settings
+        .get(group)?
+        .get(key)
+        .and_then(|v| <&str>::try_from(v).ok())
+}
+
+pub fn setting_bytes(settings: &OwnedSettings, group: &str, key: &str) -> Option<Vec<u8>> {
+    let value = settings.get(group)?.get(key)?.try_clone().ok()?;
+    Vec::<u8>::try_from(value).ok()
+}
+
+/// "AA:BB:CC:DD:EE:FF" as bytes.
+fn parse_mac(mac: &str) -> Option<[u8; 6]> {
+    let mut bytes = [0u8; 6];
+    let mut parts = mac.split(':');
+    for byte in &mut bytes {
+        *byte = u8::from_str_radix(parts.next()?, 16).ok()?;
+    }
+    parts.next().is_none().then_some(bytes)
+}
+
+/// One `ipv4.route-data` entry as a route; entries with unexpected types are skipped.
+fn address_from_data(data: &HashMap<String, OwnedValue>) -> Option<Ipv4Address> {
+    let addr = <&str>::try_from(data.get("address")?).ok()?.parse().ok()?;
+    let prefix = u32::try_from(data.get("prefix")?).ok()?;
+    Some(Ipv4Address { addr, prefix })
+}
+
+fn route_from_data(data: &HashMap<String, OwnedValue>) -> Option<StaticRoute> {
+    let dest = <&str>::try_from(data.get("dest")?).ok()?.parse().ok()?;
+    let prefix = u32::try_from(data.get("prefix")?).ok()?;
+    let next_hop = data
+        .get("next-hop")
+        .and_then(|v| <&str>::try_from(v).ok())
+        .and_then(|hop| hop.parse().ok());
+    let metric = data.get("metric").and_then(|v| u32::try_from(v).ok());
+    Some(StaticRoute { dest, prefix, next_hop, metric })
+}
+
+fn route_data(route: &StaticRoute) -> HashMap<&'static str, Value<'static>> {
+    let mut data = HashMap::new();
+    data.insert("dest", Value::from(route.dest.to_string()));
+    data.insert("prefix", Value::from(route.prefix));
+    if let Some(next_hop) = route.next_hop {
+        data.insert("next-hop", Value::from(next_hop.to_string()));
+    }
+    if let Some(metric) = route.metric {
+        data.insert("metric", Value::from(metric));
+    }
+    data
+}
+
+/// Build a security string in the same shape nmcli prints (e.g. "WPA1 WPA2").
+fn security_string(flags: u32, wpa_flags: u32, rsn_flags: u32) -> String {
+    let mut parts = Vec::new();
+    if flags & NM_802_11_AP_FLAGS_PRIVACY != 0 && wpa_flags == 0 && rsn_flags == 0 {
+        parts.push("WEP");
+    }
+    if wpa_flags != 0 {
+        parts.push("WPA1");
+    }
+    if rsn_flags & NM_802_11_AP_SEC_KEY_MGMT_PSK != 0 {
+        parts.push("WPA2");
+    }
+    if rsn_flags & NM_802_11_AP_SEC_KEY_MGMT_SAE != 0 {
+        parts.push("WPA3");
+    }
+    if (wpa_flags | rsn_flags) & NM_802_11_AP_SEC_KEY_MGMT_802_1X != 0 {
+        parts.push("802.1X");
+    }
+    parts.join(" ")
+}
+
+/// Explain why an activation failed, using the same wording as the nmcli backend.
+fn reason_error(reason: u32) -> String {
+    match reason {
+        NM_DEVICE_STATE_REASON_NO_SECRETS => {
+            "Password required. This network needs a password to connect.".to_string()
+        }
+        NM_DEVICE_STATE_REASON_SUPPLICANT_DISCONNECT | NM_DEVICE_STATE_REASON_SUPPLICANT_TIMEOUT => {
+            "Incorrect password. Please try again.".to_string()
+        }
+        _ => format!("Connection failed (reason {}).", reason),
+    }
+}
+
+/// Read NetworkManager's connectivity check settings. Goes over D-Bus
+/// whichever backend is in use, since nmcli can't change them.
+pub fn connectivity_check() -> Result<ConnectivityCheck, String> {
+    let nm = DbusBackend::new()?;
+    Ok(ConnectivityCheck {
+        available: nm.get(NM_PATH, NM_IFACE, "ConnectivityCheckAvailable")?,
+        enabled: nm.get(NM_PATH, NM_IFACE, "ConnectivityCheckEnabled")?,
+        connectivity: nm.get(NM_PATH, NM_IFACE, "Connectivity")?,
+    })
+}
+
+/// Turn connectivity checking on or off, returning the settings afterwards.
+pub fn set_connectivity_check(on: bool) -> Result<ConnectivityCheck, String> {
+    let nm = DbusBackend::new()?;
+    nm.proxy(NM_PATH, NM_IFACE)?
+        .set_property("ConnectivityCheckEnabled", on)
+        .map_err(|e| dbus_error(&e.into()))?;
+    connectivity_check()
+}
+
+/// Translate D-Bus errors into beginner-friendly text.
+fn dbus_error(e: &zbus::Error) -> String {
+    let msg = e.to_string();
+    if msg.contains("ServiceUnknown") || msg.contains("was not provided by any .service files") {
+        "NetworkManager is not running. Start it with: sudo systemctl start NetworkManager"
+            .to_string()
+    } else if msg.contains("PermissionDenied") || msg.contains("not authorized") {
+        "Permission denied. You may need to run with appropriate privileges.".to_string()
+    } else if msg.contains("NoSecrets") {
+        "Password required. This network needs a password to connect.".to_string()
+    } else {
+        msg
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_change_summary() {
+        let dns = DnsConfig {
+            servers: vec![Ipv4Addr::new(1, 1, 1, 1), Ipv4Addr::new(9, 9, 9, 9)],
+            ignore_auto: true,
+        };
+        assert_eq!(
+            change_summary("wlan0", &Change::Dns("Home", &dns)),
+            "Sets ipv4.ignore-auto-dns=yes and ipv4.dns=[1.1.1.1, 9.9.9.9] on 'Home', then reapplies it to wlan0."
+        );
+    }
+
+    #[test]
+    fn test_security_string() {
+        assert_eq!(security_string(0, 0, 0), "");
+        assert_eq!(security_string(NM_802_11_AP_FLAGS_PRIVACY, 0, 0), "WEP");
+        assert_eq!(
+            security_string(NM_802_11_AP_FLAGS_PRIVACY, 0x188, 0x188),
+            "WPA1 WPA2"
+        );
+        assert_eq!(
+            security_string(NM_802_11_AP_FLAGS_PRIVACY, 0, 0x588),
+            "WPA2 WPA3"
+        );
+        assert_eq!(security_string(NM_802_11_AP_FLAGS_PRIVACY, 0, 0x200), "802.1X");
+    }
+
+    #[test]
+    fn test_parse_mac() {
+        assert_eq!(
+            parse_mac("AA:bb:0C:dd:EE:01"),
+            Some([0xaa, 0xbb, 0x0c, 0xdd, 0xee, 0x01])
+        );
+        assert_eq!(parse_mac("AA:BB:CC:DD:EE"), None);
+        assert_eq!(parse_mac("AA:BB:CC:DD:EE:FF:00"), None);
+        assert_eq!(parse_mac("AA:BB:CC:DD:EE:GG"), None);
+    }
+}
@@ -0,0 +1,54 @@
+use std::path::PathBuf;
+
+use crate::backend::is_mac_address;
+use crate::config::config_dir;
+
+/// `~/.config/wifi-tui/device`, holding the permanent hardware address of
+/// the adapter to use when `--device` isn't given. An address rather than a
+/// name, since interface names can change between boots.
+fn pref_path() -> Option<PathBuf> {
+    Some(config_dir()?.join("device"))
+}
+
+/// The preferred adapter's address, if one was picked.
+pub fn load() -> Option<String> {
+    let text = std::fs::read_to_string(pref_path()?).ok()?;
+    parse_pref(&text)
+}
+
+/// Remember `mac` as the preferred adapter, or forget the preference.
+pub fn save(mac: Option<&str>) -> Result<(), String> {
+    let path = pref_path().ok_or("Couldn't find a config directory (HOME is not set).")?;
+    let Some(mac) = mac else {
+        return match std::fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(format!("Couldn't remove {}: {}", path.display(), e))
+            }
+            _ => Ok(()),
+        };
+    };
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Couldn't create {}: {}", dir.display(), e))?;
+    }
+    std::fs::write(&path, format!("{}\n", mac)).map_err(|e| format!("Couldn't write {}: {}", path.display(), e))
+}
+
+fn parse_pref(text: &str) -> Option<String> {
+    text.lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter(|line| is_mac_address(line))
+        .map(str::to_uppercase)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pref() {
+        assert_eq!(parse_pref("# wlan1\n aa:bb:cc:00:11:22\n"), Some("AA:BB:CC:00:11:22".to_string()));
+        assert_eq!(parse_pref("wlan1\n"), None);
+        assert_eq!(parse_pref(""), None);
+    }
+}
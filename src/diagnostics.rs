@@ -0,0 +1,122 @@
+use std::fmt::Write as _;
+use std::path::PathBuf;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::logging;
+
+/// What the app knew about a connection right before it dropped.
+#[derive(Debug, Clone)]
+pub struct DropReport {
+    pub device: String,
+    pub ssid: String,
+    /// Recent signal samples in percent, oldest first.
+    pub signal_history: Vec<u8>,
+}
+
+/// Write a snapshot of the dropped connection (signal history, `iw link`,
+/// recent NetworkManager log) to a file and return its path. Sections that
+/// can't be collected say why instead of failing the whole capture.
+pub fn capture(report: &DropReport) -> Result<PathBuf, String> {
+    let dir = state_dir().ok_or("Couldn't find a directory for diagnostics (HOME is not set).")?;
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Couldn't create {}: {}", dir.display(), e))?;
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let path = dir.join(format!("disconnect-{}.log", now));
+
+    let mut text = format_header(report, now);
+    section(
+        &mut text,
+        &format!("iw dev {} link", report.device),
+        command_output("iw", &["dev", &report.device, "link"]),
+    );
+    section(
+        &mut text,
+        "journalctl -u NetworkManager (last 200 lines)",
+        command_output("journalctl", &["-u", "NetworkManager", "-n", "200", "--no-pager"]),
+    );
+
+    std::fs::write(&path, text).map_err(|e| format!("Couldn't write {}: {}", path.display(), e))?;
+    Ok(path)
+}
+
+/// `$XDG_STATE_HOME/wifi-tui`, falling back to `~/.local/state/wifi-tui`.
+pub fn state_dir() -> Option<PathBuf> {
+    state_dir_in(std::env::var("XDG_STATE_HOME").ok(), std::env::var("HOME").ok())
+}
+
+fn state_dir_in(xdg_state_home: Option<String>, home: Option<String>) -> Option<PathBuf> {
+    match xdg_state_home.filter(|dir| !dir.is_empty()) {
+        Some(dir) => Some(PathBuf::from(dir).join("wifi-tui")),
+        None => home
+            .filter(|dir| !dir.is_empty())
+            .map(|dir| PathBuf::from(dir).join(".local/state/wifi-tui")),
+    }
+}
+
+fn format_header(report: &DropReport, timestamp: u64) -> String {
+    let mut text = String::new();
+    let _ = writeln!(text, "wifi-tui unexpected disconnect report");
+    let _ = writeln!(text, "time (unix): {}", timestamp);
+    let _ = writeln!(text, "device: {}", report.device);
+    let _ = writeln!(text, "network: {}", report.ssid);
+    let samples: Vec<String> = report.signal_history.iter().map(|s| format!("{}%", s)).collect();
+    let _ = writeln!(
+        text,
+        "signal (oldest first): {}",
+        if samples.is_empty() { "no samples".to_string() } else { samples.join(" ") }
+    );
+    text
+}
+
+fn section(text: &mut String, title: &str, body: Result<String, String>) {
+    let _ = writeln!(text, "\n== {} ==", title);
+    match body {
+        Ok(output) => text.push_str(&output),
+        Err(e) => {
+            let _ = writeln!(text, "(not available: {})", e);
+        }
+    }
+}
+
+fn command_output(program: &str, args: &[&str]) -> Result<String, String> {
+    let output = logging::output(Command::new(program).args(args)).map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_state_dir() {
+        assert_eq!(
+            state_dir_in(Some("/tmp/state".into()), Some("/home/me".into())),
+            Some(PathBuf::from("/tmp/state/wifi-tui"))
+        );
+        assert_eq!(
+            state_dir_in(Some(String::new()), Some("/home/me".into())),
+            Some(PathBuf::from("/home/me/.local/state/wifi-tui"))
+        );
+        assert_eq!(state_dir_in(None, None), None);
+    }
+
+    #[test]
+    fn test_format_header() {
+        let report = DropReport {
+            device: "wlan0".into(),
+            ssid: "Cafe".into(),
+            signal_history: vec![70, 42, 18],
+        };
+        let text = format_header(&report, 1700000000);
+        assert!(text.contains("network: Cafe"));
+        assert!(text.contains("signal (oldest first): 70% 42% 18%"));
+    }
+}
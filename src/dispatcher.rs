@@ -0,0 +1,154 @@
+use std::os::unix::fs::{MetadataExt, PermissionsExt};
+use std::path::Path;
+
+use crate::pkexec;
+
+/// NetworkManager runs every executable, root-owned file in here on
+/// connection events, in alphabetical order.
+pub const DISPATCHER_DIR: &str = "/etc/NetworkManager/dispatcher.d";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DispatcherScript {
+    pub name: String,
+    /// Executable, so NetworkManager will run it.
+    pub enabled: bool,
+    /// Not root-owned or writable by others: NetworkManager ignores it.
+    pub insecure: bool,
+}
+
+/// When a new script should run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DispatcherEvent {
+    Up,
+    Down,
+}
+
+impl DispatcherEvent {
+    pub const ALL: [DispatcherEvent; 2] = [DispatcherEvent::Up, DispatcherEvent::Down];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            DispatcherEvent::Up => "WiFi connects",
+            DispatcherEvent::Down => "WiFi disconnects",
+        }
+    }
+
+    fn action(self) -> &'static str {
+        match self {
+            DispatcherEvent::Up => "up",
+            DispatcherEvent::Down => "down",
+        }
+    }
+}
+
+/// Scripts in the dispatcher directory, skipping the `pre-up.d`-style
+/// subdirectories and hidden files.
+pub fn list() -> Result<Vec<DispatcherScript>, String> {
+    let entries = std::fs::read_dir(DISPATCHER_DIR)
+        .map_err(|e| format!("Couldn't read {}: {}", DISPATCHER_DIR, e))?;
+
+    let mut scripts: Vec<DispatcherScript> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let meta = entry.metadata().ok()?;
+            if name.starts_with('.') || !meta.is_file() {
+                return None;
+            }
+            let mode = meta.permissions().mode();
+            Some(DispatcherScript {
+                name,
+                enabled: mode & 0o111 != 0,
+                insecure: meta.uid() != 0 || mode & 0o022 != 0,
+            })
+        })
+        .collect();
+    scripts.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(scripts)
+}
+
+/// Enable or disable a script by toggling its executable bit, via pkexec.
+pub fn set_enabled(name: &str, enabled: bool) -> Result<String, String> {
+    let path = script_path(name)?;
+    let mode = if enabled { "755" } else { "644" };
+    pkexec::run(&["chmod", mode, &path], None)?;
+    Ok(format!(
+        "{} dispatcher script '{}'.",
+        if enabled { "Enabled" } else { "Disabled" },
+        name
+    ))
+}
+
+/// Write a new, enabled script that runs `command` when `device` goes up or down.
+pub fn create(name: &str, device: &str, event: DispatcherEvent, command: &str) -> Result<String, String> {
+    let path = script_path(name)?;
+    if Path::new(&path).exists() {
+        return Err(format!("A dispatcher script named '{}' already exists.", name));
+    }
+
+    // One pkexec call so the user authenticates once
+    pkexec::run(
+        &["sh", "-c", "cat > \"$1\" && chmod 755 \"$1\"", "sh", &path],
+        Some(&script_template(device, event, command)),
+    )?;
+    Ok(format!("Created dispatcher script '{}'.", name))
+}
+
+pub fn validate_script_name(name: &str) -> Result<(), String> {
+    if name.is_empty() {
+        return Err("required".to_string());
+    }
+    if name.starts_with('.')
+        || !name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    {
+        return Err("use letters, digits, '-', '_' and '.' only".to_string());
+    }
+    Ok(())
+}
+
+fn script_path(name: &str) -> Result<String, String> {
+    validate_script_name(name).map_err(|e| format!("Invalid script name: {}", e))?;
+    Ok(format!("{}/{}", DISPATCHER_DIR, name))
+}
+
+/// NetworkManager passes the interface as `$1` and the action as `$2`.
+fn script_template(device: &str, event: DispatcherEvent, command: &str) -> String {
+    format!(
+        "#!/bin/sh\n\
+         # Created by wifi-tui: runs when {} goes {}.\n\
+         [ \"$1\" = \"{}\" ] && [ \"$2\" = \"{}\" ] || exit 0\n\
+         {}\n",
+        device,
+        event.action(),
+        device,
+        event.action(),
+        command.trim()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_script_name() {
+        assert!(validate_script_name("50-vpn-up").is_ok());
+        assert!(validate_script_name("sync.sh").is_ok());
+        assert!(validate_script_name("").is_err());
+        assert!(validate_script_name("../evil").is_err());
+        assert!(validate_script_name(".hidden").is_err());
+        assert!(validate_script_name("has space").is_err());
+    }
+
+    #[test]
+    fn test_script_template() {
+        let script = script_template("wlan0", DispatcherEvent::Up, "  logger wifi up ");
+        assert_eq!(
+            script,
+            "#!/bin/sh\n\
+             # Created by wifi-tui: runs when wlan0 goes up.\n\
+             [ \"$1\" = \"wlan0\" ] && [ \"$2\" = \"up\" ] || exit 0\n\
+             logger wifi up\n"
+        );
+    }
+}
@@ -0,0 +1,120 @@
+use serde::Serialize;
+
+use crate::app::{sort_networks, SortMode};
+use crate::backend::{Backend, ConnectionStatus, Network, SavedNetwork};
+use crate::checks::{Check, CheckState};
+use crate::ui::ssid_text;
+use crate::units::Units;
+
+/// `wifi-tui scan`: networks in range, in the configured sort order.
+pub fn scan(backend: &dyn Backend, device: &str, sort: SortMode, units: &Units, json: bool) -> Result<(), String> {
+    let mut networks = backend.scan_networks(device)?;
+    sort_networks(&mut networks, sort);
+    print(&networks, json, |networks| networks.iter().map(|net| network_line(net, units)).collect())
+}
+
+/// `wifi-tui status`: the current connection.
+pub fn status(backend: &dyn Backend, device: &str, units: &Units, json: bool) -> Result<(), String> {
+    let status = backend.get_status(device);
+    print(&status, json, |status| vec![status_line(status, units)])
+}
+
+/// `wifi-tui saved`: saved profiles.
+pub fn saved(backend: &dyn Backend, json: bool) -> Result<(), String> {
+    let saved = backend.saved_networks()?;
+    print(&saved, json, |saved| saved.iter().map(saved_line).collect())
+}
+
+/// `wifi-tui doctor`: one line per check, with the fix for each failed
+/// one. Fails when any did.
+pub fn checks(checks: &[Check]) -> Result<(), String> {
+    for check in checks {
+        println!("{}", check_line(check));
+    }
+    match checks.iter().filter(|check| check.state != CheckState::Ok).count() {
+        0 => Ok(()),
+        1 => Err("1 check failed.".to_string()),
+        failed => Err(format!("{} checks failed.", failed)),
+    }
+}
+
+/// One JSON document for scripts, or aligned text for people.
+fn print<T: Serialize>(value: &T, json: bool, lines: impl Fn(&T) -> Vec<String>) -> Result<(), String> {
+    if json {
+        let text = serde_json::to_string(value).map_err(|e| e.to_string())?;
+        println!("{}", text);
+    } else {
+        for line in lines(value) {
+            println!("{}", line);
+        }
+    }
+    Ok(())
+}
+
+fn network_line(net: &Network, units: &Units) -> String {
+    let marker = if net.in_use { '*' } else { ' ' };
+    let channel = net.channel.map_or("-".to_string(), |c| c.to_string());
+    let security = if net.is_open() { "open" } else { &net.security };
+    let ssid = ssid_text(&net.ssid);
+    format!("{} {:<32} {:>4}  ch {:<4} {}", marker, ssid, units.signal(net.signal), channel, security)
+}
+
+fn status_line(status: &ConnectionStatus, units: &Units) -> String {
+    match &status.ssid {
+        Some(ssid) => format!(
+            "connected to {}  signal {}  ip {}  speed {}",
+            ssid_text(ssid),
+            status.signal.map_or("-".to_string(), |s| units.link_signal(s, status.dbm)),
+            status.ip.as_deref().unwrap_or("-"),
+            status.bitrate.map_or("-".to_string(), |kbit| units.bitrate(kbit.into())),
+        ),
+        None => "not connected".to_string(),
+    }
+}
+
+fn check_line(check: &Check) -> String {
+    let mark = match check.state {
+        CheckState::Ok => "✓",
+        CheckState::Missing => "✗",
+        CheckState::Unknown => "?",
+    };
+    format!("{} {:<18} {}", mark, check.name, check.detail)
+}
+
+fn saved_line(saved: &SavedNetwork) -> String {
+    let marker = if saved.active { '*' } else { ' ' };
+    let kind = if saved.hotspot { "  (hotspot)" } else { "" };
+    format!("{} {}{}", marker, saved.name, kind)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::log_file_check;
+
+    #[test]
+    fn test_lines() {
+        let net = Network {
+            ssid: "Cafe".to_string(),
+            signal: 71,
+            security: "WPA2".to_string(),
+            in_use: true,
+            channel: Some(36),
+            ..Network::default()
+        };
+        let units = Units::default();
+        assert_eq!(network_line(&net, &units), format!("* {:<32}  71%  ch 36   WPA2", "Cafe"));
+        assert_eq!(status_line(&ConnectionStatus::default(), &units), "not connected");
+        let status = ConnectionStatus {
+            ssid: Some("Cafe".to_string()),
+            signal: Some(71),
+            bitrate: Some(866_700),
+            ..ConnectionStatus::default()
+        };
+        assert_eq!(status_line(&status, &units), "connected to Cafe  signal 71%  ip -  speed 866.7 Mbit/s");
+        let saved = SavedNetwork { name: "Lab".to_string(), active: false, hotspot: true };
+        assert_eq!(saved_line(&saved), "  Lab  (hotspot)");
+        let check = log_file_check("HOME is not set.");
+        assert_eq!(check_line(&check), format!("? {:<18} HOME is not set.", "Log file"));
+    }
+}
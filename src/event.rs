@@ -1,52 +1,222 @@
-use std::sync::mpsc;
+use std::io::{BufRead, BufReader};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
 use std::thread;
 use std::time::{Duration, Instant};
 
-use ratatui::crossterm::event::{self, Event as CEvent, KeyEvent};
+use ratatui::crossterm::event::{self, Event as CEvent, KeyEvent, MouseEvent, MouseEventKind};
 
-use crate::nmcli;
+use crate::agent::SecretRequest;
+use crate::backend::{self, Backend};
+use crate::cancel::{self, CancelToken};
+use crate::checks;
+use crate::dbus;
+use crate::diagnostics;
+use crate::hooks;
+use crate::internet::{self, Internet};
+use crate::password_manager;
+use crate::ping::{self, PingReport};
+use crate::dispatcher::{self, DispatcherEvent, DispatcherScript};
+use crate::ratelimit;
+use crate::rfkill::{self, Rfkill};
+use crate::roaming::{self, Bgscan};
+use crate::update;
 
 /// Events that the main loop receives.
 pub enum Event {
     /// A keyboard event.
     Key(KeyEvent),
+    /// Text pasted into the terminal (bracketed paste).
+    Paste(String),
+    /// A mouse button press, with `mouse` on; other mouse events are dropped.
+    Mouse(MouseEvent),
     /// A periodic tick (for auto-refresh, spinner animation).
     Tick,
     /// A background task completed.
     TaskResult(TaskResult),
+    /// NetworkManager asked our secret agent for a network's password.
+    SecretRequest(SecretRequest),
+    /// NetworkManager withdrew its pending password request.
+    SecretCancelled,
+    /// `nmcli monitor` reported a change made outside the TUI.
+    NetworkChanged(MonitorChange),
+    /// A NetworkManager/wpa_supplicant journal line about our device.
+    LogLine(String),
+}
+
+/// What an `nmcli monitor` line says needs refreshing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MonitorChange {
+    /// Device or connectivity state changed.
+    Status,
+    /// A connection profile was created, changed or removed.
+    Saved,
+    /// A network device appeared or went away, e.g. a USB adapter.
+    Devices,
 }
 
 /// Tasks sent to the background worker.
 pub enum Task {
     Scan(String),                          // device
-    Connect(String, Option<String>),       // ssid, password
+    Connect(String, String, Option<String>, Option<Duration>), // device, ssid, password, timeout
+    ConnectBssid(String, String, String),    // device, profile name, bssid
     Disconnect(String),                    // device
     Forget(String),                        // network name
+    MakeTemporary(String),                 // profile name
+    RemoveTemporary(String),               // profile name
     RefreshStatus(String),                 // device
+    RefreshSignal(String),                 // device
     RefreshSaved,
+    CheckSharing(String),                  // device
+    CheckConnectivity,
+    CheckSixGhz(String, usize), // device, 6 GHz networks seen
+    SetConnectivityCheck(bool),
+    SaveHotspot(backend::HotspotConfig),
+    StartHotspot(String),                  // profile name
+    CreateHotspot(String, String, String), // device, ssid, password
+    StopHotspot(String),                   // profile name
+    RefreshHotspotClients(String),         // device
+    LoadHotspotQr(String),                 // profile name
+    ShareNetwork(String),                  // profile name
+    RevealPassword(String),                // profile name
+    ListDispatcherScripts,
+    SetDispatcherEnabled(String, bool),    // script name, enabled
+    CreateDispatcherScript(String, String, DispatcherEvent, String), // name, device, event, command
+    ApplyRateLimit(String, u32),           // device, kbit/s
+    ClearRateLimit(String),                // device
+    ApplyBgscan(String, Bgscan),           // device, background scan
+    SetLowData(String, Option<String>, bool), // device, active profile, on
+    FetchPassword(String, String),         // password command, ssid
+    SetCallMode(String, String, bool, Option<String>), // device, profile, on, band
+    LoadProfileOptions(String),            // profile name
+    SetProfileOptions(String, backend::ProfileOptions), // profile name, options
+    RenameProfile(String, String),         // profile name, new name
+    LoadDns(String),                       // profile name
+    SetDns(String, String, backend::DnsConfig), // device, profile name, DNS
+    LoadIpv4(String),                      // profile name
+    SetIpv4(String, String, backend::Ipv4Config), // device, profile name, addressing
+    ConnectEnterprise(String, String, backend::EapConfig), // device, ssid, credentials
+    LoadRoutes(String),                    // profile name
+    SetRoutes(String, String, backend::RouteConfig), // device, profile name, routing
+    SetRouteMetric(String, String, Option<u32>), // device, profile name, metric
+    AdjustRouteMetric(String, String, i64), // device, profile name, change
+    RefreshUplinks,
+    RefreshRfkill(String),                 // device
+    DetectDevices,
+    UnblockRadio,
+    CaptureDiagnostics(diagnostics::DropReport),
+}
+
+impl Task {
+    /// Quick reads that keep the status bar and lists current; they run on
+    /// the status worker instead of queueing behind scans and connects.
+    pub fn is_status_read(&self) -> bool {
+        matches!(
+            self,
+            Task::RefreshStatus(_)
+                | Task::RefreshSignal(_)
+                | Task::RefreshSaved
+                | Task::RefreshUplinks
+                | Task::RefreshRfkill(_)
+                | Task::RefreshHotspotClients(_)
+                | Task::DetectDevices
+        )
+    }
 }
 
 /// Results from background tasks.
 pub enum TaskResult {
-    ScanComplete(Result<Vec<nmcli::Network>, String>),
+    ScanComplete(Result<Vec<backend::Network>, String>),
     /// (result, ssid) - ssid carried through for password retry
     ConnectComplete(Result<String, String>, String),
     DisconnectComplete(Result<String, String>),
     ForgetComplete(Result<String, String>),
-    StatusUpdate(nmcli::ConnectionStatus),
-    SavedUpdate(Result<Vec<nmcli::SavedNetwork>, String>),
+    /// (profile, outcome of turning its autoconnect off)
+    MadeTemporary(String, Result<String, String>),
+    /// (profile, outcome of deleting it)
+    TemporaryRemoved(String, Result<String, String>),
+    StatusUpdate(backend::ConnectionStatus),
+    SignalUpdate(Option<u8>, Option<i32>), // percent, measured dBm
+    SavedUpdate(Result<Vec<backend::SavedNetwork>, String>),
+    SharingChecked(Vec<checks::Check>),
+    ConnectivityChecked(Result<backend::ConnectivityCheck, String>),
+    SixGhzChecked(Vec<checks::Check>),
+    /// (on, settings afterwards)
+    ConnectivityCheckChanged(bool, Result<backend::ConnectivityCheck, String>),
+    HotspotSaved(Result<String, String>),
+    HotspotStarted(Result<String, String>),
+    HotspotStopped(Result<String, String>),
+    HotspotClients(Option<usize>),
+    /// (profile, the running hotspot's secret)
+    HotspotSecret(String, Result<backend::WifiSecret, String>),
+    SecretFetched(Result<backend::WifiSecret, String>),
+    /// (profile, its stored secret)
+    PasswordRevealed(String, Result<backend::WifiSecret, String>),
+    DispatcherListed(Result<Vec<DispatcherScript>, String>),
+    /// (result of the change, scripts listed afterwards)
+    DispatcherChanged(Result<String, String>, Vec<DispatcherScript>),
+    RateLimitChanged(Result<String, String>),
+    BgscanApplied(Result<String, String>),
+    /// (on, outcome of marking the profile metered and running the hook)
+    LowDataChanged(bool, Result<String, String>),
+    PasswordFetched(String, Result<String, String>), // ssid, password
+    /// (profile, on, outcome)
+    CallModeChanged(String, bool, Result<String, String>),
+    /// (profile, its autoconnect/priority/metered options)
+    ProfileOptionsLoaded(String, Result<backend::ProfileOptions, String>),
+    ProfileOptionsChanged(Result<String, String>),
+    /// (old name, new name, outcome)
+    ProfileRenamed(String, String, Result<String, String>),
+    /// (profile, its DNS servers)
+    DnsLoaded(String, Result<backend::DnsConfig, String>),
+    DnsChanged(Result<String, String>),
+    /// (profile, its addressing)
+    Ipv4Loaded(String, Result<backend::Ipv4Config, String>),
+    Ipv4Changed(Result<String, String>),
+    /// (profile, its routing options)
+    RoutesLoaded(String, Result<backend::RouteConfig, String>),
+    RoutesChanged(Result<String, String>),
+    RouteMetricChanged(Result<String, String>),
+    UplinksUpdate(Vec<backend::Uplink>),
+    RfkillUpdate(Option<Rfkill>),
+    DevicesDetected(Result<Vec<String>, String>),
+    /// How far the running connect got
+    ConnectStage(Option<backend::ConnectStage>),
+    PingUpdate(PingReport),
+    InternetChecked(Internet),
+    /// The newest release on crates.io
+    UpdateChecked(Result<String, String>),
+    RadioUnblocked(Result<String, String>),
+    /// (path of the saved report, ssid that dropped)
+    DiagnosticsSaved(Result<PathBuf, String>, String),
 }
 
 pub struct EventLoop {
     rx: mpsc::Receiver<Event>,
+    tx: mpsc::Sender<Event>,
     task_tx: mpsc::Sender<Task>,
+    /// Shared with the workers, for the cleanup in `remove_profiles`.
+    backend: Arc<dyn Backend>,
+    /// Queue of the status worker, for the reads in `Task::is_status_read`.
+    status_tx: mpsc::Sender<Task>,
+    /// Token of the task the worker is running.
+    current_task: Arc<Mutex<CancelToken>>,
+    /// Whether the backend registered a secret agent, so passwords are
+    /// requested by NetworkManager instead of after a failed connect.
+    pub secret_agent: bool,
 }
 
 impl EventLoop {
-    /// Start the event loop with keyboard polling and a background worker.
-    pub fn new(tick_rate: Duration) -> Self {
+    /// Start the event loop with keyboard polling and a background worker
+    /// that runs tasks against `backend`.
+    pub fn new(tick_rate: Duration, mut backend: Box<dyn Backend>) -> Self {
         let (event_tx, event_rx) = mpsc::channel();
         let (task_tx, task_rx) = mpsc::channel::<Task>();
+        let (status_tx, status_rx) = mpsc::channel::<Task>();
+
+        let secret_agent = backend.start_secret_agent(event_tx.clone());
 
         // Keyboard + tick polling thread
         let tx = event_tx.clone();
@@ -58,8 +228,16 @@ impl EventLoop {
                     .unwrap_or(Duration::ZERO);
 
                 if event::poll(timeout).unwrap_or(false) {
-                    if let Ok(CEvent::Key(key)) = event::read() {
-                        if tx.send(Event::Key(key)).is_err() {
+                    let ev = match event::read() {
+                        Ok(CEvent::Key(key)) => Some(Event::Key(key)),
+                        Ok(CEvent::Paste(text)) => Some(Event::Paste(text)),
+                        Ok(CEvent::Mouse(mouse)) if matches!(mouse.kind, MouseEventKind::Down(_)) => {
+                            Some(Event::Mouse(mouse))
+                        }
+                        _ => None,
+                    };
+                    if let Some(ev) = ev {
+                        if tx.send(ev).is_err() {
                             return;
                         }
                     }
@@ -74,50 +252,491 @@ impl EventLoop {
             }
         });
 
-        // Background worker thread - serializes all nmcli operations
-        let tx = event_tx;
+        // Background workers. Status reads get their own, so a slow rescan or
+        // a connect doesn't leave signal and IP stale while it runs; every
+        // other backend operation is serialized on the main worker.
+        let backend: Arc<dyn Backend> = Arc::from(backend);
+        let tx = event_tx.clone();
+        let current_task = Arc::new(Mutex::new(CancelToken::default()));
+        let worker_task = Arc::clone(&current_task);
+        let worker_backend = Arc::clone(&backend);
+        let status_backend = Arc::clone(&backend);
         thread::spawn(move || {
             for task in task_rx {
-                let result = match task {
-                    Task::Scan(device) => {
-                        TaskResult::ScanComplete(nmcli::scan_networks(&device))
-                    }
-                    Task::Connect(ssid, password) => {
-                        let result = nmcli::connect(&ssid, password.as_deref());
-                        TaskResult::ConnectComplete(result, ssid)
-                    }
-                    Task::Disconnect(device) => {
-                        TaskResult::DisconnectComplete(nmcli::disconnect(&device))
-                    }
-                    Task::Forget(name) => {
-                        TaskResult::ForgetComplete(nmcli::forget(&name))
-                    }
-                    Task::RefreshStatus(device) => {
-                        TaskResult::StatusUpdate(nmcli::get_status(&device))
-                    }
-                    Task::RefreshSaved => {
-                        TaskResult::SavedUpdate(nmcli::saved_networks())
-                    }
-                };
+                let token = CancelToken::default();
+                *worker_task.lock().unwrap() = token.clone();
+                cancel::set_current(token);
+                let result = run_task(worker_backend.as_ref(), task);
                 if tx.send(Event::TaskResult(result)).is_err() {
                     return;
                 }
             }
         });
 
+        // Status worker thread - never cancelled, its reads are quick
+        let tx = event_tx.clone();
+        thread::spawn(move || {
+            for task in status_rx {
+                if tx.send(Event::TaskResult(run_task(status_backend.as_ref(), task))).is_err() {
+                    return;
+                }
+            }
+        });
+
         Self {
             rx: event_rx,
+            tx: event_tx,
             task_tx,
+            backend,
+            status_tx,
+            current_task,
+            secret_agent,
         }
     }
 
+    /// Follow `nmcli monitor` so changes made elsewhere (e.g. from GNOME) show
+    /// up immediately. Best-effort: does nothing if nmcli isn't available.
+    pub fn start_monitor(&self, device: &str) {
+        spawn_monitor(device, self.tx.clone());
+    }
+
+    /// Follow the NetworkManager and wpa_supplicant journal, keeping lines that
+    /// mention `device`. Without permission to read the system journal
+    /// journalctl simply prints nothing.
+    pub fn start_log_tail(&self, device: &str) {
+        let Ok(mut child) = Command::new("journalctl")
+            .args([
+                "-u", "NetworkManager",
+                "-u", "wpa_supplicant",
+                "-f", "-n", "200",
+                "-o", "short",
+                "-q", "--no-pager",
+            ])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+        else {
+            return;
+        };
+        let Some(stdout) = child.stdout.take() else {
+            return;
+        };
+
+        let tx = self.tx.clone();
+        let device = device.to_string();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                if line.contains(&device) && tx.send(Event::LogLine(line)).is_err() {
+                    break;
+                }
+            }
+            let _ = child.kill();
+            let _ = child.wait();
+        });
+    }
+
     /// Try to receive the next event (non-blocking).
     pub fn try_recv(&self) -> Option<Event> {
         self.rx.try_recv().ok()
     }
 
+    /// The backend the workers use, for describing changes before they run.
+    pub fn backend(&self) -> Arc<dyn Backend> {
+        Arc::clone(&self.backend)
+    }
+
     /// Send a task to the background worker.
     pub fn send_task(&self, task: Task) {
-        let _ = self.task_tx.send(task);
+        let queue = if task.is_status_read() { &self.status_tx } else { &self.task_tx };
+        let _ = queue.send(task);
+    }
+
+    /// Delete saved profiles right away, e.g. temporary ones on exit. Errors
+    /// are ignored: there's nobody left to show them to.
+    pub fn remove_profiles<'a>(&self, names: impl IntoIterator<Item = &'a String>) {
+        for name in names {
+            let _ = self.backend.forget(name);
+        }
+    }
+
+    /// Stop the task the worker is running, killing the command it waits
+    /// on. The task then finishes with a "Cancelled." error.
+    pub fn cancel_task(&self) {
+        self.current_task.lock().unwrap().cancel();
+    }
+
+    /// Look up how far a connect on `device` got. Runs on its own, since the
+    /// worker is busy with the connect itself.
+    pub fn poll_connect_stage(&self, device: &str) {
+        let device = device.to_string();
+        let tx = self.tx.clone();
+        let backend = Arc::clone(&self.backend);
+        thread::spawn(move || {
+            let stage = backend.connect_stage(&device);
+            let _ = tx.send(Event::TaskResult(TaskResult::ConnectStage(stage)));
+        });
+    }
+
+    /// Ping `device`'s gateway and `host`. Runs on its own, since lost pings
+    /// take a second each to time out.
+    pub fn ping(&self, device: &str, host: &str) {
+        let (device, host) = (device.to_string(), host.to_string());
+        let tx = self.tx.clone();
+        thread::spawn(move || {
+            let report = ping::measure(&device, &host);
+            let _ = tx.send(Event::TaskResult(TaskResult::PingUpdate(report)));
+        });
+    }
+
+    /// Probe for internet access past the WiFi link. Runs on its own, since
+    /// it may wait on the network.
+    pub fn check_internet(&self, url: &str) {
+        let url = url.to_string();
+        let tx = self.tx.clone();
+        thread::spawn(move || {
+            let _ = tx.send(Event::TaskResult(TaskResult::InternetChecked(internet::probe(&url))));
+        });
+    }
+
+    /// Look up the newest release. Runs on its own, since it may wait on
+    /// the network.
+    pub fn check_for_update(&self) {
+        let tx = self.tx.clone();
+        thread::spawn(move || {
+            let _ = tx.send(Event::TaskResult(TaskResult::UpdateChecked(update::latest_version())));
+        });
+    }
+
+    /// Stop a connection attempt on `device`; its `ConnectComplete` then
+    /// arrives as a failure.
+    pub fn cancel_activation(&self, device: &str) {
+        let device = device.to_string();
+        let backend = Arc::clone(&self.backend);
+        thread::spawn(move || {
+            let _ = backend.cancel_activation(&device);
+        });
+    }
+}
+
+/// Run one task against `backend`, on whichever worker picked it up.
+fn run_task(backend: &dyn Backend, task: Task) -> TaskResult {
+    match task {
+        Task::Scan(device) => {
+            TaskResult::ScanComplete(backend.scan_networks(&device))
+        }
+        Task::Connect(device, ssid, password, timeout) => {
+            let password = password.as_deref();
+            let result = match timeout {
+                Some(timeout) => connect_with_timeout(backend, &device, &ssid, password, timeout),
+                None => backend.connect(&device, &ssid, password),
+            };
+            TaskResult::ConnectComplete(result, ssid)
+        }
+        Task::ConnectBssid(device, name, bssid) => {
+            let result = backend.connect_bssid(&device, &name, &bssid);
+            TaskResult::ConnectComplete(result, name)
+        }
+        Task::Disconnect(device) => {
+            TaskResult::DisconnectComplete(backend.disconnect(&device))
+        }
+        Task::Forget(name) => {
+            TaskResult::ForgetComplete(backend.forget(&name))
+        }
+        Task::MakeTemporary(name) => {
+            let result = backend.profile_options(&name).and_then(|options| {
+                let options = backend::ProfileOptions { autoconnect: false, ..options };
+                backend.set_profile_options(&name, &options)
+            });
+            TaskResult::MadeTemporary(name, result)
+        }
+        Task::RemoveTemporary(name) => {
+            let result = backend.forget(&name);
+            TaskResult::TemporaryRemoved(name, result)
+        }
+        Task::RefreshStatus(device) => {
+            TaskResult::StatusUpdate(backend.get_status(&device))
+        }
+        Task::RefreshSignal(device) => {
+            TaskResult::SignalUpdate(backend.current_signal(&device), backend::link_dbm(&device))
+        }
+        Task::RefreshSaved => {
+            TaskResult::SavedUpdate(backend.saved_networks())
+        }
+        Task::CheckSharing(device) => {
+            TaskResult::SharingChecked(checks::sharing_checks(&device))
+        }
+        Task::CheckSixGhz(device, seen) => {
+            TaskResult::SixGhzChecked(checks::six_ghz_checks(&device, seen))
+        }
+        Task::CheckConnectivity => {
+            TaskResult::ConnectivityChecked(dbus::connectivity_check())
+        }
+        Task::SetConnectivityCheck(on) => {
+            TaskResult::ConnectivityCheckChanged(on, dbus::set_connectivity_check(on))
+        }
+        Task::SaveHotspot(config) => {
+            TaskResult::HotspotSaved(backend.save_hotspot(&config))
+        }
+        Task::StartHotspot(name) => {
+            TaskResult::HotspotStarted(backend.start_hotspot(&name))
+        }
+        Task::CreateHotspot(device, ssid, password) => {
+            TaskResult::HotspotStarted(backend.create_hotspot(&device, &ssid, &password))
+        }
+        Task::StopHotspot(name) => {
+            TaskResult::HotspotStopped(backend.stop_hotspot(&name))
+        }
+        Task::RefreshHotspotClients(device) => {
+            TaskResult::HotspotClients(backend::hotspot_clients(&device))
+        }
+        Task::ShareNetwork(name) => {
+            TaskResult::SecretFetched(backend.wifi_secret(&name))
+        }
+        Task::LoadHotspotQr(name) => {
+            let result = backend.wifi_secret(&name);
+            TaskResult::HotspotSecret(name, result)
+        }
+        Task::RevealPassword(name) => {
+            let result = backend.wifi_secret(&name);
+            TaskResult::PasswordRevealed(name, result)
+        }
+        Task::ListDispatcherScripts => {
+            TaskResult::DispatcherListed(dispatcher::list())
+        }
+        Task::SetDispatcherEnabled(name, enabled) => {
+            let result = dispatcher::set_enabled(&name, enabled);
+            TaskResult::DispatcherChanged(result, dispatcher::list().unwrap_or_default())
+        }
+        Task::CreateDispatcherScript(name, device, event, command) => {
+            let result = dispatcher::create(&name, &device, event, &command);
+            TaskResult::DispatcherChanged(result, dispatcher::list().unwrap_or_default())
+        }
+        Task::ApplyRateLimit(device, kbit) => {
+            TaskResult::RateLimitChanged(ratelimit::apply(&device, kbit))
+        }
+        Task::ClearRateLimit(device) => {
+            TaskResult::RateLimitChanged(ratelimit::clear(&device))
+        }
+        Task::ApplyBgscan(device, bgscan) => {
+            TaskResult::BgscanApplied(roaming::apply(&device, &bgscan))
+        }
+        Task::SetCallMode(device, name, on, band) => {
+            let result = backend.set_call_mode(&device, &name, on, band.as_deref());
+            TaskResult::CallModeChanged(name, on, result)
+        }
+        Task::LoadProfileOptions(name) => {
+            let result = backend.profile_options(&name);
+            TaskResult::ProfileOptionsLoaded(name, result)
+        }
+        Task::SetProfileOptions(name, options) => {
+            TaskResult::ProfileOptionsChanged(backend.set_profile_options(&name, &options))
+        }
+        Task::RenameProfile(name, new_name) => {
+            let result = backend.rename_profile(&name, &new_name);
+            TaskResult::ProfileRenamed(name, new_name, result)
+        }
+        Task::LoadDns(name) => {
+            let result = backend.dns(&name);
+            TaskResult::DnsLoaded(name, result)
+        }
+        Task::SetDns(device, name, config) => {
+            TaskResult::DnsChanged(backend.set_dns(&device, &name, &config))
+        }
+        Task::LoadIpv4(name) => {
+            let result = backend.ipv4(&name);
+            TaskResult::Ipv4Loaded(name, result)
+        }
+        Task::SetIpv4(device, name, config) => {
+            TaskResult::Ipv4Changed(backend.set_ipv4(&device, &name, &config))
+        }
+        Task::ConnectEnterprise(device, ssid, config) => {
+            let result = backend.connect_enterprise(&device, &ssid, &config);
+            TaskResult::ConnectComplete(result, ssid)
+        }
+        Task::LoadRoutes(name) => {
+            let result = backend.routes(&name);
+            TaskResult::RoutesLoaded(name, result)
+        }
+        Task::SetRoutes(device, name, config) => {
+            TaskResult::RoutesChanged(backend.set_routes(&device, &name, &config))
+        }
+        Task::SetRouteMetric(device, name, metric) => {
+            TaskResult::RouteMetricChanged(backend.set_route_metric(&device, &name, metric))
+        }
+        Task::AdjustRouteMetric(device, name, change) => {
+            TaskResult::RouteMetricChanged(adjust_route_metric(backend, &device, &name, change))
+        }
+        Task::RefreshUplinks => {
+            TaskResult::UplinksUpdate(backend::uplinks())
+        }
+        Task::RefreshRfkill(device) => {
+            TaskResult::RfkillUpdate(rfkill::state(&device))
+        }
+        Task::DetectDevices => {
+            TaskResult::DevicesDetected(backend.detect_wifi_devices())
+        }
+        Task::UnblockRadio => {
+            TaskResult::RadioUnblocked(rfkill::unblock())
+        }
+        Task::FetchPassword(command, ssid) => {
+            let result = password_manager::fetch(&command, &ssid);
+            TaskResult::PasswordFetched(ssid, result)
+        }
+        Task::SetLowData(device, profile, on) => {
+            TaskResult::LowDataChanged(on, set_low_data(backend, &device, profile, on))
+        }
+        Task::CaptureDiagnostics(report) => {
+            TaskResult::DiagnosticsSaved(diagnostics::capture(&report), report.ssid)
+        }
+    }
+}
+
+/// Connect, but stop the attempt once `timeout` passes: the worker would
+/// otherwise sit in a hung connect with every other task queued behind it.
+fn connect_with_timeout(
+    backend: &dyn Backend,
+    device: &str,
+    ssid: &str,
+    password: Option<&str>,
+    timeout: Duration,
+) -> Result<String, String> {
+    let (done_tx, done_rx) = mpsc::channel::<()>();
+    let timed_out = AtomicBool::new(false);
+    let token = cancel::current();
+    let result = thread::scope(|scope| {
+        let (timed_out, token) = (&timed_out, &token);
+        scope.spawn(move || {
+            if done_rx.recv_timeout(timeout) == Err(mpsc::RecvTimeoutError::Timeout) {
+                timed_out.store(true, Ordering::SeqCst);
+                // Makes the connect below fail and return: the task's commands
+                // are killed, and the backend stops activating
+                if let Some(token) = token {
+                    token.cancel();
+                }
+                let _ = backend.cancel_activation(device);
+            }
+        });
+        let result = backend.connect(device, ssid, password);
+        drop(done_tx);
+        result
+    });
+    if timed_out.load(Ordering::SeqCst) {
+        return Err(format!(
+            "Gave up connecting to {} after {} seconds. The network may be out of range or not answering.",
+            ssid,
+            timeout.as_secs()
+        ));
+    }
+    result
+}
+
+/// Mark the active profile metered (or not) and tell the user's `low-data`
+/// hook, so it can pause syncing services.
+fn set_low_data(
+    backend: &dyn Backend,
+    device: &str,
+    profile: Option<String>,
+    on: bool,
+) -> Result<String, String> {
+    let mut msg = match profile {
+        Some(name) => backend.set_metered(device, &name, on)?,
+        None => String::new(),
+    };
+    if hooks::run("low-data", &[if on { "on" } else { "off" }])? {
+        msg.push_str(" Ran low-data hook.");
+    }
+    Ok(format!(
+        "Low-data mode {}. {}",
+        if on { "on" } else { "off" },
+        msg.trim()
+    )
+    .trim()
+    .to_string())
+}
+
+/// Raise or lower a profile's route metric from its current value (or
+/// NetworkManager's WiFi default when it has none).
+fn adjust_route_metric(backend: &dyn Backend, device: &str, name: &str, change: i64) -> Result<String, String> {
+    let current = backend.routes(name)?.metric.unwrap_or(backend::WIFI_DEFAULT_ROUTE_METRIC);
+    let metric = (i64::from(current) + change).clamp(0, i64::from(u32::MAX)) as u32;
+    backend.set_route_metric(device, name, Some(metric))
+}
+
+/// Run `nmcli monitor` in the background, sending `Event::NetworkChanged`
+/// for lines about `device` until the receiver goes away.
+pub fn spawn_monitor(device: &str, tx: mpsc::Sender<Event>) {
+    let Ok(mut child) = Command::new("nmcli")
+        .arg("monitor")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+    else {
+        return;
+    };
+    let Some(stdout) = child.stdout.take() else {
+        return;
+    };
+
+    let device = device.to_string();
+    thread::spawn(move || {
+        for line in BufReader::new(stdout).lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            if let Some(change) = classify_monitor_line(&line, &device) {
+                if tx.send(Event::NetworkChanged(change)).is_err() {
+                    break;
+                }
+            }
+        }
+        let _ = child.kill();
+        let _ = child.wait();
+    });
+}
+
+/// Decide whether an `nmcli monitor` line is relevant to us.
+fn classify_monitor_line(line: &str, device: &str) -> Option<MonitorChange> {
+    if line.contains("connection profile") {
+        Some(MonitorChange::Saved)
+    } else if line.ends_with(": device created") || line.ends_with(": device removed") {
+        Some(MonitorChange::Devices)
+    } else if line.starts_with(&format!("{}:", device))
+        || line.contains("primary connection")
+        || line.starts_with("Connectivity is now")
+        // nmcli spells it "Networkmanager" in this message
+        || line.to_lowercase().starts_with("networkmanager is now")
+    {
+        Some(MonitorChange::Status)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_monitor_line() {
+        assert_eq!(
+            classify_monitor_line("wlp3s0: using connection 'Home'", "wlp3s0"),
+            Some(MonitorChange::Status)
+        );
+        assert_eq!(
+            classify_monitor_line("Home: connection profile removed", "wlp3s0"),
+            Some(MonitorChange::Saved)
+        );
+        assert_eq!(
+            classify_monitor_line("wlx00c0ca123456: device removed", "wlp3s0"),
+            Some(MonitorChange::Devices)
+        );
+        assert_eq!(
+            classify_monitor_line("'Home' is now the primary connection", "wlp3s0"),
+            Some(MonitorChange::Status)
+        );
+        assert_eq!(classify_monitor_line("enp0s31f6: unavailable", "wlp3s0"), None);
     }
 }
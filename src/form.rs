@@ -0,0 +1,190 @@
+use ratatui::crossterm::event::{KeyCode, KeyEvent};
+
+use crate::input::TextInput;
+
+/// The editable part of a form field.
+#[derive(Debug, Clone)]
+pub enum FieldKind {
+    Text(TextInput),
+    /// Text that is masked when drawn.
+    Secret(TextInput),
+    /// One of a fixed set of options, cycled with ←/→ or Space.
+    Choice(Vec<String>, usize),
+}
+
+#[derive(Debug, Clone)]
+pub struct Field {
+    pub label: &'static str,
+    pub kind: FieldKind,
+}
+
+/// What the owner of a form should do after a key press.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormAction {
+    None,
+    /// All fields validated; read the values and close the form.
+    Submit,
+    Cancel,
+}
+
+/// A multi-field modal form: Tab/↑↓ cycle focus, Enter validates and submits,
+/// Esc cancels. Fields are looked up by label when reading values back.
+#[derive(Debug, Clone, Default)]
+pub struct Form {
+    pub title: String,
+    pub fields: Vec<Field>,
+    pub focus: usize,
+    pub error: Option<String>,
+}
+
+impl Form {
+    pub fn new(title: &str) -> Self {
+        Self {
+            title: title.to_string(),
+            ..Self::default()
+        }
+    }
+
+    pub fn text(mut self, label: &'static str, input: TextInput) -> Self {
+        self.fields.push(Field { label, kind: FieldKind::Text(input) });
+        self
+    }
+
+    pub fn secret(mut self, label: &'static str, input: TextInput) -> Self {
+        self.fields.push(Field { label, kind: FieldKind::Secret(input) });
+        self
+    }
+
+    pub fn choice(mut self, label: &'static str, options: &[&str], selected: usize) -> Self {
+        let options = options.iter().map(|o| o.to_string()).collect();
+        self.fields.push(Field { label, kind: FieldKind::Choice(options, selected) });
+        self
+    }
+
+    fn field(&self, label: &str) -> Option<&FieldKind> {
+        self.fields.iter().find(|f| f.label == label).map(|f| &f.kind)
+    }
+
+    /// Text of a text/secret field, or the selected option of a choice field.
+    pub fn value(&self, label: &str) -> &str {
+        match self.field(label) {
+            Some(FieldKind::Text(input)) | Some(FieldKind::Secret(input)) => input.value(),
+            Some(FieldKind::Choice(options, selected)) => &options[*selected],
+            None => "",
+        }
+    }
+
+    /// Selected index of a choice field.
+    pub fn selected(&self, label: &str) -> usize {
+        match self.field(label) {
+            Some(FieldKind::Choice(_, selected)) => *selected,
+            _ => 0,
+        }
+    }
+
+    /// The text input of the focused field, if it has one.
+    fn focused_input(&mut self) -> Option<&mut TextInput> {
+        match &mut self.fields.get_mut(self.focus)?.kind {
+            FieldKind::Text(input) | FieldKind::Secret(input) => Some(input),
+            _ => None,
+        }
+    }
+
+    fn move_focus(&mut self, forward: bool) {
+        let len = self.fields.len().max(1);
+        self.focus = if forward {
+            (self.focus + 1) % len
+        } else {
+            (self.focus + len - 1) % len
+        };
+    }
+
+    /// Validate every text field, focusing the first invalid one.
+    fn validate(&mut self) -> Result<(), String> {
+        for (i, field) in self.fields.iter().enumerate() {
+            if let FieldKind::Text(input) | FieldKind::Secret(input) = &field.kind {
+                if let Err(e) = input.validate() {
+                    self.focus = i;
+                    return Err(format!("{}: {}", field.label, e));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> FormAction {
+        match key.code {
+            KeyCode::Esc => return FormAction::Cancel,
+            KeyCode::Tab | KeyCode::Down => self.move_focus(true),
+            KeyCode::BackTab | KeyCode::Up => self.move_focus(false),
+            KeyCode::Enter => match self.validate() {
+                Ok(()) => {
+                    self.error = None;
+                    return FormAction::Submit;
+                }
+                Err(e) => self.error = Some(e),
+            },
+            _ => {
+                let Some(field) = self.fields.get_mut(self.focus) else {
+                    return FormAction::None;
+                };
+                match &mut field.kind {
+                    FieldKind::Text(input) | FieldKind::Secret(input) => {
+                        input.handle_key(key);
+                    }
+                    FieldKind::Choice(options, selected) => match key.code {
+                        KeyCode::Right | KeyCode::Char(' ') => {
+                            *selected = (*selected + 1) % options.len();
+                        }
+                        KeyCode::Left => {
+                            *selected = (*selected + options.len() - 1) % options.len();
+                        }
+                        _ => {}
+                    },
+                }
+            }
+        }
+        FormAction::None
+    }
+
+    pub fn handle_paste(&mut self, text: &str) {
+        if let Some(input) = self.focused_input() {
+            input.insert_str(text);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::crossterm::event::KeyModifiers;
+
+    fn press(form: &mut Form, code: KeyCode) -> FormAction {
+        form.handle_key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    #[test]
+    fn test_submit_focuses_first_invalid_field() {
+        let mut form = Form::new("Test")
+            .text("Name", TextInput::with_value("ok"))
+            .text("SSID", TextInput::new().validator(|v| {
+                if v.is_empty() { Err("required".to_string()) } else { Ok(()) }
+            }));
+        assert_eq!(press(&mut form, KeyCode::Enter), FormAction::None);
+        assert_eq!(form.focus, 1);
+        assert_eq!(form.error.as_deref(), Some("SSID: required"));
+
+        press(&mut form, KeyCode::Char('x'));
+        assert_eq!(press(&mut form, KeyCode::Enter), FormAction::Submit);
+        assert_eq!(form.value("SSID"), "x");
+    }
+
+    #[test]
+    fn test_choice_cycles() {
+        let mut form = Form::new("Test").choice("Band", &["Auto", "2.4 GHz", "5 GHz"], 0);
+        press(&mut form, KeyCode::Left);
+        assert_eq!(form.value("Band"), "5 GHz");
+        press(&mut form, KeyCode::Right);
+        assert_eq!(form.selected("Band"), 0);
+    }
+}
@@ -0,0 +1,26 @@
+/// Case-insensitive subsequence match: every char of `query` appears in
+/// `text` in order, e.g. "hmw" matches "Home WiFi". An empty query matches
+/// everything.
+pub fn matches(query: &str, text: &str) -> bool {
+    let mut text = text.chars().flat_map(char::to_lowercase);
+    query
+        .chars()
+        .flat_map(char::to_lowercase)
+        .filter(|c| !c.is_whitespace())
+        .all(|q| text.any(|t| t == q))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches() {
+        assert!(matches("", "anything"));
+        assert!(matches("hmw", "Home WiFi"));
+        assert!(matches("home wifi", "HomeWiFi-5G"));
+        assert!(matches("5G", "HomeWiFi-5g"));
+        assert!(!matches("wh", "Home WiFi"));
+        assert!(!matches("cafe", "Caf"));
+    }
+}
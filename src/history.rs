@@ -0,0 +1,59 @@
+use crate::keyfile::{self, Entries};
+
+/// How connecting to a network has gone so far.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ConnectStats {
+    pub successes: u32,
+    pub failures: u32,
+}
+
+impl ConnectStats {
+    pub fn attempts(&self) -> u32 {
+        self.successes + self.failures
+    }
+
+    /// Share of attempts that connected, in percent; None before the first.
+    pub fn reliability(&self) -> Option<u8> {
+        let attempts = self.attempts();
+        (attempts > 0).then(|| (u64::from(self.successes) * 100 / u64::from(attempts)) as u8)
+    }
+}
+
+/// Connection attempts per network, keyed by SSID.
+pub type ConnectHistory = Entries<ConnectStats>;
+
+/// `~/.config/wifi-tui/history`, one `ssid=successes:failures` per line.
+const FILE: &str = "history";
+
+/// Load the history so far. A missing or unreadable file means no attempts yet.
+pub fn load() -> ConnectHistory {
+    keyfile::load(FILE, parse_stats)
+}
+
+pub fn save(history: &ConnectHistory) -> Result<(), String> {
+    keyfile::save(FILE, history, |stats| format!("{}:{}", stats.successes, stats.failures))
+}
+
+/// `successes:failures`
+fn parse_stats(value: &str) -> Option<ConnectStats> {
+    let (successes, failures) = value.trim().split_once(':')?;
+    Some(ConnectStats {
+        successes: successes.parse().ok()?,
+        failures: failures.parse().ok()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_stats() {
+        let cafe = parse_stats("9:3").unwrap();
+        assert_eq!(cafe, ConnectStats { successes: 9, failures: 3 });
+        assert_eq!(cafe.reliability(), Some(75));
+        assert_eq!(parse_stats("0:2").unwrap().reliability(), Some(0));
+        assert_eq!(ConnectStats::default().reliability(), None);
+        assert_eq!(parse_stats("7"), None);
+    }
+}
@@ -0,0 +1,24 @@
+use std::process::Command;
+
+use crate::config::config_dir;
+use crate::logging;
+
+/// Run the user's hook `~/.config/wifi-tui/hooks/<name>` with `args`, if it
+/// exists. Returns Ok(false) when there is no hook to run.
+pub fn run(name: &str, args: &[&str]) -> Result<bool, String> {
+    let Some(path) = config_dir().map(|dir| dir.join("hooks").join(name)) else {
+        return Ok(false);
+    };
+    if !path.is_file() {
+        return Ok(false);
+    }
+
+    let output = logging::output(Command::new(&path).args(args))
+        .map_err(|e| format!("Couldn't run hook {}: {}", path.display(), e))?;
+    if output.status.success() {
+        Ok(true)
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        Err(format!("Hook {} failed: {}", path.display(), stderr.trim()))
+    }
+}
@@ -0,0 +1,276 @@
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// Validation hook: returns an error message for invalid input.
+pub type Validator = fn(&str) -> Result<(), String>;
+
+/// A single-line text input with a cursor, shift-selection, paste and
+/// optional validation. Positions are in chars, not bytes.
+#[derive(Debug, Clone, Default)]
+pub struct TextInput {
+    value: String,
+    cursor: usize,
+    /// Selection anchor; the selection spans anchor..cursor.
+    anchor: Option<usize>,
+    max_len: Option<usize>,
+    validator: Option<Validator>,
+}
+
+impl TextInput {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_value(value: &str) -> Self {
+        let mut input = Self::new();
+        input.set_value(value);
+        input
+    }
+
+    /// Limit the input to at most `max_len` chars.
+    pub fn max_len(mut self, max_len: usize) -> Self {
+        self.max_len = Some(max_len);
+        self
+    }
+
+    pub fn validator(mut self, validator: Validator) -> Self {
+        self.validator = Some(validator);
+        self
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    pub fn set_value(&mut self, value: &str) {
+        self.value = value.to_string();
+        self.cursor = self.len();
+        self.anchor = None;
+    }
+
+    pub fn clear(&mut self) {
+        self.set_value("");
+    }
+
+    /// Length in chars.
+    pub fn len(&self) -> usize {
+        self.value.chars().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.value.is_empty()
+    }
+
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// Selected char range (start, end), if any non-empty selection exists.
+    pub fn selection(&self) -> Option<(usize, usize)> {
+        let anchor = self.anchor?;
+        if anchor == self.cursor {
+            return None;
+        }
+        Some((anchor.min(self.cursor), anchor.max(self.cursor)))
+    }
+
+    /// Run the validator, if any.
+    pub fn validate(&self) -> Result<(), String> {
+        match self.validator {
+            Some(validate) => validate(&self.value),
+            None => Ok(()),
+        }
+    }
+
+    pub fn insert_char(&mut self, c: char) {
+        self.delete_selection();
+        if self.max_len.is_some_and(|max| self.len() >= max) {
+            return;
+        }
+        let idx = self.byte_index(self.cursor);
+        self.value.insert(idx, c);
+        self.cursor += 1;
+    }
+
+    /// Insert pasted text at the cursor, replacing any selection. Newlines are
+    /// dropped since the input is single-line.
+    pub fn insert_str(&mut self, text: &str) {
+        for c in text.chars().filter(|c| *c != '\n' && *c != '\r') {
+            self.insert_char(c);
+        }
+    }
+
+    pub fn backspace(&mut self) {
+        if self.delete_selection() || self.cursor == 0 {
+            return;
+        }
+        self.cursor -= 1;
+        let idx = self.byte_index(self.cursor);
+        self.value.remove(idx);
+    }
+
+    pub fn delete(&mut self) {
+        if self.delete_selection() || self.cursor >= self.len() {
+            return;
+        }
+        let idx = self.byte_index(self.cursor);
+        self.value.remove(idx);
+    }
+
+    /// Delete from the cursor back to the start of the previous word.
+    pub fn delete_word_back(&mut self) {
+        if self.delete_selection() {
+            return;
+        }
+        let start = self.prev_word_start();
+        self.remove_range(start, self.cursor);
+        self.cursor = start;
+    }
+
+    pub fn select_all(&mut self) {
+        self.anchor = Some(0);
+        self.cursor = self.len();
+    }
+
+    fn move_to(&mut self, pos: usize, select: bool) {
+        if select {
+            self.anchor.get_or_insert(self.cursor);
+        } else {
+            self.anchor = None;
+        }
+        self.cursor = pos.min(self.len());
+    }
+
+    /// Handle an editing key. Returns true if the key was consumed.
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        let select = key.modifiers.contains(KeyModifiers::SHIFT);
+        if key.modifiers.contains(KeyModifiers::CONTROL) {
+            match key.code {
+                KeyCode::Char('u') => self.clear(),
+                KeyCode::Char('w') => self.delete_word_back(),
+                KeyCode::Char('a') => self.select_all(),
+                KeyCode::Left => self.move_to(self.prev_word_start(), select),
+                KeyCode::Right => self.move_to(self.next_word_end(), select),
+                _ => return false,
+            }
+            return true;
+        }
+        match key.code {
+            KeyCode::Left => self.move_to(self.cursor.saturating_sub(1), select),
+            KeyCode::Right => self.move_to(self.cursor + 1, select),
+            KeyCode::Home => self.move_to(0, select),
+            KeyCode::End => self.move_to(self.len(), select),
+            KeyCode::Backspace => self.backspace(),
+            KeyCode::Delete => self.delete(),
+            KeyCode::Char(c) => self.insert_char(c),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Delete the selected text, if any. Returns true if something was deleted.
+    fn delete_selection(&mut self) -> bool {
+        let Some((start, end)) = self.selection() else {
+            self.anchor = None;
+            return false;
+        };
+        self.remove_range(start, end);
+        self.cursor = start;
+        self.anchor = None;
+        true
+    }
+
+    fn remove_range(&mut self, start: usize, end: usize) {
+        let (start, end) = (self.byte_index(start), self.byte_index(end));
+        self.value.replace_range(start..end, "");
+    }
+
+    fn prev_word_start(&self) -> usize {
+        let chars: Vec<char> = self.value.chars().collect();
+        let mut pos = self.cursor;
+        while pos > 0 && chars[pos - 1].is_whitespace() {
+            pos -= 1;
+        }
+        while pos > 0 && !chars[pos - 1].is_whitespace() {
+            pos -= 1;
+        }
+        pos
+    }
+
+    fn next_word_end(&self) -> usize {
+        let chars: Vec<char> = self.value.chars().collect();
+        let mut pos = self.cursor;
+        while pos < chars.len() && chars[pos].is_whitespace() {
+            pos += 1;
+        }
+        while pos < chars.len() && !chars[pos].is_whitespace() {
+            pos += 1;
+        }
+        pos
+    }
+
+    fn byte_index(&self, pos: usize) -> usize {
+        self.value
+            .char_indices()
+            .nth(pos)
+            .map(|(i, _)| i)
+            .unwrap_or(self.value.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn test_insert_and_move_cursor() {
+        let mut input = TextInput::with_value("helo");
+        input.handle_key(key(KeyCode::Left, KeyModifiers::NONE));
+        input.handle_key(key(KeyCode::Char('l'), KeyModifiers::NONE));
+        assert_eq!(input.value(), "hello");
+        assert_eq!(input.cursor(), 4);
+    }
+
+    #[test]
+    fn test_selection_replaced_by_typing() {
+        let mut input = TextInput::with_value("héllo world");
+        input.handle_key(key(KeyCode::Home, KeyModifiers::NONE));
+        for _ in 0..5 {
+            input.handle_key(key(KeyCode::Right, KeyModifiers::SHIFT));
+        }
+        assert_eq!(input.selection(), Some((0, 5)));
+        input.insert_str("goodbye");
+        assert_eq!(input.value(), "goodbye world");
+    }
+
+    #[test]
+    fn test_delete_word_back() {
+        let mut input = TextInput::with_value("correct horse battery");
+        input.handle_key(key(KeyCode::Char('w'), KeyModifiers::CONTROL));
+        assert_eq!(input.value(), "correct horse ");
+    }
+
+    #[test]
+    fn test_move_by_word() {
+        let mut input = TextInput::with_value("correct horse battery");
+        input.handle_key(key(KeyCode::Left, KeyModifiers::CONTROL));
+        assert_eq!(input.cursor(), 14);
+        input.handle_key(key(KeyCode::Left, KeyModifiers::CONTROL | KeyModifiers::SHIFT));
+        assert_eq!(input.selection(), Some((8, 14)));
+        input.handle_key(key(KeyCode::Home, KeyModifiers::NONE));
+        input.handle_key(key(KeyCode::Right, KeyModifiers::CONTROL));
+        assert_eq!(input.cursor(), 7);
+        input.handle_key(key(KeyCode::Right, KeyModifiers::CONTROL));
+        assert_eq!(input.cursor(), 13);
+    }
+
+    #[test]
+    fn test_max_len_and_paste_newlines() {
+        let mut input = TextInput::new().max_len(4);
+        input.insert_str("ab\ncdef");
+        assert_eq!(input.value(), "abcd");
+    }
+}
@@ -0,0 +1,73 @@
+use std::process::{Command, Stdio};
+use std::thread;
+
+use crate::logging;
+
+/// What's reachable past the WiFi link, from an HTTP probe like the one
+/// NetworkManager runs for its connectivity check.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Internet {
+    Online,
+    /// Something answered the probe other than the probe server, most
+    /// likely a hotel or airport sign-in page. Holds the page it redirected
+    /// to, or else the probe URL, which shows the page when opened.
+    CaptivePortal(String),
+    Offline,
+}
+
+/// Answers plain HTTP with an empty 204; anything else is in the way.
+pub const DEFAULT_PROBE_URL: &str = "http://connectivitycheck.gstatic.com/generate_204";
+
+/// Fetch `url`, which should answer with 204 No Content. Counts as offline
+/// if curl isn't there to ask.
+pub fn probe(url: &str) -> Internet {
+    let output = logging::output(Command::new("curl").args([
+        "-sS", "-o", "/dev/null", "--max-time", "5", "-w", "%{http_code} %{redirect_url}", url,
+    ]));
+    match output {
+        Ok(output) => classify(&String::from_utf8_lossy(&output.stdout), url),
+        Err(_) => Internet::Offline,
+    }
+}
+
+/// From curl's `%{http_code} %{redirect_url}`; the code is "000" when
+/// nothing answered.
+fn classify(written: &str, url: &str) -> Internet {
+    let (code, redirect) = written.trim().split_once(' ').unwrap_or((written.trim(), ""));
+    match code {
+        "204" => Internet::Online,
+        "000" | "" => Internet::Offline,
+        _ if redirect.is_empty() => Internet::CaptivePortal(url.to_string()),
+        _ => Internet::CaptivePortal(redirect.to_string()),
+    }
+}
+
+/// Open `url` in the desktop's browser, without waiting for it to close.
+pub fn open_in_browser(url: &str) -> Result<(), String> {
+    let mut child = Command::new("xdg-open")
+        .arg(url)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|_| format!("xdg-open not found. Open {} in a browser to sign in.", url))?;
+    thread::spawn(move || child.wait());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        let url = DEFAULT_PROBE_URL;
+        assert_eq!(classify("204 ", url), Internet::Online);
+        assert_eq!(classify("000 ", url), Internet::Offline);
+        assert_eq!(
+            classify("302 https://portal.example/login?x=1", url),
+            Internet::CaptivePortal("https://portal.example/login?x=1".to_string())
+        );
+        assert_eq!(classify("200 ", url), Internet::CaptivePortal(url.to_string()));
+    }
+}
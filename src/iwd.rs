@@ -0,0 +1,383 @@
+use std::process::{Command, Output};
+
+use crate::backend::{
+    is_virtual_interface, shell_quote, Backend, Change, ConnectionStatus, DnsConfig, EapConfig, HotspotConfig, Ipv4Config, Network, ProfileOptions,
+    RouteConfig, SavedNetwork, WifiSecret, NO_WIFI_ADAPTER,
+};
+use crate::cancel;
+use crate::nl80211;
+
+/// Backend for systems running iwd without NetworkManager, driven through
+/// `iwctl`. iwd has no hotspot profiles, so those operations are unsupported.
+pub struct IwdBackend;
+
+impl Backend for IwdBackend {
+    fn name(&self) -> &'static str {
+        "iwd"
+    }
+
+    fn detect_wifi_devices(&self) -> Result<Vec<String>, String> {
+        let stdout = iwctl(&["device", "list"])?;
+        // Columns: Name, Address, Powered, Adapter, Mode
+        let devices: Vec<String> = table_rows(&stdout)
+            .into_iter()
+            .filter(|row| row.last().is_some_and(|mode| mode == "station"))
+            .map(|row| row[0].clone())
+            .filter(|device| !is_virtual_interface(device))
+            .collect();
+        if devices.is_empty() {
+            return Err(NO_WIFI_ADAPTER.to_string());
+        }
+        Ok(devices)
+    }
+
+    fn scan_networks(&self, device: &str) -> Result<Vec<Network>, String> {
+        // Trigger a rescan first (best-effort, ignore errors)
+        let _ = iwctl(&["station", device, "scan"]);
+
+        let stdout = iwctl(&["station", device, "get-networks", "rssi-dbms"])?;
+        Ok(stdout.lines().filter_map(parse_network_row).collect())
+    }
+
+    fn get_status(&self, device: &str) -> ConnectionStatus {
+        let mut status = ConnectionStatus::default();
+        let Ok(stdout) = iwctl(&["station", device, "show"]) else {
+            return status;
+        };
+
+        for row in table_rows(&stdout) {
+            // The optional "Settable" column makes rows either 2 or 3 wide.
+            let [.., property, value] = row.as_slice() else {
+                continue;
+            };
+            match property.as_str() {
+                "Connected network" => status.ssid = Some(value.clone()),
+                "IPv4 address" => status.ip = Some(value.clone()),
+                "RSSI" => {
+                    status.dbm = parse_dbm(value);
+                    status.signal = status.dbm.map(dbm_to_percent);
+                }
+                "TxBitrate" => status.bitrate = parse_bitrate(value),
+                _ => {}
+            }
+        }
+        // iwd doesn't say which 802.11 generation the link uses
+        if status.ssid.is_some() {
+            status.link = nl80211::link_station(device).and_then(|station| station.tx_rate);
+        }
+        status
+    }
+
+    fn saved_networks(&self) -> Result<Vec<SavedNetwork>, String> {
+        let stdout = iwctl(&["known-networks", "list"])?;
+        let active = self
+            .detect_wifi_device()
+            .ok()
+            .and_then(|device| self.get_status(&device).ssid);
+
+        // Columns: Name, Security, [Hidden], Last connected
+        Ok(table_rows(&stdout)
+            .into_iter()
+            .filter(|row| row.len() >= 2)
+            .map(|row| SavedNetwork {
+                active: active.as_deref() == Some(row[0].as_str()),
+                name: row[0].clone(),
+                hotspot: false,
+            })
+            .collect())
+    }
+
+    fn connect(&self, device: &str, ssid: &str, password: Option<&str>) -> Result<String, String> {
+        match password {
+            Some(pw) if !pw.is_empty() => {
+                // iwctl asks for the passphrase itself; answered on stdin, it stays out of `ps`
+                iwctl_with_input(&["station", device, "connect", ssid], &format!("{}\n", pw))?
+            }
+            _ => iwctl(&["station", device, "connect", ssid])?,
+        };
+        Ok(format!("Connected to {}", ssid))
+    }
+
+    fn connect_enterprise(&self, _device: &str, ssid: &str, _config: &EapConfig) -> Result<String, String> {
+        Err(format!(
+            "iwd joins enterprise networks from a provisioning file, /var/lib/iwd/{}.8021x; see iwd.network(5).",
+            ssid
+        ))
+    }
+
+    fn disconnect(&self, device: &str) -> Result<String, String> {
+        iwctl(&["station", device, "disconnect"])?;
+        Ok("Disconnected.".to_string())
+    }
+
+    fn cancel_activation(&self, device: &str) -> Result<(), String> {
+        // iwd keeps connecting after iwctl is killed, until told to stop
+        iwctl(&["station", device, "disconnect"]).map(|_| ())
+    }
+
+    fn forget(&self, name: &str) -> Result<String, String> {
+        iwctl(&["known-networks", name, "forget"])?;
+        Ok(format!("Forgot network '{}'.", name))
+    }
+
+    fn rename_profile(&self, _name: &str, _new_name: &str) -> Result<String, String> {
+        Err("iwd names known networks after their SSID; renaming requires NetworkManager.".to_string())
+    }
+
+    fn save_hotspot(&self, _config: &HotspotConfig) -> Result<String, String> {
+        Err("Hotspot profiles require NetworkManager; iwd does not support them.".to_string())
+    }
+
+    fn start_hotspot(&self, _name: &str) -> Result<String, String> {
+        Err("Hotspot profiles require NetworkManager; iwd does not support them.".to_string())
+    }
+
+    fn create_hotspot(&self, _device: &str, _ssid: &str, _password: &str) -> Result<String, String> {
+        Err("Hotspots require NetworkManager; iwd does not support them.".to_string())
+    }
+
+    fn stop_hotspot(&self, _name: &str) -> Result<String, String> {
+        Err("Hotspots require NetworkManager; iwd does not support them.".to_string())
+    }
+
+    fn wifi_secret(&self, _name: &str) -> Result<WifiSecret, String> {
+        Err("Sharing saved passwords requires NetworkManager.".to_string())
+    }
+
+    fn connect_bssid(&self, _device: &str, _name: &str, _bssid: &str) -> Result<String, String> {
+        Err("Connecting to a specific access point requires NetworkManager.".to_string())
+    }
+
+    fn set_call_mode(&self, _device: &str, _name: &str, _on: bool, _band: Option<&str>) -> Result<String, String> {
+        Err("Call mode requires NetworkManager.".to_string())
+    }
+
+    fn set_metered(&self, _device: &str, _name: &str, _metered: bool) -> Result<String, String> {
+        Err("Metered connections require NetworkManager.".to_string())
+    }
+
+    fn profile_options(&self, _name: &str) -> Result<ProfileOptions, String> {
+        Err("Editing saved profiles requires NetworkManager.".to_string())
+    }
+
+    fn set_profile_options(&self, _name: &str, _options: &ProfileOptions) -> Result<String, String> {
+        Err("Editing saved profiles requires NetworkManager.".to_string())
+    }
+
+    fn dns(&self, _name: &str) -> Result<DnsConfig, String> {
+        Err("Custom DNS requires NetworkManager.".to_string())
+    }
+
+    fn set_dns(&self, _device: &str, _name: &str, _config: &DnsConfig) -> Result<String, String> {
+        Err("Custom DNS requires NetworkManager.".to_string())
+    }
+
+    fn routes(&self, _name: &str) -> Result<RouteConfig, String> {
+        Err("Custom routes require NetworkManager.".to_string())
+    }
+
+    fn set_routes(&self, _device: &str, _name: &str, _config: &RouteConfig) -> Result<String, String> {
+        Err("Custom routes require NetworkManager.".to_string())
+    }
+
+    fn ipv4(&self, _name: &str) -> Result<Ipv4Config, String> {
+        Err("Static addresses require NetworkManager.".to_string())
+    }
+
+    fn set_ipv4(&self, _device: &str, _name: &str, _config: &Ipv4Config) -> Result<String, String> {
+        Err("Static addresses require NetworkManager.".to_string())
+    }
+
+    fn set_route_metric(&self, _device: &str, _name: &str, _metric: Option<u32>) -> Result<String, String> {
+        Err("Route metrics require NetworkManager.".to_string())
+    }
+
+    fn change_summary(&self, device: &str, change: &Change) -> String {
+        match change {
+            Change::Disconnect => format!("Runs: iwctl station {} disconnect", shell_quote(device)),
+            Change::Forget(name) => format!("Runs: iwctl known-networks {} forget", shell_quote(name)),
+            _ => "Needs NetworkManager; iwd can't change this.".to_string(),
+        }
+    }
+}
+
+/// Run `iwctl` non-interactively and return its stdout with colors stripped.
+fn iwctl(args: &[&str]) -> Result<String, String> {
+    let output = cancel::output(Command::new("iwctl").arg("--dont-ask").args(args))
+        .map_err(|e| friendly_error(&e.to_string()))?;
+    iwctl_result(&output)
+}
+
+/// Run `iwctl` with `input` answering its prompts.
+fn iwctl_with_input(args: &[&str], input: &str) -> Result<String, String> {
+    let output = cancel::output_with_input(Command::new("iwctl").args(args), input)
+        .map_err(|e| friendly_error(&e.to_string()))?;
+    iwctl_result(&output)
+}
+
+/// iwctl's stdout with colors stripped, or its error.
+fn iwctl_result(output: &Output) -> Result<String, String> {
+    let stdout = strip_ansi(&String::from_utf8_lossy(&output.stdout));
+    if output.status.success() {
+        Ok(stdout)
+    } else {
+        // iwctl prints most errors on stdout
+        let stderr = strip_ansi(&String::from_utf8_lossy(&output.stderr));
+        let msg = if stderr.trim().is_empty() { stdout } else { stderr };
+        Err(friendly_error(msg.trim()))
+    }
+}
+
+/// Parse a row of `station <dev> get-networks rssi-dbms`.
+fn parse_network_row(line: &str) -> Option<Network> {
+    let trimmed = line.trim_start();
+    let in_use = trimmed.starts_with('>');
+    let row = split_columns(trimmed.trim_start_matches('>'));
+    let [ssid, security, signal] = row.as_slice() else {
+        return None;
+    };
+    let signal = parse_dbm(signal)?;
+    // iwd only lists SSIDs that are valid UTF-8
+    Some(Network {
+        ssid: ssid.clone(),
+        ssid_bytes: ssid.clone().into_bytes(),
+        signal: dbm_to_percent(signal),
+        security: match security.as_str() {
+            "open" => String::new(),
+            "psk" => "WPA2".to_string(),
+            "8021x" => "802.1X".to_string(),
+            other => other.to_uppercase(),
+        },
+        in_use,
+        ..Network::default()
+    })
+}
+
+/// Rows of an iwctl table: everything after the header's second dashed rule,
+/// split into columns.
+fn table_rows(output: &str) -> Vec<Vec<String>> {
+    let mut rules = 0;
+    let mut rows = Vec::new();
+    for line in output.lines() {
+        if line.trim_start().starts_with("---") {
+            rules += 1;
+            continue;
+        }
+        if rules >= 2 {
+            let row = split_columns(line);
+            if !row.is_empty() {
+                rows.push(row);
+            }
+        }
+    }
+    rows
+}
+
+/// Split a table line on runs of two or more spaces, so single spaces inside
+/// values (e.g. "Connected network") are kept.
+fn split_columns(line: &str) -> Vec<String> {
+    line.split("  ")
+        .map(str::trim)
+        .filter(|col| !col.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn strip_ansi(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(ch) = chars.next() {
+        if ch == '\x1b' {
+            // Skip a CSI sequence: ESC [ ... final byte in @..~
+            for c in chars.by_ref() {
+                if ('@'..='~').contains(&c) && c != '[' {
+                    break;
+                }
+            }
+        } else {
+            out.push(ch);
+        }
+    }
+    out
+}
+
+/// Parse "-52" or "-52 dBm". `get-networks rssi-dbms` reports hundredths of a dBm.
+fn parse_dbm(value: &str) -> Option<i32> {
+    let n: i32 = value.split_whitespace().next()?.parse().ok()?;
+    Some(if n < -1000 { n / 100 } else { n })
+}
+
+/// Map RSSI to the 0-100 quality scale nmcli uses.
+fn dbm_to_percent(dbm: i32) -> u8 {
+    (2 * (dbm + 100)).clamp(0, 100) as u8
+}
+
+/// "866700 Kbit/s" -> 866700.
+fn parse_bitrate(value: &str) -> Option<u32> {
+    value.split_whitespace().next()?.parse().ok()
+}
+
+/// Translate iwctl error messages into beginner-friendly text.
+fn friendly_error(msg: &str) -> String {
+    if msg.contains("No such file or directory") {
+        "iwctl is not installed. Install iwd to use wifi-tui without NetworkManager.".to_string()
+    } else if msg.contains("Passphrase") || msg.contains("passphrase") {
+        "Password required. This network needs a password to connect.".to_string()
+    } else if msg.contains("Invalid format") || msg.contains("Operation failed") {
+        "Incorrect password. Please try again.".to_string()
+    } else if msg.contains("not found") || msg.contains("Network not found") {
+        "Network not found. It may be out of range or hidden.".to_string()
+    } else if msg.contains("not running") || msg.contains("Failed to connect to") {
+        "iwd is not running. Start it with: sudo systemctl start iwd".to_string()
+    } else if msg.contains("Not authorized") || msg.contains("permission") {
+        "Permission denied. You may need to run with appropriate privileges.".to_string()
+    } else if msg.is_empty() {
+        "An unknown error occurred.".to_string()
+    } else {
+        msg.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_change_summary() {
+        assert_eq!(
+            IwdBackend.change_summary("wlan0", &Change::Disconnect),
+            "Runs: iwctl station wlan0 disconnect"
+        );
+    }
+
+    #[test]
+    fn test_parse_network_row() {
+        let net = parse_network_row("        My Home Net       psk        -5600").unwrap();
+        assert_eq!(net.ssid, "My Home Net");
+        assert_eq!(net.security, "WPA2");
+        assert_eq!(net.signal, 88);
+        assert!(!net.in_use);
+
+        let stripped = strip_ansi("  \x1b[1;90m> \x1b[0m  Cafe       open        -7000");
+        let net = parse_network_row(&stripped).unwrap();
+        assert!(net.in_use);
+        assert_eq!(net.security, "");
+        assert_eq!(net.signal, 60);
+    }
+
+    #[test]
+    fn test_table_rows_station_show() {
+        let output = "\
+                                 Station: wlan0
+--------------------------------------------------------------------------------
+  Settable  Property              Value
+--------------------------------------------------------------------------------
+            State                 connected
+            Connected network     My Home Net
+            RSSI                  -52 dBm
+";
+        let rows = table_rows(output);
+        assert_eq!(rows[1], vec!["Connected network", "My Home Net"]);
+        assert_eq!(parse_dbm(&rows[2][1]), Some(-52));
+    }
+}
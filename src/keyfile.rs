@@ -0,0 +1,64 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use crate::config::config_dir;
+
+/// Entries of a `name=value`-per-line file in the config directory, keyed by
+/// profile name or SSID.
+pub type Entries<T> = BTreeMap<String, T>;
+
+fn path(file: &str) -> Option<PathBuf> {
+    Some(config_dir()?.join(file))
+}
+
+/// Load `~/.config/wifi-tui/<file>`. A missing or unreadable file has no
+/// entries.
+pub fn load<T>(file: &str, parse_value: impl Fn(&str) -> Option<T>) -> Entries<T> {
+    path(file)
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .map(|text| parse(&text, parse_value))
+        .unwrap_or_default()
+}
+
+pub fn save<T>(file: &str, entries: &Entries<T>, format_value: impl Fn(&T) -> String) -> Result<(), String> {
+    let path = path(file).ok_or("Couldn't find a config directory (HOME is not set).")?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Couldn't create {}: {}", dir.display(), e))?;
+    }
+    std::fs::write(&path, format(entries, format_value))
+        .map_err(|e| format!("Couldn't write {}: {}", path.display(), e))
+}
+
+/// Skips comments and lines whose value `parse_value` rejects.
+pub fn parse<T>(text: &str, parse_value: impl Fn(&str) -> Option<T>) -> Entries<T> {
+    text.lines()
+        .filter(|line| !line.trim_start().starts_with('#'))
+        .filter_map(|line| {
+            // Split on the last '=' so names containing '=' survive
+            let (name, value) = line.rsplit_once('=')?;
+            Some((name.to_string(), parse_value(value)?))
+        })
+        .collect()
+}
+
+pub fn format<T>(entries: &Entries<T>, format_value: impl Fn(&T) -> String) -> String {
+    entries
+        .iter()
+        .map(|(name, value)| format!("{}={}\n", name, format_value(value)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_roundtrip() {
+        let number = |value: &str| value.trim().parse::<u32>().ok();
+        let entries = parse("# comment\nPhone Hotspot=2000\nbad line\nweird=name=512\nnope=abc\n", number);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries["Phone Hotspot"], 2000);
+        assert_eq!(entries["weird=name"], 512);
+        assert_eq!(parse(&format(&entries, u32::to_string), number), entries);
+    }
+}
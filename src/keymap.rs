@@ -0,0 +1,409 @@
+use std::collections::HashMap;
+
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer};
+
+/// Something a key can do in the list views. Names are the keys of the
+/// `[keys]` table in config.toml.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    Up,
+    Down,
+    /// Move the selection a screenful, or half of one, at a time.
+    PageUp,
+    PageDown,
+    HalfPageUp,
+    HalfPageDown,
+    /// Jump to the first or last row.
+    Top,
+    Bottom,
+    /// Expand a network's access points.
+    Expand,
+    Collapse,
+    NextView,
+    PrevView,
+    Connect,
+    /// Join a new network with a profile that's deleted on disconnect and
+    /// when wifi-tui exits.
+    ConnectTemporarily,
+    Disconnect,
+    Forget,
+    /// Rescan, and re-read the connection status and saved profiles.
+    Refresh,
+    /// Only rescan for networks.
+    Rescan,
+    /// Only re-read the connection status and saved profiles.
+    RefreshStatus,
+    Quit,
+    /// Quit, asking for confirmation if something is running.
+    ForceQuit,
+    Details,
+    Sort,
+    Filter,
+    Logs,
+    LowData,
+    CallMode,
+    Scripts,
+    CheckSharing,
+    /// Show NetworkManager's connectivity checking; in there, turn it on or off.
+    Connectivity,
+    /// Whether the adapter can use 6 GHz (WiFi 6E) networks.
+    SixGhz,
+    NewHotspot,
+    ShareQr,
+    Limit,
+    /// Tune how eagerly a saved profile roams between access points.
+    Roaming,
+    /// Yes in confirmation dialogs; any other key cancels.
+    Confirm,
+    /// Ask the password manager again in the password prompt.
+    FetchPassword,
+    /// Hold Ctrl (or Shift) for the next key, for people who can't press
+    /// chords. Unbound by default; an F-key works well.
+    StickyCtrl,
+    StickyShift,
+    /// Edit a saved profile's autoconnect, priority and metered setting.
+    Edit,
+    /// Give a saved profile a new name.
+    Rename,
+    /// Edit a saved profile's default route and static routes.
+    Routes,
+    /// Pin a saved profile's DNS servers.
+    Dns,
+    /// Give a saved profile a fixed IPv4 address, or back to DHCP.
+    Ipv4,
+    /// Reveal a saved profile's password.
+    ShowPassword,
+    /// Copy the revealed password.
+    Copy,
+    /// Copy the current IP address.
+    CopyIp,
+    /// Paste from the system clipboard into a password or form field.
+    Paste,
+    /// Move internet traffic between WiFi and wired when both are up.
+    PreferUplink,
+    /// Manage the next WiFi adapter.
+    SwitchDevice,
+    /// Use the current adapter at every start, or stop preferring it.
+    PreferDevice,
+    /// Make a saved profile less preferred for the default route.
+    RaiseMetric,
+    LowerMetric,
+    StopHotspot,
+    /// Show a shorter or longer time window in the Monitor view.
+    ZoomIn,
+    ZoomOut,
+    /// Step through the 2.4, 5 and 6 GHz bands: filters the Available list,
+    /// and picks the band charted in the Analyzer view.
+    Band,
+    /// Turn the WiFi radio back on after airplane mode or `rfkill block`.
+    Unblock,
+    /// List everything that can be done with the selected network.
+    Menu,
+    /// Show signal strength in dBm instead of percent, or back.
+    SignalUnit,
+    /// Stop scanning in the background until pressed again.
+    PauseRefresh,
+    /// Look back at recent results and error messages.
+    Messages,
+}
+
+/// One key, e.g. "j", "Q", "enter", "ctrl-r".
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyBinding {
+    code: KeyCode,
+    ctrl: bool,
+}
+
+impl KeyBinding {
+    fn parse(text: &str) -> Option<Self> {
+        let (ctrl, name) = match text.strip_prefix("ctrl-") {
+            Some(name) => (true, name),
+            None => (false, text),
+        };
+        let mut chars = name.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match name.to_ascii_lowercase().as_str() {
+                "enter" => KeyCode::Enter,
+                "esc" => KeyCode::Esc,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "space" => KeyCode::Char(' '),
+                "backspace" => KeyCode::Backspace,
+                "delete" => KeyCode::Delete,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                f => KeyCode::F(f.strip_prefix('f')?.parse().ok().filter(|n| (1..=12).contains(n))?),
+            },
+        };
+        Some(Self { code, ctrl })
+    }
+
+    /// Shift is ignored: it's already part of the char (`Q`) or the code (BackTab).
+    fn matches(&self, key: &KeyEvent) -> bool {
+        self.code == key.code && self.ctrl == key.modifiers.contains(KeyModifiers::CONTROL)
+    }
+
+    fn label(&self) -> String {
+        let name = match self.code {
+            KeyCode::Char(' ') => "Space".to_string(),
+            KeyCode::Char(c) => c.to_string(),
+            KeyCode::Enter => "Enter".to_string(),
+            KeyCode::Esc => "Esc".to_string(),
+            KeyCode::Tab => "Tab".to_string(),
+            KeyCode::BackTab => "S-Tab".to_string(),
+            KeyCode::Up => "↑".to_string(),
+            KeyCode::Down => "↓".to_string(),
+            KeyCode::Left => "←".to_string(),
+            KeyCode::Right => "→".to_string(),
+            KeyCode::F(n) => format!("F{}", n),
+            code => format!("{:?}", code),
+        };
+        if self.ctrl {
+            format!("^{}", name)
+        } else {
+            name
+        }
+    }
+}
+
+/// Which keys trigger which actions. Config entries replace the defaults for
+/// their action; letters are case-sensitive, so bind both to match either.
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: HashMap<Action, Vec<KeyBinding>>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        use Action::*;
+        let defaults: &[(Action, &[&str])] = &[
+            (Up, &["up", "k"]),
+            (Down, &["down", "j"]),
+            (PageUp, &["pageup"]),
+            (PageDown, &["pagedown"]),
+            (HalfPageUp, &["ctrl-u"]),
+            (HalfPageDown, &["ctrl-d"]),
+            (Top, &["g", "home"]),
+            (Bottom, &["G", "end"]),
+            (Expand, &["right"]),
+            (Collapse, &["left"]),
+            (NextView, &["tab"]),
+            (PrevView, &["backtab"]),
+            (Connect, &["enter"]),
+            (ConnectTemporarily, &["ctrl-t"]),
+            (Disconnect, &["d", "D"]),
+            (Forget, &["f", "F"]),
+            (Refresh, &["r", "R"]),
+            (Rescan, &["ctrl-r"]),
+            (RefreshStatus, &["ctrl-l"]),
+            (Quit, &["q"]),
+            (ForceQuit, &["Q"]),
+            (Details, &["i", "I"]),
+            (Sort, &["s", "S"]),
+            (Filter, &["/"]),
+            (Logs, &["l"]),
+            (LowData, &["m", "M"]),
+            (CallMode, &["c", "C"]),
+            (Scripts, &["x", "X"]),
+            (CheckSharing, &["n", "N"]),
+            (Connectivity, &["t", "T"]),
+            (SixGhz, &["6"]),
+            (NewHotspot, &["h", "H"]),
+            (ShareQr, &["ctrl-s"]),
+            (Limit, &["b", "B"]),
+            (Roaming, &["v", "V"]),
+            (Confirm, &["y", "Y"]),
+            (FetchPassword, &["ctrl-p"]),
+            (StickyCtrl, &[]),
+            (StickyShift, &[]),
+            (Edit, &["e", "E"]),
+            (Rename, &["f2", "ctrl-n"]),
+            (Routes, &["o", "O"]),
+            (Dns, &["a", "A"]),
+            (Ipv4, &["4"]),
+            (ShowPassword, &["p"]),
+            (Copy, &["c", "C"]),
+            (CopyIp, &["Y"]),
+            (Paste, &["ctrl-v"]),
+            (PreferUplink, &["ctrl-e"]),
+            (SwitchDevice, &["w", "W"]),
+            (PreferDevice, &["ctrl-w"]),
+            (RaiseMetric, &["+", "="]),
+            (LowerMetric, &["-"]),
+            (StopHotspot, &["s", "S"]),
+            (ZoomIn, &["+", "="]),
+            (ZoomOut, &["-"]),
+            (Band, &["b", "B"]),
+            (Unblock, &["u", "U"]),
+            (Menu, &["space"]),
+            (SignalUnit, &["%"]),
+            (PauseRefresh, &["P"]),
+            (Messages, &["L"]),
+        ];
+        let bindings = defaults
+            .iter()
+            .map(|(action, keys)| {
+                let keys = keys.iter().filter_map(|key| KeyBinding::parse(key)).collect();
+                (*action, keys)
+            })
+            .collect();
+        Self { bindings }
+    }
+}
+
+impl Keymap {
+    pub fn is(&self, key: &KeyEvent, action: Action) -> bool {
+        self.bindings
+            .get(&action)
+            .is_some_and(|keys| keys.iter().any(|binding| binding.matches(key)))
+    }
+
+    /// A press of the action's first key, to run it as if it was typed.
+    pub fn key_event(&self, action: Action) -> Option<KeyEvent> {
+        let first = self.bindings.get(&action)?.first()?;
+        let modifiers = if first.ctrl { KeyModifiers::CONTROL } else { KeyModifiers::NONE };
+        Some(KeyEvent::new(first.code, modifiers))
+    }
+
+    /// Key shown in the help bar: the first binding, upper-cased when the
+    /// action is bound to both cases of a letter.
+    pub fn label(&self, action: Action) -> String {
+        let Some(keys) = self.bindings.get(&action) else {
+            return String::new();
+        };
+        let Some(first) = keys.first() else {
+            return "-".to_string();
+        };
+        if let KeyCode::Char(c) = first.code {
+            let both_cases = keys.iter().any(|k| k.code == KeyCode::Char(c.to_ascii_uppercase()))
+                && keys.iter().any(|k| k.code == KeyCode::Char(c.to_ascii_lowercase()));
+            if both_cases && !first.ctrl {
+                return c.to_ascii_uppercase().to_string();
+            }
+        }
+        first.label()
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum OneOrMany {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl<'de> Deserialize<'de> for Keymap {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let overrides = HashMap::<Action, OneOrMany>::deserialize(deserializer)?;
+        let mut keymap = Keymap::default();
+        for (action, keys) in overrides {
+            let keys = match keys {
+                OneOrMany::One(key) => vec![key],
+                OneOrMany::Many(keys) => keys,
+            };
+            let bindings = keys
+                .iter()
+                .map(|key| KeyBinding::parse(key).ok_or_else(|| D::Error::custom(format!("unknown key '{}'", key))))
+                .collect::<Result<_, _>>()?;
+            keymap.bindings.insert(action, bindings);
+        }
+        Ok(keymap)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn test_parse_binding() {
+        let parse = |text| KeyBinding::parse(text).map(|b| b.label());
+        assert_eq!(parse("j"), Some("j".to_string()));
+        assert_eq!(parse("enter"), Some("Enter".to_string()));
+        assert_eq!(parse("ctrl-r"), Some("^r".to_string()));
+        assert_eq!(parse("F5"), Some("F5".to_string()));
+        assert_eq!(parse("f13"), None);
+        assert_eq!(parse("hyper"), None);
+    }
+
+    #[test]
+    fn test_defaults() {
+        let keymap = Keymap::default();
+        assert!(keymap.is(&key(KeyCode::Char('k'), KeyModifiers::NONE), Action::Up));
+        assert!(keymap.is(&key(KeyCode::Char('Q'), KeyModifiers::SHIFT), Action::ForceQuit));
+        assert!(!keymap.is(&key(KeyCode::Char('Q'), KeyModifiers::SHIFT), Action::Quit));
+        assert!(!keymap.is(&key(KeyCode::Char('r'), KeyModifiers::CONTROL), Action::Refresh));
+        assert_eq!(keymap.label(Action::Refresh), "R");
+        assert_eq!(keymap.label(Action::Quit), "q");
+        assert_eq!(keymap.label(Action::Connect), "Enter");
+        assert_eq!(keymap.label(Action::FetchPassword), "^p");
+        assert_eq!(keymap.label(Action::StickyCtrl), "-");
+    }
+
+    #[test]
+    fn test_defaults_dont_overlap() {
+        use Action::*;
+        // Handled in every view, then by the view itself
+        let global = [
+            Quit, ForceQuit, NextView, PrevView, Filter, Refresh, Rescan, RefreshStatus, Logs, Messages, PauseRefresh,
+            LowData, CallMode, CopyIp, SignalUnit, Unblock, SwitchDevice, PreferDevice, PreferUplink, Scripts,
+            CheckSharing, SixGhz, Connectivity,
+        ];
+        let lists = [Up, Down, PageUp, PageDown, HalfPageUp, HalfPageDown, Top, Bottom, Connect, Disconnect, Sort, Menu];
+        let views: [(&str, &[Action]); 5] = [
+            ("available", &[Expand, Collapse, ConnectTemporarily, Band, Details]),
+            (
+                "saved",
+                &[
+                    Forget, NewHotspot, ShareQr, ShowPassword, Limit, Roaming, Edit, Rename, Dns, Ipv4, Routes,
+                    RaiseMetric, LowerMetric,
+                ],
+            ),
+            ("hotspot", &[Connect, StopHotspot]),
+            ("monitor", &[ZoomIn, ZoomOut]),
+            ("analyzer", &[Band]),
+        ];
+        let keymap = Keymap::default();
+        for (view, own) in views {
+            let mut actions: Vec<Action> = global.iter().chain(own).copied().collect();
+            if matches!(view, "available" | "saved") {
+                actions.extend(lists);
+            }
+            let mut seen: Vec<(KeyBinding, Action)> = Vec::new();
+            for action in actions {
+                for binding in &keymap.bindings[&action] {
+                    if let Some((_, other)) = seen.iter().find(|(b, _)| b == binding) {
+                        panic!("{} is bound to both {:?} and {:?} in the {} view", binding.label(), other, action, view);
+                    }
+                    seen.push((*binding, action));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_overrides() {
+        let keymap: Keymap = toml::from_str("up = \"t\"\nforget = [\"ctrl-d\", \"delete\"]").unwrap();
+        assert!(keymap.is(&key(KeyCode::Char('t'), KeyModifiers::NONE), Action::Up));
+        assert!(!keymap.is(&key(KeyCode::Char('k'), KeyModifiers::NONE), Action::Up));
+        assert!(keymap.is(&key(KeyCode::Char('d'), KeyModifiers::CONTROL), Action::Forget));
+        assert_eq!(keymap.key_event(Action::Forget), Some(key(KeyCode::Char('d'), KeyModifiers::CONTROL)));
+        assert!(keymap.is(&key(KeyCode::Char('j'), KeyModifiers::NONE), Action::Down));
+        assert!(toml::from_str::<Keymap>("teleport = \"t\"").is_err());
+        assert!(toml::from_str::<Keymap>("up = \"hyper\"").is_err());
+    }
+}
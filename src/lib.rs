@@ -0,0 +1,46 @@
+//! wifi-tui's modules, as a library so benches can reach parsers and the UI.
+
+pub mod agent;
+pub mod analyzer;
+pub mod app;
+pub mod cancel;
+pub mod backend;
+pub mod checks;
+pub mod cli;
+pub mod clipboard;
+pub mod config;
+pub mod dbus;
+pub mod devicepref;
+pub mod diagnostics;
+pub mod dispatcher;
+pub mod dump;
+pub mod event;
+pub mod form;
+pub mod fuzzy;
+pub mod history;
+pub mod hooks;
+pub mod input;
+pub mod internet;
+pub mod iwd;
+pub mod keyfile;
+pub mod keymap;
+pub mod logging;
+pub mod nl80211;
+pub mod nmcli;
+pub mod oui;
+pub mod password_manager;
+pub mod phy;
+pub mod ping;
+pub mod pkexec;
+pub mod qr;
+pub mod ratelimit;
+pub mod rfkill;
+pub mod roaming;
+pub mod snapshot;
+pub mod stream;
+pub mod theme;
+pub mod ui;
+pub mod units;
+pub mod update;
+pub mod usage;
+pub mod viewstate;
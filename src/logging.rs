@@ -0,0 +1,179 @@
+use std::io;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+use tracing::level_filters::LevelFilter;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::filter::Targets;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+
+/// Days of logs kept; a new file starts every day.
+const KEEP_DAYS: usize = 7;
+
+/// How much goes into the log file (`log_level` in config.toml, or
+/// `--log-level`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    /// No log file at all
+    Off,
+    Error,
+    /// Failed commands and errors shown in the app
+    #[default]
+    Warn,
+    /// Also connects, disconnects and the backend in use
+    Info,
+    /// Also every command run (nmcli, iwctl, ip, curl, ...), its exit code
+    /// and how long it took
+    Debug,
+    /// Also what D-Bus and other libraries do
+    Trace,
+}
+
+impl LogLevel {
+    fn filter(self) -> LevelFilter {
+        match self {
+            LogLevel::Off => LevelFilter::OFF,
+            LogLevel::Error => LevelFilter::ERROR,
+            LogLevel::Warn => LevelFilter::WARN,
+            LogLevel::Info => LevelFilter::INFO,
+            LogLevel::Debug => LevelFilter::DEBUG,
+            LogLevel::Trace => LevelFilter::TRACE,
+        }
+    }
+}
+
+/// `$XDG_STATE_HOME/wifi-tui/logs`, where `wifi-tui.<date>.log` files go.
+pub fn log_dir() -> Option<PathBuf> {
+    crate::diagnostics::state_dir().map(|dir| dir.join("logs"))
+}
+
+/// Start writing `level` and more severe events to today's log file. Other
+/// crates' debug and info events only go in at `trace`.
+pub fn init(level: LogLevel) -> Result<(), String> {
+    if level == LogLevel::Off {
+        return Ok(());
+    }
+    let dir = log_dir().ok_or("Couldn't find a directory for logs (HOME is not set).")?;
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Couldn't create {}: {}", dir.display(), e))?;
+    let appender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix("wifi-tui")
+        .filename_suffix("log")
+        .max_log_files(KEEP_DAYS)
+        .build(&dir)
+        .map_err(|e| format!("Couldn't start a log file in {}: {}", dir.display(), e))?;
+
+    let others = if level == LogLevel::Trace { LevelFilter::TRACE } else { level.filter().min(LevelFilter::WARN) };
+    let filter = Targets::new().with_default(others).with_target("wifi_tui", level.filter());
+    tracing_subscriber::fmt()
+        .with_writer(appender)
+        .with_ansi(false)
+        .finish()
+        .with(filter)
+        .try_init()
+        .map_err(|e| format!("Couldn't start logging: {}", e))
+}
+
+/// `Command::output`, logged.
+pub fn output(command: &mut Command) -> io::Result<Output> {
+    let started = Instant::now();
+    let result = command.output();
+    log_command(command, started.elapsed(), &result);
+    result
+}
+
+/// Log a finished command: at debug when it worked, with its stderr at warn
+/// when it didn't.
+pub fn log_command(command: &Command, elapsed: Duration, result: &io::Result<Output>) {
+    let ms = elapsed.as_millis();
+    match result {
+        Ok(output) if output.status.success() => {
+            tracing::debug!("{} exited with 0 after {} ms", command_line(command), ms);
+        }
+        Ok(output) => {
+            let code = output.status.code().map_or("a signal".to_string(), |code| code.to_string());
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            tracing::warn!("{} exited with {} after {} ms: {}", command_line(command), code, ms, stderr.trim());
+        }
+        Err(e) => tracing::warn!("{} failed after {} ms: {}", command_line(command), ms, e),
+    }
+}
+
+/// The command as it would be typed, with passwords and keys replaced.
+fn command_line(command: &Command) -> String {
+    let args: Vec<String> = command.get_args().map(|arg| arg.to_string_lossy().into_owned()).collect();
+    let mut line = command.get_program().to_string_lossy().into_owned();
+    for arg in redact(&args) {
+        line.push(' ');
+        if arg.is_empty() || arg.contains(|c: char| c.is_whitespace() || c == '"' || c == '\'') {
+            line.push_str(&format!("{:?}", arg));
+        } else {
+            line.push_str(&arg);
+        }
+    }
+    line
+}
+
+/// `args` with every value following an nmcli secret setting, e.g.
+/// `wifi-sec.psk` or `802-1x.password`, hidden. Passwords normally go in on
+/// stdin; this catches any that don't.
+fn redact(args: &[String]) -> Vec<String> {
+    let mut redacted = Vec::with_capacity(args.len());
+    let mut secret_next = false;
+    let mut fields_next = false;
+    for arg in args {
+        if secret_next {
+            redacted.push("<redacted>".to_string());
+            secret_next = false;
+        } else {
+            redacted.push(arg.clone());
+            // `-f …,802-11-wireless-security.psk` only names the setting to show
+            secret_next = !fields_next && is_secret_setting(arg);
+        }
+        fields_next = matches!(arg.as_str(), "-f" | "--fields" | "-g" | "--get-values");
+    }
+    redacted
+}
+
+fn is_secret_setting(arg: &str) -> bool {
+    // `+802-1x.password` and `-wifi-sec.psk` add to or remove from a setting
+    let name = arg.trim_start_matches(['+', '-']);
+    let name = name.rsplit('.').next().unwrap_or(name);
+    matches!(name, "psk" | "pin")
+        || name.ends_with("password")
+        || (name.starts_with("wep-key") && name != "wep-key-type")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact() {
+        let args = ["connection", "modify", "id", "Cafe", "wifi-sec.psk", "hunter2", "connection.metered", "yes"];
+        let args: Vec<String> = args.map(String::from).into();
+        assert_eq!(redact(&args)[5], "<redacted>");
+        assert_eq!(redact(&args)[7], "yes");
+
+        let show: Vec<String> = ["-s", "-f", "802-11-wireless-security.psk", "connection"].map(String::from).into();
+        assert_eq!(redact(&show)[3], "connection");
+        assert!(is_secret_setting("802-11-wireless-security.wep-key0"));
+        assert!(is_secret_setting("+802-1x.private-key-password"));
+        assert!(!is_secret_setting("wifi-sec.wep-key-type"));
+        assert!(!is_secret_setting("-s"));
+    }
+
+    #[test]
+    fn test_command_line() {
+        let mut command = Command::new("nmcli");
+        command.args(["connection", "modify", "id", "My Cafe", "802-1x.identity", "joe", "802-1x.password", "secret"]);
+        assert_eq!(
+            command_line(&command),
+            "nmcli connection modify id \"My Cafe\" 802-1x.identity joe 802-1x.password <redacted>"
+        );
+    }
+}
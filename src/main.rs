@@ -1,22 +1,72 @@
-mod app;
-mod event;
-mod nmcli;
-mod ui;
-
+use std::io::IsTerminal;
 use std::time::Duration;
 
+use clap::Parser;
 use color_eyre::eyre::{Result, WrapErr};
-use ratatui::crossterm::event::KeyEventKind;
+use ratatui::crossterm::event::{
+    DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture, KeyEventKind,
+};
+use ratatui::crossterm::execute;
 use ratatui::DefaultTerminal;
 
+use wifi_tui::units::Units;
+use wifi_tui::{
+    app, backend, checks, cli, config, devicepref, dump, event, history, logging, ratelimit, roaming, snapshot, stream,
+    ui, usage, viewstate,
+};
+
 fn main() -> Result<()> {
     color_eyre::install()?;
+    let cli = cli::Cli::parse();
+
+    let (mut config, config_error) = match config::load() {
+        Ok(config) => (config, None),
+        Err(e) => (config::Config::default(), Some(e)),
+    };
+    cli.apply(&mut config);
+    let log_error = logging::init(config.log_level).err();
+
+    if let Some(cli::Command::Doctor) = cli.command {
+        return doctor(&config, config_error, log_error);
+    }
+    if let Some(command) = cli.command {
+        if let Some(e) = &log_error {
+            eprintln!("Not logging to a file. {}", e);
+        }
+        return run_command(command, &config, config_error, cli.device.as_deref());
+    }
+    // Piped (`wifi-tui | grep`) or run from cron: there's no terminal to draw
+    // on, so print what `wifi-tui status` and `wifi-tui scan` would
+    if !std::io::stdin().is_terminal() || !std::io::stdout().is_terminal() {
+        let device = cli.device.as_deref();
+        run_command(cli::Command::Status { json: false }, &config, config_error, device)?;
+        return run_command(cli::Command::Scan { json: false }, &config, None, device);
+    }
 
-    // Detect WiFi device before entering TUI
-    let device = match nmcli::detect_wifi_device() {
+    // Pick a backend and detect the WiFi device before entering TUI
+    let backend = backend::detect();
+    // iwd doesn't go through NetworkManager, so there's nothing to check
+    let mut startup_checks = if backend.name() == "iwd" {
+        Vec::new()
+    } else {
+        checks::startup_checks()
+    };
+    // First, since it explains why everything else may be missing
+    if let Some(check) = checks::namespace_check(config.host_devices_command.as_deref()) {
+        startup_checks.insert(0, check);
+    }
+    if let Some(e) = &log_error {
+        startup_checks.push(checks::log_file_check(e));
+    }
+    let preferred_mac = devicepref::load();
+    let device = match backend::choose_device(backend.as_ref(), cli.device.as_deref(), preferred_mac.as_deref()) {
         Ok(d) => d,
         Err(e) => {
+            tracing::error!("{}", e);
             eprintln!("Error: {}", e);
+            for check in startup_checks.iter().filter(|c| c.state != checks::CheckState::Ok) {
+                eprintln!("  {}: {}", check.name, check.detail);
+            }
             std::process::exit(1);
         }
     };
@@ -25,28 +75,131 @@ fn main() -> Result<()> {
     // Without this, a panic leaves the terminal in raw/alternate-screen mode.
     let default_hook = std::panic::take_hook();
     std::panic::set_hook(Box::new(move |info| {
+        let _ = execute!(std::io::stdout(), DisableBracketedPaste, DisableMouseCapture);
         ratatui::restore();
         default_hook(info);
     }));
 
     let terminal = ratatui::init();
-    let result = run(terminal, device);
+    let _ = execute!(std::io::stdout(), EnableBracketedPaste);
+    let mouse = config.mouse;
+    if mouse {
+        let _ = execute!(std::io::stdout(), EnableMouseCapture);
+    }
+    tracing::info!("Starting on {} with the {} backend", device, backend.name());
+    let result = run(terminal, backend, device, preferred_mac, config, config_error, startup_checks);
+    if mouse {
+        let _ = execute!(std::io::stdout(), DisableMouseCapture);
+    }
+    let _ = execute!(std::io::stdout(), DisableBracketedPaste);
     ratatui::restore();
     result
 }
 
-fn run(mut terminal: DefaultTerminal, device: String) -> Result<()> {
-    let mut app = app::App::new(device);
-    let events = event::EventLoop::new(Duration::from_millis(250));
+/// `wifi-tui doctor`: every check, not only the failed ones, then exit
+/// non-zero if any failed.
+fn doctor(config: &config::Config, config_error: Option<String>, log_error: Option<String>) -> Result<()> {
+    if let Some(e) = config_error {
+        eprintln!("Using default settings. {}", e);
+    }
+    let mut checks = checks::doctor_checks();
+    if let Some(check) = checks::namespace_check(config.host_devices_command.as_deref()) {
+        checks.insert(0, check);
+    }
+    if let Some(e) = log_error {
+        checks.push(checks::log_file_check(&e));
+    }
+    if let Err(e) = dump::checks(&checks) {
+        eprintln!("\n{}", e);
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// `wifi-tui events|scan|status|saved [--json]`: print to stdout instead of
+/// starting the UI.
+fn run_command(
+    command: cli::Command,
+    config: &config::Config,
+    config_error: Option<String>,
+    device: Option<&str>,
+) -> Result<()> {
+    if let Some(e) = config_error {
+        eprintln!("Using default settings. {}", e);
+    }
+    let backend = backend::detect();
+    let units = Units::new(config.units, config.signal_unit);
+    let result = backend::choose_device(backend.as_ref(), device, devicepref::load().as_deref())
+        .and_then(|device| match command {
+            cli::Command::Events { json } => {
+                let refresh = Duration::from_secs(config.auto_refresh_secs.max(1));
+                stream::run(backend, &device, &units, json, refresh)
+            }
+            cli::Command::Scan { json } => dump::scan(backend.as_ref(), &device, config.default_sort, &units, json),
+            cli::Command::Status { json } => dump::status(backend.as_ref(), &device, &units, json),
+            cli::Command::Saved { json } => dump::saved(backend.as_ref(), json),
+            // Needs no device, so it never gets here
+            cli::Command::Doctor => unreachable!(),
+        });
+    if let Err(e) = result {
+        tracing::error!("{}", e);
+        eprintln!("Error: {}", e);
+        let namespace = checks::namespace_check(config.host_devices_command.as_deref());
+        if let Some(check) = namespace.filter(|c| c.state != checks::CheckState::Ok) {
+            eprintln!("  {}: {}", check.name, check.detail);
+        }
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn run(
+    mut terminal: DefaultTerminal,
+    backend: Box<dyn backend::Backend>,
+    device: String,
+    preferred_mac: Option<String>,
+    config: config::Config,
+    config_error: Option<String>,
+    startup_checks: Vec<checks::Check>,
+) -> Result<()> {
+    let tick_rate = config.tick_rate();
+    let check_updates = config.check_updates;
+    let events = event::EventLoop::new(tick_rate, backend);
+    let mut app = app::App::new(device, events.backend(), config);
+    app.set_preferred_mac(preferred_mac);
+    if startup_checks.iter().any(|c| c.state != checks::CheckState::Ok) {
+        app.modal = Some(app::Modal::Checks("Startup checks".to_string(), startup_checks));
+    } else if let Some(e) = config_error {
+        app.modal = Some(app::Modal::Message(format!("Using default settings. {}", e)));
+    }
+    app.rate_limits = ratelimit::load();
+    app.roam_settings = roaming::load();
+    app.usage = usage::load();
+    app.connect_history = history::load();
+    app.restore_views(viewstate::load());
+    if let Some(snapshot) = snapshot::load(&app.device) {
+        app.restore_snapshot(snapshot);
+    }
+    app.secret_agent = events.secret_agent;
+    events.start_monitor(&app.device);
+    app.start(&events);
+    if check_updates {
+        events.check_for_update();
+    }
 
+    let mut dirty = true;
     loop {
-        // Draw
-        terminal
-            .draw(|frame| ui::draw(frame, &app))
-            .wrap_err("failed to draw")?;
+        // Only redraw after something happened; ticks keep spinners and toasts moving
+        if dirty {
+            terminal
+                .draw(|frame| ui::draw(frame, &app))
+                .wrap_err("failed to draw")?;
+            dirty = false;
+        }
 
         // Process all pending events
         while let Some(ev) = events.try_recv() {
+            dirty = true;
             match ev {
                 event::Event::Key(key) => {
                     // Only handle key press events (ignore release/repeat on some platforms)
@@ -54,16 +207,38 @@ fn run(mut terminal: DefaultTerminal, device: String) -> Result<()> {
                         app.handle_key(key, &events);
                     }
                 }
+                event::Event::Paste(text) => {
+                    app.handle_paste(&text);
+                }
+                event::Event::Mouse(mouse) => {
+                    app.handle_mouse(mouse, &events);
+                }
                 event::Event::Tick => {
                     app.handle_tick(&events);
                 }
                 event::Event::TaskResult(result) => {
                     app.handle_task_result(result);
                 }
+                event::Event::SecretRequest(request) => {
+                    app.handle_secret_request(request);
+                }
+                event::Event::SecretCancelled => {
+                    app.handle_secret_cancelled();
+                }
+                event::Event::NetworkChanged(change) => {
+                    app.handle_network_changed(change);
+                }
+                event::Event::LogLine(line) => {
+                    app.handle_log_line(line);
+                }
             }
         }
 
         if !app.running {
+            events.remove_profiles(app.temporary_profiles.keys());
+            // Only a head start for next time; not worth complaining about
+            let _ = snapshot::save(&app.snapshot());
+            app.save_usage();
             return Ok(());
         }
 
@@ -0,0 +1,225 @@
+use neli::attr::Attribute;
+use neli::consts::nl::{NlmF, NlmFFlags};
+use neli::consts::socket::NlFamily;
+use neli::genl::{Genlmsghdr, Nlattr};
+use neli::nl::{NlPayload, Nlmsghdr};
+use neli::socket::NlSocketHandle;
+use neli::types::GenlBuffer;
+
+use crate::phy::{Generation, LinkRate};
+
+#[neli::neli_enum(serialized_type = "u8")]
+pub enum Command {
+    GetStation = 17,
+}
+impl neli::consts::genl::Cmd for Command {}
+
+#[neli::neli_enum(serialized_type = "u16")]
+pub enum Attr {
+    Ifindex = 3,
+    Mac = 6,
+    StaInfo = 21,
+}
+impl neli::consts::genl::NlAttrType for Attr {}
+
+/// `NL80211_STA_INFO_*`, nested in [`Attr::StaInfo`].
+#[neli::neli_enum(serialized_type = "u16")]
+pub enum StaInfo {
+    Signal = 7,
+    TxBitrate = 8,
+}
+impl neli::consts::genl::NlAttrType for StaInfo {}
+
+/// `NL80211_RATE_INFO_*`, nested in [`StaInfo::TxBitrate`].
+#[neli::neli_enum(serialized_type = "u16")]
+pub enum RateInfo {
+    Bitrate = 1,
+    Mcs = 2,
+    Width40 = 3,
+    Bitrate32 = 5,
+    VhtMcs = 6,
+    VhtNss = 7,
+    Width80 = 8,
+    Width80p80 = 9,
+    Width160 = 10,
+    HeMcs = 13,
+    HeNss = 14,
+    Width320 = 18,
+    EhtMcs = 19,
+    EhtNss = 20,
+}
+impl neli::consts::genl::NlAttrType for RateInfo {}
+
+/// A peer of a wireless interface: the access point it's connected to, or
+/// a client of its hotspot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Station {
+    pub mac: String,
+    pub signal_dbm: Option<i32>,
+    /// Transmit rate in kbit/s.
+    pub tx_bitrate: Option<u32>,
+    pub tx_rate: Option<LinkRate>,
+}
+
+/// The stations `device` knows about, straight from the kernel. A client
+/// has one while connected; an access point has one per client.
+pub fn stations(device: &str) -> Result<Vec<Station>, String> {
+    let ifindex = ifindex(device)?;
+    let err = |e: &dyn std::fmt::Display| format!("Couldn't query {} over nl80211: {}", device, e);
+    let mut sock = NlSocketHandle::connect(NlFamily::Generic, None, &[]).map_err(|e| err(&e))?;
+    let family = sock.resolve_genl_family("nl80211").map_err(|e| err(&e))?;
+
+    let mut attrs = GenlBuffer::new();
+    attrs.push(Nlattr::new(false, false, Attr::Ifindex, ifindex).map_err(|e| err(&e))?);
+    let request = Nlmsghdr::new(
+        None,
+        family,
+        NlmFFlags::new(&[NlmF::Request, NlmF::Dump]),
+        None,
+        None,
+        NlPayload::Payload(Genlmsghdr::new(Command::GetStation, 1, attrs)),
+    );
+    sock.send(request).map_err(|e| err(&e))?;
+
+    let mut stations = Vec::new();
+    for msg in sock.iter::<u16, Genlmsghdr<Command, Attr>>(false) {
+        match msg.map_err(|e| err(&e))?.nl_payload {
+            NlPayload::Payload(payload) => stations.extend(parse_station(&payload)),
+            NlPayload::Err(e) => return Err(err(&e)),
+            _ => {}
+        }
+    }
+    Ok(stations)
+}
+
+/// The access point `device` is connected to; None when it isn't, or
+/// nl80211 isn't available.
+pub fn link_station(device: &str) -> Option<Station> {
+    stations(device).ok()?.into_iter().next()
+}
+
+fn ifindex(device: &str) -> Result<u32, String> {
+    let path = format!("/sys/class/net/{}/ifindex", device);
+    std::fs::read_to_string(&path)
+        .ok()
+        .and_then(|text| text.trim().parse().ok())
+        .ok_or_else(|| format!("No network interface called {}.", device))
+}
+
+fn parse_station(payload: &Genlmsghdr<Command, Attr>) -> Option<Station> {
+    let attrs = payload.get_attr_handle();
+    let mac = attrs.get_attribute(Attr::Mac)?.payload().as_ref();
+    let mac = mac.iter().map(|b| format!("{:02x}", b)).collect::<Vec<_>>().join(":");
+    let info = attrs.get_attribute(Attr::StaInfo)?.get_attr_handle::<StaInfo>().ok()?;
+    let signal_dbm = info.get_attr_payload_as::<i8>(StaInfo::Signal).ok().map(i32::from);
+
+    let rate = info.get_attribute(StaInfo::TxBitrate).and_then(|a| a.get_attr_handle::<RateInfo>().ok());
+    let (tx_bitrate, tx_rate) = match rate {
+        Some(rate) => {
+            let u8_of = |t| rate.get_attr_payload_as::<u8>(t).ok();
+            let has = |t| rate.get_attribute(t).is_some();
+            // Both are in units of 100 kbit/s; the 16-bit one saturates
+            // above 6.5 Gbit/s
+            let bitrate = rate
+                .get_attr_payload_as::<u32>(RateInfo::Bitrate32)
+                .ok()
+                .or_else(|| rate.get_attr_payload_as::<u16>(RateInfo::Bitrate).ok().map(u32::from))
+                .map(|hundreds| hundreds * 100);
+            let fields = RateFields {
+                ht_mcs: u8_of(RateInfo::Mcs),
+                vht: u8_of(RateInfo::VhtMcs).map(|mcs| (mcs, u8_of(RateInfo::VhtNss))),
+                he: u8_of(RateInfo::HeMcs).map(|mcs| (mcs, u8_of(RateInfo::HeNss))),
+                eht: u8_of(RateInfo::EhtMcs).map(|mcs| (mcs, u8_of(RateInfo::EhtNss))),
+                width_mhz: if has(RateInfo::Width320) {
+                    320
+                } else if has(RateInfo::Width160) || has(RateInfo::Width80p80) {
+                    160
+                } else if has(RateInfo::Width80) {
+                    80
+                } else if has(RateInfo::Width40) {
+                    40
+                } else {
+                    20
+                },
+            };
+            (bitrate, Some(fields.link_rate()))
+        }
+        None => (None, None),
+    };
+    Some(Station { mac, signal_dbm, tx_bitrate, tx_rate })
+}
+
+/// The parts of a `NL80211_ATTR_STA_INFO`'s rate that say which 802.11
+/// generation it is. MCS and stream counts come as (mcs, nss).
+#[derive(Debug, Default)]
+struct RateFields {
+    ht_mcs: Option<u8>,
+    vht: Option<(u8, Option<u8>)>,
+    he: Option<(u8, Option<u8>)>,
+    eht: Option<(u8, Option<u8>)>,
+    width_mhz: u32,
+}
+
+impl RateFields {
+    fn link_rate(&self) -> LinkRate {
+        let (generation, mcs, nss) = if let Some((mcs, nss)) = self.eht {
+            (Generation::Be, Some(mcs), nss)
+        } else if let Some((mcs, nss)) = self.he {
+            (Generation::Ax, Some(mcs), nss)
+        } else if let Some((mcs, nss)) = self.vht {
+            (Generation::Ac, Some(mcs), nss)
+        } else if let Some(mcs) = self.ht_mcs {
+            // HT MCS 0-7 is one stream, 8-15 two, and so on
+            (Generation::N, Some(mcs % 8), Some(mcs / 8 + 1))
+        } else {
+            (Generation::Legacy, None, None)
+        };
+        LinkRate { generation, width_mhz: self.width_mhz, mcs, nss }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use neli::types::Buffer;
+
+    fn station_reply(rate: &[(RateInfo, Option<u8>)]) -> Genlmsghdr<Command, Attr> {
+        let mut tx = Nlattr::new(true, false, StaInfo::TxBitrate, Buffer::new()).unwrap();
+        tx.add_nested_attribute(&Nlattr::new(false, false, RateInfo::Bitrate32, 8667u32).unwrap()).unwrap();
+        for (t, value) in rate {
+            let attr = match value {
+                Some(v) => Nlattr::new(false, false, *t, *v).unwrap(),
+                None => Nlattr::new(false, false, *t, Buffer::new()).unwrap(),
+            };
+            tx.add_nested_attribute(&attr).unwrap();
+        }
+        let mut info = Nlattr::new(true, false, Attr::StaInfo, Buffer::new()).unwrap();
+        info.add_nested_attribute(&Nlattr::new(false, false, StaInfo::Signal, -58i8).unwrap()).unwrap();
+        info.add_nested_attribute(&tx).unwrap();
+
+        let mut attrs = GenlBuffer::new();
+        let mac = Buffer::from(vec![0xaa, 0xbb, 0xcc, 0x01, 0x02, 0x03]);
+        attrs.push(Nlattr::new(false, false, Attr::Mac, mac).unwrap());
+        attrs.push(info);
+        Genlmsghdr::new(Command::GetStation, 1, attrs)
+    }
+
+    #[test]
+    fn test_parse_station() {
+        let reply = station_reply(&[(RateInfo::VhtMcs, Some(9)), (RateInfo::VhtNss, Some(2)), (RateInfo::Width80, None)]);
+        let station = parse_station(&reply).unwrap();
+        assert_eq!(station.mac, "aa:bb:cc:01:02:03");
+        assert_eq!(station.signal_dbm, Some(-58));
+        assert_eq!(station.tx_bitrate, Some(866_700));
+        let ac = station.tx_rate.unwrap();
+        assert_eq!(ac, LinkRate { generation: Generation::Ac, width_mhz: 80, mcs: Some(9), nss: Some(2) });
+        assert_eq!(ac.summary(), "80 MHz, MCS 9, 2 streams");
+
+        let reply = station_reply(&[(RateInfo::HeMcs, Some(11)), (RateInfo::HeNss, Some(1)), (RateInfo::Width160, None)]);
+        let ax = parse_station(&reply).unwrap().tx_rate.unwrap();
+        assert_eq!((ax.generation, ax.width_mhz, ax.mcs, ax.nss), (Generation::Ax, 160, Some(11), Some(1)));
+        let n = parse_station(&station_reply(&[(RateInfo::Mcs, Some(15))])).unwrap().tx_rate.unwrap();
+        assert_eq!((n.generation, n.width_mhz, n.mcs, n.nss), (Generation::N, 20, Some(7), Some(2)));
+        assert_eq!(parse_station(&station_reply(&[])).unwrap().tx_rate.unwrap().summary(), "20 MHz");
+    }
+}
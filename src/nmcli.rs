@@ -1,239 +1,902 @@
-use std::collections::HashMap;
-use std::process::Command;
+use std::collections::HashSet;
+use std::ffi::OsStr;
+use std::os::unix::ffi::OsStrExt;
+use std::process::{Command, Output};
 
-#[derive(Debug, Clone)]
-pub struct Network {
-    pub ssid: String,
-    pub signal: u8,
-    pub security: String,
-    pub in_use: bool,
+use crate::backend::{
+    call_mode_message, device_state_stage, route_metric, shell_quote, yes_no, Change, decode_ssid, dns_message, encode_ssid, format_dns, format_routes, group_networks, is_virtual_interface, parse_dns,
+    ipv4_addresses, ipv4_gateway, ipv4_message, ipv4_method, parse_routes, profile_options_message, route_metric_message,
+    routes_message, Backend, NO_WIFI_ADAPTER, ClonedMac, ConnectStage, ConnectionStatus, DnsConfig, EapConfig, HotspotBand, HotspotConfig,
+    Ipv4Address, Ipv4Config, Metered, Network, ProfileOptions, RouteConfig, SavedNetwork,
+    WifiSecret, SECRETS_DENIED,
+    POWERSAVE_DEFAULT, POWERSAVE_DISABLE, QUICK_HOTSPOT_NAME,
+};
+use crate::cancel;
+use crate::logging;
+use crate::nl80211;
+
+/// Backend that drives NetworkManager through the `nmcli` command.
+pub struct NmcliBackend;
+
+impl Backend for NmcliBackend {
+    fn name(&self) -> &'static str {
+        "nmcli"
+    }
+
+    fn supports_bgscan(&self) -> bool {
+        true
+    }
+
+    fn detect_wifi_devices(&self) -> Result<Vec<String>, String> {
+        detect_wifi_devices()
+    }
+
+    fn scan_networks(&self, device: &str) -> Result<Vec<Network>, String> {
+        scan_networks(device)
+    }
+
+    fn get_status(&self, device: &str) -> ConnectionStatus {
+        get_status(device)
+    }
+
+    fn connect_stage(&self, device: &str) -> Option<ConnectStage> {
+        connect_stage(device)
+    }
+
+    fn current_signal(&self, device: &str) -> Option<u8> {
+        current_signal(device)
+    }
+
+    fn saved_networks(&self) -> Result<Vec<SavedNetwork>, String> {
+        saved_networks()
+    }
+
+    fn connect(&self, _device: &str, ssid: &str, password: Option<&str>) -> Result<String, String> {
+        connect(ssid, password)
+    }
+
+    fn connect_enterprise(&self, device: &str, ssid: &str, config: &EapConfig) -> Result<String, String> {
+        connect_enterprise(device, ssid, config)
+    }
+
+    fn disconnect(&self, device: &str) -> Result<String, String> {
+        disconnect(device)
+    }
+
+    fn cancel_activation(&self, device: &str) -> Result<(), String> {
+        // Not through `cancel`: this runs while the worker's task is cancelled
+        let output = logging::output(Command::new("nmcli").args(["device", "disconnect", device]))
+            .map_err(|e| friendly_error(&e.to_string()))?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            Err(friendly_error(stderr.trim()))
+        }
+    }
+
+    fn forget(&self, name: &str) -> Result<String, String> {
+        forget(name)
+    }
+
+    fn rename_profile(&self, name: &str, new_name: &str) -> Result<String, String> {
+        rename_profile(name, new_name)
+    }
+
+    fn save_hotspot(&self, config: &HotspotConfig) -> Result<String, String> {
+        save_hotspot(config)
+    }
+
+    fn start_hotspot(&self, name: &str) -> Result<String, String> {
+        start_hotspot(name)
+    }
+
+    fn create_hotspot(&self, device: &str, ssid: &str, password: &str) -> Result<String, String> {
+        create_hotspot(device, ssid, password)
+    }
+
+    fn stop_hotspot(&self, name: &str) -> Result<String, String> {
+        stop_hotspot(name)
+    }
+
+    fn wifi_secret(&self, name: &str) -> Result<WifiSecret, String> {
+        wifi_secret(name)
+    }
+
+    fn connect_bssid(&self, device: &str, name: &str, bssid: &str) -> Result<String, String> {
+        connect_bssid(device, name, bssid)
+    }
+
+    fn set_call_mode(&self, device: &str, name: &str, on: bool, band: Option<&str>) -> Result<String, String> {
+        set_call_mode(device, name, on, band)
+    }
+
+    fn set_metered(&self, device: &str, name: &str, metered: bool) -> Result<String, String> {
+        set_metered(device, name, metered)
+    }
+
+    fn profile_options(&self, name: &str) -> Result<ProfileOptions, String> {
+        profile_options(name)
+    }
+
+    fn set_profile_options(&self, name: &str, options: &ProfileOptions) -> Result<String, String> {
+        set_profile_options(name, options)
+    }
+
+    fn dns(&self, name: &str) -> Result<DnsConfig, String> {
+        dns(name)
+    }
+
+    fn set_dns(&self, device: &str, name: &str, config: &DnsConfig) -> Result<String, String> {
+        set_dns(device, name, config)
+    }
+
+    fn routes(&self, name: &str) -> Result<RouteConfig, String> {
+        routes(name)
+    }
+
+    fn set_routes(&self, device: &str, name: &str, config: &RouteConfig) -> Result<String, String> {
+        set_routes(device, name, config)
+    }
+
+    fn ipv4(&self, name: &str) -> Result<Ipv4Config, String> {
+        ipv4(name)
+    }
+
+    fn set_ipv4(&self, device: &str, name: &str, config: &Ipv4Config) -> Result<String, String> {
+        set_ipv4(device, name, config)
+    }
+
+    fn set_route_metric(&self, device: &str, name: &str, metric: Option<u32>) -> Result<String, String> {
+        set_route_metric(device, name, metric)
+    }
+
+    fn change_summary(&self, device: &str, change: &Change) -> String {
+        change_summary(device, change)
+    }
 }
 
-#[derive(Debug, Clone)]
-pub struct SavedNetwork {
-    pub name: String,
-    pub active: bool,
+/// The activation stage of `device` from `nmcli device show`.
+fn connect_stage(device: &str) -> Option<ConnectStage> {
+    let output = logging::output(Command::new("nmcli").args(["-g", "GENERAL.STATE", "device", "show", device]))
+        .ok()?;
+    parse_device_state(&String::from_utf8_lossy(&output.stdout))
 }
 
-#[derive(Debug, Clone)]
-pub struct ConnectionStatus {
-    pub ssid: Option<String>,
-    pub signal: Option<u8>,
-    pub ip: Option<String>,
-    pub speed: Option<String>,
+/// Parse e.g. `70 (connecting (getting IP configuration))`.
+fn parse_device_state(text: &str) -> Option<ConnectStage> {
+    device_state_stage(text.split_whitespace().next()?.parse().ok()?)
 }
 
-/// Detect the WiFi device name (e.g. wlp3s0, wlan0).
-pub fn detect_wifi_device() -> Result<String, String> {
-    let output = Command::new("nmcli")
-        .args(["-t", "-f", "DEVICE,TYPE", "device"])
-        .output()
+/// Detect the WiFi device names (e.g. wlp3s0, wlan0).
+fn detect_wifi_devices() -> Result<Vec<String>, String> {
+    let output = logging::output(Command::new("nmcli").args(["-t", "-f", "DEVICE,TYPE", "device"]))
         .map_err(|e| friendly_error(&e.to_string()))?;
 
     let stdout = String::from_utf8_lossy(&output.stdout);
-    for line in stdout.lines() {
-        let fields = parse_terse_line(line);
-        if fields.len() >= 2 && fields[1] == "wifi" {
-            return Ok(fields[0].clone());
-        }
+    let devices: Vec<String> = stdout
+        .lines()
+        .map(parse_terse_line)
+        .filter(|fields| fields.len() >= 2 && fields[1] == "wifi")
+        .map(|fields| fields[0].clone())
+        .filter(|device| !is_virtual_interface(device))
+        .collect();
+    if devices.is_empty() {
+        return Err(NO_WIFI_ADAPTER.to_string());
     }
-    Err("No WiFi adapter found. Make sure your WiFi hardware is enabled.".to_string())
+    Ok(devices)
 }
 
-/// Scan for available networks. Returns deduplicated list sorted by signal strength.
-pub fn scan_networks(device: &str) -> Result<Vec<Network>, String> {
-    // Trigger a rescan first (best-effort, ignore errors)
-    let _ = Command::new("nmcli")
-        .args(["device", "wifi", "rescan", "ifname", device])
-        .output();
+/// Scan for available networks, one entry per SSID.
+fn scan_networks(device: &str) -> Result<Vec<Network>, String> {
+    // Trigger a rescan first (best-effort, ignore errors other than cancelling)
+    let rescan = cancel::output(Command::new("nmcli").args(["device", "wifi", "rescan", "ifname", device]));
+    if let Err(e) = rescan {
+        if e.kind() == std::io::ErrorKind::Interrupted {
+            return Err(e.to_string());
+        }
+    }
 
-    let output = Command::new("nmcli")
-        .args([
-            "-t", "-f", "IN-USE,SSID,SIGNAL,SECURITY", "device", "wifi", "list", "ifname", device,
-        ])
-        .output()
-        .map_err(|e| friendly_error(&e.to_string()))?;
+    let output = cancel::output(Command::new("nmcli").args([
+        "-t", "-f", "IN-USE,SSID,SIGNAL,SECURITY,BSSID,CHAN,FREQ,RATE,MODE,SSID-HEX",
+        "device", "wifi", "list", "ifname", device,
+    ]))
+    .map_err(|e| friendly_error(&e.to_string()))?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
         return Err(friendly_error(stderr.trim()));
     }
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let mut best: HashMap<String, Network> = HashMap::new();
+    Ok(parse_scan(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Networks from `-f IN-USE,SSID,SIGNAL,SECURITY,BSSID,CHAN,FREQ,RATE,MODE,SSID-HEX
+/// device wifi list` terse output, one entry per SSID.
+pub fn parse_scan(stdout: &str) -> Vec<Network> {
+    let mut networks = Vec::new();
 
     for line in stdout.lines() {
         let fields = parse_terse_line(line);
-        if fields.len() < 4 {
-            continue;
-        }
-
-        let ssid = fields[1].clone();
-        if ssid.is_empty() {
+        if fields.len() < 9 {
             continue;
         }
 
-        let signal: u8 = fields[2].parse().unwrap_or(0);
-        let security = fields[3].clone();
-        let in_use = fields[0].trim() == "*";
-
-        let net = Network {
-            ssid: ssid.clone(),
-            signal,
-            security,
-            in_use,
-        };
-
-        // Keep the entry with highest signal, but always prefer the in_use one
-        if let Some(existing) = best.get(&ssid) {
-            if in_use || (!existing.in_use && signal > existing.signal) {
-                best.insert(ssid, net);
-            }
-        } else {
-            best.insert(ssid, net);
-        }
+        // SSID mangles bytes that aren't UTF-8; SSID-HEX has them all
+        let ssid_bytes = fields
+            .get(9)
+            .and_then(|hex| parse_hex(hex))
+            .unwrap_or_else(|| fields[1].clone().into_bytes());
+        networks.push(Network {
+            ssid: decode_ssid(&ssid_bytes),
+            ssid_bytes,
+            signal: fields[2].parse().unwrap_or(0),
+            security: fields[3].clone(),
+            in_use: fields[0].trim() == "*",
+            bssid: fields[4].clone(),
+            channel: fields[5].parse().ok(),
+            frequency: leading_number(&fields[6]),
+            rate: leading_number(&fields[7]),
+            mode: fields[8].clone(),
+            access_points: Vec::new(),
+        });
     }
 
-    let mut networks: Vec<Network> = best.into_values().collect();
-    // Sort: in_use first, then by signal descending
-    networks.sort_by(|a, b| {
-        b.in_use
-            .cmp(&a.in_use)
-            .then(b.signal.cmp(&a.signal))
-    });
-
-    Ok(networks)
+    group_networks(networks)
 }
 
 /// Get the current connection status.
-pub fn get_status(device: &str) -> ConnectionStatus {
+fn get_status(device: &str) -> ConnectionStatus {
     let mut status = ConnectionStatus {
         ssid: None,
         signal: None,
         ip: None,
-        speed: None,
+        bitrate: None,
+        dbm: None,
+        link: None,
     };
 
     // Get SSID + signal from the in-use wifi entry (gives actual broadcast SSID,
     // not the NM profile name which GENERAL.CONNECTION returns)
-    if let Ok(output) = Command::new("nmcli")
-        .args([
-            "-t", "-f", "IN-USE,SSID,SIGNAL",
-            "device", "wifi", "list", "ifname", device,
-        ])
-        .output()
+    if let Ok(output) = logging::output(Command::new("nmcli").args([
+        "-t", "-f", "IN-USE,SSID,SIGNAL,SSID-HEX",
+        "device", "wifi", "list", "ifname", device,
+    ]))
     {
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        for line in stdout.lines() {
-            let fields = parse_terse_line(line);
-            if fields.len() >= 3 && fields[0].trim() == "*" && !fields[1].is_empty() {
-                status.ssid = Some(fields[1].clone());
-                status.signal = fields[2].parse().ok();
-                break;
-            }
+        if let Some((ssid, signal)) = parse_in_use(&String::from_utf8_lossy(&output.stdout)) {
+            status.ssid = Some(ssid);
+            status.signal = signal;
         }
     }
 
     // If connected, get IP and speed
     if status.ssid.is_some() {
         // Get IP address
-        if let Ok(output) = Command::new("nmcli")
-            .args(["-t", "-f", "IP4.ADDRESS", "device", "show", device])
-            .output()
+        if let Ok(output) = logging::output(Command::new("nmcli").args([
+            "-t", "-f", "IP4.ADDRESS", "device", "show", device,
+        ]))
         {
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            for line in stdout.lines() {
-                let fields = parse_terse_line(line);
-                if fields.len() >= 2 && fields[0].starts_with("IP4.ADDRESS") {
-                    let ip = fields[1].split('/').next().unwrap_or(&fields[1]);
-                    status.ip = Some(ip.to_string());
-                    break;
-                }
-            }
+            status.ip = parse_ip4_address(&String::from_utf8_lossy(&output.stdout));
         }
 
-        // Get link speed via iw
-        if let Ok(output) = Command::new("iw")
-            .args(["dev", device, "link"])
-            .output()
-        {
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            for line in stdout.lines() {
-                let trimmed = line.trim();
-                if trimmed.starts_with("tx bitrate:") {
-                    let rate = trimmed
-                        .trim_start_matches("tx bitrate:")
-                        .trim()
-                        .split_whitespace()
-                        .take(2)
-                        .collect::<Vec<&str>>()
-                        .join(" ");
-                    status.speed = Some(rate);
-                    break;
-                }
-            }
+        // Get link speed and signal in dBm from the kernel
+        if let Some(station) = nl80211::link_station(device) {
+            status.bitrate = station.tx_bitrate;
+            status.dbm = station.signal_dbm;
+            status.link = station.tx_rate;
         }
     }
 
     status
 }
 
+/// Signal of the in-use entry from NetworkManager's cached scan results.
+fn current_signal(device: &str) -> Option<u8> {
+    let output = logging::output(Command::new("nmcli").args([
+        "-t", "-f", "IN-USE,SSID,SIGNAL,SSID-HEX",
+        "device", "wifi", "list", "ifname", device, "--rescan", "no",
+    ]))
+    .ok()?;
+    parse_in_use(&String::from_utf8_lossy(&output.stdout))?.1
+}
+
+/// SSID and signal of the in-use entry of `-f IN-USE,SSID,SIGNAL,SSID-HEX
+/// device wifi list` terse output.
+pub fn parse_in_use(stdout: &str) -> Option<(String, Option<u8>)> {
+    stdout.lines().map(parse_terse_line).find_map(|fields| {
+        if fields.len() >= 3 && fields[0].trim() == "*" && !fields[1].is_empty() {
+            let ssid = match fields.get(3).and_then(|hex| parse_hex(hex)) {
+                Some(bytes) => decode_ssid(&bytes),
+                None => fields[1].clone(),
+            };
+            Some((ssid, fields[2].parse().ok()))
+        } else {
+            None
+        }
+    })
+}
+
+/// Bytes of an `SSID-HEX` field, e.g. "486F6D65".
+fn parse_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.is_empty() || !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len()).step_by(2).map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok()).collect()
+}
+
+/// First address, without its prefix length, of `-f IP4.ADDRESS device show`.
+pub fn parse_ip4_address(stdout: &str) -> Option<String> {
+    let (_, address) = parse_terse_properties(stdout).into_iter().find(|(key, _)| key == "IP4.ADDRESS")?;
+    Some(address.split('/').next().unwrap_or(&address).to_string())
+}
+
 /// List saved (known) WiFi connections.
-pub fn saved_networks() -> Result<Vec<SavedNetwork>, String> {
-    let output = Command::new("nmcli")
-        .args([
-            "-t",
-            "-f",
-            "NAME,TYPE,ACTIVE",
-            "connection",
-            "show",
-        ])
-        .output()
+fn saved_networks() -> Result<Vec<SavedNetwork>, String> {
+    let output = logging::output(Command::new("nmcli").args([
+        "-t",
+        "-f",
+        "NAME,UUID,TYPE,ACTIVE",
+        "connection",
+        "show",
+    ]))
+    .map_err(|e| friendly_error(&e.to_string()))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(friendly_error(stderr.trim()));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let wifi: Vec<Vec<String>> = stdout
+        .lines()
+        .map(parse_terse_line)
+        .filter(|fields| fields.len() >= 4 && fields[2].contains("wireless"))
+        .collect();
+    let uuids: Vec<&str> = wifi.iter().map(|fields| fields[1].as_str()).collect();
+    let access_points = access_point_uuids(&uuids);
+
+    Ok(wifi
+        .iter()
+        .map(|fields| SavedNetwork {
+            name: fields[0].clone(),
+            active: fields[3] == "yes",
+            hotspot: access_points.contains(&fields[1]),
+        })
+        .collect())
+}
+
+/// Which of the connections `uuids` are in AP (hotspot) mode, looked up in
+/// one `nmcli connection show` for all of them.
+fn access_point_uuids(uuids: &[&str]) -> HashSet<String> {
+    if uuids.is_empty() {
+        return HashSet::new();
+    }
+    let mut command = Command::new("nmcli");
+    command.args(["-t", "-f", "connection.uuid,802-11-wireless.mode", "connection", "show"]).args(uuids);
+    match logging::output(&mut command) {
+        Ok(output) if output.status.success() => parse_access_points(&String::from_utf8_lossy(&output.stdout)),
+        _ => HashSet::new(),
+    }
+}
+
+/// UUIDs whose `802-11-wireless.mode` is "ap", from the `connection.uuid`
+/// and mode lines `nmcli -t connection show` prints for each connection.
+fn parse_access_points(stdout: &str) -> HashSet<String> {
+    let mut uuid = None;
+    let mut access_points = HashSet::new();
+    for (key, value) in parse_terse_properties(stdout) {
+        match key.as_str() {
+            "connection.uuid" => uuid = Some(value),
+            "802-11-wireless.mode" if value == "ap" => access_points.extend(uuid.take()),
+            _ => {}
+        }
+    }
+    access_points
+}
+
+/// Create a saved hotspot profile. The profile is not activated and does not
+/// autoconnect; start it like any other saved connection.
+fn save_hotspot(config: &HotspotConfig) -> Result<String, String> {
+    add_hotspot(config)?;
+    Ok(format!("Saved hotspot '{}'.", config.name))
+}
+
+/// Add a hotspot profile and return its UUID. The password is stored
+/// separately through stdin, so it never shows up in `ps`.
+fn add_hotspot(config: &HotspotConfig) -> Result<String, String> {
+    let mut args = vec![
+        "connection", "add",
+        "type", "wifi",
+        "ifname", &config.interface,
+        "con-name", &config.name,
+        "autoconnect", "no",
+        "ssid", &config.ssid,
+        "802-11-wireless.mode", "ap",
+        "ipv4.method", "shared",
+    ];
+    if let Some(band) = band_value(config.band) {
+        args.extend(["802-11-wireless.band", band]);
+    }
+    if !config.password.is_empty() {
+        args.extend(["wifi-sec.key-mgmt", "wpa-psk"]);
+    }
+
+    let output = logging::output(Command::new("nmcli").args(&args))
+        .map_err(|e| friendly_error(&e.to_string()))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(friendly_error(stderr.trim()));
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let uuid = parse_added_uuid(&stdout).ok_or_else(|| format!("Unexpected nmcli output: {}", stdout.trim()))?;
+
+    if !config.password.is_empty() {
+        if let Err(e) = set_secret(&uuid, "802-11-wireless-security.psk", &config.password) {
+            let _ = logging::output(Command::new("nmcli").args(["connection", "delete", "uuid", &uuid]));
+            return Err(e);
+        }
+    }
+    Ok(uuid)
+}
+
+/// UUID in nmcli's "Connection 'Cafe' (8e9f…) successfully added."
+fn parse_added_uuid(stdout: &str) -> Option<String> {
+    let (_, rest) = stdout.trim_end().rsplit_once('(')?;
+    let (uuid, _) = rest.split_once(')')?;
+    Some(uuid.to_string())
+}
+
+/// Store one secret of a saved profile through nmcli's editor, which reads
+/// it from stdin instead of the command line. The editor takes the secret as
+/// one trimmed line of its script, so one that wouldn't survive that is
+/// refused.
+fn set_secret(uuid: &str, setting: &str, secret: &str) -> Result<(), String> {
+    if secret.chars().any(char::is_control) || secret.trim() != secret {
+        return Err("The password can't contain line breaks or start or end with a space.".to_string());
+    }
+    let input = format!("set {} {}\nsave\nquit\n", setting, secret);
+    let output = nmcli_with_input(&["connection", "edit", "uuid", uuid], &input)
+        .map_err(|e| friendly_error(&e.to_string()))?;
+    // The editor reports a bad value or a failed save but still exits with 0
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    match stdout.lines().chain(stderr.lines()).map(str::trim).find(|line| line.starts_with("Error")) {
+        Some(error) => Err(friendly_error(error)),
+        None if output.status.success() => Ok(()),
+        None => Err(friendly_error(stderr.trim())),
+    }
+}
+
+/// Value for the `802-11-wireless.band` property, if the band is pinned.
+fn band_value(band: HotspotBand) -> Option<&'static str> {
+    match band {
+        HotspotBand::Auto => None,
+        HotspotBand::Ghz2 => Some("bg"),
+        HotspotBand::Ghz5 => Some("a"),
+    }
+}
+
+/// Start a saved hotspot profile.
+fn start_hotspot(name: &str) -> Result<String, String> {
+    let output = logging::output(Command::new("nmcli").args(["connection", "up", name]))
+        .map_err(|e| friendly_error(&e.to_string()))?;
+
+    if output.status.success() {
+        Ok(format!("Started hotspot '{}'.", name))
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        Err(friendly_error(stderr.trim()))
+    }
+}
+
+/// Start an access point on `device`: replace the profile named "Hotspot",
+/// as `nmcli device wifi hotspot` would, by adding a new one with
+/// `add_hotspot` and bringing it up by UUID. Unlike that command, this keeps
+/// the password off the command line.
+fn create_hotspot(device: &str, ssid: &str, password: &str) -> Result<String, String> {
+    // Replace the previous quick hotspot, like nmcli does
+    let _ = logging::output(Command::new("nmcli").args(["connection", "delete", "id", QUICK_HOTSPOT_NAME]));
+
+    let config = HotspotConfig {
+        name: QUICK_HOTSPOT_NAME.to_string(),
+        ssid: ssid.to_string(),
+        password: password.to_string(),
+        band: HotspotBand::Auto,
+        interface: device.to_string(),
+    };
+    let uuid = add_hotspot(&config)?;
+    let output = logging::output(Command::new("nmcli").args(["connection", "up", "uuid", &uuid, "ifname", device]))
         .map_err(|e| friendly_error(&e.to_string()))?;
 
+    if output.status.success() {
+        Ok(format!("Started hotspot '{}'.", ssid))
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        Err(friendly_error(stderr.trim()))
+    }
+}
+
+fn stop_hotspot(name: &str) -> Result<String, String> {
+    let output = logging::output(Command::new("nmcli").args(["connection", "down", "id", name]))
+        .map_err(|e| friendly_error(&e.to_string()))?;
+
+    if output.status.success() {
+        Ok(format!("Stopped hotspot '{}'.", name))
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        Err(friendly_error(stderr.trim()))
+    }
+}
+
+/// Read a profile's SSID and password. `-s` reveals secrets, which NetworkManager
+/// only allows for the user's own profiles (or with polkit permission).
+fn wifi_secret(name: &str) -> Result<WifiSecret, String> {
+    let output = logging::output(Command::new("nmcli").args([
+        "-s", "-t",
+        "-f", "802-11-wireless.ssid,802-11-wireless-security.key-mgmt,802-11-wireless-security.psk",
+        "connection", "show", "id", name,
+    ]))
+    .map_err(|e| friendly_error(&e.to_string()))?;
+
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
+        if stderr.contains("not authorized") || stderr.contains("Insufficient privileges") {
+            return Err(SECRETS_DENIED.to_string());
+        }
         return Err(friendly_error(stderr.trim()));
     }
 
+    // One `property:value` line per field
     let stdout = String::from_utf8_lossy(&output.stdout);
-    let mut networks = Vec::new();
+    let mut secret = WifiSecret {
+        ssid: String::new(),
+        key_mgmt: String::new(),
+        psk: String::new(),
+    };
+    for (key, value) in parse_terse_properties(&stdout) {
+        match key.as_str() {
+            "802-11-wireless.ssid" => secret.ssid = value,
+            "802-11-wireless-security.key-mgmt" => secret.key_mgmt = value,
+            "802-11-wireless-security.psk" => secret.psk = value,
+            _ => {}
+        }
+    }
+    if !secret.key_mgmt.is_empty() && secret.psk.is_empty() {
+        return Err("The password for this network isn't stored or can't be read.".to_string());
+    }
+    Ok(secret)
+}
 
-    for line in stdout.lines() {
-        let fields = parse_terse_line(line);
-        if fields.len() >= 3 && fields[1].contains("wireless") {
-            networks.push(SavedNetwork {
-                name: fields[0].clone(),
-                active: fields[2] == "yes",
-            });
+/// Pin a saved profile to `bssid`, then bring it up again so the pin applies.
+fn connect_bssid(device: &str, name: &str, bssid: &str) -> Result<String, String> {
+    for args in [
+        vec!["connection", "modify", "id", name, "802-11-wireless.bssid", bssid],
+        vec!["connection", "up", "id", name, "ifname", device],
+    ] {
+        let output = logging::output(Command::new("nmcli").args(&args))
+            .map_err(|e| friendly_error(&e.to_string()))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(friendly_error(stderr.trim()));
+        }
+    }
+    Ok(format!("Connected to {} via {}", name, bssid))
+}
+
+fn set_call_mode(device: &str, name: &str, on: bool, band: Option<&str>) -> Result<String, String> {
+    let powersave = if on { POWERSAVE_DISABLE } else { POWERSAVE_DEFAULT }.to_string();
+    let pinned = if on { band.unwrap_or("") } else { "" };
+    for args in [
+        vec![
+            "connection", "modify", "id", name,
+            "802-11-wireless.powersave", &powersave,
+            "802-11-wireless.band", pinned,
+        ],
+        vec!["connection", "up", "id", name, "ifname", device],
+    ] {
+        let output = logging::output(Command::new("nmcli").args(&args))
+            .map_err(|e| friendly_error(&e.to_string()))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(friendly_error(stderr.trim()));
+        }
+    }
+    Ok(call_mode_message(name, on, band))
+}
+
+fn set_metered(device: &str, name: &str, metered: bool) -> Result<String, String> {
+    let value = if metered { "yes" } else { "unknown" };
+    let output = logging::output(Command::new("nmcli").args([
+        "connection", "modify", "id", name, "connection.metered", value,
+    ]))
+    .map_err(|e| friendly_error(&e.to_string()))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(friendly_error(stderr.trim()));
+    }
+
+    // Best-effort: push the change to the running connection without reconnecting
+    let _ = logging::output(Command::new("nmcli").args(["device", "reapply", device]));
+
+    Ok(if metered {
+        format!("Marked '{}' as metered.", name)
+    } else {
+        format!("'{}' is no longer marked as metered.", name)
+    })
+}
+
+fn profile_options(name: &str) -> Result<ProfileOptions, String> {
+    let output = logging::output(Command::new("nmcli").args([
+        "-t", "-f",
+        "connection.autoconnect,connection.autoconnect-priority,connection.metered,802-11-wireless.cloned-mac-address",
+        "connection", "show", "id", name,
+    ]))
+    .map_err(|e| friendly_error(&e.to_string()))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(friendly_error(stderr.trim()));
+    }
+
+    // One `property:value` line per field
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut options = ProfileOptions {
+        autoconnect: true,
+        priority: 0,
+        metered: Metered::Auto,
+        mac: ClonedMac::Default,
+    };
+    for (key, value) in parse_terse_properties(&stdout) {
+        match key.as_str() {
+            "connection.autoconnect" => options.autoconnect = value == "yes",
+            "connection.autoconnect-priority" => options.priority = value.parse().unwrap_or(0),
+            "connection.metered" => options.metered = Metered::from_nmcli(&value),
+            "802-11-wireless.cloned-mac-address" => options.mac = ClonedMac::from_nmcli(&value),
+            _ => {}
+        }
+    }
+    Ok(options)
+}
+
+fn set_profile_options(name: &str, options: &ProfileOptions) -> Result<String, String> {
+    let autoconnect = if options.autoconnect { "yes" } else { "no" };
+    let priority = options.priority.to_string();
+    let output = logging::output(Command::new("nmcli").args([
+        "connection", "modify", "id", name,
+        "connection.autoconnect", autoconnect,
+        "connection.autoconnect-priority", &priority,
+        "connection.metered", options.metered.nmcli_value(),
+        "802-11-wireless.cloned-mac-address", options.mac.nmcli_value(),
+    ]))
+    .map_err(|e| friendly_error(&e.to_string()))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(friendly_error(stderr.trim()));
+    }
+    Ok(profile_options_message(name, options))
+}
+
+fn dns(name: &str) -> Result<DnsConfig, String> {
+    let output = logging::output(Command::new("nmcli").args([
+        "-t", "-f", "ipv4.ignore-auto-dns,ipv4.dns", "connection", "show", "id", name,
+    ]))
+    .map_err(|e| friendly_error(&e.to_string()))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(friendly_error(stderr.trim()));
+    }
+
+    // One `property:value` line per field
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut config = DnsConfig::default();
+    for (key, value) in parse_terse_properties(&stdout) {
+        match key.as_str() {
+            "ipv4.ignore-auto-dns" => config.ignore_auto = value == "yes",
+            "ipv4.dns" => config.servers = parse_dns(&value)?,
+            _ => {}
+        }
+    }
+    Ok(config)
+}
+
+fn set_dns(device: &str, name: &str, config: &DnsConfig) -> Result<String, String> {
+    let ignore_auto = if config.ignore_auto { "yes" } else { "no" };
+    let servers = format_dns(&config.servers);
+    let output = logging::output(Command::new("nmcli").args([
+        "connection", "modify", "id", name,
+        "ipv4.ignore-auto-dns", ignore_auto,
+        "ipv4.dns", &servers,
+    ]))
+    .map_err(|e| friendly_error(&e.to_string()))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(friendly_error(stderr.trim()));
+    }
+
+    // Best-effort: push the change to the running connection without reconnecting
+    let _ = logging::output(Command::new("nmcli").args(["device", "reapply", device]));
+
+    Ok(dns_message(name, config))
+}
+
+fn routes(name: &str) -> Result<RouteConfig, String> {
+    let output = logging::output(Command::new("nmcli").args([
+        "-t", "-f", "ipv4.never-default,ipv4.route-metric,ipv4.routes",
+        "connection", "show", "id", name,
+    ]))
+    .map_err(|e| friendly_error(&e.to_string()))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(friendly_error(stderr.trim()));
+    }
+
+    // One `property:value` line per field
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut config = RouteConfig::default();
+    for (key, value) in parse_terse_properties(&stdout) {
+        match key.as_str() {
+            "ipv4.never-default" => config.never_default = value == "yes",
+            // -1 when unset
+            "ipv4.route-metric" => config.metric = value.parse().ok(),
+            "ipv4.routes" => config.routes = parse_routes(&value)?,
+            _ => {}
         }
     }
+    Ok(config)
+}
 
-    Ok(networks)
+fn set_routes(device: &str, name: &str, config: &RouteConfig) -> Result<String, String> {
+    let never_default = if config.never_default { "yes" } else { "no" };
+    let metric = config.metric.map_or("-1".to_string(), |m| m.to_string());
+    let routes = format_routes(&config.routes);
+    let output = logging::output(Command::new("nmcli").args([
+        "connection", "modify", "id", name,
+        "ipv4.never-default", never_default,
+        "ipv4.route-metric", &metric,
+        "ipv4.routes", &routes,
+    ]))
+    .map_err(|e| friendly_error(&e.to_string()))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(friendly_error(stderr.trim()));
+    }
+
+    // Best-effort: push the change to the running connection without reconnecting
+    let _ = logging::output(Command::new("nmcli").args(["device", "reapply", device]));
+
+    Ok(routes_message(name, config))
+}
+
+fn ipv4(name: &str) -> Result<Ipv4Config, String> {
+    let output = logging::output(Command::new("nmcli").args([
+        "-t", "-f", "ipv4.method,ipv4.addresses,ipv4.gateway,ipv4.dns",
+        "connection", "show", "id", name,
+    ]))
+    .map_err(|e| friendly_error(&e.to_string()))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(friendly_error(stderr.trim()));
+    }
+
+    // One `property:value` line per field
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut config = Ipv4Config::default();
+    let mut manual = false;
+    for (key, value) in parse_terse_properties(&stdout) {
+        match key.as_str() {
+            "ipv4.method" => manual = value == "manual",
+            // Only the first of several addresses is edited
+            "ipv4.addresses" => config.address = value.split(',').next().and_then(|a| Ipv4Address::parse(a).ok()),
+            "ipv4.gateway" => config.gateway = value.parse().ok(),
+            "ipv4.dns" => config.dns = parse_dns(&value)?,
+            _ => {}
+        }
+    }
+    if !manual {
+        config.address = None;
+    }
+    Ok(config)
+}
+
+fn set_ipv4(device: &str, name: &str, config: &Ipv4Config) -> Result<String, String> {
+    let output = logging::output(Command::new("nmcli").args([
+        "connection", "modify", "id", name,
+        "ipv4.method", ipv4_method(config),
+        "ipv4.addresses", &ipv4_addresses(config),
+        "ipv4.gateway", &ipv4_gateway(config),
+        "ipv4.dns", &format_dns(&config.dns),
+    ]))
+    .map_err(|e| friendly_error(&e.to_string()))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(friendly_error(stderr.trim()));
+    }
+
+    // Best-effort: push the change to the running connection without reconnecting
+    let _ = logging::output(Command::new("nmcli").args(["device", "reapply", device]));
+
+    Ok(ipv4_message(name, config))
+}
+
+fn set_route_metric(device: &str, name: &str, metric: Option<u32>) -> Result<String, String> {
+    let value = metric.map_or("-1".to_string(), |m| m.to_string());
+    let output = logging::output(Command::new("nmcli").args([
+        "connection", "modify", "id", name, "ipv4.route-metric", &value,
+    ]))
+    .map_err(|e| friendly_error(&e.to_string()))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(friendly_error(stderr.trim()));
+    }
+
+    // Best-effort: push the change to the running connection without reconnecting
+    let _ = logging::output(Command::new("nmcli").args(["device", "reapply", device]));
+
+    Ok(route_metric_message(name, metric))
+}
+
+/// The nmcli commands behind `change`.
+fn change_summary(device: &str, change: &Change) -> String {
+    match change {
+        Change::Disconnect => format!("Runs: nmcli device disconnect {}", shell_quote(device)),
+        Change::Forget(name) => format!("Runs: nmcli connection delete {}", shell_quote(name)),
+        Change::Dns(name, config) => format!(
+            "Runs: nmcli connection modify id {} ipv4.ignore-auto-dns {} ipv4.dns {}, then nmcli device reapply {}",
+            shell_quote(name),
+            yes_no(config.ignore_auto),
+            shell_quote(&format_dns(&config.servers)),
+            shell_quote(device)
+        ),
+        Change::Routes(name, config) => format!(
+            "Runs: nmcli connection modify id {} ipv4.never-default {} ipv4.route-metric {} ipv4.routes {}, \
+             then nmcli device reapply {}",
+            shell_quote(name),
+            yes_no(config.never_default),
+            route_metric(config),
+            shell_quote(&format_routes(&config.routes)),
+            shell_quote(device)
+        ),
+        Change::Ipv4(name, config) => format!(
+            "Runs: nmcli connection modify id {} ipv4.method {} ipv4.addresses {} ipv4.gateway {} ipv4.dns {}, \
+             then nmcli device reapply {}",
+            shell_quote(name),
+            ipv4_method(config),
+            shell_quote(&ipv4_addresses(config)),
+            shell_quote(&ipv4_gateway(config)),
+            shell_quote(&format_dns(&config.dns)),
+            shell_quote(device)
+        ),
+        Change::RouteMetric(name, metric) => format!(
+            "Runs: nmcli connection modify id {} ipv4.route-metric {}, then nmcli device reapply {}",
+            shell_quote(name),
+            shell_quote(&metric.value()),
+            shell_quote(device)
+        ),
+    }
 }
 
 /// Connect to a network. If password is Some, use `device wifi connect` for new connections.
 /// If None, use `connection up` to reconnect to a saved network.
-pub fn connect(ssid: &str, password: Option<&str>) -> Result<String, String> {
+fn connect(ssid: &str, password: Option<&str>) -> Result<String, String> {
+    // nmcli matches the argument's bytes against the SSID, so pass the
+    // exact ones for SSIDs that aren't UTF-8
+    let ssid_bytes = encode_ssid(ssid);
+    let raw_ssid = OsStr::from_bytes(&ssid_bytes);
     let output = match password {
         Some(pw) if !pw.is_empty() => {
-            Command::new("nmcli")
-                .args(["device", "wifi", "connect", ssid, "password", pw])
-                .output()
+            // `--ask` reads the password from stdin: on the command line
+            // anyone could see it in `ps`
+            let args = ["--ask", "device", "wifi", "connect"].map(OsStr::new);
+            nmcli_with_input(&[&args[..], &[raw_ssid]].concat(), &format!("{}\n", pw))
                 .map_err(|e| friendly_error(&e.to_string()))?
         }
         Some(_) => {
             // Open network (no password)
-            Command::new("nmcli")
-                .args(["device", "wifi", "connect", ssid])
-                .output()
+            cancel::output(Command::new("nmcli").args(["device", "wifi", "connect"]).arg(raw_ssid))
                 .map_err(|e| friendly_error(&e.to_string()))?
         }
         None => {
             // Saved network - reconnect
-            Command::new("nmcli")
-                .args(["connection", "up", ssid])
-                .output()
+            cancel::output(Command::new("nmcli").args(["connection", "up", ssid]))
                 .map_err(|e| friendly_error(&e.to_string()))?
         }
     };
@@ -246,11 +909,54 @@ pub fn connect(ssid: &str, password: Option<&str>) -> Result<String, String> {
     }
 }
 
+/// Save an enterprise network without its password, then bring it up with
+/// the password on stdin, where `ps` can't see it. A profile that didn't
+/// connect is deleted again so the next try starts clean.
+fn connect_enterprise(device: &str, ssid: &str, config: &EapConfig) -> Result<String, String> {
+    let ca_cert = config.ca_cert.as_ref().map(|path| path.to_string_lossy().into_owned());
+    let mut args = vec![
+        "connection", "add", "type", "wifi", "con-name", ssid, "ifname", device, "ssid", ssid,
+        "wifi-sec.key-mgmt", "wpa-eap",
+        "802-1x.eap", config.method.nm_value(),
+        "802-1x.phase2-auth", "mschapv2",
+        "802-1x.identity", &config.identity,
+    ];
+    if let Some(ca_cert) = &ca_cert {
+        args.extend(["802-1x.ca-cert", ca_cert]);
+    }
+    let output = logging::output(Command::new("nmcli").args(&args)).map_err(|e| friendly_error(&e.to_string()))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(friendly_error(stderr.trim()));
+    }
+    // By UUID from here on: another profile may already be named `ssid`
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let uuid = parse_added_uuid(&stdout).ok_or_else(|| format!("Unexpected nmcli output: {}", stdout.trim()))?;
+
+    // nmcli answers NetworkManager's secret request from the file, and
+    // NetworkManager stores the password with the profile
+    let input = format!("802-1x.password:{}\n", config.password);
+    let result = match nmcli_with_input(&["connection", "up", "uuid", &uuid, "passwd-file", "/dev/stdin"], &input) {
+        Ok(output) if output.status.success() => Ok(format!("Connected to {}", ssid)),
+        Ok(output) => Err(friendly_error(String::from_utf8_lossy(&output.stderr).trim())),
+        // Cancelled, or nmcli didn't run
+        Err(e) => Err(friendly_error(&e.to_string())),
+    };
+    if result.is_err() {
+        let _ = logging::output(Command::new("nmcli").args(["connection", "delete", "uuid", &uuid]));
+    }
+    result
+}
+
+/// Run nmcli with `input` on its stdin, killed like `cancel::output` when
+/// the task is cancelled.
+fn nmcli_with_input<S: AsRef<OsStr>>(args: &[S], input: &str) -> std::io::Result<Output> {
+    cancel::output_with_input(Command::new("nmcli").args(args), input)
+}
+
 /// Disconnect from the current network.
-pub fn disconnect(device: &str) -> Result<String, String> {
-    let output = Command::new("nmcli")
-        .args(["device", "disconnect", device])
-        .output()
+fn disconnect(device: &str) -> Result<String, String> {
+    let output = cancel::output(Command::new("nmcli").args(["device", "disconnect", device]))
         .map_err(|e| friendly_error(&e.to_string()))?;
 
     if output.status.success() {
@@ -262,10 +968,8 @@ pub fn disconnect(device: &str) -> Result<String, String> {
 }
 
 /// Forget (delete) a saved network connection.
-pub fn forget(name: &str) -> Result<String, String> {
-    let output = Command::new("nmcli")
-        .args(["connection", "delete", name])
-        .output()
+fn forget(name: &str) -> Result<String, String> {
+    let output = cancel::output(Command::new("nmcli").args(["connection", "delete", name]))
         .map_err(|e| friendly_error(&e.to_string()))?;
 
     if output.status.success() {
@@ -276,32 +980,48 @@ pub fn forget(name: &str) -> Result<String, String> {
     }
 }
 
-/// Check if an error message indicates that a password is needed to connect.
-pub fn error_needs_password(msg: &str) -> bool {
-    msg.contains("Password required") || msg.contains("Incorrect password")
+fn rename_profile(name: &str, new_name: &str) -> Result<String, String> {
+    let output = logging::output(Command::new("nmcli").args([
+        "connection", "modify", "id", name, "connection.id", new_name,
+    ]))
+    .map_err(|e| friendly_error(&e.to_string()))?;
+
+    if output.status.success() {
+        Ok(format!("Renamed '{}' to '{}'.", name, new_name))
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        Err(friendly_error(stderr.trim()))
+    }
 }
 
-/// Parse nmcli terse output line, handling `\:` escaped colons within fields.
-fn parse_terse_line(line: &str) -> Vec<String> {
+/// Number at the start of values like "2437 MHz" or "130 Mbit/s".
+fn leading_number(value: &str) -> Option<u32> {
+    value.split_whitespace().next()?.parse().ok()
+}
+
+/// Split one line of nmcli terse (`-t`) output into fields. Inside values
+/// nmcli escapes `:` as `\:` and `\` as `\\`; `\n` stands for a newline.
+/// Any other backslash is kept as it is.
+pub fn parse_terse_line(line: &str) -> Vec<String> {
     let mut fields = Vec::new();
     let mut current = String::new();
     let mut chars = line.chars().peekable();
 
     while let Some(ch) = chars.next() {
-        if ch == '\\' {
-            if let Some(&next) = chars.peek() {
-                if next == ':' {
-                    current.push(':');
+        match ch {
+            '\\' => match chars.peek() {
+                Some(&next @ (':' | '\\')) => {
+                    current.push(next);
                     chars.next();
-                    continue;
                 }
-            }
-            current.push(ch);
-        } else if ch == ':' {
-            fields.push(current.clone());
-            current.clear();
-        } else {
-            current.push(ch);
+                Some('n') => {
+                    current.push('\n');
+                    chars.next();
+                }
+                _ => current.push(ch),
+            },
+            ':' => fields.push(std::mem::take(&mut current)),
+            _ => current.push(ch),
         }
     }
     fields.push(current);
@@ -309,6 +1029,27 @@ fn parse_terse_line(line: &str) -> Vec<String> {
     fields
 }
 
+/// `property:value` lines of `nmcli -t -f ... show` output. Multi-value
+/// properties come one value per line as `IP4.ADDRESS[1]`, `IP4.ADDRESS[2]`;
+/// the index is dropped, so each value is an entry for `IP4.ADDRESS`.
+pub fn parse_terse_properties(stdout: &str) -> Vec<(String, String)> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let mut fields = parse_terse_line(line);
+            if fields.len() < 2 {
+                return None;
+            }
+            let value = fields.split_off(1).join(":");
+            let mut key = fields.remove(0);
+            if let Some(index) = key.find('[').filter(|_| key.ends_with(']')) {
+                key.truncate(index);
+            }
+            Some((key, value))
+        })
+        .collect()
+}
+
 /// Translate nmcli error messages into beginner-friendly text.
 fn friendly_error(msg: &str) -> String {
     if msg.contains("No such file or directory") || msg.contains("not found") && msg.contains("nmcli") {
@@ -338,6 +1079,30 @@ fn friendly_error(msg: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::backend::MetricChange;
+
+    #[test]
+    fn test_parse_device_state() {
+        assert_eq!(
+            parse_device_state("70 (connecting (getting IP configuration))\n"),
+            Some(ConnectStage::GettingIp)
+        );
+        assert_eq!(parse_device_state("100 (connected)"), None);
+        assert_eq!(parse_device_state("30 (disconnected)"), None);
+        assert_eq!(parse_device_state(""), None);
+    }
+
+    #[test]
+    fn test_change_summary() {
+        assert_eq!(
+            change_summary("wlan0", &Change::Forget("Joe's Cafe")),
+            "Runs: nmcli connection delete 'Joe'\\''s Cafe'"
+        );
+        assert_eq!(
+            change_summary("wlan0", &Change::RouteMetric("Home", MetricChange::Adjust(-100))),
+            "Runs: nmcli connection modify id Home ipv4.route-metric '<current - 100>', then nmcli device reapply wlan0"
+        );
+    }
 
     #[test]
     fn test_parse_terse_line_basic() {
@@ -356,4 +1121,66 @@ mod tests {
         let fields = parse_terse_line("*::85:WPA2");
         assert_eq!(fields, vec!["*", "", "85", "WPA2"]);
     }
+
+    #[test]
+    fn test_parse_access_points() {
+        let stdout = "connection.uuid:1111\n802-11-wireless.mode:infrastructure\n\
+                      connection.uuid:2222\n802-11-wireless.mode:ap\n\
+                      connection.uuid:3333\n";
+        assert_eq!(parse_access_points(stdout), HashSet::from(["2222".to_string()]));
+    }
+
+    #[test]
+    fn test_parse_terse_line_escapes() {
+        let fields = parse_terse_line(r"*:back\\slash\:ssid:line\nbreak:C:\path");
+        assert_eq!(fields, vec!["*", "back\\slash:ssid", "line\nbreak", "C", "\\path"]);
+    }
+
+    #[test]
+    fn test_parse_terse_properties() {
+        let stdout = "IP4.ADDRESS[1]:10.0.0.2/24\nIP4.ADDRESS[2]:10.0.0.3/24\n802-11-wireless-security.psk:pa\\:ss\nbroken\n";
+        assert_eq!(
+            parse_terse_properties(stdout),
+            vec![
+                ("IP4.ADDRESS".to_string(), "10.0.0.2/24".to_string()),
+                ("IP4.ADDRESS".to_string(), "10.0.0.3/24".to_string()),
+                ("802-11-wireless-security.psk".to_string(), "pa:ss".to_string()),
+            ]
+        );
+    }
+
+    /// How nmcli writes a value in terse output.
+    fn escape(value: &str) -> String {
+        value.replace('\\', "\\\\").replace(':', "\\:").replace('\n', "\\n")
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn prop_terse_line_round_trip(fields in proptest::collection::vec("[a-zA-Z0-9 :,\\\\\n\\[\\]]*", 1..6)) {
+            let line = fields.iter().map(|field| escape(field)).collect::<Vec<_>>().join(":");
+            proptest::prop_assert_eq!(parse_terse_line(&line), fields);
+        }
+
+        #[test]
+        fn prop_terse_properties_round_trip(key in "[a-z0-9.-]{1,20}", index in 1u8..4, value in "[a-zA-Z0-9 :,\\\\\n]*") {
+            let stdout = format!("{}[{}]:{}\n", key, index, escape(&value));
+            proptest::prop_assert_eq!(parse_terse_properties(&stdout), vec![(key, value)]);
+        }
+    }
+
+    #[test]
+    fn test_parse_status() {
+        assert_eq!(parse_in_use(" :Other:40\n*:Home:72\n"), Some(("Home".to_string(), Some(72))));
+        assert_eq!(parse_in_use(" :Other:40"), None);
+        // Not UTF-8: the hex has the real bytes
+        assert_eq!(parse_in_use("*:Caf?:72:436166E9\n"), Some(("Caf\\xe9".to_string(), Some(72))));
+        assert_eq!(parse_ip4_address("IP4.ADDRESS[1]:192.168.1.20/24"), Some("192.168.1.20".to_string()));
+    }
+
+    #[test]
+    fn test_parse_added_uuid() {
+        let stdout = "Connection 'Cafe (2)' (5b8e9c4a-1f3d-4c57-9d0e-2a6b7c8d9e0f) successfully added.\n";
+        assert_eq!(parse_added_uuid(stdout), Some("5b8e9c4a-1f3d-4c57-9d0e-2a6b7c8d9e0f".to_string()));
+        assert_eq!(parse_added_uuid("Warning: something else\n"), None);
+    }
 }
@@ -0,0 +1,120 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Where distributions put the IEEE's vendor prefixes: hwdata and
+/// ieee-data's `oui.txt`, and Wireshark's `manuf`.
+const OUI_FILES: &[&str] = &[
+    "/usr/share/hwdata/oui.txt",
+    "/usr/share/ieee-data/oui.txt",
+    "/usr/share/misc/oui.txt",
+    "/usr/share/wireshark/manuf",
+];
+
+/// Vendors by the first three bytes of a MAC address, read on first use.
+static VENDORS: OnceLock<HashMap<[u8; 3], String>> = OnceLock::new();
+
+/// Who made the device with MAC address `bssid`, e.g. "Ubiquiti Networks".
+/// None when it's locally administered (randomized, or a mesh node's extra
+/// BSSID) or no OUI table is installed.
+pub fn vendor(bssid: &str) -> Option<&'static str> {
+    let prefix = parse_prefix(bssid)?;
+    if is_locally_administered(prefix) {
+        return None;
+    }
+    VENDORS
+        .get_or_init(|| {
+            OUI_FILES
+                .iter()
+                .find_map(|path| std::fs::read_to_string(path).ok())
+                .map(|text| parse_table(&text))
+                .unwrap_or_default()
+        })
+        .get(&prefix)
+        .map(String::as_str)
+}
+
+/// Set by the device rather than assigned by the IEEE, so no vendor.
+pub fn is_locally_administered(prefix: [u8; 3]) -> bool {
+    prefix[0] & 0x02 != 0
+}
+
+/// First three bytes of "aa:bb:cc:...", "AA-BB-CC" or "AABBCC".
+pub fn parse_prefix(text: &str) -> Option<[u8; 3]> {
+    let hex: String = text.chars().filter(|c| !matches!(c, ':' | '-' | '.')).take(6).collect();
+    if hex.len() != 6 {
+        return None;
+    }
+    let byte = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some([byte(0)?, byte(2)?, byte(4)?])
+}
+
+/// Either format: `00-00-0C   (hex)\t\tCisco Systems, Inc` lines of
+/// `oui.txt`, or `00:00:0C\tCisco\tCisco Systems, Inc` lines of `manuf`.
+/// Entries for blocks smaller than a whole prefix (`/28`, `/36`) are skipped.
+fn parse_table(text: &str) -> HashMap<[u8; 3], String> {
+    text.lines()
+        .filter_map(|line| {
+            if let Some((prefix, name)) = line.split_once("(hex)") {
+                return Some((parse_prefix(prefix.trim())?, short_name(name.trim())));
+            }
+            let mut fields = line.split('\t');
+            let prefix = fields.next()?;
+            if prefix.starts_with('#') || prefix.contains('/') {
+                return None;
+            }
+            let short = fields.next()?;
+            let name = fields.next().unwrap_or(short);
+            Some((parse_prefix(prefix.trim())?, short_name(name.trim())))
+        })
+        .filter(|(_, name)| !name.is_empty())
+        .collect()
+}
+
+/// "TP-LINK TECHNOLOGIES CO.,LTD." is "TP-LINK", "Ubiquiti Networks Inc."
+/// "Ubiquiti Networks": the registered name without its company form.
+fn short_name(name: &str) -> String {
+    const SUFFIXES: &[&str] = &[
+        "inc", "ltd", "co", "corp", "corporation", "limited", "llc", "gmbh", "ag", "sa", "bv", "technologies",
+        "technology",
+    ];
+    let mut words: Vec<&str> = name.split(',').next().unwrap_or(name).split_whitespace().collect();
+    while words.len() > 1 {
+        let last = words[words.len() - 1].trim_end_matches('.').to_ascii_lowercase();
+        if !SUFFIXES.contains(&last.as_str()) {
+            break;
+        }
+        words.pop();
+    }
+    words.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_table() {
+        let oui = "OUI/MA-L\t\t\tOrganization\n\
+                   F4-92-BF   (hex)\t\tUbiquiti Networks Inc.\n\
+                   F492BF     (base 16)\t\tUbiquiti Networks Inc.\n\
+                   50-C7-BF   (hex)\t\tTP-LINK TECHNOLOGIES CO.,LTD.\n";
+        let table = parse_table(oui);
+        assert_eq!(table.len(), 2);
+        assert_eq!(table[&[0xf4, 0x92, 0xbf]], "Ubiquiti Networks");
+        assert_eq!(table[&[0x50, 0xc7, 0xbf]], "TP-LINK");
+
+        let manuf = "# comment\n00:00:0C\tCisco\tCisco Systems, Inc\n00:1B:C5:00:00:00/36\tConverg\tConverging\n";
+        let table = parse_table(manuf);
+        assert_eq!(table.len(), 1);
+        assert_eq!(table[&[0x00, 0x00, 0x0c]], "Cisco Systems");
+    }
+
+    #[test]
+    fn test_parse_prefix() {
+        assert_eq!(parse_prefix("f4:92:bf:01:02:03"), Some([0xf4, 0x92, 0xbf]));
+        assert_eq!(parse_prefix("F4-92-BF"), Some([0xf4, 0x92, 0xbf]));
+        assert_eq!(parse_prefix("f4:92"), None);
+        assert!(is_locally_administered(parse_prefix("7a:45:58:00:00:01").unwrap()));
+        assert!(!is_locally_administered([0xf4, 0x92, 0xbf]));
+    }
+}
@@ -0,0 +1,55 @@
+use std::process::{Command, Stdio};
+
+use crate::cancel;
+
+/// Run the user's `password_command` for `ssid` and return the first line it
+/// prints, e.g. `rbw get wifi/{ssid}` or `pass show wifi/{ssid}`.
+///
+/// The SSID is passed to `sh` as a positional argument rather than pasted
+/// into the command, so quotes or `$(...)` in a network name can't run
+/// anything.
+pub fn fetch(command: &str, ssid: &str) -> Result<String, String> {
+    let mut sh = Command::new("sh");
+    sh.args(["-c", &shell_command(command), "wifi-tui", ssid]).stdin(Stdio::null());
+    let output = cancel::output(&mut sh)
+        .map_err(|e| format!("Couldn't run the password command: {}", e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Password command failed: {}", stderr.trim()));
+    }
+    first_line(&String::from_utf8_lossy(&output.stdout))
+        .ok_or_else(|| "The password command printed nothing.".to_string())
+}
+
+fn shell_command(command: &str) -> String {
+    command.replace("{ssid}", "\"$1\"")
+}
+
+/// Password managers print the secret on the first line (`pass` puts
+/// metadata below it).
+fn first_line(stdout: &str) -> Option<String> {
+    stdout
+        .lines()
+        .next()
+        .map(|line| line.trim_end_matches('\r').to_string())
+        .filter(|line| !line.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shell_command() {
+        assert_eq!(shell_command("rbw get wifi/{ssid}"), "rbw get wifi/\"$1\"");
+        assert_eq!(shell_command("pass show wifi"), "pass show wifi");
+    }
+
+    #[test]
+    fn test_first_line() {
+        assert_eq!(first_line("hunter2\nuser: me\n"), Some("hunter2".to_string()));
+        assert_eq!(first_line("pass word\r\n"), Some("pass word".to_string()));
+        assert_eq!(first_line(""), None);
+        assert_eq!(first_line("\nsecond"), None);
+    }
+}
@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+
+/// The 802.11 amendment a link's rate belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Generation {
+    /// 802.11a/b/g: a plain rate without MCS
+    Legacy,
+    N,
+    Ac,
+    Ax,
+    Be,
+}
+
+impl Generation {
+    pub fn label(self) -> &'static str {
+        match self {
+            Generation::Legacy => "802.11a/b/g",
+            Generation::N => "802.11n (WiFi 4)",
+            Generation::Ac => "802.11ac (WiFi 5)",
+            Generation::Ax => "802.11ax (WiFi 6)",
+            Generation::Be => "802.11be (WiFi 7)",
+        }
+    }
+}
+
+/// How the current link transmits, from nl80211's station info.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct LinkRate {
+    pub generation: Generation,
+    /// Channel width in MHz.
+    pub width_mhz: u32,
+    /// Modulation and coding scheme index.
+    pub mcs: Option<u8>,
+    /// Spatial streams.
+    pub nss: Option<u8>,
+}
+
+impl LinkRate {
+    /// e.g. "80 MHz, MCS 11, 2 streams".
+    pub fn summary(&self) -> String {
+        let mut parts = vec![format!("{} MHz", self.width_mhz)];
+        if let Some(mcs) = self.mcs {
+            parts.push(format!("MCS {}", mcs));
+        }
+        match self.nss {
+            Some(1) => parts.push("1 stream".to_string()),
+            Some(nss) => parts.push(format!("{} streams", nss)),
+            None => {}
+        }
+        parts.join(", ")
+    }
+}
@@ -0,0 +1,105 @@
+use std::process::Command;
+use std::thread;
+
+use crate::logging;
+
+/// Round trip and loss to one host, from a short burst of pings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PingResult {
+    /// Average round trip in microseconds; None if every ping was lost.
+    pub rtt_us: Option<u32>,
+    /// Percentage of pings lost.
+    pub loss: u8,
+}
+
+/// The gateway and the internet probe host, measured at the same time.
+/// None where there was nothing to ping or `ping` couldn't run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct PingReport {
+    pub gateway: Option<PingResult>,
+    pub internet: Option<PingResult>,
+}
+
+/// Ping `device`'s gateway and `host` (skipped when empty) in parallel.
+pub fn measure(device: &str, host: &str) -> PingReport {
+    let internet = (!host.is_empty()).then(|| {
+        let host = host.to_string();
+        thread::spawn(move || ping(&host))
+    });
+    let gateway = gateway(device).and_then(|gateway| ping(&gateway));
+    PingReport {
+        gateway,
+        internet: internet.and_then(|handle| handle.join().ok().flatten()),
+    }
+}
+
+/// `device`'s IPv4 default gateway from `ip route`.
+fn gateway(device: &str) -> Option<String> {
+    let output = logging::output(Command::new("ip").args(["-4", "route", "show", "default", "dev", device])).ok()?;
+    parse_gateway(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// The address after `via` in `default via 192.168.1.1 proto dhcp ...`.
+fn parse_gateway(text: &str) -> Option<String> {
+    text.lines().find_map(|line| {
+        let mut words = line.split_whitespace();
+        words.find(|word| *word == "via")?;
+        words.next().map(str::to_string)
+    })
+}
+
+/// Three pings, a second apart at most. None if `ping` isn't installed or
+/// can't resolve `host`.
+fn ping(host: &str) -> Option<PingResult> {
+    let output = logging::output(Command::new("ping").args(["-n", "-q", "-c", "3", "-i", "0.2", "-W", "1", host]))
+        .ok()?;
+    parse_ping(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// The summary of `ping -q`:
+///
+/// ```text
+/// 3 packets transmitted, 2 received, 33.3333% packet loss, time 402ms
+/// rtt min/avg/max/mdev = 1.234/2.345/3.456/0.500 ms
+/// ```
+fn parse_ping(text: &str) -> Option<PingResult> {
+    let loss = text.lines().find_map(|line| {
+        let before = line.split("% packet loss").next().filter(|before| before.len() < line.len())?;
+        before.rsplit([' ', ',']).next()?.parse::<f64>().ok()
+    })?;
+    let rtt_us = text.lines().find_map(|line| {
+        let (_, values) = line.split_once(" = ")?;
+        let avg: f64 = values.split('/').nth(1)?.parse().ok()?;
+        Some((avg * 1000.0).round() as u32)
+    });
+    Some(PingResult {
+        rtt_us,
+        loss: loss.round().clamp(0.0, 100.0) as u8,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ping() {
+        let ok = "PING 1.1.1.1 (1.1.1.1) 56(84) bytes of data.\n\n\
+                  --- 1.1.1.1 ping statistics ---\n\
+                  3 packets transmitted, 2 received, 33.3333% packet loss, time 402ms\n\
+                  rtt min/avg/max/mdev = 1.234/2.345/3.456/0.500 ms\n";
+        assert_eq!(parse_ping(ok), Some(PingResult { rtt_us: Some(2345), loss: 33 }));
+        let lost = "3 packets transmitted, 0 received, 100% packet loss, time 2040ms\n";
+        assert_eq!(parse_ping(lost), Some(PingResult { rtt_us: None, loss: 100 }));
+        assert_eq!(parse_ping(""), None);
+    }
+
+    #[test]
+    fn test_parse_gateway() {
+        assert_eq!(
+            parse_gateway("default via 192.168.1.1 proto dhcp src 192.168.1.20 metric 600\n"),
+            Some("192.168.1.1".to_string())
+        );
+        assert_eq!(parse_gateway("default proto static scope link metric 50\n"), None);
+    }
+}
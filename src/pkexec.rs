@@ -0,0 +1,31 @@
+use std::process::{Command, Stdio};
+
+use crate::cancel;
+
+/// Run a command as root through pkexec, optionally feeding it `input` on
+/// stdin. Errors are already beginner-friendly text.
+pub fn run(args: &[&str], input: Option<&str>) -> Result<(), String> {
+    let mut command = Command::new("pkexec");
+    command.args(args);
+    let output = match input {
+        Some(input) => cancel::output_with_input(&mut command, input),
+        None => cancel::output(command.stdin(Stdio::null())),
+    }
+    .map_err(|e| {
+        if e.kind() == std::io::ErrorKind::NotFound {
+            "pkexec not found. Install polkit to make system changes.".to_string()
+        } else {
+            e.to_string()
+        }
+    })?;
+    if output.status.success() {
+        return Ok(());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stderr = stderr.trim();
+    if stderr.is_empty() || stderr.contains("Not authorized") || stderr.contains("dismissed") {
+        Err("Permission denied. Authentication was cancelled or failed.".to_string())
+    } else {
+        Err(stderr.to_string())
+    }
+}
@@ -0,0 +1,97 @@
+use qrcode::{Color, QrCode};
+
+use crate::backend::WifiSecret;
+
+/// Light modules drawn around the code so scanners can find its edges.
+const QUIET_ZONE: usize = 2;
+
+/// A saved network's credentials, ready to show as a scannable code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WifiQr {
+    pub ssid: String,
+    pub password: String,
+    /// Square grid of modules including the quiet zone; true is dark.
+    pub modules: Vec<Vec<bool>>,
+}
+
+impl WifiQr {
+    pub fn new(secret: &WifiSecret) -> Result<Self, String> {
+        let code = QrCode::new(wifi_payload(secret))
+            .map_err(|e| format!("Couldn't create a QR code: {}", e))?;
+        let width = code.width();
+        let colors = code.to_colors();
+
+        let size = width + QUIET_ZONE * 2;
+        let mut modules = vec![vec![false; size]; size];
+        for (i, color) in colors.iter().enumerate() {
+            modules[i / width + QUIET_ZONE][i % width + QUIET_ZONE] = *color == Color::Dark;
+        }
+
+        Ok(Self {
+            ssid: secret.ssid.clone(),
+            password: secret.psk.clone(),
+            modules,
+        })
+    }
+}
+
+/// The `WIFI:` URI understood by Android and iOS camera apps.
+fn wifi_payload(secret: &WifiSecret) -> String {
+    let auth = match secret.key_mgmt.as_str() {
+        "" | "none" if secret.psk.is_empty() => "nopass",
+        "none" => "WEP",
+        _ => "WPA",
+    };
+    let mut payload = format!("WIFI:T:{};S:{};", auth, escape(&secret.ssid));
+    if auth != "nopass" {
+        payload.push_str(&format!("P:{};", escape(&secret.psk)));
+    }
+    payload.push(';');
+    payload
+}
+
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '\\' | ';' | ',' | ':' | '"') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secret(ssid: &str, key_mgmt: &str, psk: &str) -> WifiSecret {
+        WifiSecret {
+            ssid: ssid.to_string(),
+            key_mgmt: key_mgmt.to_string(),
+            psk: psk.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_wifi_payload() {
+        assert_eq!(
+            wifi_payload(&secret("Home", "wpa-psk", "hunter22")),
+            "WIFI:T:WPA;S:Home;P:hunter22;;"
+        );
+        assert_eq!(wifi_payload(&secret("Cafe", "", "")), "WIFI:T:nopass;S:Cafe;;");
+        assert_eq!(
+            wifi_payload(&secret("a;b", "sae", "p:w\\d")),
+            "WIFI:T:WPA;S:a\\;b;P:p\\:w\\\\d;;"
+        );
+    }
+
+    #[test]
+    fn test_modules_have_quiet_zone() {
+        let qr = WifiQr::new(&secret("Home", "wpa-psk", "hunter22")).unwrap();
+        let size = qr.modules.len();
+        assert!(qr.modules.iter().all(|row| row.len() == size));
+        assert!(!qr.modules[0].iter().any(|&dark| dark));
+        assert!(qr.modules[QUIET_ZONE][QUIET_ZONE]); // finder pattern corner
+    }
+}
@@ -0,0 +1,64 @@
+use crate::keyfile::{self, Entries};
+use crate::pkexec;
+
+/// Per-profile bandwidth limits in kbit/s, keyed by connection name.
+pub type RateLimits = Entries<u32>;
+
+/// `~/.config/wifi-tui/rate-limits`, one `name=kbit` per line.
+const FILE: &str = "rate-limits";
+
+/// Load saved limits. A missing or unreadable file means no limits.
+pub fn load() -> RateLimits {
+    keyfile::load(FILE, parse_kbit)
+}
+
+pub fn save(limits: &RateLimits) -> Result<(), String> {
+    keyfile::save(FILE, limits, u32::to_string)
+}
+
+fn parse_kbit(value: &str) -> Option<u32> {
+    value.trim().parse().ok()
+}
+
+/// Shape both directions on `device` to `kbit` kbit/s using tc (via pkexec):
+/// a token bucket on egress and a policer on ingress.
+pub fn apply(device: &str, kbit: u32) -> Result<String, String> {
+    let rate = format!("{}kbit", kbit);
+    let script = "tc qdisc replace dev \"$1\" root tbf rate \"$2\" burst 32kbit latency 400ms && \
+                  tc qdisc del dev \"$1\" ingress 2>/dev/null; \
+                  tc qdisc add dev \"$1\" handle ffff: ingress && \
+                  tc filter add dev \"$1\" parent ffff: protocol all u32 match u32 0 0 \
+                  police rate \"$2\" burst 64k drop flowid :1";
+    run_privileged(&["sh", "-c", script, "sh", device, &rate])?;
+    Ok(format!("Limited {} to {} kbit/s.", device, kbit))
+}
+
+/// Remove any limit set by `apply`.
+pub fn clear(device: &str) -> Result<String, String> {
+    let script = "tc qdisc del dev \"$1\" root 2>/dev/null; tc qdisc del dev \"$1\" ingress 2>/dev/null; true";
+    run_privileged(&["sh", "-c", script, "sh", device])?;
+    Ok(format!("Removed bandwidth limit on {}.", device))
+}
+
+/// pkexec, with a clearer message when tc itself is missing.
+fn run_privileged(args: &[&str]) -> Result<(), String> {
+    pkexec::run(args, None).map_err(|e| {
+        if e.contains("tc: not found") || e.contains("tc: command not found") {
+            "tc not found. Install iproute2 to limit bandwidth.".to_string()
+        } else {
+            e
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_kbit() {
+        assert_eq!(parse_kbit(" 2000"), Some(2000));
+        assert_eq!(parse_kbit("abc"), None);
+        assert_eq!(parse_kbit("-5"), None);
+    }
+}
@@ -0,0 +1,85 @@
+use std::path::Path;
+use std::process::Command;
+
+use crate::cancel;
+use crate::pkexec;
+
+/// Whether the radio behind a WiFi device is switched off: in software
+/// (airplane mode, `rfkill block`) or by a hardware switch or key.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Rfkill {
+    pub soft: bool,
+    pub hard: bool,
+}
+
+impl Rfkill {
+    pub fn blocked(&self) -> bool {
+        self.soft || self.hard
+    }
+}
+
+/// The rfkill state of `device`'s radio, or of every WiFi radio when the
+/// device's own switch can't be found. None without rfkill support.
+pub fn state(device: &str) -> Option<Rfkill> {
+    let phy = Path::new("/sys/class/net").join(device).join("phy80211");
+    if let Some(state) = read_switches(&phy, |_| true) {
+        return Some(state);
+    }
+    read_switches(Path::new("/sys/class/rfkill"), |dir| {
+        std::fs::read_to_string(dir.join("type")).is_ok_and(|kind| kind.trim() == "wlan")
+    })
+}
+
+/// Combine the `rfkill*` switches under `dir` that pass `filter`.
+fn read_switches(dir: &Path, filter: impl Fn(&Path) -> bool) -> Option<Rfkill> {
+    let mut found = None;
+    for entry in std::fs::read_dir(dir).ok()?.flatten() {
+        let path = entry.path();
+        if !entry.file_name().to_string_lossy().starts_with("rfkill") || !filter(&path) {
+            continue;
+        }
+        let flag = |name| std::fs::read_to_string(path.join(name)).is_ok_and(|v| v.trim() == "1");
+        let state: &mut Rfkill = found.get_or_insert_with(Rfkill::default);
+        state.soft |= flag("soft");
+        state.hard |= flag("hard");
+    }
+    found
+}
+
+/// Lift a soft block with `rfkill unblock wifi`, through pkexec if the
+/// user can't write /dev/rfkill. A hardware switch can't be undone here.
+pub fn unblock() -> Result<String, String> {
+    let output = cancel::output(Command::new("rfkill").args(["unblock", "wifi"])).map_err(|e| {
+        if e.kind() == std::io::ErrorKind::NotFound {
+            "rfkill not found. Install util-linux, or turn WiFi on in your desktop settings.".to_string()
+        } else {
+            e.to_string()
+        }
+    })?;
+    if !output.status.success() {
+        pkexec::run(&["rfkill", "unblock", "wifi"], None)?;
+    }
+    Ok("WiFi radio turned on.".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_switches() {
+        let dir = std::env::temp_dir().join(format!("wifi-tui-rfkill-{}", std::process::id()));
+        for (name, soft, hard) in [("rfkill0", "0", "0"), ("rfkill1", "1", "0")] {
+            std::fs::create_dir_all(dir.join(name)).unwrap();
+            std::fs::write(dir.join(name).join("soft"), soft).unwrap();
+            std::fs::write(dir.join(name).join("hard"), hard).unwrap();
+        }
+        std::fs::create_dir_all(dir.join("subsystem")).unwrap();
+
+        assert_eq!(read_switches(&dir, |_| true), Some(Rfkill { soft: true, hard: false }));
+        assert_eq!(read_switches(&dir, |path| path.ends_with("rfkill0")), Some(Rfkill::default()));
+        assert_eq!(read_switches(&dir, |_| false), None);
+        assert_eq!(read_switches(&dir.join("missing"), |_| true), None);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
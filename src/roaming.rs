@@ -0,0 +1,95 @@
+use crate::keyfile::{self, Entries};
+use crate::pkexec;
+
+/// wpa_supplicant's "simple" background scan: scan every `short_secs` while
+/// the signal is below `threshold` dBm, every `long_secs` otherwise. A
+/// higher threshold and shorter interval make the client roam sooner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bgscan {
+    pub short_secs: u32,
+    pub threshold: i32,
+    pub long_secs: u32,
+}
+
+impl Bgscan {
+    /// What NetworkManager uses when it sees several access points for a
+    /// network; a starting point for tuning.
+    pub const NM_MULTI_AP: Self = Self {
+        short_secs: 30,
+        threshold: -65,
+        long_secs: 300,
+    };
+
+    /// e.g. "simple:30:-65:300"
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut parts = text.trim().strip_prefix("simple:")?.split(':');
+        let bgscan = Self {
+            short_secs: parts.next()?.parse().ok()?,
+            threshold: parts.next()?.parse().ok()?,
+            long_secs: parts.next()?.parse().ok()?,
+        };
+        parts.next().is_none().then_some(bgscan)
+    }
+
+    pub fn format(&self) -> String {
+        format!("simple:{}:{}:{}", self.short_secs, self.threshold, self.long_secs)
+    }
+}
+
+/// Per-profile background scan settings, keyed by connection name.
+pub type RoamSettings = Entries<Bgscan>;
+
+/// `~/.config/wifi-tui/roaming`, one `name=simple:short:threshold:long` per line.
+const FILE: &str = "roaming";
+
+/// Load saved settings. A missing or unreadable file means NetworkManager's.
+pub fn load() -> RoamSettings {
+    keyfile::load(FILE, Bgscan::parse)
+}
+
+pub fn save(settings: &RoamSettings) -> Result<(), String> {
+    keyfile::save(FILE, settings, Bgscan::format)
+}
+
+/// Set the background scan of the network `device` is associated with,
+/// through wpa_cli (via pkexec). NetworkManager writes its own again on the
+/// next connect, so this is redone each time.
+pub fn apply(device: &str, bgscan: &Bgscan) -> Result<String, String> {
+    let script = "id=$(wpa_cli -i \"$1\" status | sed -n 's/^id=//p') && \
+                  [ -n \"$id\" ] || { echo \"not associated\" >&2; exit 1; }; \
+                  out=$(wpa_cli -i \"$1\" set_network \"$id\" bgscan \"\\\"$2\\\"\") && \
+                  [ \"$out\" = OK ] || { echo \"wpa_supplicant refused the setting: $out\" >&2; exit 1; }";
+    pkexec::run(&["sh", "-c", script, "sh", device, &bgscan.format()], None).map_err(|e| {
+        if e.contains("wpa_cli: not found") || e.contains("wpa_cli: command not found") {
+            "wpa_cli not found. Install wpa_supplicant's command line tools to tune roaming.".to_string()
+        } else if e.contains("Failed to connect") {
+            format!(
+                "wpa_supplicant has no control socket for {}, so roaming can't be tuned. \
+                 It needs to run with -O /run/wpa_supplicant.",
+                device
+            )
+        } else if e.contains("not associated") {
+            format!("{} isn't connected.", device)
+        } else {
+            e
+        }
+    })?;
+    Ok(format!(
+        "Roaming on {}: scan every {}s below {} dBm.",
+        device, bgscan.short_secs, bgscan.threshold
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_bgscan() {
+        let mesh = Bgscan { short_secs: 10, threshold: -60, long_secs: 120 };
+        assert_eq!(Bgscan::parse("simple:10:-60:120"), Some(mesh));
+        assert_eq!(Bgscan::parse(&Bgscan::NM_MULTI_AP.format()), Some(Bgscan::NM_MULTI_AP));
+        assert_eq!(Bgscan::parse("learn:30"), None);
+        assert_eq!(Bgscan::parse("simple:1:2:3:4"), None);
+    }
+}
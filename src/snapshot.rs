@@ -0,0 +1,37 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::backend::{ConnectionStatus, Network, SavedNetwork};
+use crate::config::config_dir;
+
+/// What the last run saw, shown (marked as cached) while the first scan of
+/// this one runs; some drivers take several seconds to scan.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub device: String,
+    pub networks: Vec<Network>,
+    pub saved: Vec<SavedNetwork>,
+    pub status: ConnectionStatus,
+}
+
+/// `~/.config/wifi-tui/snapshot.json`, rewritten on every exit.
+fn snapshot_path() -> Option<PathBuf> {
+    Some(config_dir()?.join("snapshot.json"))
+}
+
+/// The last run's snapshot, if it was of `device`. A missing or unreadable
+/// file (e.g. from an older version) means there's none.
+pub fn load(device: &str) -> Option<Snapshot> {
+    let text = std::fs::read_to_string(snapshot_path()?).ok()?;
+    serde_json::from_str::<Snapshot>(&text).ok().filter(|snapshot| snapshot.device == device)
+}
+
+pub fn save(snapshot: &Snapshot) -> Result<(), String> {
+    let path = snapshot_path().ok_or("Couldn't find a config directory (HOME is not set).")?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Couldn't create {}: {}", dir.display(), e))?;
+    }
+    let text = serde_json::to_string(snapshot).map_err(|e| e.to_string())?;
+    std::fs::write(&path, text).map_err(|e| format!("Couldn't write {}: {}", path.display(), e))
+}
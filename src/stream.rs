@@ -0,0 +1,172 @@
+use std::io::Write;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+
+use crate::backend::{Backend, ConnectionStatus, Network, SavedNetwork};
+use crate::event::{self, Event, MonitorChange};
+use crate::units::Units;
+
+/// One line of `wifi-tui events` output.
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum StreamEvent {
+    Scan { networks: Vec<Network> },
+    Status(ConnectionStatus),
+    Connected { ssid: String },
+    Disconnected { ssid: String },
+    Saved { networks: Vec<SavedNetwork> },
+    Error { message: String },
+}
+
+#[derive(Serialize)]
+struct Record<'a> {
+    /// Unix time in seconds.
+    time: u64,
+    #[serde(flatten)]
+    event: &'a StreamEvent,
+}
+
+/// `wifi-tui events`: print connection changes as NetworkManager reports them,
+/// and a scan every `refresh`, until stdout is closed. With `json` each event
+/// is one JSON object per line, for piping into other tools.
+pub fn run(backend: Box<dyn Backend>, device: &str, units: &Units, json: bool, refresh: Duration) -> Result<(), String> {
+    let (tx, rx) = mpsc::channel();
+    event::spawn_monitor(device, tx);
+
+    let mut out = std::io::stdout().lock();
+    let mut status = ConnectionStatus::default();
+    let mut emit = |event: StreamEvent| -> bool {
+        let line = if json {
+            let time = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs());
+            serde_json::to_string(&Record { time, event: &event }).unwrap_or_default()
+        } else {
+            describe(&event, units)
+        };
+        // A closed pipe (e.g. `| head`) ends the stream
+        writeln!(out, "{}", line).and_then(|_| out.flush()).is_ok()
+    };
+
+    let mut next_scan = Instant::now();
+    loop {
+        let mut changes = Vec::new();
+        if Instant::now() >= next_scan {
+            let event = match backend.scan_networks(device) {
+                Ok(networks) => StreamEvent::Scan { networks },
+                Err(message) => StreamEvent::Error { message },
+            };
+            if !emit(event) {
+                return Ok(());
+            }
+            // Also polls the status for backends without `nmcli monitor` (iwd)
+            changes.push(MonitorChange::Status);
+            next_scan = Instant::now() + refresh;
+        }
+
+        let wait = next_scan.saturating_duration_since(Instant::now());
+        match rx.recv_timeout(wait) {
+            Ok(Event::NetworkChanged(change)) => changes.push(change),
+            Ok(_) | Err(RecvTimeoutError::Timeout) => {}
+            // No monitor running
+            Err(RecvTimeoutError::Disconnected) => thread::sleep(wait),
+        }
+        changes.dedup();
+
+        for change in changes {
+            let events = match change {
+                // An unplugged adapter shows up as a disconnect
+                MonitorChange::Status | MonitorChange::Devices => {
+                    let new = backend.get_status(device);
+                    let events = status_events(&status, &new);
+                    status = new;
+                    events
+                }
+                MonitorChange::Saved => vec![match backend.saved_networks() {
+                    Ok(networks) => StreamEvent::Saved { networks },
+                    Err(message) => StreamEvent::Error { message },
+                }],
+            };
+            for event in events {
+                if !emit(event) {
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+/// Events for a status update: connected/disconnected when the network
+/// changed, then the new status if anything changed at all.
+fn status_events(old: &ConnectionStatus, new: &ConnectionStatus) -> Vec<StreamEvent> {
+    let mut events = Vec::new();
+    if old.ssid != new.ssid {
+        if let Some(ssid) = &old.ssid {
+            events.push(StreamEvent::Disconnected { ssid: ssid.clone() });
+        }
+        if let Some(ssid) = &new.ssid {
+            events.push(StreamEvent::Connected { ssid: ssid.clone() });
+        }
+    }
+    if old != new {
+        events.push(StreamEvent::Status(new.clone()));
+    }
+    events
+}
+
+/// Plain-text form of an event.
+fn describe(event: &StreamEvent, units: &Units) -> String {
+    match event {
+        StreamEvent::Scan { networks } => format!("scan: {} networks", networks.len()),
+        StreamEvent::Status(status) => format!(
+            "status: {} signal={} ip={}",
+            status.ssid.as_deref().unwrap_or("not connected"),
+            status.signal.map_or("-".to_string(), |s| units.link_signal(s, status.dbm)),
+            status.ip.as_deref().unwrap_or("-"),
+        ),
+        StreamEvent::Connected { ssid } => format!("connected: {}", ssid),
+        StreamEvent::Disconnected { ssid } => format!("disconnected: {}", ssid),
+        StreamEvent::Saved { networks } => format!("saved: {} profiles", networks.len()),
+        StreamEvent::Error { message } => format!("error: {}", message),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(ssid: Option<&str>, signal: Option<u8>) -> ConnectionStatus {
+        ConnectionStatus {
+            ssid: ssid.map(str::to_string),
+            signal,
+            ..ConnectionStatus::default()
+        }
+    }
+
+    #[test]
+    fn test_status_events() {
+        let home = status(Some("Home"), Some(70));
+        let names = |events: Vec<StreamEvent>| -> Vec<String> {
+            events.iter().map(|event| describe(event, &Units::default())).collect()
+        };
+        assert!(status_events(&home, &home).is_empty());
+        assert_eq!(
+            names(status_events(&home, &status(Some("Home"), Some(50)))),
+            ["status: Home signal=50% ip=-"]
+        );
+        assert_eq!(
+            names(status_events(&home, &status(Some("Cafe"), Some(60)))),
+            ["disconnected: Home", "connected: Cafe", "status: Cafe signal=60% ip=-"]
+        );
+    }
+
+    #[test]
+    fn test_json_record() {
+        let event = StreamEvent::Connected { ssid: "Home".to_string() };
+        let line = serde_json::to_string(&Record { time: 1, event: &event }).unwrap();
+        assert_eq!(line, r#"{"time":1,"event":"connected","ssid":"Home"}"#);
+    }
+}
@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+
+use ratatui::style::Color;
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer};
+
+/// Built-in themes, for `theme = "..."` and `--theme`.
+pub const THEME_NAMES: [&str; 4] = ["default", "solarized", "gruvbox", "monochrome"];
+
+/// UI palette used by every `draw_*` function.
+#[derive(Debug, Clone)]
+pub struct Theme {
+    /// Connected networks, strong signal, success messages.
+    pub good: Color,
+    /// Busy status, medium signal, warnings, bandwidth limits.
+    pub warn: Color,
+    /// Errors and weak signal.
+    pub bad: Color,
+    /// IP address, speed and other details.
+    pub info: Color,
+    /// Mode badges in the status bar.
+    pub accent: Color,
+    pub text: Color,
+    /// Secondary text.
+    pub dim: Color,
+    /// Hints, borders and inactive items.
+    pub muted: Color,
+    /// Background of the selected row.
+    pub highlight: Color,
+    /// Selected text in inputs.
+    pub selection: Color,
+    /// Key names in the help bar.
+    pub key: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            good: Color::Green,
+            warn: Color::Yellow,
+            bad: Color::Red,
+            info: Color::Cyan,
+            accent: Color::Magenta,
+            text: Color::White,
+            dim: Color::Gray,
+            muted: Color::DarkGray,
+            highlight: Color::Indexed(236),
+            selection: Color::Blue,
+            key: Color::Indexed(248),
+        }
+    }
+}
+
+impl Theme {
+    /// A built-in theme by name (see `THEME_NAMES`).
+    pub fn named(name: &str) -> Option<Self> {
+        match name {
+            "default" => Some(Self::default()),
+            "solarized" => Some(Self {
+                good: Color::Rgb(0x85, 0x99, 0x00),
+                warn: Color::Rgb(0xb5, 0x89, 0x00),
+                bad: Color::Rgb(0xdc, 0x32, 0x2f),
+                info: Color::Rgb(0x2a, 0xa1, 0x98),
+                accent: Color::Rgb(0xd3, 0x36, 0x82),
+                text: Color::Rgb(0x93, 0xa1, 0xa1),
+                dim: Color::Rgb(0x83, 0x94, 0x96),
+                muted: Color::Rgb(0x58, 0x6e, 0x75),
+                highlight: Color::Rgb(0x07, 0x36, 0x42),
+                selection: Color::Rgb(0x26, 0x8b, 0xd2),
+                key: Color::Rgb(0xee, 0xe8, 0xd5),
+            }),
+            "gruvbox" => Some(Self {
+                good: Color::Rgb(0xb8, 0xbb, 0x26),
+                warn: Color::Rgb(0xfa, 0xbd, 0x2f),
+                bad: Color::Rgb(0xfb, 0x49, 0x34),
+                info: Color::Rgb(0x8e, 0xc0, 0x7c),
+                accent: Color::Rgb(0xd3, 0x86, 0x9b),
+                text: Color::Rgb(0xeb, 0xdb, 0xb2),
+                dim: Color::Rgb(0xa8, 0x99, 0x84),
+                muted: Color::Rgb(0x7c, 0x6f, 0x64),
+                highlight: Color::Rgb(0x3c, 0x38, 0x36),
+                selection: Color::Rgb(0x45, 0x85, 0x88),
+                key: Color::Rgb(0xd5, 0xc4, 0xa1),
+            }),
+            // For terminals without color, or people who'd rather not
+            "monochrome" => Some(Self {
+                good: Color::White,
+                warn: Color::Gray,
+                bad: Color::White,
+                info: Color::Gray,
+                accent: Color::White,
+                text: Color::White,
+                dim: Color::Gray,
+                muted: Color::DarkGray,
+                highlight: Color::DarkGray,
+                selection: Color::Gray,
+                key: Color::White,
+            }),
+            _ => None,
+        }
+    }
+
+    /// A built-in theme with the user's `[colors]` on top.
+    pub fn with_colors(mut self, colors: &HashMap<ColorRole, ThemeColor>) -> Self {
+        for (role, ThemeColor(color)) in colors {
+            let slot = match role {
+                ColorRole::Good => &mut self.good,
+                ColorRole::Warn => &mut self.warn,
+                ColorRole::Bad => &mut self.bad,
+                ColorRole::Info => &mut self.info,
+                ColorRole::Accent => &mut self.accent,
+                ColorRole::Text => &mut self.text,
+                ColorRole::Dim => &mut self.dim,
+                ColorRole::Muted => &mut self.muted,
+                ColorRole::Highlight => &mut self.highlight,
+                ColorRole::Selection => &mut self.selection,
+                ColorRole::Key => &mut self.key,
+            };
+            *slot = *color;
+        }
+        self
+    }
+}
+
+/// A `Theme` field, as named in the `[colors]` table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColorRole {
+    Good,
+    Warn,
+    Bad,
+    Info,
+    Accent,
+    Text,
+    Dim,
+    Muted,
+    Highlight,
+    Selection,
+    Key,
+}
+
+/// A color name ("green", "lightblue"), "#rrggbb" or a 256-color index ("236").
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ThemeColor(pub Color);
+
+impl<'de> Deserialize<'de> for ThemeColor {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        name.parse()
+            .map(ThemeColor)
+            .map_err(|_| D::Error::custom(format!("unknown color '{}'", name)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_named() {
+        for name in THEME_NAMES {
+            assert!(Theme::named(name).is_some(), "{}", name);
+        }
+        assert!(Theme::named("neon").is_none());
+    }
+
+    #[test]
+    fn test_with_colors() {
+        let colors = HashMap::from([(ColorRole::Bad, ThemeColor(Color::Indexed(9)))]);
+        let theme = Theme::named("gruvbox").unwrap().with_colors(&colors);
+        assert_eq!(theme.bad, Color::Indexed(9));
+        assert_eq!(theme.good, Color::Rgb(0xb8, 0xbb, 0x26));
+    }
+}
@@ -1,27 +1,63 @@
+use std::cell::{Cell, RefMut};
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::time::{Duration, Instant};
+
+use ratatui::crossterm::event::KeyModifiers;
 use ratatui::layout::{Alignment, Constraint, Layout, Rect};
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
+use ratatui::symbols;
 use ratatui::widgets::{
-    Block, Borders, Clear, List, ListItem, Padding, Paragraph, Tabs, Wrap,
+    Axis, Bar, BarChart, Block, Borders, Chart, Clear, Dataset, GraphType, List, ListItem, ListState, Padding,
+    Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState, Sparkline, Tabs, Wrap,
 };
 use ratatui::Frame;
 
-use crate::app::{App, BgStatus, Modal, View};
+use crate::app::{
+    ActionMenu, App, BgStatus, FormKind, MessageKind, Modal, MonitorSample, NetRow, SavedSort, StatusLine,
+    StatusTarget, View, MONITOR_WINDOWS,
+};
+use crate::analyzer::{channel_usage, clearest_channel};
+use crate::backend::{distance_hint, AccessPoint, Band, MetricChange, Network};
+use crate::checks::{connectivity_checks, Check, CheckState};
+use crate::dispatcher::DISPATCHER_DIR;
+use crate::form::FieldKind;
+use crate::history::ConnectStats;
+use crate::input::TextInput;
+use crate::internet::Internet;
+use crate::keymap::Action;
+use crate::oui;
+use crate::phy::LinkRate;
+use crate::ping::{PingReport, PingResult};
+use crate::qr::WifiQr;
+use crate::theme::Theme;
+use crate::update;
+use crate::usage::Usage;
 
 const SPINNER: &[&str] = &["◐", "◓", "◑", "◒"];
 const SSID_WIDTH: usize = 28;
+const LOG_PANEL_HEIGHT: u16 = 10;
 
 pub fn draw(frame: &mut Frame, app: &App) {
     let chunks = Layout::vertical([
-        Constraint::Length(2),  // status bar
+        Constraint::Length(1),  // status bar
         Constraint::Min(6),    // main content
         Constraint::Length(3), // help bar
     ])
     .split(frame.area());
 
     draw_status_bar(frame, app, chunks[0]);
-    draw_main(frame, app, chunks[1]);
+    if app.show_logs {
+        let main = Layout::vertical([Constraint::Min(6), Constraint::Length(LOG_PANEL_HEIGHT)])
+            .split(chunks[1]);
+        draw_main(frame, app, main[0]);
+        draw_logs(frame, app, main[1]);
+    } else {
+        draw_main(frame, app, chunks[1]);
+    }
     draw_help_bar(frame, app, chunks[2]);
+    draw_toasts(frame, app, chunks[1]);
 
     // Draw modal overlay on top if active
     if let Some(ref modal) = app.modal {
@@ -30,104 +66,474 @@ pub fn draw(frame: &mut Frame, app: &App) {
 }
 
 fn draw_status_bar(frame: &mut Frame, app: &App, area: Rect) {
-    let line = if app.status.ssid.is_some() {
-        build_status_line(app)
+    let theme = &app.theme;
+
+    // Redrawn on every tick, but only changes when the connection or a
+    // background task does; draw last frame's line by reference until then
+    let key = status_bar_key(app);
+    let mut cache = app.status_line.borrow_mut();
+    if cache.as_ref().is_none_or(|cached| cached.key != key) {
+        let mut targets = Vec::new();
+        let line = status_bar_line(app, &mut targets);
+        *cache = Some(StatusLine { key, line, targets });
+    }
+    if let Some(cached) = cache.as_ref() {
+        frame.render_widget(&cached.line, area);
+        let areas = target_areas(&cached.line, &cached.targets, area);
+        if let Some((area, _)) = areas.iter().find(|(_, target)| *target == StatusTarget::SignalTrend) {
+            draw_signal_trend(frame, app, *area);
+        }
+        *app.status_targets.borrow_mut() = areas;
+    }
+
+    let mut right = Vec::new();
+    if app.devices.len() > 1 {
+        let i = app.devices.iter().position(|device| *device == app.device).unwrap_or(0);
+        let preferred = if app.preferred_device.as_ref() == Some(&app.device) { " ★" } else { "" };
+        right.push(Span::styled(
+            format!("{}{} {}/{}", app.device, preferred, i + 1, app.devices.len()),
+            Style::default().fg(theme.info),
+        ));
+        right.push(Span::styled(
+            format!(
+                " ({}: switch, {}: prefer) ",
+                app.keys.label(Action::SwitchDevice),
+                app.keys.label(Action::PreferDevice)
+            ),
+            Style::default().fg(theme.muted),
+        ));
+    }
+    // NetworkManager (over D-Bus or nmcli) is the default; only call out alternatives
+    if !matches!(app.backend.name(), "dbus" | "nmcli") {
+        right.push(Span::styled(format!("via {} ", app.backend.name()), Style::default().fg(theme.muted)));
+    }
+    if !right.is_empty() {
+        frame.render_widget(Paragraph::new(Line::from(right)).alignment(Alignment::Right), area);
+    }
+}
+
+/// Toasts stacked in the bottom-right corner of `area`, newest at the bottom.
+fn draw_toasts(frame: &mut Frame, app: &App, area: Rect) {
+    const MAX_WIDTH: u16 = 60;
+    let theme = &app.theme;
+    let width = app
+        .toasts
+        .iter()
+        .map(|toast| toast.text.chars().count() as u16 + 4)
+        .max()
+        .unwrap_or(0)
+        .min(MAX_WIDTH)
+        .min(area.width);
+    let mut bottom = area.bottom();
+    for toast in app.toasts.iter().rev() {
+        let inner_width = width.saturating_sub(4).max(1);
+        // Long ones wrap onto more lines
+        let lines = (toast.text.chars().count() as u16).div_ceil(inner_width).max(1);
+        let height = lines + 2;
+        if bottom < area.y + height {
+            break;
+        }
+        bottom -= height;
+        let rect = Rect::new(area.right() - width, bottom, width, height);
+        let (symbol, color) = if toast.warning { ("!", theme.warn) } else { ("✓", theme.good) };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(color))
+            .title(format!(" {} ", symbol))
+            .padding(Padding::horizontal(1));
+        frame.render_widget(Clear, rect);
+        frame.render_widget(
+            Paragraph::new(toast.text.as_str())
+                .style(Style::default().fg(theme.text))
+                .wrap(Wrap { trim: true })
+                .block(block),
+            rect,
+        );
+    }
+}
+
+/// Hash of everything `status_bar_line` reads, to tell when it needs rebuilding.
+fn status_bar_key(app: &App) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    app.status.hash(&mut hasher);
+    app.bg_status.hash(&mut hasher);
+    if app.bg_status != BgStatus::Idle {
+        app.spinner_frame.hash(&mut hasher);
+    }
+    (app.quit_armed_ticks > 0).hash(&mut hasher);
+    app.call_mode.is_some().hash(&mut hasher);
+    app.uplinks.hash(&mut hasher);
+    app.sticky.bits().hash(&mut hasher);
+    app.rfkill.hash(&mut hasher);
+    app.low_data.hash(&mut hasher);
+    app.refresh_paused.hash(&mut hasher);
+    app.waiting_for_adapter.hash(&mut hasher);
+    app.connect_stage.hash(&mut hasher);
+    app.signal_history.hash(&mut hasher);
+    app.throughput.hash(&mut hasher);
+    app.ping.hash(&mut hasher);
+    app.internet.hash(&mut hasher);
+    app.units.hash(&mut hasher);
+    // For the in-use network's security
+    app.networks.iter().find(|net| net.in_use).map(|net| &net.security).hash(&mut hasher);
+    hasher.finish()
+}
+
+/// e.g. "gateway 3 ms, 1.1.1.1 24 ms (33% lost)".
+fn ping_text(app: &App, ping: &PingReport) -> String {
+    let result = |name: &str, result: &PingResult| {
+        let rtt = match result.rtt_us {
+            Some(us) => rtt_label(app, us),
+            None => "no reply".to_string(),
+        };
+        match result.loss {
+            1..=99 => format!("{} {} ({}% lost)", name, rtt, result.loss),
+            _ => format!("{} {}", name, rtt),
+        }
+    };
+    let parts: Vec<String> = [("gateway", ping.gateway), (app.ping_host.as_str(), ping.internet)]
+        .into_iter()
+        .filter_map(|(name, measured)| Some(result(name, &measured?)))
+        .collect();
+    parts.join(", ")
+}
+
+/// A round trip in whole milliseconds, e.g. "24 ms".
+fn rtt_label(app: &App, us: u32) -> String {
+    if us < 1000 {
+        "<1 ms".to_string()
     } else {
-        let mut spans = vec![Span::styled(
-            " Not connected",
-            Style::default().fg(Color::DarkGray),
-        )];
+        format!("{} ms", app.units.integer(u64::from(us + 500) / 1000))
+    }
+}
+
+/// Bad when a host doesn't answer at all, a warning when pings get lost or slow.
+fn ping_color(theme: &Theme, ping: &PingReport) -> Color {
+    let results = || [ping.gateway, ping.internet].into_iter().flatten();
+    if results().any(|result| result.rtt_us.is_none()) {
+        theme.bad
+    } else if results().any(|result| result.loss > 0 || result.rtt_us.is_some_and(|us| us > 200_000)) {
+        theme.warn
+    } else {
+        theme.info
+    }
+}
+
+/// Most recent signal readings shown next to the percentage.
+const SIGNAL_TREND_LEN: usize = 12;
+
+/// Whether the link is getting better or worse, at a glance.
+fn draw_signal_trend(frame: &mut Frame, app: &App, area: Rect) {
+    let history = &app.signal_history;
+    let skip = history.len().saturating_sub(area.width as usize);
+    let data: Vec<u64> = history.iter().skip(skip).map(|&signal| u64::from(signal)).collect();
+    let now = history.back().copied().unwrap_or(0);
+    let sparkline = Sparkline::default()
+        .data(&data)
+        .max(100)
+        .style(Style::default().fg(signal_color(&app.theme, now)));
+    frame.render_widget(sparkline, area);
+}
+
+/// Screen cells of the spans listed in `targets`, clipped to `area`.
+fn target_areas(line: &Line, targets: &[(usize, StatusTarget)], area: Rect) -> Vec<(Rect, StatusTarget)> {
+    let mut x = area.x;
+    let mut starts = Vec::with_capacity(line.spans.len());
+    for span in &line.spans {
+        starts.push(x);
+        x = x.saturating_add(span.width() as u16);
+    }
+    targets
+        .iter()
+        .filter_map(|&(i, target)| {
+            let start = *starts.get(i)?;
+            let width = (line.spans[i].width() as u16).min(area.right().saturating_sub(start));
+            (width > 0).then(|| (Rect::new(start, area.y, width, 1), target))
+        })
+        .collect()
+}
+
+fn status_bar_line(app: &App, targets: &mut Vec<(usize, StatusTarget)>) -> Line<'static> {
+    let theme = &app.theme;
+    let mut line = if app.status.ssid.is_some() {
+        build_status_line(app, targets)
+    } else {
+        let text = if app.waiting_for_adapter {
+            " Waiting for a WiFi adapter..."
+        } else {
+            " Not connected"
+        };
+        let mut spans = vec![Span::styled(text, Style::default().fg(theme.muted))];
         if let Some(bg_text) = bg_status_text(app) {
             spans.push(Span::raw("  │  "));
-            spans.push(Span::styled(bg_text, Style::default().fg(Color::Yellow)));
+            targets.push((spans.len(), StatusTarget::Spinner));
+            spans.push(Span::styled(bg_text, Style::default().fg(theme.warn)));
         }
         Line::from(spans)
     };
+    if app.call_mode.is_some() {
+        line.spans.push(Span::raw("  │  "));
+        line.spans.push(Span::styled(
+            "CALL MODE",
+            Style::default().fg(theme.info).add_modifier(Modifier::BOLD),
+        ));
+    }
+    if let Some((wired, wifi)) = app.dual_uplink() {
+        let (via, other) = if app.uplinks.first() == Some(wired) {
+            (format!("Internet via wired ({})", wired.device), "WiFi")
+        } else {
+            (format!("Internet via WiFi ({})", wifi.device), "wired")
+        };
+        line.spans.push(Span::raw("  │  "));
+        line.spans.push(Span::styled(via, Style::default().fg(theme.info)));
+        line.spans.push(Span::styled(
+            format!(" ({}: prefer {})", app.keys.label(Action::PreferUplink), other),
+            Style::default().fg(theme.muted),
+        ));
+    }
+    for (modifier, name) in [(KeyModifiers::CONTROL, "CTRL"), (KeyModifiers::SHIFT, "SHIFT")] {
+        if app.sticky.contains(modifier) {
+            line.spans.push(Span::raw("  │  "));
+            line.spans.push(Span::styled(
+                format!("{} held", name),
+                Style::default().fg(theme.warn).add_modifier(Modifier::BOLD),
+            ));
+        }
+    }
+    if app.rfkill.blocked() {
+        line.spans.push(Span::raw("  │  "));
+        line.spans.push(Span::styled(
+            "RADIO OFF",
+            Style::default().fg(theme.bad).add_modifier(Modifier::BOLD),
+        ));
+        let hint = if app.rfkill.hard {
+            " (hardware switch)".to_string()
+        } else {
+            format!(" ({}: turn on)", app.keys.label(Action::Unblock))
+        };
+        line.spans.push(Span::styled(hint, Style::default().fg(theme.muted)));
+    }
+    if app.low_data {
+        line.spans.push(Span::raw("  │  "));
+        line.spans.push(Span::styled(
+            "LOW DATA",
+            Style::default().fg(theme.accent).add_modifier(Modifier::BOLD),
+        ));
+    }
+    if app.refresh_paused {
+        line.spans.push(Span::raw("  │  "));
+        line.spans.push(Span::styled("SCANS PAUSED", Style::default().fg(theme.warn).add_modifier(Modifier::BOLD)));
+        line.spans.push(Span::styled(
+            format!(" ({}: resume)", app.keys.label(Action::PauseRefresh)),
+            Style::default().fg(theme.muted),
+        ));
+    }
 
-    let chunks = Layout::vertical([Constraint::Length(1), Constraint::Length(1)]).split(area);
-
-    let paragraph = Paragraph::new(line);
-    frame.render_widget(paragraph, chunks[0]);
+    line
 }
 
-fn build_status_line(app: &App) -> Line<'static> {
+fn build_status_line(app: &App, targets: &mut Vec<(usize, StatusTarget)>) -> Line<'static> {
+    let theme = &app.theme;
     let mut spans = Vec::new();
 
     if let Some(ref ssid) = app.status.ssid {
+        // The scan's in-use entry; the status itself doesn't say
+        let weakness = app.networks.iter().find(|net| net.in_use && net.ssid == *ssid).and_then(Network::weakness);
+        let (state, color) = match app.internet {
+            Some(Internet::Offline) => (" (no internet)", theme.warn),
+            // Something is answering in the internet's place
+            Some(Internet::CaptivePortal(_)) => (" (sign-in needed)", theme.bad),
+            _ if weakness.is_some() => ("", theme.bad),
+            Some(Internet::Online) | None => ("", theme.good),
+        };
+        targets.push((spans.len(), StatusTarget::Ssid));
         spans.push(Span::styled(
-            format!(" Connected: {}", ssid),
+            format!(" Connected{}: {}", state, ssid_text(ssid)),
             Style::default()
-                .fg(Color::Green)
+                .fg(color)
                 .add_modifier(Modifier::BOLD),
         ));
+        if let Some(weakness) = weakness {
+            spans.push(Span::styled(
+                format!(" ⚠ {}", weakness),
+                Style::default().fg(theme.bad).add_modifier(Modifier::BOLD),
+            ));
+        }
     }
 
     if let Some(signal) = app.status.signal {
         spans.push(Span::raw("  │  "));
-        let color = signal_color(signal);
+        let color = signal_color(theme, signal);
+        targets.push((spans.len(), StatusTarget::Signal));
         spans.push(Span::styled(
-            format!("Signal: {} {}%", signal_bars(signal), signal),
+            format!(
+                "Signal: {} {}",
+                signal_indicator(app, signal).trim_end(),
+                app.units.link_signal(signal, app.status.dbm)
+            ),
             Style::default().fg(color),
         ));
+        // A single reading has no trend yet
+        let trend = app.signal_history.len().min(SIGNAL_TREND_LEN);
+        if trend > 1 {
+            spans.push(Span::raw(" "));
+            targets.push((spans.len(), StatusTarget::SignalTrend));
+            spans.push(Span::raw(" ".repeat(trend)));
+        }
     }
 
     if let Some(ref ip) = app.status.ip {
         spans.push(Span::raw("  │  "));
         spans.push(Span::styled(
             format!("IP: {}", ip),
-            Style::default().fg(Color::Cyan),
+            Style::default().fg(theme.info),
+        ));
+    }
+
+    if let Some(bitrate) = app.status.bitrate {
+        spans.push(Span::raw("  │  "));
+        spans.push(Span::styled(
+            format!("Speed: {}", app.units.bitrate(bitrate.into())),
+            Style::default().fg(theme.info),
         ));
     }
 
-    if let Some(ref speed) = app.status.speed {
+    if let Some(throughput) = app.throughput {
         spans.push(Span::raw("  │  "));
         spans.push(Span::styled(
-            format!("Speed: {}", speed),
-            Style::default().fg(Color::Cyan),
+            format!(
+                "↓ {}  ↑ {}",
+                app.units.byte_rate(throughput.rx),
+                app.units.byte_rate(throughput.tx)
+            ),
+            Style::default().fg(theme.info),
+        ));
+    }
+
+    if let Some(ping) = app.ping.filter(|ping| ping.gateway.is_some() || ping.internet.is_some()) {
+        spans.push(Span::raw("  │  "));
+        spans.push(Span::styled(
+            format!("Ping: {}", ping_text(app, &ping)),
+            Style::default().fg(ping_color(theme, &ping)),
         ));
     }
 
     if let Some(bg_text) = bg_status_text(app) {
         spans.push(Span::raw("  │  "));
-        spans.push(Span::styled(bg_text, Style::default().fg(Color::Yellow)));
+        targets.push((spans.len(), StatusTarget::Spinner));
+        spans.push(Span::styled(bg_text, Style::default().fg(theme.warn)));
     }
 
     Line::from(spans)
 }
 
 fn bg_status_text(app: &App) -> Option<String> {
-    match app.bg_status {
+    if app.quit_armed_ticks > 0 {
+        return Some("Operation in progress - press q again to quit".to_string());
+    }
+    let text = match app.bg_status {
         BgStatus::Idle => None,
         BgStatus::Scanning => Some(format!("{} Scanning...", SPINNER[app.spinner_frame])),
-        BgStatus::Connecting => Some(format!("{} Connecting...", SPINNER[app.spinner_frame])),
+        BgStatus::Refreshing => Some(format!("{} Refreshing...", SPINNER[app.spinner_frame])),
+        BgStatus::Connecting => Some(match app.connect_stage {
+            Some(stage) => format!("{} Connecting: {}...", SPINNER[app.spinner_frame], stage.label()),
+            None => format!("{} Connecting...", SPINNER[app.spinner_frame]),
+        }),
         BgStatus::Disconnecting => Some(format!("{} Disconnecting...", SPINNER[app.spinner_frame])),
         BgStatus::Forgetting => Some(format!("{} Forgetting...", SPINNER[app.spinner_frame])),
+        BgStatus::Checking => Some(format!("{} Checking...", SPINNER[app.spinner_frame])),
+        BgStatus::Saving => Some(format!("{} Saving...", SPINNER[app.spinner_frame])),
+        BgStatus::StartingHotspot => {
+            Some(format!("{} Starting hotspot...", SPINNER[app.spinner_frame]))
+        }
+        BgStatus::StoppingHotspot => {
+            Some(format!("{} Stopping hotspot...", SPINNER[app.spinner_frame]))
+        }
+        BgStatus::ReadingPassword => {
+            Some(format!("{} Reading password...", SPINNER[app.spinner_frame]))
+        }
+        BgStatus::Unblocking => {
+            Some(format!("{} Turning the radio on...", SPINNER[app.spinner_frame]))
+        }
+    };
+    if app.bg_status.cancellable() {
+        return text.map(|text| format!("{} (Esc cancels)", text));
     }
+    text
 }
 
 fn draw_main(frame: &mut Frame, app: &App, area: Rect) {
-    let tab_labels = vec![
-        format!(" Available ({}) ", app.networks.len()),
-        format!(" Saved ({}) ", app.saved.len()),
-    ];
-    let selected = match app.view {
-        View::AvailableNetworks => 0,
-        View::SavedNetworks => 1,
+    let theme = &app.theme;
+    let count = |filter: &TextInput, shown: usize, total: usize| {
+        if !filter.value().is_empty() {
+            format!("{}/{}", shown, total)
+        } else {
+            total.to_string()
+        }
     };
+    let shown_networks = app
+        .available_rows()
+        .iter()
+        .filter(|row| matches!(row, NetRow::Network(_)))
+        .count();
+    // Last run's lists, until the first scan is back
+    let cached = if app.stale { ", cached" } else { "" };
+    let tab_labels: Vec<String> = app
+        .tabs
+        .iter()
+        .map(|view| match view {
+            View::AvailableNetworks => match app.band_filter {
+                Some(band) => format!(
+                    " Available ({}/{}, {}) by {}{} ",
+                    shown_networks,
+                    app.networks.len(),
+                    band.label(),
+                    app.sort_mode.label(),
+                    cached
+                ),
+                None => format!(
+                    " Available ({}) by {}{} ",
+                    count(&app.available_filter, shown_networks, app.networks.len()),
+                    app.sort_mode.label(),
+                    cached
+                ),
+            },
+            View::SavedNetworks => {
+                let shown = count(&app.saved_filter, app.visible_saved().len(), app.saved.len());
+                match app.saved_sort {
+                    SavedSort::Default => format!(" Saved ({}){} ", shown, cached),
+                    sort => format!(" Saved ({}) by {}{} ", shown, sort.label(), cached),
+                }
+            }
+            View::Hotspot if app.active_hotspot().is_some() => " Hotspot (on) ".to_string(),
+            View::Hotspot => " Hotspot ".to_string(),
+            View::Monitor => format!(" Monitor ({}) ", window_label(MONITOR_WINDOWS[app.monitor_window])),
+            View::Analyzer => format!(" Analyzer ({}) ", app.analyzer_band.label()),
+        })
+        .collect();
+    let selected = app.tabs.iter().position(|view| *view == app.view).unwrap_or(0);
 
     let tabs = Tabs::new(tab_labels)
         .select(selected)
-        .style(Style::default().fg(Color::DarkGray))
+        .style(Style::default().fg(theme.muted))
         .highlight_style(
             Style::default()
-                .fg(Color::White)
+                .fg(theme.text)
                 .add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
         )
         .divider("│");
 
+    let version = match &app.newer_version {
+        Some(latest) => Span::styled(
+            format!(" v{} ({} available) ", update::VERSION, latest),
+            Style::default().fg(theme.warn),
+        ),
+        None => Span::styled(format!(" v{} ", update::VERSION), Style::default().fg(theme.muted)),
+    };
     let block = Block::default()
         .borders(Borders::ALL)
-        .padding(Padding::horizontal(1));
+        .padding(Padding::horizontal(1))
+        .title_bottom(Line::from(version).right_aligned());
 
     let inner = block.inner(area);
 
@@ -136,174 +542,806 @@ fn draw_main(frame: &mut Frame, app: &App, area: Rect) {
     frame.render_widget(block, area);
     frame.render_widget(tabs, tab_chunks[0]);
 
+    let mut list_area = tab_chunks[1];
+    if app.view.has_filter() && (app.filtering || !app.filter().value().is_empty()) {
+        let chunks = Layout::vertical([Constraint::Length(1), Constraint::Min(1)]).split(list_area);
+        let mut spans = vec![Span::styled("Filter:", Style::default().fg(theme.warn))];
+        let width = (chunks[0].width as usize).saturating_sub(9);
+        spans.extend(input_spans(theme, app.filter(), false, app.filtering, width));
+        frame.render_widget(Paragraph::new(Line::from(spans)), chunks[0]);
+        list_area = chunks[1];
+    }
+
     match app.view {
-        View::AvailableNetworks => draw_available_networks(frame, app, tab_chunks[1]),
-        View::SavedNetworks => draw_saved_networks(frame, app, tab_chunks[1]),
+        View::AvailableNetworks => draw_available_networks(frame, app, list_area),
+        View::SavedNetworks => draw_saved_networks(frame, app, list_area),
+        View::Hotspot => draw_hotspot(frame, app, tab_chunks[1]),
+        View::Monitor => draw_monitor(frame, app, tab_chunks[1]),
+        View::Analyzer => draw_analyzer(frame, app, tab_chunks[1]),
     }
 }
 
 fn draw_available_networks(frame: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
     if app.networks.is_empty() {
-        let text = if app.bg_status == BgStatus::Scanning {
-            "Scanning for networks..."
+        let text = if app.waiting_for_adapter {
+            "The WiFi adapter went away. Plug one in to continue.".to_string()
+        } else if app.rfkill.hard {
+            "The WiFi radio is off at a hardware switch or key.".to_string()
+        } else if app.rfkill.soft {
+            format!("The WiFi radio is off (airplane mode). Press {} to turn it on.", app.keys.label(Action::Unblock))
+        } else if app.bg_status == BgStatus::Scanning {
+            "Scanning for networks...".to_string()
         } else {
-            "No networks found. Press R to scan."
+            format!("No networks found. Press {} to scan.", app.keys.label(Action::Refresh))
         };
         let paragraph = Paragraph::new(text)
-            .style(Style::default().fg(Color::DarkGray))
+            .style(Style::default().fg(theme.muted))
             .alignment(Alignment::Center);
         frame.render_widget(paragraph, area);
         return;
     }
 
-    let items: Vec<ListItem> = app
-        .networks
-        .iter()
-        .enumerate()
-        .map(|(i, net)| {
-            let selected = i == app.net_index;
-            let marker = if net.in_use { "● " } else { "  " };
-            let color = signal_color(net.signal);
-            let is_open = net.security.is_empty() || net.security == "--";
-
-            let security_text = if is_open {
-                "Open".to_string()
-            } else {
-                simplify_security(&net.security)
-            };
+    let rows = app.available_rows();
+    if rows.is_empty() {
+        draw_no_matches(frame, app, area);
+        return;
+    }
 
-            let line = Line::from(vec![
-                Span::styled(
-                    marker.to_string(),
-                    Style::default().fg(Color::Green),
-                ),
-                Span::styled(
-                    truncate_pad(&net.ssid, SSID_WIDTH),
-                    if selected {
-                        Style::default()
-                            .fg(Color::White)
-                            .add_modifier(Modifier::BOLD)
-                    } else {
-                        Style::default().fg(Color::White)
-                    },
-                ),
-                Span::styled(
-                    format!(" {}  {:>3}%", signal_bars(net.signal), net.signal),
-                    Style::default().fg(color),
-                ),
-                Span::styled(
-                    format!("  {}", security_text),
-                    if is_open {
-                        Style::default().fg(Color::Yellow)
-                    } else if selected {
-                        Style::default().fg(Color::Gray)
-                    } else {
-                        Style::default().fg(Color::DarkGray)
-                    },
-                ),
-            ]);
+    let mut cache = RowCache::new(app);
+    draw_list(frame, app, area, &app.net_list, rows.len(), app.net_index, |i| {
+        let selected = i == app.net_index;
+        match rows[i] {
+            NetRow::Network(n) => {
+                let net = &app.networks[n];
+                let expanded = app.expanded.contains(&net.ssid);
+                let key = (
+                    ("net", &net.ssid, net.signal, net.in_use, &net.security),
+                    (net.access_points.len(), net.has_6ghz(), expanded, selected),
+                );
+                cache.row(key, || network_item(app, net, selected))
+            }
+            NetRow::AccessPoint(n, ap) => {
+                let ap = &app.networks[n].access_points[ap];
+                let key = ("ap", &ap.bssid, ap.frequency, ap.signal, ap.in_use, selected);
+                cache.row(key, || access_point_item(app, ap, selected))
+            }
+        }
+    });
+    cache.finish();
+}
+
+/// Draw a `len`-row list scrolled so `selected` is on screen, with a
+/// scrollbar when it doesn't all fit. Only the rows on screen are built by
+/// `item`; 200+ BSSIDs is common in apartment blocks.
+fn draw_list<'a>(
+    frame: &mut Frame,
+    app: &App,
+    area: Rect,
+    state: &Cell<ListState>,
+    len: usize,
+    selected: usize,
+    mut item: impl FnMut(usize) -> ListItem<'a>,
+) {
+    let height = area.height as usize;
+    app.list_height.set(height);
+    let overflows = len > height;
+    let list_area = if overflows { Rect { width: area.width.saturating_sub(1), ..area } } else { area };
+
+    // The list moves its offset the same way when rendering; working it out
+    // first tells which rows need building
+    let mut list_state = state.get().with_selected(Some(selected));
+    let offset = scroll_offset(list_state.offset(), selected, len, height);
+    *list_state.offset_mut() = offset;
+    let items: Vec<ListItem> = (0..len)
+        .map(|i| if (offset..offset + height).contains(&i) { item(i) } else { ListItem::new("") })
+        .collect();
+    frame.render_stateful_widget(List::new(items), list_area, &mut list_state);
+    state.set(list_state);
+
+    if overflows {
+        let mut scrollbar = ScrollbarState::new(len - height + 1).position(list_state.offset());
+        let bar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+            .begin_symbol(None)
+            .end_symbol(None)
+            .track_style(Style::default().fg(app.theme.dim))
+            .thumb_style(Style::default().fg(app.theme.muted));
+        frame.render_stateful_widget(bar, area, &mut scrollbar);
+    }
+}
+
+/// Last frame's rows from `App::row_cache`, handing back unchanged ones
+/// instead of rebuilding them. Rows not drawn this frame are dropped.
+struct RowCache<'a> {
+    last: RefMut<'a, HashMap<u64, ListItem<'static>>>,
+    next: HashMap<u64, ListItem<'static>>,
+}
+
+impl<'a> RowCache<'a> {
+    fn new(app: &'a App) -> Self {
+        RowCache {
+            last: app.row_cache.borrow_mut(),
+            next: HashMap::new(),
+        }
+    }
+
+    /// The row drawn from `key`, built only if last frame didn't draw it.
+    fn row(&mut self, key: impl Hash, build: impl FnOnce() -> ListItem<'static>) -> ListItem<'static> {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        let key = hasher.finish();
+        let item = self.last.remove(&key).unwrap_or_else(build);
+        self.next.insert(key, item.clone());
+        item
+    }
+
+    fn finish(mut self) {
+        *self.last = std::mem::take(&mut self.next);
+    }
+}
 
+/// First row to draw so `selected` is on screen, moving `offset` no further
+/// than needed.
+fn scroll_offset(offset: usize, selected: usize, len: usize, height: usize) -> usize {
+    let offset = offset.min(len.saturating_sub(height));
+    if selected < offset {
+        selected
+    } else if selected >= offset + height {
+        selected + 1 - height.max(1)
+    } else {
+        offset
+    }
+}
+
+fn network_item(app: &App, net: &Network, selected: bool) -> ListItem<'static> {
+    let theme = &app.theme;
+    let marker = if net.in_use { "● " } else { "  " };
+    let expander = match net.access_points.len() {
+        0 | 1 => "  ",
+        _ if app.expanded.contains(&net.ssid) => "▾ ",
+        _ => "▸ ",
+    };
+    let color = signal_color(theme, net.signal);
+    let is_open = net.is_open();
+
+    let security_text = if is_open {
+        "Open".to_string()
+    } else {
+        simplify_security(&net.security)
+    };
+
+    let line = Line::from(vec![
+        Span::styled(
+            marker.to_string(),
+            Style::default().fg(theme.good),
+        ),
+        Span::styled(expander, Style::default().fg(theme.muted)),
+        Span::styled(
+            truncate_pad(&ssid_text(&net.ssid), SSID_WIDTH - 2),
             if selected {
-                ListItem::new(line).style(Style::default().bg(Color::Indexed(236)))
+                Style::default()
+                    .fg(theme.text)
+                    .add_modifier(Modifier::BOLD)
             } else {
-                ListItem::new(line)
-            }
-        })
-        .collect();
+                Style::default().fg(theme.text)
+            },
+        ),
+        Span::styled(
+            format!(" {}  {:>4}", signal_indicator(app, net.signal), app.units.signal(net.signal)),
+            Style::default().fg(color),
+        ),
+        Span::styled(
+            format!("  {:<7}", bands_text(&net.bands())),
+            Style::default().fg(theme.info),
+        ),
+        Span::styled(
+            format!("  {}", security_text),
+            if is_open {
+                Style::default().fg(theme.warn)
+            } else if selected {
+                Style::default().fg(theme.dim)
+            } else {
+                Style::default().fg(theme.muted)
+            },
+        ),
+    ]);
 
-    let list = List::new(items);
-    frame.render_widget(list, area);
+    if selected {
+        ListItem::new(line).style(Style::default().bg(theme.highlight))
+    } else {
+        ListItem::new(line)
+    }
+}
+
+/// The band column: "2.4", "5", or "2.4/5" for a band-steered network.
+fn bands_text(bands: &[Band]) -> String {
+    bands.iter().map(|band| band.short_label()).collect::<Vec<_>>().join("/")
+}
+
+/// An expanded network's access point: BSSID, channel, signal and band.
+fn access_point_item(app: &App, ap: &AccessPoint, selected: bool) -> ListItem<'static> {
+    let theme = &app.theme;
+    let channel = ap.channel.map(|c| format!("ch {}", c)).unwrap_or_default();
+    let band: Vec<Band> = ap.frequency.and_then(Band::of).into_iter().collect();
+    let line = Line::from(vec![
+        Span::styled(
+            if ap.in_use { "●   └ " } else { "    └ " },
+            Style::default().fg(if ap.in_use { theme.good } else { theme.muted }),
+        ),
+        Span::styled(
+            truncate_pad(&format!("{}  {}", ap.bssid, channel), SSID_WIDTH - 2),
+            Style::default().fg(if selected { theme.text } else { theme.dim }),
+        ),
+        Span::styled(
+            format!(" {}  {:>4}", signal_indicator(app, ap.signal), app.units.signal(ap.signal)),
+            Style::default().fg(signal_color(theme, ap.signal)),
+        ),
+        Span::styled(format!("  {:<7}", bands_text(&band)), Style::default().fg(theme.info)),
+        // Tells repeaters of one network apart
+        Span::styled(
+            match oui::vendor(&ap.bssid) {
+                Some(vendor) => format!("  {}  {}", distance_hint(ap.signal, ap.frequency), vendor),
+                None => format!("  {}", distance_hint(ap.signal, ap.frequency)),
+            },
+            Style::default().fg(theme.muted),
+        ),
+    ]);
+    if selected {
+        ListItem::new(line).style(Style::default().bg(theme.highlight))
+    } else {
+        ListItem::new(line)
+    }
 }
 
 fn draw_saved_networks(frame: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
     if app.saved.is_empty() {
         let paragraph = Paragraph::new("No saved networks.")
-            .style(Style::default().fg(Color::DarkGray))
+            .style(Style::default().fg(theme.muted))
             .alignment(Alignment::Center);
         frame.render_widget(paragraph, area);
         return;
     }
 
-    let items: Vec<ListItem> = app
-        .saved
+    let visible = app.visible_saved();
+    if visible.is_empty() {
+        draw_no_matches(frame, app, area);
+        return;
+    }
+
+    draw_list(frame, app, area, &app.saved_list, visible.len(), app.saved_index, |i| {
+        let net = &app.saved[visible[i]];
+        let selected = i == app.saved_index;
+        let status_str = match (net.hotspot, net.active) {
+            (true, true) => "(hotspot, active)",
+            (true, false) => "(hotspot)",
+            (false, true) => "(connected)",
+            (false, false) => "(saved)",
+        };
+
+        let line = Line::from(vec![
+            Span::styled(
+                format!("  {}", truncate_pad(&net.name, SSID_WIDTH)),
+                if selected {
+                    Style::default()
+                        .fg(theme.text)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(theme.text)
+                },
+            ),
+            Span::styled(
+                format!(" {}", status_str),
+                if net.active {
+                    Style::default().fg(theme.good)
+                } else {
+                    Style::default().fg(if selected { theme.dim } else { theme.muted })
+                },
+            ),
+            Span::styled(
+                if app.temporary_profiles.contains_key(&net.name) { "  temporary" } else { "" },
+                Style::default().fg(theme.info),
+            ),
+            Span::styled(
+                app.rate_limits
+                    .get(&net.name)
+                    .map(|kbit| format!("  limit {}", app.units.bitrate((*kbit).into())))
+                    .unwrap_or_default(),
+                Style::default().fg(theme.warn),
+            ),
+        ]);
+
+        if selected {
+            ListItem::new(line).style(Style::default().bg(theme.highlight))
+        } else {
+            ListItem::new(line)
+        }
+    });
+}
+
+fn draw_no_matches(frame: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let paragraph = Paragraph::new(format!("Nothing matches '{}'. Press Esc to clear.", app.filter().value()))
+        .style(Style::default().fg(theme.muted))
+        .alignment(Alignment::Center);
+    frame.render_widget(paragraph, area);
+}
+
+fn draw_hotspot(frame: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let Some(hotspot) = app.active_hotspot() else {
+        let text = vec![
+            Line::from(""),
+            Line::from(Span::styled(
+                "No hotspot running.",
+                Style::default().fg(theme.muted),
+            )),
+            Line::from(Span::styled(
+                format!("Press Enter to share this connection from {}.", app.device),
+                Style::default().fg(theme.muted),
+            )),
+        ];
+        let paragraph = Paragraph::new(text).alignment(Alignment::Center);
+        frame.render_widget(paragraph, area);
+        return;
+    };
+
+    let clients = match app.hotspot_clients {
+        Some(1) => "1 client".to_string(),
+        Some(n) => format!("{} clients", n),
+        None => "unknown (needs iw)".to_string(),
+    };
+    let label = Style::default().fg(theme.muted);
+    let text = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            "  Hotspot running",
+            Style::default().fg(theme.good).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(vec![Span::styled("  Profile:    ", label), Span::raw(hotspot.name.clone())]),
+        Line::from(vec![Span::styled("  Interface:  ", label), Span::raw(app.device.clone())]),
+        Line::from(vec![Span::styled("  Connected:  ", label), Span::raw(clients)]),
+    ];
+    let chunks = Layout::vertical([Constraint::Length(text.len() as u16 + 1), Constraint::Min(0)]).split(area);
+    frame.render_widget(Paragraph::new(text), chunks[0]);
+
+    // Guests scan this instead of being told the password
+    let qr = app
+        .hotspot_qr
+        .as_ref()
+        .filter(|(name, _)| *name == hotspot.name)
+        .map(|(_, qr)| qr);
+    let fits = |qr: &WifiQr| {
+        let size = qr.modules.len() as u16;
+        chunks[1].width >= size && chunks[1].height > size.div_ceil(2)
+    };
+    let (text, style) = match qr {
+        Some(Ok(qr)) if fits(qr) => {
+            let password = if qr.password.is_empty() { "(open network)" } else { &qr.password };
+            let mut lines = qr_lines(&qr.modules);
+            lines.push(Line::from(vec![
+                Span::styled("Password: ", label),
+                Span::styled(password.to_string(), Style::default().fg(theme.text)),
+            ]));
+            frame.render_widget(Paragraph::new(lines).alignment(Alignment::Center), chunks[1]);
+            return;
+        }
+        Some(Ok(_)) => ("  Enlarge the terminal to show the join code.".to_string(), label),
+        Some(Err(e)) => (format!("  No join code: {}", e), Style::default().fg(theme.warn)),
+        None => ("  Loading the join code...".to_string(), label),
+    };
+    frame.render_widget(Paragraph::new(text).style(style).wrap(Wrap { trim: false }), chunks[1]);
+}
+
+/// e.g. "5 min", "1 h"
+fn window_label(window: Duration) -> String {
+    match window.as_secs() / 60 {
+        minutes if minutes % 60 == 0 => format!("{} h", minutes / 60),
+        minutes => format!("{} min", minutes),
+    }
+}
+
+/// Signal, link rate, throughput and latency of the current connection over
+/// the chosen time window, for finding a good spot for a router or antenna.
+fn draw_monitor(frame: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let window = MONITOR_WINDOWS[app.monitor_window];
+    let now = Instant::now();
+    let samples: Vec<&MonitorSample> = app
+        .monitor_samples
         .iter()
-        .enumerate()
-        .map(|(i, net)| {
-            let selected = i == app.saved_index;
-            let status_str = if net.active {
-                "(connected)"
-            } else {
-                "(saved)"
-            };
+        .filter(|sample| now.duration_since(sample.at) <= window)
+        .collect();
+    if samples.is_empty() {
+        let text = if app.status.ssid.is_some() { "Collecting readings..." } else { "Not connected." };
+        let paragraph = Paragraph::new(vec![Line::from(""), Line::from(text)])
+            .style(Style::default().fg(theme.muted))
+            .alignment(Alignment::Center);
+        frame.render_widget(paragraph, area);
+        return;
+    }
 
-            let line = Line::from(vec![
-                Span::styled(
-                    format!("  {}", truncate_pad(&net.name, SSID_WIDTH)),
-                    if selected {
-                        Style::default()
-                            .fg(Color::White)
-                            .add_modifier(Modifier::BOLD)
-                    } else {
-                        Style::default().fg(Color::White)
-                    },
-                ),
-                Span::styled(
-                    format!(" {}", status_str),
-                    if net.active {
-                        Style::default().fg(Color::Green)
-                    } else {
-                        Style::default().fg(if selected { Color::Gray } else { Color::DarkGray })
-                    },
-                ),
-            ]);
+    // Seconds before now, so the newest reading sits at the right edge
+    let x = |sample: &MonitorSample| -now.duration_since(sample.at).as_secs_f64();
+    let signal: Vec<(f64, f64)> = samples.iter().map(|s| (x(s), f64::from(s.signal))).collect();
+    let rate: Vec<(f64, f64)> = samples
+        .iter()
+        .filter_map(|s| Some((x(s), f64::from(s.bitrate?) / 1000.0)))
+        .collect();
+    let max_rate = rate.iter().map(|&(_, mbit)| mbit).fold(1.0, f64::max);
+    let now_signal = samples.last().map_or(0, |s| s.signal);
+
+    let x_axis = || {
+        Axis::default()
+            .style(Style::default().fg(theme.muted))
+            .bounds([-window.as_secs_f64(), 0.0])
+            .labels([format!("-{}", window_label(window)), "now".to_string()])
+    };
+    let chunks = Layout::vertical([Constraint::Ratio(1, 4); 4]).split(area);
+
+    let signal_chart = Chart::new(vec![Dataset::default()
+        .marker(symbols::Marker::Braille)
+        .graph_type(GraphType::Line)
+        .style(Style::default().fg(signal_color(theme, now_signal)))
+        .data(&signal)])
+    .block(Block::default().title(format!(" Signal: {} ", app.units.signal(now_signal))))
+    .x_axis(x_axis())
+    .y_axis(
+        Axis::default()
+            .style(Style::default().fg(theme.muted))
+            .bounds([0.0, 100.0])
+            .labels([app.units.signal(0), app.units.signal(50), app.units.signal(100)]),
+    );
+    frame.render_widget(signal_chart, chunks[0]);
+
+    let rate_title = match samples.last().and_then(|s| s.bitrate) {
+        Some(kbit) => format!(" Link rate: {} ", app.units.bitrate(kbit.into())),
+        None => " Link rate: unknown ".to_string(),
+    };
+    let rate_chart = Chart::new(vec![Dataset::default()
+        .marker(symbols::Marker::Braille)
+        .graph_type(GraphType::Line)
+        .style(Style::default().fg(theme.info))
+        .data(&rate)])
+    .block(Block::default().title(rate_title))
+    .x_axis(x_axis())
+    .y_axis(
+        Axis::default()
+            .style(Style::default().fg(theme.muted))
+            .bounds([0.0, max_rate])
+            .labels(["0".to_string(), app.units.bitrate((max_rate * 1000.0) as u64)]),
+    );
+    frame.render_widget(rate_chart, chunks[1]);
+
+    let down: Vec<(f64, f64)> = samples.iter().filter_map(|s| Some((x(s), s.throughput?.rx as f64))).collect();
+    let up: Vec<(f64, f64)> = samples.iter().filter_map(|s| Some((x(s), s.throughput?.tx as f64))).collect();
+    let max_bytes = down.iter().chain(&up).map(|&(_, bytes)| bytes).fold(1.0, f64::max);
+    let throughput_title = match app.throughput {
+        Some(now) => format!(
+            " Throughput: ↓ {}  ↑ {} ",
+            app.units.byte_rate(now.rx),
+            app.units.byte_rate(now.tx)
+        ),
+        None => " Throughput: unknown ".to_string(),
+    };
+    let throughput_chart = Chart::new(vec![
+        Dataset::default()
+            .name("down")
+            .marker(symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(theme.good))
+            .data(&down),
+        Dataset::default()
+            .name("up")
+            .marker(symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(theme.accent))
+            .data(&up),
+    ])
+    .block(Block::default().title(throughput_title))
+    .x_axis(x_axis())
+    .y_axis(
+        Axis::default()
+            .style(Style::default().fg(theme.muted))
+            .bounds([0.0, max_bytes])
+            .labels(["0".to_string(), app.units.byte_rate(max_bytes as u64)]),
+    );
+    frame.render_widget(throughput_chart, chunks[2]);
+
+    let rtt_ms = |result: Option<PingResult>| Some(f64::from(result?.rtt_us?) / 1000.0);
+    let gateway: Vec<(f64, f64)> = samples.iter().filter_map(|s| Some((x(s), rtt_ms(s.ping?.gateway)?))).collect();
+    let internet: Vec<(f64, f64)> = samples.iter().filter_map(|s| Some((x(s), rtt_ms(s.ping?.internet)?))).collect();
+    let max_ms = gateway.iter().chain(&internet).map(|&(_, ms)| ms).fold(1.0, f64::max);
+    let latency_title = match app.ping {
+        Some(ping) if ping.gateway.is_some() || ping.internet.is_some() => format!(" Ping: {} ", ping_text(app, &ping)),
+        _ => " Ping ".to_string(),
+    };
+    let latency_chart = Chart::new(vec![
+        Dataset::default()
+            .name("gateway")
+            .marker(symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(theme.good))
+            .data(&gateway),
+        Dataset::default()
+            .name(app.ping_host.clone())
+            .marker(symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(theme.info))
+            .data(&internet),
+    ])
+    .block(Block::default().title(latency_title))
+    .x_axis(x_axis())
+    .y_axis(
+        Axis::default()
+            .style(Style::default().fg(theme.muted))
+            .bounds([0.0, max_ms])
+            .labels(["0".to_string(), rtt_label(app, (max_ms * 1000.0) as u32)]),
+    );
+    frame.render_widget(latency_chart, chunks[3]);
+}
 
-            if selected {
-                ListItem::new(line).style(Style::default().bg(Color::Indexed(236)))
-            } else {
-                ListItem::new(line)
-            }
+/// Access points per channel of the chosen band, for picking a quiet
+/// channel for your own router.
+fn draw_analyzer(frame: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let band = app.analyzer_band;
+    let usage = channel_usage(&app.networks, band);
+    let clearest = clearest_channel(&usage, band);
+
+    let chunks = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Percentage(50),
+        Constraint::Percentage(50),
+    ])
+    .split(area);
+
+    let heard: usize = usage.iter().map(|used| used.access_points).sum();
+    let summary = match clearest {
+        _ if app.networks.is_empty() && app.bg_status == BgStatus::Scanning => "Scanning for networks...".to_string(),
+        Some(channel) if heard > 0 => format!(
+            "{} access points on {}. Clearest channel for a router: {}",
+            heard,
+            band.label(),
+            channel
+        ),
+        _ => format!("No access points heard on {}.", band.label()),
+    };
+    frame.render_widget(Paragraph::new(summary).style(Style::default().fg(theme.text)), chunks[0]);
+
+    // Fill the width, but keep bars narrow enough to tell apart
+    let gap = 1;
+    let count = usage.len().max(1) as u16;
+    let bar_width = (area.width.saturating_sub(gap * (count - 1)) / count).clamp(1, 5);
+    let bar_color = |channel: u32| if Some(channel) == clearest { theme.good } else { theme.info };
+
+    let bars: Vec<Bar> = usage
+        .iter()
+        .map(|used| {
+            Bar::default()
+                .value(used.access_points as u64)
+                .label(Line::from(used.channel.to_string()))
+                .style(Style::default().fg(bar_color(used.channel)))
+        })
+        .collect();
+    let count_chart = BarChart::vertical(bars)
+        .block(Block::default().title(" Access points per channel "))
+        .bar_width(bar_width)
+        .bar_gap(gap)
+        .value_style(Style::default().fg(theme.text).add_modifier(Modifier::BOLD))
+        .label_style(Style::default().fg(theme.muted));
+    frame.render_widget(count_chart, chunks[1]);
+
+    let bars: Vec<Bar> = usage
+        .iter()
+        .map(|used| {
+            Bar::default()
+                .value(u64::from(used.signal))
+                .label(Line::from(used.channel.to_string()))
+                .text_value(String::new())
+                .style(Style::default().fg(bar_color(used.channel)))
         })
         .collect();
+    let signal_chart = BarChart::vertical(bars)
+        .block(Block::default().title(" Combined signal per channel "))
+        .bar_width(bar_width)
+        .bar_gap(gap)
+        .label_style(Style::default().fg(theme.muted));
+    frame.render_widget(signal_chart, chunks[2]);
+}
+
+/// Most recent journal lines about the device, newest at the bottom.
+fn draw_logs(frame: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(" Log ({}) ", app.device))
+        .style(Style::default().fg(theme.muted));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    if app.logs.is_empty() {
+        let hint = Paragraph::new(
+            "No log lines yet. Reading the NetworkManager journal may require \
+             membership in the systemd-journal or adm group.",
+        )
+        .style(Style::default().fg(theme.muted))
+        .wrap(Wrap { trim: true });
+        frame.render_widget(hint, inner);
+        return;
+    }
 
-    let list = List::new(items);
-    frame.render_widget(list, area);
+    let skip = app.logs.len().saturating_sub(inner.height as usize);
+    let lines: Vec<Line> = app
+        .logs
+        .iter()
+        .skip(skip)
+        .map(|line| {
+            let lower = line.to_lowercase();
+            let color = if lower.contains("fail") || lower.contains("error") {
+                theme.bad
+            } else if line.contains("CTRL-EVENT") || lower.contains("<warn>") {
+                theme.warn
+            } else {
+                theme.dim
+            };
+            Line::from(Span::styled(line.clone(), Style::default().fg(color)))
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(lines), inner);
 }
 
 fn draw_help_bar(frame: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
     let block = Block::default().borders(Borders::TOP);
     let inner = block.inner(area);
     frame.render_widget(block, area);
 
     let line = if app.modal.is_some() {
         match &app.modal {
-            Some(Modal::PasswordInput) => {
-                help_line(&[("Enter", "Submit"), ("Esc", "Cancel"), ("Tab", "Show/Hide")])
+            Some(Modal::PasswordInput) => help_line(theme, &[
+                ("Enter", "Submit"),
+                ("Esc", "Cancel"),
+                ("Tab", "Show/Hide"),
+                (app.keys.label(Action::Paste).as_str(), "Paste"),
+            ]),
+            Some(Modal::ConfirmDisconnect)
+            | Some(Modal::ConfirmForget(_))
+            | Some(Modal::ConfirmApply(_))
+            | Some(Modal::ConfirmMetric(..))
+            | Some(Modal::ConfirmQuit) => {
+                help_line(theme, &[(app.keys.label(Action::Confirm).as_str(), "Confirm"), ("N", "Cancel")])
+            }
+            Some(Modal::Form(_)) => help_line(theme, &[
+                ("Tab", "Next field"),
+                ("←→", "Choose"),
+                ("Enter", "Save"),
+                ("Esc", "Cancel"),
+            ]),
+            Some(Modal::Connectivity(check)) if check.available => help_line(theme, &[
+                (app.keys.label(Action::Connectivity).as_str(), if check.enabled { "Turn off" } else { "Turn on" }),
+                ("Any key", "Dismiss"),
+            ]),
+            Some(Modal::Password(_, password)) if !password.is_empty() => {
+                help_line(theme, &[(app.keys.label(Action::Copy).as_str(), "Copy"), ("Any key", "Dismiss")])
             }
-            Some(Modal::ConfirmDisconnect) | Some(Modal::ConfirmForget(_)) => {
-                help_line(&[("Y", "Confirm"), ("N", "Cancel")])
+            Some(Modal::CaptivePortal(_)) => help_line(theme, &[
+                (app.keys.label(Action::Confirm).as_str(), "Open in browser"),
+                (app.keys.label(Action::Copy).as_str(), "Copy address"),
+                ("Any key", "Dismiss"),
+            ]),
+            Some(Modal::Message(_))
+            | Some(Modal::Checks(..))
+            | Some(Modal::Qr(_))
+            | Some(Modal::Password(..))
+            | Some(Modal::SignalHistory)
+            | Some(Modal::NetworkDetails(_))
+            | Some(Modal::Connectivity(_)) => {
+                help_line(theme, &[("Any key", "Dismiss")])
             }
-            Some(Modal::Message(_)) => help_line(&[("Any key", "Dismiss")]),
+            Some(Modal::Actions(_)) => help_line(theme, &[
+                ("↑↓", "Navigate"),
+                (app.keys.label(Action::Connect).as_str(), "Run"),
+                ("Esc", "Close"),
+            ]),
+            Some(Modal::Messages) => help_line(theme, &[("↑↓", "Navigate"), ("Esc", "Close")]),
+            Some(Modal::Dispatcher) => help_line(theme, &[
+                ("Space", "Enable/Disable"),
+                ("A", "New script"),
+                ("↑↓", "Navigate"),
+                ("Esc", "Close"),
+            ]),
             None => unreachable!(),
         }
+    } else if app.filtering {
+        help_line(theme, &[("Type", "Filter"), ("Enter", "Keep"), ("Esc", "Clear"), ("↑↓", "Navigate")])
     } else {
+        let keys = &app.keys;
         match app.view {
-            View::AvailableNetworks => help_line(&[
-                ("Tab", "Switch view"),
-                ("Enter", "Connect"),
-                ("D", "Disconnect"),
-                ("R", "Refresh"),
-                ("Q", "Quit"),
+            View::AvailableNetworks => help_line(theme, &[
+                (keys.label(Action::NextView).as_str(), "Switch view"),
+                (keys.label(Action::Connect).as_str(), "Connect"),
+                (keys.label(Action::Menu).as_str(), "Actions"),
+                (keys.label(Action::ConnectTemporarily).as_str(), "Join temporarily"),
+                (keys.label(Action::Disconnect).as_str(), "Disconnect"),
+                (keys.label(Action::Details).as_str(), "Details"),
+                (keys.label(Action::Expand).as_str(), "APs"),
+                (keys.label(Action::Filter).as_str(), "Filter"),
+                (keys.label(Action::Sort).as_str(), "Sort"),
+                (keys.label(Action::Band).as_str(), "Band"),
+                (keys.label(Action::SignalUnit).as_str(), "% / dBm"),
+                (keys.label(Action::Refresh).as_str(), "Refresh"),
+                (keys.label(Action::Rescan).as_str(), "Rescan only"),
+                (keys.label(Action::PauseRefresh).as_str(), "Pause scans"),
+                (keys.label(Action::CheckSharing).as_str(), "Check sharing"),
+                (keys.label(Action::Connectivity).as_str(), "Connectivity"),
+                (keys.label(Action::SixGhz).as_str(), "6 GHz"),
+                (keys.label(Action::Scripts).as_str(), "Scripts"),
+                (keys.label(Action::Logs).as_str(), "Logs"),
+                (keys.label(Action::Messages).as_str(), "Messages"),
+                (keys.label(Action::LowData).as_str(), "Low data"),
+                (keys.label(Action::CallMode).as_str(), "Call mode"),
+                (keys.label(Action::CopyIp).as_str(), "Copy IP"),
+                (keys.label(Action::Quit).as_str(), "Quit"),
                 ("↑↓", "Navigate"),
             ]),
-            View::SavedNetworks => help_line(&[
-                ("Tab", "Switch view"),
-                ("Enter", "Reconnect"),
-                ("F", "Forget"),
-                ("D", "Disconnect"),
-                ("R", "Refresh"),
-                ("Q", "Quit"),
+            View::SavedNetworks => help_line(theme, &[
+                (keys.label(Action::NextView).as_str(), "Switch view"),
+                (keys.label(Action::Connect).as_str(), "Reconnect"),
+                (keys.label(Action::Menu).as_str(), "Actions"),
+                (keys.label(Action::Forget).as_str(), "Forget"),
+                (keys.label(Action::NewHotspot).as_str(), "New hotspot"),
+                (keys.label(Action::ShareQr).as_str(), "Share QR"),
+                (keys.label(Action::ShowPassword).as_str(), "Password"),
+                (keys.label(Action::Limit).as_str(), "Limit"),
+                (keys.label(Action::Roaming).as_str(), "Roaming"),
+                (keys.label(Action::Edit).as_str(), "Edit"),
+                (keys.label(Action::Rename).as_str(), "Rename"),
+                (keys.label(Action::Routes).as_str(), "Routes"),
+                (keys.label(Action::Dns).as_str(), "DNS"),
+                (keys.label(Action::Ipv4).as_str(), "IPv4"),
+                (
+                    format!("{}/{}", keys.label(Action::RaiseMetric), keys.label(Action::LowerMetric)).as_str(),
+                    "Metric",
+                ),
+                (keys.label(Action::Filter).as_str(), "Filter"),
+                (keys.label(Action::Sort).as_str(), "Sort"),
+                (keys.label(Action::Logs).as_str(), "Logs"),
+                (keys.label(Action::Messages).as_str(), "Messages"),
+                (keys.label(Action::LowData).as_str(), "Low data"),
+                (keys.label(Action::CallMode).as_str(), "Call mode"),
+                (keys.label(Action::Disconnect).as_str(), "Disconnect"),
+                (keys.label(Action::Refresh).as_str(), "Refresh"),
+                (keys.label(Action::Quit).as_str(), "Quit"),
                 ("↑↓", "Navigate"),
             ]),
+            View::Hotspot if app.active_hotspot().is_some() => help_line(theme, &[
+                (keys.label(Action::NextView).as_str(), "Switch view"),
+                (keys.label(Action::StopHotspot).as_str(), "Stop hotspot"),
+                (keys.label(Action::Quit).as_str(), "Quit"),
+            ]),
+            View::Hotspot => help_line(theme, &[
+                (keys.label(Action::NextView).as_str(), "Switch view"),
+                (keys.label(Action::Connect).as_str(), "Start hotspot"),
+                (keys.label(Action::Quit).as_str(), "Quit"),
+            ]),
+            View::Monitor => help_line(theme, &[
+                (keys.label(Action::NextView).as_str(), "Switch view"),
+                (
+                    format!("{}/{}", keys.label(Action::ZoomIn), keys.label(Action::ZoomOut)).as_str(),
+                    "Time window",
+                ),
+                (keys.label(Action::Logs).as_str(), "Logs"),
+                (keys.label(Action::Messages).as_str(), "Messages"),
+                (keys.label(Action::Quit).as_str(), "Quit"),
+            ]),
+            View::Analyzer => help_line(theme, &[
+                (keys.label(Action::NextView).as_str(), "Switch view"),
+                (keys.label(Action::Band).as_str(), "Band"),
+                (keys.label(Action::Refresh).as_str(), "Rescan"),
+                (keys.label(Action::Quit).as_str(), "Quit"),
+            ]),
         }
     };
 
@@ -312,7 +1350,7 @@ fn draw_help_bar(frame: &mut Frame, app: &App, area: Rect) {
 }
 
 /// Build a styled help line: keys are bright, descriptions are dim.
-fn help_line(items: &[(&str, &str)]) -> Line<'static> {
+fn help_line(theme: &Theme, items: &[(&str, &str)]) -> Line<'static> {
     let mut spans = Vec::new();
     for (i, (key, desc)) in items.iter().enumerate() {
         if i > 0 {
@@ -320,24 +1358,61 @@ fn help_line(items: &[(&str, &str)]) -> Line<'static> {
         }
         spans.push(Span::styled(
             format!("[{}]", key),
-            Style::default().fg(Color::Indexed(248)),
+            Style::default().fg(theme.key),
         ));
         spans.push(Span::styled(
             format!(" {}", desc),
-            Style::default().fg(Color::DarkGray),
+            Style::default().fg(theme.muted),
         ));
     }
     Line::from(spans)
 }
 
 fn draw_modal(frame: &mut Frame, app: &App, modal: &Modal) {
+    let theme = &app.theme;
     let area = frame.area();
-    let modal_width = 50u16.min(area.width.saturating_sub(4));
+    let modal_width = match modal {
+        Modal::Checks(..) | Modal::Connectivity(_) | Modal::Messages => 70u16,
+        Modal::Qr(qr) => (qr.modules.len() as u16 + 2).max(50),
+        Modal::Dispatcher => 64,
+        _ => 50u16,
+    }
+    .min(area.width.saturating_sub(4));
     let modal_height = match modal {
+        Modal::PasswordInput if app.password_status.is_some() || app.password_error.is_some() => 8,
         Modal::PasswordInput => 7,
-        Modal::ConfirmDisconnect | Modal::ConfirmForget(_) => 6,
-        Modal::Message(_) => 6,
-    };
+        Modal::ConfirmDisconnect
+        | Modal::ConfirmForget(_)
+        | Modal::ConfirmApply(_)
+        | Modal::ConfirmMetric(..)
+        | Modal::ConfirmQuit => {
+            // The summary wraps below the question
+            let summary = app.confirm_summary().map_or(0, |summary| {
+                summary.chars().count() as u16 / modal_width.saturating_sub(2).max(1) + 2
+            });
+            6 + summary
+        }
+        Modal::Message(msg) => {
+            // Grow for long messages (e.g. file paths) instead of cutting them off
+            let lines = msg.chars().count() as u16 / modal_width.saturating_sub(2).max(1) + 1;
+            (lines + 3).max(6)
+        }
+        Modal::Checks(_, checks) => (checks.len() as u16) * 2 + 4,
+        Modal::Connectivity(_) => 8,
+        Modal::CaptivePortal(url) => url.chars().count() as u16 / modal_width.saturating_sub(2).max(1) + 7,
+        Modal::Form(_) => app.form.fields.len() as u16 + 5,
+        // Two modules per row, plus SSID, password and hint lines
+        Modal::Qr(qr) => (qr.modules.len() as u16).div_ceil(2) + 5,
+        Modal::Dispatcher => app.dispatcher_scripts.len().max(1) as u16 + 5,
+        Modal::Actions(menu) => menu.items.len() as u16 + 2,
+        Modal::Password(..) => 7,
+        Modal::SignalHistory => 9,
+        Modal::Messages => 18,
+        Modal::NetworkDetails(net) => {
+            19 + if link_rate(app, net).is_some() { 2 } else { 0 } + u16::from(raw_ssid(net).is_some())
+        }
+    }
+    .min(area.height);
 
     let x = (area.width.saturating_sub(modal_width)) / 2;
     let y = (area.height.saturating_sub(modal_height)) / 2;
@@ -350,128 +1425,751 @@ fn draw_modal(frame: &mut Frame, app: &App, modal: &Modal) {
         Modal::PasswordInput => {
             let block = Block::default()
                 .borders(Borders::ALL)
-                .title(format!(" Connect to {} ", app.password_target_ssid))
-                .style(Style::default().fg(Color::Yellow));
+                .title(format!(" Connect to {} ", ssid_text(&app.password_target_ssid)))
+                .style(Style::default().fg(theme.warn));
 
             let inner = block.inner(modal_area);
             frame.render_widget(block, modal_area);
 
-            let chunks =
-                Layout::vertical([Constraint::Length(1), Constraint::Length(1), Constraint::Min(0)])
-                    .split(inner);
+            let status_height = (app.password_status.is_some() || app.password_error.is_some()) as u16;
+            let chunks = Layout::vertical([
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Length(status_height),
+                Constraint::Min(0),
+            ])
+            .split(inner);
 
-            let label = Paragraph::new("Password:").style(Style::default().fg(Color::White));
+            let label = Paragraph::new("Password:").style(Style::default().fg(theme.text));
             frame.render_widget(label, chunks[0]);
 
-            let display_pw = if app.password_visible {
-                app.password.clone()
-            } else {
-                "●".repeat(app.password.len())
-            };
-
-            let pw_line = Line::from(vec![
-                Span::styled(
-                    format!(" {} ", display_pw),
-                    Style::default().fg(Color::White).bg(Color::DarkGray),
-                ),
-                Span::styled("█", Style::default().fg(Color::White)),
-            ]);
+            let width = (chunks[1].width as usize).saturating_sub(2);
+            let pw_line = Line::from(input_spans(theme, &app.password, !app.password_visible, true, width));
 
             let pw_input = Paragraph::new(pw_line);
             frame.render_widget(pw_input, chunks[1]);
 
-            let hint = help_line(&[("Tab", "show/hide"), ("Enter", "submit"), ("Esc", "cancel")]);
+            if let Some(error) = &app.password_error {
+                let error = Paragraph::new(format!("✗ {}", error)).style(Style::default().fg(theme.bad));
+                frame.render_widget(error, chunks[2]);
+            } else if let Some(status) = &app.password_status {
+                let status = Paragraph::new(status.as_str()).style(Style::default().fg(theme.muted));
+                frame.render_widget(status, chunks[2]);
+            }
+
+            let hint = if app.password_command.is_some() {
+                help_line(theme, &[
+                    ("Tab", "show/hide"),
+                    (app.keys.label(Action::FetchPassword).as_str(), "fetch"),
+                    ("Enter", "submit"),
+                    ("Esc", "cancel"),
+                ])
+            } else {
+                help_line(theme, &[("Tab", "show/hide"), ("Enter", "submit"), ("Esc", "cancel")])
+            };
             let hint_p = Paragraph::new(hint).alignment(Alignment::Center);
-            frame.render_widget(hint_p, chunks[2]);
+            frame.render_widget(hint_p, chunks[3]);
         }
         Modal::ConfirmDisconnect => {
+            let ssid = app.status.ssid.as_deref().unwrap_or("current network");
+            let question = format!("Disconnect from {}?", ssid_text(ssid));
+            draw_confirm(frame, app, modal_area, " Disconnect ", theme.warn, &question);
+        }
+        Modal::ConfirmForget(name) => {
+            let question = format!("Forget '{}'?\nYou'll need the password to reconnect.", name);
+            draw_confirm(frame, app, modal_area, " Forget Network ", theme.bad, &question);
+        }
+        Modal::ConfirmApply(kind) => {
+            let what = match kind {
+                FormKind::Dns => "DNS servers",
+                FormKind::Ipv4 => "IPv4 address",
+                _ => "routing",
+            };
+            let question = format!("Change the {} of '{}'?", what, app.form_target);
+            draw_confirm(frame, app, modal_area, " Apply Changes ", theme.warn, &question);
+        }
+        Modal::ConfirmMetric(name, metric) => {
+            let question = match metric {
+                MetricChange::Set(metric) => format!("Set the route metric of '{}' to {}?", name, metric),
+                MetricChange::Adjust(change) if *change < 0 => {
+                    format!("Lower the route metric of '{}' by {}?", name, change.unsigned_abs())
+                }
+                MetricChange::Adjust(change) => format!("Raise the route metric of '{}' by {}?", name, change),
+            };
+            draw_confirm(frame, app, modal_area, " Route Metric ", theme.warn, &question);
+        }
+        Modal::Message(msg) => {
+            let color = if msg.starts_with("Connected")
+                || msg.starts_with("Disconnected")
+                || msg.starts_with("Forgot")
+                || msg.starts_with("Saved")
+                || msg.starts_with("Started")
+            {
+                theme.good
+            } else if msg.starts_with("Already") {
+                theme.warn
+            } else {
+                theme.bad
+            };
+
             let block = Block::default()
                 .borders(Borders::ALL)
-                .title(" Disconnect ")
-                .style(Style::default().fg(Color::Yellow));
+                .style(Style::default().fg(color));
 
             let inner = block.inner(modal_area);
             frame.render_widget(block, modal_area);
 
-            let ssid = app
-                .status
-                .ssid
-                .as_deref()
-                .unwrap_or("current network");
-
             let chunks =
-                Layout::vertical([Constraint::Length(2), Constraint::Min(0)]).split(inner);
+                Layout::vertical([Constraint::Min(2), Constraint::Length(1)]).split(inner);
 
-            let text = Paragraph::new(format!("Disconnect from {}?", ssid))
-                .style(Style::default().fg(Color::White))
-                .alignment(Alignment::Center);
+            let text = Paragraph::new(msg.clone())
+                .style(Style::default().fg(theme.text))
+                .alignment(Alignment::Center)
+                .wrap(Wrap { trim: false });
             frame.render_widget(text, chunks[0]);
 
-            let hint = help_line(&[("Y", "Yes"), ("N", "No")]);
-            let hint_p = Paragraph::new(hint).alignment(Alignment::Center);
-            frame.render_widget(hint_p, chunks[1]);
+            let hint = Paragraph::new("[Any key] Dismiss")
+                .style(Style::default().fg(theme.muted))
+                .alignment(Alignment::Center);
+            frame.render_widget(hint, chunks[1]);
         }
-        Modal::ConfirmForget(name) => {
+        Modal::ConfirmQuit => {
+            let question = "An operation is still running.\nQuit anyway?";
+            draw_confirm(frame, app, modal_area, " Quit ", theme.warn, question);
+        }
+        Modal::Form(_) => {
+            let form = &app.form;
             let block = Block::default()
                 .borders(Borders::ALL)
-                .title(" Forget Network ")
-                .style(Style::default().fg(Color::Red));
+                .title(format!(" {} ", form.title))
+                .style(Style::default().fg(theme.warn));
 
             let inner = block.inner(modal_area);
             frame.render_widget(block, modal_area);
 
-            let chunks =
-                Layout::vertical([Constraint::Length(2), Constraint::Min(0)]).split(inner);
-
-            let text = Paragraph::new(format!(
-                "Forget '{}'?\nYou'll need the password to reconnect.",
-                name
-            ))
-            .style(Style::default().fg(Color::White))
-            .alignment(Alignment::Center);
-            frame.render_widget(text, chunks[0]);
+            let chunks = Layout::vertical([
+                Constraint::Length(form.fields.len() as u16),
+                Constraint::Length(1),
+                Constraint::Min(0),
+            ])
+            .split(inner);
+
+            let label_width = form.fields.iter().map(|f| f.label.len()).max().unwrap_or(0) + 1;
+            let input_width = (chunks[0].width as usize).saturating_sub(label_width + 2);
+            let lines: Vec<Line> = form
+                .fields
+                .iter()
+                .enumerate()
+                .map(|(i, field)| {
+                    let focused = i == form.focus;
+                    let mut spans = vec![Span::styled(
+                        format!("{:<width$}", field.label, width = label_width),
+                        Style::default().fg(theme.text),
+                    )];
+                    match &field.kind {
+                        FieldKind::Text(input) => spans.extend(input_spans(theme, input, false, focused, input_width)),
+                        FieldKind::Secret(input) => spans.extend(input_spans(theme, input, true, focused, input_width)),
+                        FieldKind::Choice(options, selected) => spans.push(Span::styled(
+                            format!(" ◂ {} ▸ ", options[*selected]),
+                            if focused {
+                                Style::default().fg(theme.text).bg(theme.muted)
+                            } else {
+                                Style::default().fg(theme.dim)
+                            },
+                        )),
+                    }
+                    Line::from(spans)
+                })
+                .collect();
+            frame.render_widget(Paragraph::new(lines), chunks[0]);
+
+            if let Some(ref error) = form.error {
+                let text = Paragraph::new(error.clone()).style(Style::default().fg(theme.bad));
+                frame.render_widget(text, chunks[1]);
+            }
 
-            let hint = help_line(&[("Y", "Yes"), ("N", "No")]);
+            let hint = help_line(theme, &[("Tab", "next"), ("Enter", "save"), ("Esc", "cancel")]);
             let hint_p = Paragraph::new(hint).alignment(Alignment::Center);
-            frame.render_widget(hint_p, chunks[1]);
+            frame.render_widget(hint_p, chunks[2]);
         }
-        Modal::Message(msg) => {
-            let color = if msg.starts_with("Connected")
-                || msg.starts_with("Disconnected")
-                || msg.starts_with("Forgot")
-            {
-                Color::Green
-            } else if msg.starts_with("Already") {
-                Color::Yellow
-            } else {
-                Color::Red
+        Modal::Checks(title, checks) => draw_checks(frame, theme, modal_area, title, checks, "[Any key] Dismiss"),
+        Modal::Connectivity(check) => {
+            let key = app.keys.label(Action::Connectivity);
+            let checks = connectivity_checks(check, &key);
+            let hint = match (check.available, check.enabled) {
+                (false, _) => "[Any key] Dismiss".to_string(),
+                (true, true) => format!("[{}] Turn off  [Any key] Dismiss", key),
+                (true, false) => format!("[{}] Turn on  [Any key] Dismiss", key),
             };
-
+            draw_checks(frame, theme, modal_area, "Connectivity check", &checks, &hint);
+        }
+        Modal::Password(name, password) => {
             let block = Block::default()
                 .borders(Borders::ALL)
-                .style(Style::default().fg(color));
+                .title(format!(" Password for {} ", name))
+                .style(Style::default().fg(theme.info));
 
             let inner = block.inner(modal_area);
             frame.render_widget(block, modal_area);
 
-            let chunks =
-                Layout::vertical([Constraint::Min(2), Constraint::Length(1)]).split(inner);
+            let chunks = Layout::vertical([Constraint::Min(2), Constraint::Length(1)]).split(inner);
 
-            let text = Paragraph::new(msg.clone())
-                .style(Style::default().fg(Color::White))
+            let text = if password.is_empty() {
+                Span::styled("(open network, no password)", Style::default().fg(theme.muted))
+            } else {
+                Span::styled(password.clone(), Style::default().fg(theme.text).add_modifier(Modifier::BOLD))
+            };
+            let text = Paragraph::new(Line::from(text))
                 .alignment(Alignment::Center)
                 .wrap(Wrap { trim: false });
             frame.render_widget(text, chunks[0]);
 
-            let hint = Paragraph::new("[Any key] Dismiss")
-                .style(Style::default().fg(Color::DarkGray))
+            let hint = if password.is_empty() {
+                "[Any key] Dismiss".to_string()
+            } else {
+                format!("[{}] Copy  [Any key] Dismiss", app.keys.label(Action::Copy))
+            };
+            let hint = Paragraph::new(hint)
+                .style(Style::default().fg(theme.muted))
                 .alignment(Alignment::Center);
             frame.render_widget(hint, chunks[1]);
         }
+        Modal::CaptivePortal(url) => {
+            let block = Block::default()
+                .borders(Borders::ALL)
+                .title(" Sign-in needed ")
+                .style(Style::default().fg(theme.warn));
+
+            let inner = block.inner(modal_area);
+            frame.render_widget(block, modal_area);
+
+            let chunks = Layout::vertical([Constraint::Min(3), Constraint::Length(1)]).split(inner);
+
+            let text = Paragraph::new(vec![
+                Line::from(Span::styled(
+                    "This network needs you to sign in before it lets you online.",
+                    Style::default().fg(theme.text),
+                )),
+                Line::from(""),
+                Line::from(Span::styled(url.clone(), Style::default().fg(theme.info))),
+            ])
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: false });
+            frame.render_widget(text, chunks[0]);
+
+            let hint = format!(
+                "[{}] Open in browser  [{}] Copy  [Any key] Dismiss",
+                app.keys.label(Action::Confirm),
+                app.keys.label(Action::Copy)
+            );
+            let hint = Paragraph::new(hint)
+                .style(Style::default().fg(theme.muted))
+                .alignment(Alignment::Center);
+            frame.render_widget(hint, chunks[1]);
+        }
+        Modal::SignalHistory => draw_signal_history(frame, app, modal_area),
+        Modal::Dispatcher => draw_dispatcher(frame, app, modal_area),
+        Modal::Messages => draw_messages(frame, app, modal_area),
+        Modal::Actions(menu) => draw_action_menu(frame, app, menu, modal_area),
+        Modal::NetworkDetails(net) => draw_network_details(frame, app, net, modal_area),
+        Modal::Qr(qr) => {
+            let block = Block::default()
+                .borders(Borders::ALL)
+                .title(" Scan to join ")
+                .style(Style::default().fg(theme.info));
+
+            let inner = block.inner(modal_area);
+            frame.render_widget(block, modal_area);
+
+            let chunks = Layout::vertical([
+                Constraint::Min(1),
+                Constraint::Length(2),
+                Constraint::Length(1),
+            ])
+            .split(inner);
+
+            let size = qr.modules.len() as u16;
+            if chunks[0].width < size || chunks[0].height < size.div_ceil(2) {
+                let text = Paragraph::new("Enlarge the terminal to show the QR code.")
+                    .style(Style::default().fg(theme.warn))
+                    .alignment(Alignment::Center)
+                    .wrap(Wrap { trim: false });
+                frame.render_widget(text, chunks[0]);
+            } else {
+                let code = Paragraph::new(qr_lines(&qr.modules)).alignment(Alignment::Center);
+                frame.render_widget(code, chunks[0]);
+            }
+
+            let password = if qr.password.is_empty() {
+                "(open network)".to_string()
+            } else {
+                qr.password.clone()
+            };
+            let details = Paragraph::new(vec![
+                Line::from(vec![
+                    Span::styled("SSID: ", Style::default().fg(theme.muted)),
+                    Span::styled(qr.ssid.clone(), Style::default().fg(theme.text)),
+                ]),
+                Line::from(vec![
+                    Span::styled("Password: ", Style::default().fg(theme.muted)),
+                    Span::styled(password, Style::default().fg(theme.text)),
+                ]),
+            ])
+            .alignment(Alignment::Center);
+            frame.render_widget(details, chunks[1]);
+
+            let hint = Paragraph::new("[Any key] Dismiss")
+                .style(Style::default().fg(theme.muted))
+                .alignment(Alignment::Center);
+            frame.render_widget(hint, chunks[2]);
+        }
+    }
+}
+
+/// A yes/no modal: `question`, then what confirming runs (with
+/// `confirm_details` on), then the keys.
+fn draw_confirm(frame: &mut Frame, app: &App, area: Rect, title: &str, color: Color, question: &str) {
+    let theme = &app.theme;
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(title.to_string())
+        .style(Style::default().fg(color));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let summary = app.confirm_summary();
+    let chunks = Layout::vertical([
+        Constraint::Length(2),
+        Constraint::Length(inner.height.saturating_sub(3) * u16::from(summary.is_some())),
+        Constraint::Min(0),
+    ])
+    .split(inner);
+
+    let text = Paragraph::new(question.to_string())
+        .style(Style::default().fg(theme.text))
+        .alignment(Alignment::Center);
+    frame.render_widget(text, chunks[0]);
+
+    if let Some(summary) = summary {
+        let summary = Paragraph::new(summary)
+            .style(Style::default().fg(theme.muted))
+            .wrap(Wrap { trim: true });
+        frame.render_widget(summary, chunks[1]);
     }
+
+    let hint = help_line(theme, &[(app.keys.label(Action::Confirm).as_str(), "Yes"), ("N", "No")]);
+    let hint_p = Paragraph::new(hint).alignment(Alignment::Center);
+    frame.render_widget(hint_p, chunks[2]);
+}
+
+/// A modal listing `checks` with their state and details.
+fn draw_checks(frame: &mut Frame, theme: &Theme, area: Rect, title: &str, checks: &[Check], hint: &str) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(" {} ", title))
+        .style(Style::default().fg(theme.warn));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks =
+        Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).split(inner);
+
+    let lines: Vec<Line> = checks
+        .iter()
+        .flat_map(|check| {
+            let (mark, color) = match check.state {
+                CheckState::Ok => ("✓", theme.good),
+                CheckState::Missing => ("✗", theme.bad),
+                CheckState::Unknown => ("?", theme.warn),
+            };
+            [
+                Line::from(Span::styled(
+                    format!("{} {}", mark, check.name),
+                    Style::default().fg(color).add_modifier(Modifier::BOLD),
+                )),
+                Line::from(Span::styled(
+                    format!("  {}", check.detail),
+                    Style::default().fg(theme.dim),
+                )),
+            ]
+        })
+        .collect();
+
+    let text = Paragraph::new(lines).wrap(Wrap { trim: false });
+    frame.render_widget(text, chunks[0]);
+
+    let hint = Paragraph::new(hint)
+        .style(Style::default().fg(theme.muted))
+        .alignment(Alignment::Center);
+    frame.render_widget(hint, chunks[1]);
+}
+
+fn draw_network_details(frame: &mut Frame, app: &App, net: &Network, area: Rect) {
+    let theme = &app.theme;
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(" {} ", ssid_text(&net.ssid)))
+        .style(Style::default().fg(theme.info));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).split(inner);
+
+    let unknown = || "unknown".to_string();
+    let security = if net.is_open() {
+        "Open".to_string()
+    } else {
+        net.security.clone()
+    };
+    let mut rows = vec![
+        ("Signal", format!("{} {}", signal_indicator(app, net.signal).trim_end(), app.units.signal(net.signal))),
+        ("Security", security),
+        ("BSSID", Some(net.bssid.clone()).filter(|b| !b.is_empty()).unwrap_or_else(unknown)),
+        ("Vendor", vendor_text(&net.bssid)),
+        ("Channel", net.channel.map(|c| c.to_string()).unwrap_or_else(unknown)),
+        ("Frequency", net.frequency.map(|f| format!("{} MHz", f)).unwrap_or_else(unknown)),
+        ("Band", net.band().map(|band| band.label().to_string()).unwrap_or_else(unknown)),
+        ("Distance", distance_hint(net.signal, net.frequency).to_string()),
+        ("Max rate", net.rate.map(|r| app.units.bitrate(u64::from(r) * 1000)).unwrap_or_else(unknown)),
+        ("Mode", Some(net.mode.clone()).filter(|m| !m.is_empty()).unwrap_or_else(unknown)),
+        ("In use", if net.in_use { "yes" } else { "no" }.to_string()),
+        ("Your MAC", app.device_mac.clone().unwrap_or_else(unknown)),
+        ("Data used", data_used(app, app.usage.get(&net.ssid))),
+        ("This session", data_used(app, app.session_usage.get(&net.ssid))),
+        ("Reliability", reliability(app.connect_history.get(&net.ssid))),
+    ];
+    if let Some(link) = link_rate(app, net) {
+        rows.insert(8, ("Standard", link.generation.label().to_string()));
+        rows.insert(9, ("Link", link.summary()));
+    }
+    if let Some(raw) = raw_ssid(net) {
+        rows.insert(0, ("SSID bytes", raw));
+    }
+    let lines: Vec<Line> = rows
+        .into_iter()
+        .map(|(label, value)| {
+            Line::from(vec![
+                Span::styled(format!(" {:<13}", label), Style::default().fg(theme.muted)),
+                Span::styled(value, Style::default().fg(theme.text)),
+            ])
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(lines), chunks[0]);
+
+    let hint = Paragraph::new("[Any key] Dismiss")
+        .style(Style::default().fg(theme.muted))
+        .alignment(Alignment::Center);
+    frame.render_widget(hint, chunks[1]);
+}
+
+/// The SSID in hex, when what's shown isn't simply its text.
+fn raw_ssid(net: &Network) -> Option<String> {
+    let plain = std::str::from_utf8(&net.ssid_bytes).is_ok_and(|text| ssid_text(text) == text);
+    (!plain).then(|| net.ssid_bytes.iter().map(|b| format!("{:02x}", b)).collect::<Vec<_>>().join(" "))
+}
+
+/// How the connection to `net` transmits, if it's the current one.
+fn link_rate(app: &App, net: &Network) -> Option<LinkRate> {
+    app.status.link.filter(|_| net.in_use)
+}
+
+/// Who made the access point, from its BSSID.
+fn vendor_text(bssid: &str) -> String {
+    match (oui::vendor(bssid), oui::parse_prefix(bssid)) {
+        (Some(vendor), _) => vendor.to_string(),
+        (None, Some(prefix)) if oui::is_locally_administered(prefix) => "none (randomized or virtual BSSID)".to_string(),
+        _ => "unknown".to_string(),
+    }
+}
+
+/// How often connecting worked, e.g. "75% (9 of 12 connects)".
+fn reliability(stats: Option<&ConnectStats>) -> String {
+    match stats.and_then(|stats| Some((stats, stats.reliability()?))) {
+        Some((stats, percent)) => format!("{}% ({} of {} connects)", percent, stats.successes, stats.attempts()),
+        None => "no connects yet".to_string(),
+    }
+}
+
+/// Bytes received and sent, e.g. "↓ 1.2 GB  ↑ 80.5 MB".
+fn data_used(app: &App, usage: Option<&Usage>) -> String {
+    let usage = usage.copied().unwrap_or_default();
+    format!("↓ {}  ↑ {}", app.units.bytes(usage.rx), app.units.bytes(usage.tx))
+}
+
+/// The current connection's last readings, oldest on the left.
+fn draw_signal_history(frame: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(" Signal history ")
+        .style(Style::default().fg(theme.info));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::vertical([
+        Constraint::Min(1),
+        Constraint::Length(1),
+        Constraint::Length(1),
+    ])
+    .split(inner);
+
+    let history = &app.signal_history;
+    match (history.back(), history.iter().min(), history.iter().max()) {
+        (Some(&now), Some(&min), Some(&max)) => {
+            let data: Vec<u64> = history.iter().map(|&signal| u64::from(signal)).collect();
+            let sparkline = Sparkline::default()
+                .data(&data)
+                .max(100)
+                .style(Style::default().fg(signal_color(theme, now)));
+            frame.render_widget(sparkline, chunks[0]);
+
+            let summary = format!(
+                "now {}  min {}  max {}",
+                app.units.signal(now),
+                app.units.signal(min),
+                app.units.signal(max)
+            );
+            let summary = Paragraph::new(summary)
+                .style(Style::default().fg(theme.text))
+                .alignment(Alignment::Center);
+            frame.render_widget(summary, chunks[1]);
+        }
+        _ => {
+            let text = Paragraph::new("No readings yet.")
+                .style(Style::default().fg(theme.muted))
+                .alignment(Alignment::Center);
+            frame.render_widget(text, chunks[0]);
+        }
+    }
+
+    let hint = Paragraph::new("[Any key] Dismiss")
+        .style(Style::default().fg(theme.muted))
+        .alignment(Alignment::Center);
+    frame.render_widget(hint, chunks[2]);
+}
+
+fn draw_dispatcher(frame: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(" Dispatcher scripts ({}) ", DISPATCHER_DIR))
+        .style(Style::default().fg(theme.info));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::vertical([Constraint::Min(1), Constraint::Length(2)]).split(inner);
+
+    if app.dispatcher_scripts.is_empty() {
+        let text = Paragraph::new("No dispatcher scripts yet.")
+            .style(Style::default().fg(theme.muted))
+            .alignment(Alignment::Center);
+        frame.render_widget(text, chunks[0]);
+    } else {
+        let items: Vec<ListItem> = app
+            .dispatcher_scripts
+            .iter()
+            .enumerate()
+            .map(|(i, script)| {
+                let (mark, color) = if script.enabled {
+                    ("[✓]", theme.good)
+                } else {
+                    ("[ ]", theme.muted)
+                };
+                let mut spans = vec![
+                    Span::styled(format!(" {} ", mark), Style::default().fg(color)),
+                    Span::styled(script.name.clone(), Style::default().fg(theme.text)),
+                ];
+                if script.insecure {
+                    spans.push(Span::styled(
+                        "  (ignored: must be root-owned, not group/world-writable)",
+                        Style::default().fg(theme.warn),
+                    ));
+                }
+                let item = ListItem::new(Line::from(spans));
+                if i == app.dispatcher_index {
+                    item.style(Style::default().bg(theme.highlight))
+                } else {
+                    item
+                }
+            })
+            .collect();
+        frame.render_widget(List::new(items), chunks[0]);
+    }
+
+    let hint = Paragraph::new("Changes ask for your password (pkexec).")
+        .style(Style::default().fg(theme.muted))
+        .alignment(Alignment::Center);
+    frame.render_widget(hint, chunks[1]);
+}
+
+/// Toasts and messages from this session, newest at the bottom.
+fn draw_messages(frame: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(" Messages ")
+        .style(Style::default().fg(theme.info));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    if app.messages.is_empty() {
+        let text = Paragraph::new("Nothing yet.")
+            .style(Style::default().fg(theme.muted))
+            .alignment(Alignment::Center);
+        frame.render_widget(text, inner);
+        return;
+    }
+
+    let now = Instant::now();
+    let selected = app.message_index.min(app.messages.len() - 1);
+    draw_list(frame, app, inner, &app.message_list, app.messages.len(), selected, |i| {
+        let message = &app.messages[i];
+        let color = match message.kind {
+            MessageKind::Info => theme.good,
+            MessageKind::Warning => theme.warn,
+            MessageKind::Error => theme.bad,
+        };
+        let text = message.text.split_whitespace().collect::<Vec<_>>().join(" ");
+        let line = Line::from(vec![
+            Span::styled(format!("{:>9} ", age_label(now.duration_since(message.at))), Style::default().fg(theme.muted)),
+            Span::styled(text, Style::default().fg(color)),
+        ]);
+        let item = ListItem::new(line);
+        if i == selected {
+            item.style(Style::default().bg(theme.highlight))
+        } else {
+            item
+        }
+    });
+}
+
+/// e.g. "just now", "40s ago", "3 min ago"
+fn age_label(age: Duration) -> String {
+    match age.as_secs() {
+        0..=4 => "just now".to_string(),
+        secs @ 5..=59 => format!("{}s ago", secs),
+        secs @ 60..=3599 => format!("{} min ago", secs / 60),
+        secs => format!("{} h ago", secs / 3600),
+    }
+}
+
+/// The actions menu: one line per action, with its key on the right.
+fn draw_action_menu(frame: &mut Frame, app: &App, menu: &ActionMenu, area: Rect) {
+    let theme = &app.theme;
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(" {} ", menu.target))
+        .style(Style::default().fg(theme.info));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let width = inner.width as usize;
+    let items: Vec<ListItem> = menu
+        .items
+        .iter()
+        .enumerate()
+        .map(|(i, (action, label))| {
+            let key = app.keys.label(*action);
+            let label_width = width.saturating_sub(key.chars().count() + 3);
+            let line = Line::from(vec![
+                Span::styled(format!(" {} ", truncate_pad(label, label_width)), Style::default().fg(theme.text)),
+                Span::styled(key, Style::default().fg(theme.muted)),
+            ]);
+            if i == app.menu_index {
+                ListItem::new(line).style(Style::default().bg(theme.highlight))
+            } else {
+                ListItem::new(line)
+            }
+        })
+        .collect();
+    frame.render_widget(List::new(items), inner);
+}
+
+/// Draw QR modules two rows per line with upper half blocks. Colors are set
+/// explicitly so the code stays dark-on-light on any terminal theme.
+fn qr_lines(modules: &[Vec<bool>]) -> Vec<Line<'static>> {
+    let color = |dark: bool| if dark { Color::Black } else { Color::White };
+    modules
+        .chunks(2)
+        .map(|rows| {
+            let spans: Vec<Span> = (0..rows[0].len())
+                .map(|x| {
+                    let top = rows[0][x];
+                    let bottom = rows.get(1).is_some_and(|row| row[x]);
+                    Span::styled("▀", Style::default().fg(color(top)).bg(color(bottom)))
+                })
+                .collect();
+            Line::from(spans)
+        })
+        .collect()
+}
+
+/// Render a text input as spans: the selection is highlighted and, when
+/// focused, the char under the cursor is drawn reversed.
+/// First char shown when `len` chars plus the end-of-text cursor cell don't
+/// fit in `width` columns: scrolls just far enough to keep the cursor visible.
+fn input_scroll(len: usize, cursor: usize, width: usize) -> usize {
+    if width == 0 || len < width {
+        return 0;
+    }
+    (cursor + 1).saturating_sub(width)
+}
+
+/// Spans for a text input, scrolled so the cursor stays within `width`
+/// columns (not counting the padding on either side).
+fn input_spans(theme: &Theme, input: &TextInput, masked: bool, focused: bool, width: usize) -> Vec<Span<'static>> {
+    let base = if focused {
+        Style::default().fg(theme.text).bg(theme.muted)
+    } else {
+        Style::default().fg(theme.dim)
+    };
+    let chars: Vec<char> = if masked {
+        vec!['●'; input.len()]
+    } else {
+        input.value().chars().collect()
+    };
+    let selection = input.selection().filter(|_| focused);
+    let start = input_scroll(chars.len(), input.cursor(), width);
+    let shown = if width == 0 { chars.len() } else { width };
+
+    let mut spans = vec![Span::styled(" ", base)];
+    for (i, c) in chars.iter().enumerate().skip(start).take(shown) {
+        let style = if focused && i == input.cursor() {
+            base.add_modifier(Modifier::REVERSED)
+        } else if selection.is_some_and(|(start, end)| i >= start && i < end) {
+            base.bg(theme.selection)
+        } else {
+            base
+        };
+        spans.push(Span::styled(c.to_string(), style));
+    }
+    // Cursor past the end of the text
+    if focused && input.cursor() >= chars.len() {
+        spans.push(Span::styled(" ", base.add_modifier(Modifier::REVERSED)));
+    }
+    spans.push(Span::styled(" ", base));
+    spans
 }
 
 /// Truncate a string to max_len chars with ellipsis, then pad to max_len.
+/// An SSID safe to draw. Control characters would garble the terminal and
+/// bidi overrides can make one name pass for another, so both are escaped.
+pub fn ssid_text(ssid: &str) -> String {
+    let unsafe_char = |c: char| {
+        c.is_control() || matches!(c, '\u{200e}' | '\u{200f}' | '\u{202a}'..='\u{202e}' | '\u{2066}'..='\u{2069}')
+    };
+    ssid.chars()
+        .map(|c| if unsafe_char(c) { c.escape_default().to_string() } else { c.to_string() })
+        .collect()
+}
+
 fn truncate_pad(s: &str, max_len: usize) -> String {
     let chars: Vec<char> = s.chars().collect();
     if chars.len() <= max_len {
@@ -495,6 +2193,25 @@ fn simplify_security(sec: &str) -> String {
     }
 }
 
+/// Bars by default, or a word when `--text-signal` is set (screen readers,
+/// fonts without block glyphs). Both are padded to a fixed width.
+fn signal_indicator(app: &App, signal: u8) -> &'static str {
+    if app.text_signal {
+        signal_label(signal)
+    } else {
+        signal_bars(signal)
+    }
+}
+
+fn signal_label(signal: u8) -> &'static str {
+    match signal {
+        80..=100 => "Excellent",
+        60..=79 => "Good     ",
+        40..=59 => "Fair     ",
+        _ => "Poor     ",
+    }
+}
+
 fn signal_bars(signal: u8) -> &'static str {
     match signal {
         80..=100 => "▂▄▆█",
@@ -505,10 +2222,77 @@ fn signal_bars(signal: u8) -> &'static str {
     }
 }
 
-fn signal_color(signal: u8) -> Color {
+fn signal_color(theme: &Theme, signal: u8) -> Color {
     match signal {
-        80..=100 => Color::Green,
-        50..=79 => Color::Yellow,
-        _ => Color::Red,
+        80..=100 => theme.good,
+        50..=79 => theme.warn,
+        _ => theme.bad,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::decode_ssid;
+
+    #[test]
+    fn test_window_label() {
+        let labels: Vec<String> = MONITOR_WINDOWS.iter().map(|&window| window_label(window)).collect();
+        assert_eq!(labels, ["1 min", "5 min", "15 min", "1 h"]);
+    }
+
+    #[test]
+    fn test_age_label() {
+        let labels: Vec<String> = [2, 40, 200, 7300].map(|secs| age_label(Duration::from_secs(secs))).into();
+        assert_eq!(labels, ["just now", "40s ago", "3 min ago", "2 h ago"]);
+    }
+
+    #[test]
+    fn test_input_scroll() {
+        assert_eq!(input_scroll(5, 5, 10), 0);
+        // The end-of-text cursor needs a cell of its own
+        assert_eq!(input_scroll(10, 10, 10), 1);
+        assert_eq!(input_scroll(30, 30, 10), 21);
+        assert_eq!(input_scroll(30, 3, 10), 0);
+        assert_eq!(input_scroll(30, 15, 10), 6);
+    }
+
+    #[test]
+    fn test_ssid_text() {
+        assert_eq!(ssid_text("Café"), "Café");
+        assert_eq!(ssid_text("a\nb\x1b[2J"), "a\\nb\\u{1b}[2J");
+        assert_eq!(ssid_text("evil\u{202e}gpj"), "evil\\u{202e}gpj");
+        let net = |bytes: &[u8]| Network { ssid: decode_ssid(bytes), ssid_bytes: bytes.to_vec(), ..Network::default() };
+        assert_eq!(raw_ssid(&net(b"Home")), None);
+        assert_eq!(raw_ssid(&net(b"Caf\xe9")), Some("43 61 66 e9".to_string()));
+    }
+
+    #[test]
+    fn test_scroll_offset() {
+        // Everything fits
+        assert_eq!(scroll_offset(0, 3, 5, 10), 0);
+        // Selection past the bottom scrolls just enough
+        assert_eq!(scroll_offset(0, 12, 200, 10), 3);
+        // Moving within the window doesn't scroll
+        assert_eq!(scroll_offset(3, 5, 200, 10), 3);
+        // Selection above the window scrolls up to it
+        assert_eq!(scroll_offset(20, 4, 200, 10), 4);
+        // The list shrank under the old offset
+        assert_eq!(scroll_offset(50, 2, 6, 10), 0);
+    }
+
+    #[test]
+    fn test_target_areas() {
+        let line = Line::from(vec![Span::raw(" Connected: Cafe"), Span::raw("  │  "), Span::raw("Signal: 72%")]);
+        let targets = [(0, StatusTarget::Ssid), (2, StatusTarget::Signal)];
+        assert_eq!(
+            target_areas(&line, &targets, Rect::new(2, 5, 80, 1)),
+            [(Rect::new(2, 5, 16, 1), StatusTarget::Ssid), (Rect::new(23, 5, 11, 1), StatusTarget::Signal)]
+        );
+        // Cut off at the edge of a narrow terminal
+        assert_eq!(
+            target_areas(&line, &targets, Rect::new(0, 0, 20, 1)),
+            [(Rect::new(0, 0, 16, 1), StatusTarget::Ssid)]
+        );
     }
 }
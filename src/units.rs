@@ -0,0 +1,193 @@
+use serde::Deserialize;
+
+use crate::backend::percent_to_dbm;
+
+/// Prefixes for bit rates and sizes: SI (k = 1000) or binary (Ki = 1024).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UnitSystem {
+    #[default]
+    Si,
+    Binary,
+}
+
+/// How signal strength is written: NetworkManager's 0-100 quality, or dBm.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SignalUnit {
+    #[default]
+    Percent,
+    Dbm,
+}
+
+/// How numbers with units are written everywhere text is shown: the
+/// decimal and thousands separators of the user's locale, and the
+/// configured prefixes. JSON output keeps plain numbers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Units {
+    system: UnitSystem,
+    signal: SignalUnit,
+    decimal: char,
+    group: char,
+}
+
+impl Default for Units {
+    fn default() -> Self {
+        Self::for_locale(UnitSystem::Si, "C")
+    }
+}
+
+impl Units {
+    /// Separators from `LC_ALL`, `LC_NUMERIC` or `LANG`, like other programs.
+    pub fn new(system: UnitSystem, signal: SignalUnit) -> Self {
+        let locale = ["LC_ALL", "LC_NUMERIC", "LANG"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|value| !value.is_empty())
+            .unwrap_or_default();
+        Self { signal, ..Self::for_locale(system, &locale) }
+    }
+
+    /// e.g. "de_DE.UTF-8"; unknown languages get "1,234.5".
+    pub fn for_locale(system: UnitSystem, locale: &str) -> Self {
+        let language = locale.split(['_', '.', '@']).next().unwrap_or_default();
+        let (decimal, group) = match language {
+            "de" | "es" | "it" | "nl" | "pt" | "da" | "id" | "tr" | "el" | "ro" | "sl" | "hr" | "sr" => (',', '.'),
+            "fr" | "ru" | "pl" | "cs" | "sk" | "sv" | "fi" | "nb" | "nn" | "uk" | "hu" | "bg" | "et" | "lv" | "lt" => {
+                (',', '\u{202f}')
+            }
+            _ => ('.', ','),
+        };
+        Self { system, signal: SignalUnit::Percent, decimal, group }
+    }
+
+    pub fn signal_unit(&self) -> SignalUnit {
+        self.signal
+    }
+
+    /// Switch between percent and dBm.
+    pub fn toggle_signal_unit(&mut self) {
+        self.signal = match self.signal {
+            SignalUnit::Percent => SignalUnit::Dbm,
+            SignalUnit::Dbm => SignalUnit::Percent,
+        };
+    }
+
+    /// A signal in the chosen unit. Scans only report a percentage, so dBm
+    /// is estimated from it.
+    pub fn signal(&self, percent: u8) -> String {
+        self.link_signal(percent, None)
+    }
+
+    /// The connection's signal, in dBm as measured when known.
+    pub fn link_signal(&self, percent: u8, dbm: Option<i32>) -> String {
+        match self.signal {
+            SignalUnit::Percent => self.percent(percent),
+            SignalUnit::Dbm => self.dbm(dbm.unwrap_or_else(|| percent_to_dbm(percent))),
+        }
+    }
+
+    /// Signal quality, e.g. "72%".
+    pub fn percent(&self, value: u8) -> String {
+        format!("{}%", value)
+    }
+
+    /// Signal strength, e.g. "-52 dBm".
+    pub fn dbm(&self, value: i32) -> String {
+        format!("{} dBm", value)
+    }
+
+    /// A whole number with thousands separators, e.g. "12,000".
+    pub fn integer(&self, value: u64) -> String {
+        let digits = value.to_string();
+        let mut out = String::new();
+        for (i, digit) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i).is_multiple_of(3) {
+                out.push(self.group);
+            }
+            out.push(digit);
+        }
+        out
+    }
+
+    /// One decimal place with the locale's separators, e.g. "1,234.5".
+    fn decimal(&self, value: f64) -> String {
+        let tenths = (value * 10.0).round() as u64;
+        format!("{}{}{}", self.integer(tenths / 10), self.decimal, tenths % 10)
+    }
+
+    /// A link or limit rate given in kbit/s, e.g. "866.7 Mbit/s".
+    pub fn bitrate(&self, kbit: u64) -> String {
+        let (base, units) = match self.system {
+            UnitSystem::Si => (1000.0, ["kbit/s", "Mbit/s", "Gbit/s"]),
+            UnitSystem::Binary => (1024.0, ["Kibit/s", "Mibit/s", "Gibit/s"]),
+        };
+        // kbit/s are decimal by definition; rescale for binary prefixes
+        let bits = kbit as f64 * 1000.0;
+        self.scaled(bits / base, base, &units)
+    }
+
+    /// A size in bytes, e.g. "12.3 kB" or "12 KiB".
+    pub fn bytes(&self, bytes: u64) -> String {
+        let (base, units) = match self.system {
+            UnitSystem::Si => (1000.0, ["kB", "MB", "GB"]),
+            UnitSystem::Binary => (1024.0, ["KiB", "MiB", "GiB"]),
+        };
+        if (bytes as f64) < base {
+            return format!("{} B", bytes);
+        }
+        self.scaled(bytes as f64 / base, base, &units)
+    }
+
+    /// A transfer rate given in bytes per second, e.g. "1.2 MB/s".
+    pub fn byte_rate(&self, bytes: u64) -> String {
+        format!("{}/s", self.bytes(bytes))
+    }
+
+    /// `value` in the first of `units`, moved up a prefix while it's over `base`.
+    fn scaled(&self, mut value: f64, base: f64, units: &[&str]) -> String {
+        let mut unit = 0;
+        while value >= base && unit + 1 < units.len() {
+            value /= base;
+            unit += 1;
+        }
+        if unit == 0 && value.fract() == 0.0 {
+            format!("{} {}", self.integer(value as u64), units[0])
+        } else {
+            format!("{} {}", self.decimal(value), units[unit])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_units() {
+        let en = Units::for_locale(UnitSystem::Si, "en_US.UTF-8");
+        assert_eq!(en.bitrate(866_700), "866.7 Mbit/s");
+        assert_eq!(en.bitrate(512), "512 kbit/s");
+        assert_eq!(en.bitrate(2_400_000), "2.4 Gbit/s");
+        assert_eq!(en.bytes(999), "999 B");
+        assert_eq!(en.bytes(12_345), "12.3 kB");
+        assert_eq!(en.byte_rate(1_234_567), "1.2 MB/s");
+        assert_eq!(en.integer(1_234_567), "1,234,567");
+        assert_eq!(en.percent(72), "72%");
+        assert_eq!(en.dbm(-52), "-52 dBm");
+        assert_eq!(en.signal(72), "72%");
+        let mut dbm = en;
+        dbm.toggle_signal_unit();
+        assert_eq!(dbm.signal(72), "-64 dBm");
+        assert_eq!(dbm.link_signal(72, Some(-58)), "-58 dBm");
+
+        let de = Units::for_locale(UnitSystem::Si, "de_DE.UTF-8");
+        assert_eq!(de.bitrate(866_700), "866,7 Mbit/s");
+        assert_eq!(de.bytes(1_234_567_890), "1,2 GB");
+
+        let binary = Units::for_locale(UnitSystem::Binary, "C");
+        assert_eq!(binary.bytes(12 * 1024), "12 KiB");
+        assert_eq!(binary.bytes(12_345), "12.1 KiB");
+        assert_eq!(binary.bitrate(1024), "1,000 Kibit/s");
+    }
+}
@@ -0,0 +1,101 @@
+use std::path::PathBuf;
+use std::process::Command;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::config::config_dir;
+use crate::logging;
+
+/// The running version.
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Ask crates.io at most this often; the answer is kept in between.
+const CHECK_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+const CRATE_URL: &str = "https://crates.io/api/v1/crates/wifi-tui";
+
+/// `~/.config/wifi-tui/update-check`: when crates.io was last asked, and
+/// the newest release it named.
+fn cache_path() -> Option<PathBuf> {
+    Some(config_dir()?.join("update-check"))
+}
+
+/// The newest release on crates.io, from the cache if it was checked within
+/// the last day. Blocks on the network otherwise.
+pub fn latest_version() -> Result<String, String> {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+    let cached = cache_path()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|text| parse_cache(&text));
+    if let Some((checked, latest)) = cached {
+        if now.saturating_sub(checked) < CHECK_INTERVAL.as_secs() {
+            return Ok(latest);
+        }
+    }
+
+    // crates.io turns away requests without a user agent
+    let output = logging::output(Command::new("curl").args([
+        "-fsS", "--max-time", "10", "-A", &format!("wifi-tui/{}", VERSION), CRATE_URL,
+    ]))
+    .map_err(|_| "curl not found, so updates can't be checked.".to_string())?;
+    if !output.status.success() {
+        return Err(format!(
+            "Couldn't check for updates: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    let latest = parse_latest(&String::from_utf8_lossy(&output.stdout))
+        .ok_or("Couldn't check for updates: crates.io sent something unexpected.")?;
+    // Only saves a request next time
+    if let Some(path) = cache_path() {
+        if let Some(dir) = path.parent() {
+            let _ = std::fs::create_dir_all(dir);
+        }
+        let _ = std::fs::write(path, format!("checked={}\nlatest={}\n", now, latest));
+    }
+    Ok(latest)
+}
+
+/// (unix time of the check, version) from the cache file.
+fn parse_cache(text: &str) -> Option<(u64, String)> {
+    let value = |key: &str| text.lines().find_map(|line| line.strip_prefix(key)?.strip_prefix('='));
+    Some((value("checked")?.trim().parse().ok()?, value("latest")?.trim().to_string()))
+}
+
+/// `crate.max_stable_version` of the crates.io API's answer.
+fn parse_latest(json: &str) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_str(json).ok()?;
+    Some(value["crate"]["max_stable_version"].as_str()?.to_string())
+}
+
+/// Whether `latest` is a later release than `current`, comparing the
+/// numbers of `major.minor.patch` in turn.
+pub fn is_newer(latest: &str, current: &str) -> bool {
+    let numbers = |version: &str| -> Vec<u64> {
+        version
+            .split(['.', '-', '+'])
+            .take(3)
+            .map(|part| part.parse().unwrap_or(0))
+            .collect()
+    };
+    numbers(latest) > numbers(current)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_update_check() {
+        assert!(is_newer("0.2.0", "0.1.9"));
+        assert!(is_newer("1.10.0", "1.9.3"));
+        assert!(!is_newer("0.1.0", "0.1.0"));
+        assert!(!is_newer("0.1.0", "0.2.0-rc.1"));
+        assert_eq!(
+            parse_latest(r#"{"crate":{"name":"wifi-tui","max_stable_version":"0.3.1"},"versions":[]}"#),
+            Some("0.3.1".to_string())
+        );
+        assert_eq!(parse_latest("<html>"), None);
+        assert_eq!(parse_cache("checked=1700000000\nlatest=0.3.1\n"), Some((1_700_000_000, "0.3.1".to_string())));
+        assert_eq!(parse_cache("latest=0.3.1\n"), None);
+    }
+}
@@ -0,0 +1,41 @@
+use crate::keyfile::{self, Entries};
+
+/// Bytes received and sent while connected to a network.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Usage {
+    pub rx: u64,
+    pub tx: u64,
+}
+
+/// Data used per network, keyed by SSID.
+pub type UsageTotals = Entries<Usage>;
+
+/// `~/.config/wifi-tui/usage`, one `ssid=rx:tx` (in bytes) per line.
+const FILE: &str = "usage";
+
+/// Load the totals so far. A missing or unreadable file means nothing used yet.
+pub fn load() -> UsageTotals {
+    keyfile::load(FILE, parse_usage)
+}
+
+pub fn save(totals: &UsageTotals) -> Result<(), String> {
+    keyfile::save(FILE, totals, |usage| format!("{}:{}", usage.rx, usage.tx))
+}
+
+/// `rx:tx`
+fn parse_usage(value: &str) -> Option<Usage> {
+    let (rx, tx) = value.trim().split_once(':')?;
+    Some(Usage { rx: rx.parse().ok()?, tx: tx.parse().ok()? })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_usage() {
+        assert_eq!(parse_usage("1000:200\n"), Some(Usage { rx: 1000, tx: 200 }));
+        assert_eq!(parse_usage("7"), None);
+        assert_eq!(parse_usage("x:1"), None);
+    }
+}
@@ -0,0 +1,94 @@
+use std::path::PathBuf;
+
+use crate::app::{SavedSort, SortMode};
+use crate::backend::Band;
+use crate::config::config_dir;
+
+/// Filter and sort of the Available and Saved tabs, kept between runs.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ViewState {
+    pub available_filter: String,
+    /// None until the user picks one: `default_sort` from the config applies.
+    pub available_sort: Option<SortMode>,
+    pub available_band: Option<Band>,
+    pub saved_filter: String,
+    pub saved_sort: SavedSort,
+}
+
+/// `~/.config/wifi-tui/views`, one `key=value` per line.
+fn state_path() -> Option<PathBuf> {
+    Some(config_dir()?.join("views"))
+}
+
+/// Load the saved state. A missing or unreadable file means defaults.
+pub fn load() -> ViewState {
+    state_path()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .map(|text| parse_state(&text))
+        .unwrap_or_default()
+}
+
+pub fn save(state: &ViewState) -> Result<(), String> {
+    let path = state_path().ok_or("Couldn't find a config directory (HOME is not set).")?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Couldn't create {}: {}", dir.display(), e))?;
+    }
+    std::fs::write(&path, format_state(state))
+        .map_err(|e| format!("Couldn't write {}: {}", path.display(), e))
+}
+
+fn parse_state(text: &str) -> ViewState {
+    let mut state = ViewState::default();
+    for line in text.lines() {
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        match key {
+            "available.filter" => state.available_filter = value.to_string(),
+            "available.sort" => state.available_sort = SortMode::ALL.into_iter().find(|mode| mode.label() == value),
+            "available.band" => state.available_band = Band::ALL.into_iter().find(|band| band.label() == value),
+            "saved.filter" => state.saved_filter = value.to_string(),
+            "saved.sort" => {
+                if let Some(sort) = SavedSort::ALL.into_iter().find(|sort| sort.label() == value) {
+                    state.saved_sort = sort;
+                }
+            }
+            _ => {}
+        }
+    }
+    state
+}
+
+fn format_state(state: &ViewState) -> String {
+    // A pasted line break would split the entry
+    let one_line = |text: &str| text.replace(['\n', '\r'], " ");
+    let mut text = format!("available.filter={}\n", one_line(&state.available_filter));
+    if let Some(sort) = state.available_sort {
+        text.push_str(&format!("available.sort={}\n", sort.label()));
+    }
+    if let Some(band) = state.available_band {
+        text.push_str(&format!("available.band={}\n", band.label()));
+    }
+    text.push_str(&format!("saved.filter={}\n", one_line(&state.saved_filter)));
+    text.push_str(&format!("saved.sort={}\n", state.saved_sort.label()));
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_state_roundtrip() {
+        let state = parse_state(
+            "available.filter=cafe=wifi\navailable.sort=channel\navailable.band=5 GHz\nsaved.sort=bogus\njunk\n",
+        );
+        assert_eq!(state.available_filter, "cafe=wifi");
+        assert_eq!(state.available_sort, Some(SortMode::Channel));
+        assert_eq!(state.available_band, Some(Band::Ghz5));
+        assert_eq!(state.saved_sort, SavedSort::Default);
+        let state = ViewState { saved_sort: SavedSort::Name, ..state };
+        assert_eq!(parse_state(&format_state(&state)), state);
+        assert_eq!(parse_state(""), ViewState::default());
+    }
+}